@@ -0,0 +1,553 @@
+use std::any::TypeId;
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+        primitives::Aabb,
+        view::{InheritedVisibility, ViewVisibility},
+    },
+    utils::{HashMap, HashSet},
+};
+use serde_json::{json, Value};
+
+use space_shared::{IsolationDimmed, PrefabMarker};
+
+use crate::selected::Selected;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+const GLTF_FLOAT: u32 = 5126;
+const GLTF_UNSIGNED_INT: u32 = 5125;
+const GLTF_ARRAY_BUFFER: u32 = 34962;
+const GLTF_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+#[derive(Event)]
+/// Event to export all prefab entities to GLB file
+pub struct EditorExportGltf {
+    pub path: String,
+}
+
+/// Output path or error of the last GLB export
+#[derive(Resource, Default)]
+pub struct GltfExportState {
+    pub last_export: Option<Result<String, String>>,
+}
+
+pub struct ExportGltfPlugin;
+
+impl Plugin for ExportGltfPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EditorExportGltf>();
+        app.init_resource::<GltfExportState>();
+        app.add_systems(PostUpdate, export_gltf_event);
+    }
+}
+
+fn export_gltf_event(world: &mut World) {
+    let events: Vec<EditorExportGltf> = world
+        .resource_mut::<Events<EditorExportGltf>>()
+        .drain()
+        .collect();
+
+    for event in events {
+        let result = export_gltf(world)
+            .and_then(|data| std::fs::write(&event.path, data).map_err(|err| err.to_string()))
+            .map(|_| event.path.clone());
+        match &result {
+            Ok(path) => info!("Scene exported to {}", path),
+            Err(err) => error!("Failed to export scene to {}: {}", event.path, err),
+        }
+        world.resource_mut::<GltfExportState>().last_export = Some(result);
+    }
+}
+
+/// Build GLB file from all entities with [`PrefabMarker`]
+pub fn export_gltf(world: &mut World) -> Result<Vec<u8>, String> {
+    let mut query = world.query_filtered::<Entity, With<PrefabMarker>>();
+    let entities: Vec<Entity> = query.iter(world).collect();
+    let exported: HashSet<Entity> = entities.iter().copied().collect();
+
+    let mut builder = GltfBuilder::default();
+    let mut node_indices = HashMap::new();
+    for entity in entities.iter() {
+        node_indices.insert(*entity, builder.nodes.len());
+        builder.nodes.push(json!({}));
+    }
+
+    let mut skipped_components = HashSet::new();
+    for entity in entities.iter() {
+        let entity_ref = world.entity(*entity);
+        let mut node = serde_json::Map::new();
+
+        if let Some(name) = entity_ref.get::<Name>() {
+            node.insert("name".into(), json!(name.as_str()));
+        }
+        // Node of entity with not exported parent is a scene root, so it is placed by world transform
+        let is_root = entity_ref
+            .get::<Parent>()
+            .map_or(true, |parent| !exported.contains(&parent.get()));
+        let transform = match entity_ref.get::<GlobalTransform>() {
+            Some(global) if is_root => global.compute_transform(),
+            _ => entity_ref.get::<Transform>().copied().unwrap_or_default(),
+        };
+        node.insert(
+            "translation".into(),
+            json!(transform.translation.to_array()),
+        );
+        node.insert("rotation".into(), json!(transform.rotation.to_array()));
+        node.insert("scale".into(), json!(transform.scale.to_array()));
+
+        if let Some(mesh) = entity_ref.get::<Handle<Mesh>>() {
+            // Isolation view dimming is editor only and is not exported
+            let material = entity_ref
+                .get::<Handle<StandardMaterial>>()
+                .map(|material| IsolationDimmed::original_material(entity_ref.get(), material));
+            if let Some(mesh) = builder.mesh(world, mesh, material) {
+                node.insert("mesh".into(), json!(mesh));
+            }
+        }
+
+        if let Some(children) = entity_ref.get::<Children>() {
+            let children: Vec<usize> = children
+                .iter()
+                .filter_map(|child| node_indices.get(child).copied())
+                .collect();
+            if !children.is_empty() {
+                node.insert("children".into(), json!(children));
+            }
+        }
+
+        for info in world.inspect_entity(*entity) {
+            if !info.type_id().is_some_and(has_gltf_equivalent) {
+                skipped_components.insert(info.name().to_string());
+            }
+        }
+
+        builder.nodes[node_indices[entity]] = Value::Object(node);
+    }
+
+    if !skipped_components.is_empty() {
+        let mut names: Vec<String> = skipped_components.into_iter().collect();
+        names.sort();
+        warn!(
+            "Components without glTF equivalent were skipped: {}",
+            names.join(", ")
+        );
+    }
+
+    // Children of exported entities are placed in node tree by their parent
+    let roots: Vec<usize> = entities
+        .iter()
+        .filter(|entity| {
+            world
+                .get::<Parent>(**entity)
+                .map_or(true, |parent| !exported.contains(&parent.get()))
+        })
+        .map(|entity| node_indices[entity])
+        .collect();
+
+    Ok(builder.finish(roots))
+}
+
+/// Components which are stored in glTF nodes or are derived from them
+fn has_gltf_equivalent(type_id: TypeId) -> bool {
+    [
+        TypeId::of::<PrefabMarker>(),
+        TypeId::of::<Selected>(),
+        TypeId::of::<Name>(),
+        TypeId::of::<Transform>(),
+        TypeId::of::<GlobalTransform>(),
+        TypeId::of::<Parent>(),
+        TypeId::of::<Children>(),
+        TypeId::of::<Handle<Mesh>>(),
+        TypeId::of::<Handle<StandardMaterial>>(),
+        TypeId::of::<Aabb>(),
+        TypeId::of::<Visibility>(),
+        TypeId::of::<InheritedVisibility>(),
+        TypeId::of::<ViewVisibility>(),
+        TypeId::of::<IsolationDimmed>(),
+    ]
+    .contains(&type_id)
+}
+
+#[derive(Default)]
+struct GltfBuilder {
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    accessors: Vec<Value>,
+    buffer_views: Vec<Value>,
+    buffer: Vec<u8>,
+    mesh_indices: HashMap<(AssetId<Mesh>, Option<AssetId<StandardMaterial>>), usize>,
+    material_indices: HashMap<AssetId<StandardMaterial>, usize>,
+}
+
+impl GltfBuilder {
+    /// Index of glTF mesh for mesh and material pair. Meshes are shared between nodes
+    fn mesh(
+        &mut self,
+        world: &World,
+        mesh: &Handle<Mesh>,
+        material: Option<&Handle<StandardMaterial>>,
+    ) -> Option<usize> {
+        let key = (mesh.id(), material.map(|material| material.id()));
+        if let Some(index) = self.mesh_indices.get(&key) {
+            return Some(*index);
+        }
+
+        let mesh = world.resource::<Assets<Mesh>>().get(mesh)?;
+        let mode = match mesh.primitive_topology() {
+            PrimitiveTopology::PointList => 0,
+            PrimitiveTopology::LineList => 1,
+            PrimitiveTopology::LineStrip => 3,
+            PrimitiveTopology::TriangleList => 4,
+            PrimitiveTopology::TriangleStrip => 5,
+        };
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            warn!("Mesh without positions was skipped in glTF export");
+            return None;
+        };
+
+        let mut attributes = serde_json::Map::new();
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), pos| (min.min(Vec3::from(*pos)), max.max(Vec3::from(*pos))),
+        );
+        let accessor = self.push_accessor(
+            f32_bytes(positions.iter().flatten()),
+            positions.len(),
+            "VEC3",
+            GLTF_FLOAT,
+            GLTF_ARRAY_BUFFER,
+            Some((min.to_array().to_vec(), max.to_array().to_vec())),
+        );
+        attributes.insert("POSITION".into(), json!(accessor));
+
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        {
+            let accessor = self.push_accessor(
+                f32_bytes(normals.iter().flatten()),
+                normals.len(),
+                "VEC3",
+                GLTF_FLOAT,
+                GLTF_ARRAY_BUFFER,
+                None,
+            );
+            attributes.insert("NORMAL".into(), json!(accessor));
+        }
+
+        if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            let accessor = self.push_accessor(
+                f32_bytes(uvs.iter().flatten()),
+                uvs.len(),
+                "VEC2",
+                GLTF_FLOAT,
+                GLTF_ARRAY_BUFFER,
+                None,
+            );
+            attributes.insert("TEXCOORD_0".into(), json!(accessor));
+        }
+
+        let mut primitive = serde_json::Map::new();
+        primitive.insert("attributes".into(), Value::Object(attributes));
+        primitive.insert("mode".into(), json!(mode));
+
+        if let Some(indices) = mesh.indices() {
+            let data: Vec<u8> = match indices {
+                Indices::U16(indices) => indices
+                    .iter()
+                    .flat_map(|index| (*index as u32).to_le_bytes())
+                    .collect(),
+                Indices::U32(indices) => indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect(),
+            };
+            let accessor = self.push_accessor(
+                data,
+                indices.len(),
+                "SCALAR",
+                GLTF_UNSIGNED_INT,
+                GLTF_ELEMENT_ARRAY_BUFFER,
+                None,
+            );
+            primitive.insert("indices".into(), json!(accessor));
+        }
+
+        if let Some(material) = material.and_then(|material| self.material(world, material)) {
+            primitive.insert("material".into(), json!(material));
+        }
+
+        let index = self.meshes.len();
+        self.meshes.push(json!({
+            "primitives": [Value::Object(primitive)],
+        }));
+        self.mesh_indices.insert(key, index);
+        Some(index)
+    }
+
+    fn material(&mut self, world: &World, material: &Handle<StandardMaterial>) -> Option<usize> {
+        if let Some(index) = self.material_indices.get(&material.id()) {
+            return Some(*index);
+        }
+        let material_id = material.id();
+        let material = world.resource::<Assets<StandardMaterial>>().get(material)?;
+
+        if material.base_color_texture.is_some()
+            || material.normal_map_texture.is_some()
+            || material.metallic_roughness_texture.is_some()
+            || material.emissive_texture.is_some()
+            || material.occlusion_texture.is_some()
+        {
+            warn!("Material textures are not exported to glTF, only factors are used");
+        }
+
+        let emissive = material.emissive.as_linear_rgba_f32();
+        let mut value = json!({
+            "pbrMetallicRoughness": {
+                "baseColorFactor": material.base_color.as_linear_rgba_f32(),
+                "metallicFactor": material.metallic,
+                "roughnessFactor": material.perceptual_roughness,
+            },
+            "emissiveFactor": [emissive[0], emissive[1], emissive[2]],
+            "doubleSided": material.double_sided,
+        });
+        match material.alpha_mode {
+            AlphaMode::Opaque => {}
+            AlphaMode::Mask(cutoff) => {
+                value["alphaMode"] = json!("MASK");
+                value["alphaCutoff"] = json!(cutoff);
+            }
+            _ => {
+                value["alphaMode"] = json!("BLEND");
+            }
+        }
+
+        let index = self.materials.len();
+        self.materials.push(value);
+        self.material_indices.insert(material_id, index);
+        Some(index)
+    }
+
+    fn push_accessor(
+        &mut self,
+        data: Vec<u8>,
+        count: usize,
+        kind: &str,
+        component_type: u32,
+        target: u32,
+        bounds: Option<(Vec<f32>, Vec<f32>)>,
+    ) -> usize {
+        // Buffer views must be aligned to component size
+        while self.buffer.len() % 4 != 0 {
+            self.buffer.push(0);
+        }
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": data.len(),
+            "target": target,
+        }));
+        self.buffer.extend(data);
+
+        let mut accessor = json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": kind,
+        });
+        if let Some((min, max)) = bounds {
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn finish(mut self, roots: Vec<usize>) -> Vec<u8> {
+        while self.buffer.len() % 4 != 0 {
+            self.buffer.push(0);
+        }
+
+        let mut root = json!({
+            "asset": {
+                "version": "2.0",
+                "generator": "space_editor",
+            },
+            "scene": 0,
+            "scenes": [{ "nodes": roots }],
+            "nodes": self.nodes,
+        });
+        if !self.meshes.is_empty() {
+            root["meshes"] = json!(self.meshes);
+        }
+        if !self.materials.is_empty() {
+            root["materials"] = json!(self.materials);
+        }
+        if !self.buffer.is_empty() {
+            root["accessors"] = json!(self.accessors);
+            root["bufferViews"] = json!(self.buffer_views);
+            root["buffers"] = json!([{ "byteLength": self.buffer.len() }]);
+        }
+
+        let mut json_chunk = root.to_string().into_bytes();
+        while json_chunk.len() % 4 != 0 {
+            json_chunk.push(b' ');
+        }
+
+        let mut total_length = 12 + 8 + json_chunk.len();
+        if !self.buffer.is_empty() {
+            total_length += 8 + self.buffer.len();
+        }
+
+        let mut glb = Vec::with_capacity(total_length);
+        glb.extend(GLB_MAGIC.to_le_bytes());
+        glb.extend(GLB_VERSION.to_le_bytes());
+        glb.extend((total_length as u32).to_le_bytes());
+        glb.extend((json_chunk.len() as u32).to_le_bytes());
+        glb.extend(GLB_CHUNK_JSON.to_le_bytes());
+        glb.extend(json_chunk);
+        if !self.buffer.is_empty() {
+            glb.extend((self.buffer.len() as u32).to_le_bytes());
+            glb.extend(GLB_CHUNK_BIN.to_le_bytes());
+            glb.extend(self.buffer);
+        }
+        glb
+    }
+}
+
+fn f32_bytes<'a>(values: impl Iterator<Item = &'a f32>) -> Vec<u8> {
+    values.flat_map(|value| value.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::mesh::shape;
+
+    use super::*;
+
+    /// JSON chunk of GLB file
+    fn gltf_json(glb: &[u8]) -> Value {
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap());
+        assert_eq!(read_u32(0), GLB_MAGIC);
+        assert_eq!(read_u32(8) as usize, glb.len());
+        assert_eq!(read_u32(16), GLB_CHUNK_JSON);
+        let length = read_u32(12) as usize;
+        serde_json::from_slice(&glb[20..20 + length]).unwrap()
+    }
+
+    fn node<'a>(json: &'a Value, name: &str) -> (usize, &'a Value) {
+        json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .find(|(_, node)| node["name"] == name)
+            .unwrap()
+    }
+
+    fn spawn_at(world: &mut World, name: &str, local: Vec3, global: Vec3) -> Entity {
+        world
+            .spawn((
+                PrefabMarker,
+                Name::new(name.to_string()),
+                Transform::from_translation(local),
+                GlobalTransform::from_translation(global),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn test_export_places_nodes_of_not_exported_parents_in_world() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+
+        // Editor entity is not exported, its prefab child keeps world position
+        let editor_parent = world
+            .spawn((
+                Transform::from_xyz(10.0, 0.0, 0.0),
+                GlobalTransform::from_xyz(10.0, 0.0, 0.0),
+            ))
+            .id();
+        let orphan = spawn_at(&mut world, "orphan", Vec3::X, Vec3::new(11.0, 0.0, 0.0));
+        world.entity_mut(editor_parent).add_child(orphan);
+
+        let parent = spawn_at(&mut world, "parent", Vec3::Y, Vec3::Y);
+        let child = spawn_at(&mut world, "child", Vec3::Z, Vec3::Y + Vec3::Z);
+        world.entity_mut(parent).add_child(child);
+
+        let json = gltf_json(&export_gltf(&mut world).unwrap());
+        let (orphan_index, orphan_node) = node(&json, "orphan");
+        let (parent_index, parent_node) = node(&json, "parent");
+        let (child_index, child_node) = node(&json, "child");
+        assert_eq!(orphan_node["translation"], json!([11.0, 0.0, 0.0]));
+        assert_eq!(parent_node["translation"], json!([0.0, 1.0, 0.0]));
+        assert_eq!(child_node["translation"], json!([0.0, 0.0, 1.0]));
+        assert_eq!(parent_node["children"], json!([child_index]));
+
+        let mut roots = json["scenes"][0]["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|index| index.as_u64().unwrap() as usize)
+            .collect::<Vec<_>>();
+        roots.sort();
+        let mut expected = vec![orphan_index, parent_index];
+        expected.sort();
+        assert_eq!(roots, expected);
+    }
+
+    #[test]
+    fn test_export_shares_meshes_and_materials() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Cube::default().into());
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial::from(Color::RED));
+        for name in ["first", "second"] {
+            let entity = spawn_at(&mut world, name, Vec3::ZERO, Vec3::ZERO);
+            world
+                .entity_mut(entity)
+                .insert((mesh.clone(), material.clone()));
+        }
+
+        let glb = export_gltf(&mut world).unwrap();
+        let json = gltf_json(&glb);
+        assert_eq!(json["meshes"].as_array().unwrap().len(), 1);
+        assert_eq!(json["materials"].as_array().unwrap().len(), 1);
+        assert_eq!(node(&json, "first").1["mesh"], json!(0));
+        assert_eq!(node(&json, "second").1["mesh"], json!(0));
+        assert_eq!(
+            json["materials"][0]["pbrMetallicRoughness"]["baseColorFactor"],
+            json!(Color::RED.as_linear_rgba_f32())
+        );
+
+        // Binary chunk follows JSON chunk and holds the whole buffer
+        let buffer_length = json["buffers"][0]["byteLength"].as_u64().unwrap() as usize;
+        let json_length = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        let bin = 20 + json_length;
+        assert_eq!(
+            u32::from_le_bytes(glb[bin..bin + 4].try_into().unwrap()) as usize,
+            buffer_length
+        );
+        assert_eq!(
+            u32::from_le_bytes(glb[bin + 4..bin + 8].try_into().unwrap()),
+            GLB_CHUNK_BIN
+        );
+        assert_eq!(glb.len(), bin + 8 + buffer_length);
+    }
+}
@@ -153,6 +153,39 @@ impl HotkeyAppExt for App {
     }
 }
 
+/// Modifier keys, left and right keys of the same modifier are not distinguished
+const MODIFIERS: [[KeyCode; 2]; 4] = [
+    [KeyCode::ControlLeft, KeyCode::ControlRight],
+    [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+    [KeyCode::AltLeft, KeyCode::AltRight],
+    [KeyCode::SuperLeft, KeyCode::SuperRight],
+];
+
+fn modifier_of(code: KeyCode) -> Option<[KeyCode; 2]> {
+    MODIFIERS
+        .iter()
+        .find(|modifier| modifier.contains(&code))
+        .copied()
+}
+
+/// Binding is pressed if all its keys are pressed. Bindings with ordinary keys are not pressed with
+/// extra modifiers, so Ctrl+F does not fire F. Bindings of modifiers only can be combined with other keys
+pub fn binding_pressed(binding: &[KeyCode], input: &Input<KeyCode>) -> bool {
+    let all_pressed = binding.iter().all(|code| match modifier_of(*code) {
+        Some(modifier) => input.any_pressed(modifier),
+        None => input.pressed(*code),
+    });
+    if !all_pressed {
+        return false;
+    }
+    if binding.iter().all(|code| modifier_of(*code).is_some()) {
+        return true;
+    }
+    MODIFIERS.iter().all(|modifier| {
+        !input.any_pressed(*modifier) || binding.iter().any(|code| modifier.contains(code))
+    })
+}
+
 fn hotkey_mapper<T>(
     bindings: Res<HotkeySet<T>>,
     mut hotkeys: ResMut<Input<T>>,
@@ -162,16 +195,65 @@ fn hotkey_mapper<T>(
 {
     hotkeys.clear();
     for (key, binding) in bindings.bindings.iter() {
-        let mut pressed = true;
-        for code in binding {
-            if !input.pressed(*code) {
-                pressed = false;
-            }
-        }
-        if pressed {
+        if binding_pressed(binding, &input) {
             hotkeys.press(*key);
         } else {
             hotkeys.release(*key);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+    enum TestHotkey {
+        Focus,
+        Search,
+        Snap,
+    }
+
+    impl Hotkey for TestHotkey {
+        fn name(&self) -> String {
+            format!("{:?}", self)
+        }
+    }
+
+    fn pressed_with(keys: &[KeyCode]) -> Vec<TestHotkey> {
+        let mut app = App::new();
+        app.init_resource::<Input<KeyCode>>()
+            .editor_hotkey(TestHotkey::Focus, vec![KeyCode::F])
+            .editor_hotkey(TestHotkey::Search, vec![KeyCode::ControlLeft, KeyCode::F])
+            .editor_hotkey(TestHotkey::Snap, vec![KeyCode::ControlLeft]);
+        let mut input = app.world.resource_mut::<Input<KeyCode>>();
+        for key in keys {
+            input.press(*key);
+        }
+        app.update();
+        let hotkeys = app.world.resource::<Input<TestHotkey>>();
+        [TestHotkey::Focus, TestHotkey::Search, TestHotkey::Snap]
+            .into_iter()
+            .filter(|key| hotkeys.pressed(*key))
+            .collect()
+    }
+
+    #[test]
+    fn test_extra_modifiers_block_hotkey() {
+        assert_eq!(pressed_with(&[KeyCode::F]), vec![TestHotkey::Focus]);
+        assert_eq!(
+            pressed_with(&[KeyCode::ControlLeft, KeyCode::F]),
+            vec![TestHotkey::Search, TestHotkey::Snap]
+        );
+        // Right modifier key is the same modifier
+        assert_eq!(
+            pressed_with(&[KeyCode::ControlRight, KeyCode::F]),
+            vec![TestHotkey::Search, TestHotkey::Snap]
+        );
+        // Modifier only binding is still held with other keys
+        assert_eq!(
+            pressed_with(&[KeyCode::ShiftLeft, KeyCode::ControlLeft, KeyCode::F]),
+            vec![TestHotkey::Snap]
+        );
+    }
+}
@@ -5,13 +5,15 @@ pub mod hotkeys;
 mod load;
 pub mod selected;
 pub mod task_storage;
+pub mod validation;
 
 pub mod prelude {
     pub use super::*;
-    pub use super::{hotkeys::*, load::*, selected::*, task_storage::*};
+    pub use super::{hotkeys::*, load::*, selected::*, task_storage::*, validation::*};
     pub use space_undo;
 }
 
+pub mod gltf_export;
 pub mod gltf_unpack;
 
 use bevy::prelude::*;
@@ -21,12 +23,14 @@ use space_prefab::save::{SaveConfig, SaveState};
 use space_shared::*;
 use space_undo::AppAutoUndo;
 use task_storage::{BackgroundTask, BackgroundTaskStorage, BackgroundTaskStoragePlugin};
+use validation::{PrefabValidatorAppExt, PrefabValidators, SaveValidation};
 
 pub struct EditorCore;
 
 impl Plugin for EditorCore {
     fn build(&self, app: &mut App) {
         app.add_plugins(gltf_unpack::UnpackGltfPlugin);
+        app.add_plugins(gltf_export::ExportGltfPlugin);
 
         #[cfg(feature = "persistence_editor")]
         app.add_plugins(space_persistence::PersistencePlugin);
@@ -36,8 +40,13 @@ impl Plugin for EditorCore {
         app.configure_sets(Update, EditorLoadSet.in_set(EditorSet::Editor));
 
         app.add_event::<EditorEvent>();
+        app.add_event::<space_prefab::prelude::PrefabLoaded>();
+        app.add_event::<space_prefab::prelude::PrefabUnloaded>();
 
         app.init_resource::<PrefabMemoryCache>();
+        app.init_resource::<PrefabValidators>();
+        app.init_resource::<SaveValidation>();
+        app.prefab_validator(validation::multiple_play_cameras);
 
         app.add_systems(
             Update,
@@ -45,7 +54,10 @@ impl Plugin for EditorCore {
                 .chain()
                 .in_set(EditorLoadSet),
         );
-        app.add_systems(Update, editor_event_listener);
+        app.add_systems(
+            Update,
+            (editor_event_listener, validation::validate_save).chain(),
+        );
 
         app.auto_reflected_undo::<Parent>();
         app.auto_reflected_undo::<Children>();
@@ -70,7 +82,9 @@ fn editor_event_listener(
     mut start_game_state: ResMut<NextState<EditorState>>,
     cache: ResMut<PrefabMemoryCache>,
     mut gltf_events: EventWriter<gltf_unpack::EditorUnpackGltf>,
+    mut export_events: EventWriter<gltf_export::EditorExportGltf>,
     mut background_tasks: ResMut<BackgroundTaskStorage>,
+    mut validation: ResMut<SaveValidation>,
 ) {
     for event in events.read() {
         match event {
@@ -87,6 +101,10 @@ fn editor_event_listener(
                     load_server.scene = cache.scene.clone();
                 }
             },
+            // Saves to file wait for validators, play mode save to memory is not validated
+            EditorEvent::Save(EditorPrefabPath::File(path)) => {
+                validation.requested = Some(path.clone());
+            }
             EditorEvent::Save(path) => {
                 save_config.path = Some(path.clone());
                 save_state.set(SaveState::Save);
@@ -97,6 +115,9 @@ fn editor_event_listener(
             EditorEvent::LoadGltfAsPrefab(path) => {
                 gltf_events.send(gltf_unpack::EditorUnpackGltf { path: path.clone() })
             }
+            EditorEvent::ExportGltf(path) => {
+                export_events.send(gltf_export::EditorExportGltf { path: path.clone() })
+            }
         }
     }
 }
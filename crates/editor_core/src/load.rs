@@ -1,19 +1,70 @@
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{prelude::*, scene::DynamicEntity, utils::HashMap};
+use space_prefab::prelude::PrefabLoaded;
 use space_shared::*;
 
 use crate::EditorLoader;
 
+/// Marker for entities, which components failed to deserialize on prefab load.
+/// Contains description of all failures. Must be removed manually after fix
+#[derive(Component, Default, Clone, Debug)]
+pub struct LoadError {
+    pub errors: Vec<String>,
+}
+
+/// Summary of the last prefab load. Inserted as resource after each load
+#[derive(Resource, Default, Clone, Debug)]
+pub struct LoadReport {
+    /// Count of loaded entities
+    pub entities: usize,
+    /// Entities which got [`LoadError`]
+    pub entities_with_errors: Vec<Entity>,
+    /// Count of dropped not registered components
+    pub dropped_components: usize,
+    /// Entities with [`Parent`] or [`Children`] pointing to not existing entity
+    pub broken_references: Vec<Entity>,
+}
+
 pub fn load_listener(world: &mut World) {
     let app_registry = world.resource::<AppTypeRegistry>().clone();
     let load_server = world.resource::<EditorLoader>().clone();
     let mut prefab;
+    let path;
+    let mut load_errors: HashMap<Entity, Vec<String>> = HashMap::new();
     {
         let assets = world.resource::<Assets<DynamicScene>>();
         if let Some(scene) = &load_server.scene {
+            path = world
+                .resource::<AssetServer>()
+                .get_path(scene.id())
+                .map(|path| path.to_string());
             if let Some(scene) = assets.get(scene) {
-                let mut scene = Scene::from_dynamic_scene(scene, &app_registry).unwrap();
-                scene.world.insert_resource(app_registry);
-                prefab = DynamicScene::from_scene(&scene); //kill me, is it clone() analog for DynamicScene
+                let registry = app_registry.read();
+                prefab = DynamicScene {
+                    resources: vec![],
+                    entities: vec![],
+                };
+                for entity in &scene.entities {
+                    let mut components = Vec::with_capacity(entity.components.len());
+                    for component in &entity.components {
+                        let type_info = component.get_represented_type_info();
+                        let type_path = type_info.map_or("unknown type", |info| info.type_path());
+                        let registered = type_info
+                            .and_then(|info| registry.get(info.type_id()))
+                            .is_some_and(|reg| reg.data::<ReflectComponent>().is_some());
+                        if registered {
+                            components.push(component.clone_value());
+                        } else {
+                            load_errors
+                                .entry(entity.entity)
+                                .or_default()
+                                .push(format!("{} is not registered as component", type_path));
+                        }
+                    }
+                    prefab.entities.push(DynamicEntity {
+                        entity: entity.entity,
+                        components,
+                    });
+                }
             } else {
                 return;
             }
@@ -43,4 +94,60 @@ pub fn load_listener(world: &mut World) {
             bevy::log::error!("{}", err)
         }
     }
+
+    let mut report = LoadReport {
+        entities: map.len(),
+        ..default()
+    };
+
+    for (entity, errors) in load_errors {
+        for error in errors.iter() {
+            warn!(
+                "Failed to load component for entity {:?}: {}",
+                entity, error
+            );
+        }
+        report.dropped_components += errors.len();
+        if let Some(mut e) = map.get(&entity).and_then(|e| world.get_entity_mut(*e)) {
+            report.entities_with_errors.push(e.id());
+            e.insert(LoadError { errors });
+        }
+    }
+
+    for entity in map.values() {
+        let Some(e) = world.get_entity(*entity) else {
+            continue;
+        };
+        let broken_parent = e
+            .get::<Parent>()
+            .is_some_and(|parent| world.get_entity(parent.get()).is_none());
+        let broken_children = e.get::<Children>().is_some_and(|children| {
+            children
+                .iter()
+                .any(|child| world.get_entity(*child).is_none())
+        });
+        if broken_parent || broken_children {
+            warn!("Entity {:?} has broken hierarchy references", entity);
+            report.broken_references.push(*entity);
+        }
+    }
+
+    world.insert_resource(report);
+
+    let entities = map.values().copied().collect::<Vec<_>>();
+    let top_level = entities
+        .iter()
+        .copied()
+        .filter(|entity| {
+            world
+                .get::<Parent>(*entity)
+                .map_or(true, |parent| !entities.contains(&parent.get()))
+        })
+        .collect();
+    world.send_event(PrefabLoaded {
+        root: None,
+        entities: top_level,
+        path,
+        entity_map: map,
+    });
 }
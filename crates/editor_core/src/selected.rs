@@ -1,6 +1,8 @@
 use bevy::{
+    ecs::system::Command,
     pbr::wireframe::{Wireframe, WireframePlugin},
     prelude::*,
+    utils::HashSet,
 };
 
 use space_shared::EditorSet;
@@ -9,6 +11,113 @@ use space_shared::EditorSet;
 #[derive(Component, Default, Clone)]
 pub struct Selected;
 
+/// Editor only marker of the entity under the pointer in hierarchy or in game view.
+/// At most one entity is hovered, marker is removed when pointer leaves both
+#[derive(Component, Default, Clone)]
+pub struct Hovered;
+
+/// Editor only marker for entities which can not be selected, deleted or cloned from editor UI
+#[derive(Component, Default, Clone)]
+pub struct Locked;
+
+/// Last selected entity in current selection. Used as target of batch operations over selection
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ActiveEntity(pub Option<Entity>);
+
+/// Cached set of entities with [`Selected`] marker. Updated once per frame by [`SelectedPlugin`],
+/// so tabs do not need to query selection by themselves
+#[derive(Resource, Default, Clone, Debug)]
+pub struct CurrentSelection {
+    entities: HashSet<Entity>,
+}
+
+impl CurrentSelection {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// Sent when [`Selected`] marker was added to or removed from entities
+#[derive(Event, Default, Clone, Debug)]
+pub struct SelectionChanged {
+    pub added: Vec<Entity>,
+    pub removed: Vec<Entity>,
+}
+
+/// How [`SelectEntities`] changes current selection
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SelectMode {
+    /// Deselect all other entities
+    Replace,
+    /// Add entities to selection
+    Add,
+    /// Select not selected entities and deselect selected ones
+    Toggle,
+    /// Remove entities from selection
+    Remove,
+}
+
+/// Command to change selection. Locked entities are skipped
+pub struct SelectEntities {
+    pub entities: Vec<Entity>,
+    pub mode: SelectMode,
+}
+
+impl SelectEntities {
+    pub fn new(entities: impl IntoIterator<Item = Entity>, mode: SelectMode) -> Self {
+        Self {
+            entities: entities.into_iter().collect(),
+            mode,
+        }
+    }
+}
+
+impl Command for SelectEntities {
+    fn apply(self, world: &mut World) {
+        select_entities(world, &self.entities, self.mode);
+    }
+}
+
+/// Change selection of entities. All editor UI selection ways should use this function or [`SelectEntities`]
+pub fn select_entities(world: &mut World, entities: &[Entity], mode: SelectMode) {
+    if mode == SelectMode::Replace {
+        let mut query = world.query_filtered::<Entity, With<Selected>>();
+        for e in query.iter(world).collect::<Vec<_>>() {
+            if !entities.contains(&e) {
+                world.entity_mut(e).remove::<Selected>();
+            }
+        }
+    }
+
+    for e in entities {
+        let Some(mut entity) = world.get_entity_mut(*e) else {
+            continue;
+        };
+        let select = match mode {
+            SelectMode::Replace | SelectMode::Add => true,
+            SelectMode::Toggle => !entity.contains::<Selected>(),
+            SelectMode::Remove => false,
+        };
+        if !select {
+            entity.remove::<Selected>();
+        } else if !entity.contains::<Locked>() && !entity.contains::<Selected>() {
+            entity.insert(Selected);
+        }
+    }
+}
+
 /// Selection system plugins
 pub struct SelectedPlugin;
 
@@ -17,16 +126,45 @@ impl Plugin for SelectedPlugin {
         if !app.is_plugin_added::<WireframePlugin>() {
             app.add_plugins(WireframePlugin);
         }
+        app.init_resource::<ActiveEntity>();
+        app.init_resource::<CurrentSelection>();
+        app.add_event::<SelectionChanged>();
         app.add_systems(
             Update,
-            selected_entity_wireframe_update.in_set(EditorSet::Editor),
+            (
+                deselect_locked,
+                selected_entity_wireframe_update,
+                update_active_entity,
+                update_current_selection,
+            )
+                .chain()
+                .in_set(EditorSet::Editor),
         );
     }
 }
 
+/// Locked entities could be selected by any other selection way, so they are deselected here
+fn deselect_locked(mut cmds: Commands, query: Query<Entity, (With<Locked>, With<Selected>)>) {
+    for e in query.iter() {
+        cmds.entity(e).remove::<Selected>();
+    }
+}
+
+/// Editor only marker of entities, which are rendered as wireframe regardless of selection.
+/// Not reflected, so it is never saved to prefab
+#[derive(Component, Default, Clone, Copy)]
+pub struct WireframeOverride;
+
 fn selected_entity_wireframe_update(
     mut cmds: Commands,
-    del_wireframe: Query<Entity, (With<Wireframe>, Without<Selected>)>,
+    del_wireframe: Query<
+        Entity,
+        (
+            With<Wireframe>,
+            Without<Selected>,
+            Without<WireframeOverride>,
+        ),
+    >,
     need_wireframe: Query<Entity, (Without<Wireframe>, With<Selected>)>,
 ) {
     for e in del_wireframe.iter() {
@@ -37,3 +175,43 @@ fn selected_entity_wireframe_update(
         cmds.entity(e).insert(Wireframe);
     }
 }
+
+fn update_active_entity(
+    mut active: ResMut<ActiveEntity>,
+    added: Query<Entity, Added<Selected>>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let new_active = if let Some(e) = added.iter().last() {
+        Some(e)
+    } else if active.0.is_some_and(|e| selected.contains(e)) {
+        active.0
+    } else {
+        selected.iter().next()
+    };
+
+    if active.0 != new_active {
+        active.0 = new_active;
+    }
+}
+
+fn update_current_selection(
+    mut selection: ResMut<CurrentSelection>,
+    mut events: EventWriter<SelectionChanged>,
+    added: Query<Entity, Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    selected: Query<(), With<Selected>>,
+) {
+    let added = added
+        .iter()
+        .filter(|e| selection.entities.insert(*e))
+        .collect::<Vec<_>>();
+    // Marker can be removed and added again in one frame
+    let removed = removed
+        .read()
+        .filter(|e| !selected.contains(*e) && selection.entities.remove(e))
+        .collect::<Vec<_>>();
+
+    if !added.is_empty() || !removed.is_empty() {
+        events.send(SelectionChanged { added, removed });
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::prelude::*;
+use space_prefab::{
+    component::CameraPlay,
+    save::{SaveConfig, SaveState},
+};
+use space_shared::*;
+
+/// Check of scene, which runs before prefab is saved to file. Returns found problems, empty if scene is fine
+pub type PrefabValidator = fn(&World) -> Vec<ValidationIssue>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    /// Prefab can be saved anyway after confirmation
+    Warning,
+    /// Save is blocked until the issue is fixed
+    Error,
+}
+
+/// Problem found by [`PrefabValidator`]
+#[derive(Clone, Debug)]
+pub struct ValidationIssue {
+    pub severity: ValidationSeverity,
+    pub message: String,
+    /// Offending entities, they can be selected from the report
+    pub entities: Vec<Entity>,
+}
+
+impl ValidationIssue {
+    pub fn warning(message: impl Into<String>, entities: Vec<Entity>) -> Self {
+        Self {
+            severity: ValidationSeverity::Warning,
+            message: message.into(),
+            entities,
+        }
+    }
+
+    pub fn error(message: impl Into<String>, entities: Vec<Entity>) -> Self {
+        Self {
+            severity: ValidationSeverity::Error,
+            message: message.into(),
+            entities,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == ValidationSeverity::Error
+    }
+}
+
+/// Validators registered by [`PrefabValidatorAppExt::prefab_validator`]
+#[derive(Resource, Default)]
+pub struct PrefabValidators {
+    validators: Vec<PrefabValidator>,
+}
+
+impl PrefabValidators {
+    pub fn add(&mut self, validator: PrefabValidator) {
+        if !self.validators.contains(&validator) {
+            self.validators.push(validator);
+        }
+    }
+
+    /// Run all validators in registration order
+    pub fn validate(&self, world: &World) -> Vec<ValidationIssue> {
+        self.validators
+            .iter()
+            .flat_map(|validator| validator(world))
+            .collect()
+    }
+
+    /// Run all validators and keep issues of `root` with its descendants.
+    /// Issues without entities concern the whole scene and are kept too
+    pub fn validate_subtree(&self, world: &World, root: Entity) -> Vec<ValidationIssue> {
+        let mut subtree = vec![];
+        let mut queue = vec![root];
+        while let Some(entity) = queue.pop() {
+            subtree.push(entity);
+            if let Some(children) = world.get::<Children>(entity) {
+                queue.extend(children.iter());
+            }
+        }
+        self.validate(world)
+            .into_iter()
+            .filter(|issue| {
+                issue.entities.is_empty() || issue.entities.iter().any(|e| subtree.contains(e))
+            })
+            .collect()
+    }
+}
+
+pub trait PrefabValidatorAppExt {
+    /// Run `validator` before each save of prefab to file
+    fn prefab_validator(&mut self, validator: PrefabValidator) -> &mut Self;
+}
+
+impl PrefabValidatorAppExt for App {
+    fn prefab_validator(&mut self, validator: PrefabValidator) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(PrefabValidators::default)
+            .add(validator);
+        self
+    }
+}
+
+/// Save to file which is waiting for validation or for user decision
+#[derive(Resource, Default)]
+pub struct SaveValidation {
+    /// Path from [`EditorEvent::Save`], validated by [`validate_save`] in the same frame
+    pub requested: Option<String>,
+    /// Save blocked by found issues
+    pub report: Option<SaveValidationReport>,
+    /// Warnings wait for confirmation in report window. Set by editor UI, which shows the report.
+    /// Otherwise warnings are only logged and save continues
+    pub confirm_warnings: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct SaveValidationReport {
+    pub path: String,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl SaveValidationReport {
+    /// Report with errors can not be saved anyway
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(ValidationIssue::is_error)
+    }
+}
+
+/// Run validators for requested save. Scene without issues or only with not confirmed warnings is saved immediately,
+/// otherwise [`SaveValidation::report`] is set and save waits for [`start_save`]
+pub fn validate_save(world: &mut World) {
+    let Some(path) = world.resource_mut::<SaveValidation>().requested.take() else {
+        return;
+    };
+    let issues = world.resource::<PrefabValidators>().validate(world);
+    if issues.is_empty() {
+        start_save(world, EditorPrefabPath::File(path));
+        return;
+    }
+
+    for issue in issues.iter() {
+        match issue.severity {
+            ValidationSeverity::Warning => warn!("Save validation: {}", issue.message),
+            ValidationSeverity::Error => error!("Save validation: {}", issue.message),
+        }
+    }
+    let confirm = world.resource::<SaveValidation>().confirm_warnings;
+    if !confirm && !issues.iter().any(ValidationIssue::is_error) {
+        start_save(world, EditorPrefabPath::File(path));
+        return;
+    }
+    world.resource_mut::<SaveValidation>().report = Some(SaveValidationReport { path, issues });
+}
+
+/// Save prefab without validation
+pub fn start_save(world: &mut World, path: EditorPrefabPath) {
+    world.resource_mut::<SaveConfig>().path = Some(path);
+    world
+        .resource_mut::<NextState<SaveState>>()
+        .set(SaveState::Save);
+}
+
+/// Validator for prefab entities without [`Name`]. Not registered by default
+pub fn unnamed_entities(world: &World) -> Vec<ValidationIssue> {
+    let entities = world
+        .iter_entities()
+        .filter(|e| e.contains::<PrefabMarker>() && !e.contains::<Name>())
+        .map(|e| e.id())
+        .collect::<Vec<_>>();
+    if entities.is_empty() {
+        vec![]
+    } else {
+        vec![ValidationIssue::warning(
+            format!("{} entities without Name", entities.len()),
+            entities,
+        )]
+    }
+}
+
+/// Validator for several [`CameraPlay`] cameras, only one of them is used in play mode
+pub fn multiple_play_cameras(world: &World) -> Vec<ValidationIssue> {
+    let entities = world
+        .iter_entities()
+        .filter(|e| e.contains::<PrefabMarker>() && e.contains::<CameraPlay>())
+        .map(|e| e.id())
+        .collect::<Vec<_>>();
+    if entities.len() > 1 {
+        vec![ValidationIssue::warning(
+            format!("{} cameras with CameraPlay", entities.len()),
+            entities,
+        )]
+    } else {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_validator(_: &World) -> Vec<ValidationIssue> {
+        vec![ValidationIssue::error("broken", vec![])]
+    }
+
+    fn warning_validator(_: &World) -> Vec<ValidationIssue> {
+        vec![ValidationIssue::warning("suspicious", vec![])]
+    }
+
+    fn request_save(validators: &[PrefabValidator], confirm_warnings: bool) -> World {
+        let mut world = World::new();
+        world.init_resource::<SaveConfig>();
+        world.init_resource::<NextState<SaveState>>();
+        let mut registered = PrefabValidators::default();
+        for validator in validators {
+            registered.add(*validator);
+        }
+        world.insert_resource(registered);
+        world.insert_resource(SaveValidation {
+            requested: Some("level.scn.ron".to_string()),
+            report: None,
+            confirm_warnings,
+        });
+        validate_save(&mut world);
+        world
+    }
+
+    fn save_started(world: &World) -> bool {
+        world.resource::<NextState<SaveState>>().0 == Some(SaveState::Save)
+            && world.resource::<SaveConfig>().path.is_some()
+    }
+
+    #[test]
+    fn test_save_without_issues_starts_immediately() {
+        let world = request_save(&[], true);
+        assert!(save_started(&world));
+        assert!(world.resource::<SaveValidation>().report.is_none());
+    }
+
+    #[test]
+    fn test_save_with_errors_is_blocked() {
+        let world = request_save(&[warning_validator, error_validator], false);
+        assert!(!save_started(&world));
+        let report = world.resource::<SaveValidation>().report.clone().unwrap();
+        assert_eq!(report.path, "level.scn.ron");
+        assert_eq!(report.issues.len(), 2);
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_save_with_warnings_waits_for_confirmation() {
+        let mut world = request_save(&[warning_validator], true);
+        assert!(!save_started(&world));
+        let report = world.resource::<SaveValidation>().report.clone().unwrap();
+        assert!(!report.has_errors());
+
+        // "Save anyway" in the report window
+        start_save(&mut world, EditorPrefabPath::File(report.path));
+        assert!(save_started(&world));
+    }
+
+    #[test]
+    fn test_save_with_warnings_without_ui_is_not_blocked() {
+        let world = request_save(&[warning_validator], false);
+        assert!(save_started(&world));
+        assert!(world.resource::<SaveValidation>().report.is_none());
+    }
+
+    #[test]
+    fn test_subtree_validation_skips_other_entities() {
+        let mut world = World::new();
+        let root = world.spawn(PrefabMarker).id();
+        let child = world.spawn(PrefabMarker).set_parent(root).id();
+        let other = world.spawn(PrefabMarker).id();
+        world
+            .spawn((PrefabMarker, Name::new("named")))
+            .set_parent(root);
+
+        let mut validators = PrefabValidators::default();
+        validators.add(unnamed_entities);
+        assert_eq!(validators.validate(&world)[0].entities.len(), 3);
+        assert_eq!(validators.validate_subtree(&world, child).len(), 1);
+
+        world.entity_mut(root).insert(Name::new("root"));
+        world.entity_mut(child).insert(Name::new("child"));
+        assert!(validators.validate_subtree(&world, root).is_empty());
+        assert_eq!(validators.validate(&world)[0].entities, vec![other]);
+    }
+}
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    tasks::{block_on, IoTaskPool, Task},
+};
+use bevy_egui::*;
+use space_editor_core::prelude::*;
+use space_prefab::{load::spawn_serialized, save::serialize_entities};
+use space_shared::*;
+use space_undo::ChangeChain;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+/// Recovery file with the last autosaved scene. It is removed on normal editor exit,
+/// so existing file on startup means that previous session was not closed properly
+pub const RECOVERY_FILE: &str = "editor.autosave.scn.ron";
+
+/// Plugin to periodically store scene to [`RECOVERY_FILE`] and offer to restore it after crash
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutosaveSettings>()
+            .register_type::<AutosaveSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<AutosaveSettings>();
+        }
+        app.init_resource::<AutosaveState>();
+        app.add_systems(Startup, check_recovery_file);
+        app.add_systems(
+            Update,
+            (
+                tick_autosave,
+                autosave.run_if(|state: Res<AutosaveState>| state.due),
+                recovery_window,
+            )
+                .chain()
+                .in_set(EditorSet::Editor),
+        );
+        app.add_systems(Last, remove_recovery_on_exit);
+    }
+}
+
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    /// Seconds between autosaves. Scene is stored only if it was changed since the last autosave
+    pub interval: f32,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 60.0,
+        }
+    }
+}
+
+impl AutosaveSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Autosave");
+        ui.checkbox(&mut self.enabled, "Autosave scene for crash recovery");
+        ui.add_enabled(
+            self.enabled,
+            egui::DragValue::new(&mut self.interval)
+                .clamp_range(5.0..=3600.0)
+                .suffix(" s")
+                .prefix("Interval: "),
+        );
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct AutosaveState {
+    /// Seconds since the last autosave
+    pub elapsed: f32,
+    /// Scene was changed since the last autosave
+    pub dirty: bool,
+    /// Autosave will run in this frame
+    pub due: bool,
+    /// Undo stack length, redo stack length and address of the last change at the last check
+    chain_signature: (usize, usize, usize),
+    /// Recovery file found on startup, restore is not answered yet.
+    /// Autosave is paused while it is set, so recovery is not overwritten
+    pub recovery: Option<String>,
+    /// Recovery file write started by the last autosave
+    write_task: Option<Task<()>>,
+}
+
+impl AutosaveState {
+    /// Recovery file is being written in [`IoTaskPool`]
+    pub fn is_writing(&self) -> bool {
+        self.write_task
+            .as_ref()
+            .map_or(false, |task| !task.is_finished())
+    }
+}
+
+fn check_recovery_file(mut state: ResMut<AutosaveState>) {
+    if std::path::Path::new(RECOVERY_FILE).exists() {
+        state.recovery = Some(RECOVERY_FILE.to_string());
+    }
+}
+
+fn tick_autosave(
+    time: Res<Time>,
+    settings: Res<AutosaveSettings>,
+    mut state: ResMut<AutosaveState>,
+    change_chain: Option<Res<ChangeChain>>,
+) {
+    // Change chain is touched every frame, so changes are detected by undo and redo stacks
+    if let Some(chain) = change_chain {
+        let last = chain
+            .changes
+            .last()
+            .map_or(0, |change| Arc::as_ptr(change) as *const () as usize);
+        let signature = (chain.changes.len(), chain.changes_for_redo.len(), last);
+        if signature != state.chain_signature {
+            state.chain_signature = signature;
+            state.dirty = true;
+        }
+    }
+    if !settings.enabled || state.recovery.is_some() {
+        return;
+    }
+    state.elapsed += time.delta_seconds();
+    // Serialization runs at most once per interval and only for changed scene.
+    // Slow previous write postpones the next one, so writes never overlap
+    if state.elapsed >= settings.interval && state.dirty && !state.is_writing() {
+        state.due = true;
+    }
+}
+
+/// Serialize top level prefab entities with their descendants. File is written in [`IoTaskPool`],
+/// through temporary file, so recovery file is never left half written
+fn autosave(world: &mut World) {
+    {
+        let mut state = world.resource_mut::<AutosaveState>();
+        state.due = false;
+        state.dirty = false;
+        state.elapsed = 0.0;
+    }
+
+    let roots = world
+        .query_filtered::<(Entity, Option<&Parent>), With<PrefabMarker>>()
+        .iter(world)
+        .filter(|(_, parent)| {
+            parent.map_or(true, |parent| {
+                world.get::<PrefabMarker>(parent.get()).is_none()
+            })
+        })
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    let data = match serialize_entities(world, &roots) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("Failed to autosave scene: {}", err);
+            return;
+        }
+    };
+
+    let task = IoTaskPool::get().spawn(async move {
+        let tmp = format!("{}.tmp", RECOVERY_FILE);
+        let res = std::fs::write(&tmp, data).and_then(|_| std::fs::rename(&tmp, RECOVERY_FILE));
+        match res {
+            Ok(_) => debug!("Autosaved scene to {}", RECOVERY_FILE),
+            Err(err) => error!("Failed to write {}: {}", RECOVERY_FILE, err),
+        }
+    });
+    world.resource_mut::<AutosaveState>().write_task = Some(task);
+}
+
+/// Spawn entities from recovery file. Restored entities are added to the current scene
+pub fn restore_recovery(world: &mut World, path: &str) {
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("Failed to read recovery file {}: {}", path, err);
+            return;
+        }
+    };
+    match spawn_serialized(world, &data) {
+        Ok(map) => info!("Restored {} entities from {}", map.len(), path),
+        Err(err) => error!("Failed to restore recovery file {}: {}", path, err),
+    }
+}
+
+fn recovery_window(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    mut state: ResMut<AutosaveState>,
+) {
+    let Some(path) = state.recovery.clone() else {
+        return;
+    };
+    let mut close = false;
+    egui::Window::new("Restore autosaved scene")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label("Previous editor session was not closed properly.");
+            ui.label("Restore the last autosaved scene?");
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    let path = path.clone();
+                    commands.add(move |world: &mut World| restore_recovery(world, &path));
+                    close = true;
+                }
+                if ui.button("Discard").clicked() {
+                    if let Err(err) = std::fs::remove_file(&path) {
+                        warn!("Failed to remove {}: {}", path, err);
+                    }
+                    close = true;
+                }
+            });
+        });
+    if close {
+        state.recovery = None;
+    }
+}
+
+/// Wait for unfinished autosave write, otherwise it can recreate recovery file after exit
+fn remove_recovery_on_exit(mut exit: EventReader<AppExit>, mut state: ResMut<AutosaveState>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+    if let Some(task) = state.write_task.take() {
+        block_on(task);
+    }
+    if std::path::Path::new(RECOVERY_FILE).exists() {
+        if let Err(err) = std::fs::remove_file(RECOVERY_FILE) {
+            warn!("Failed to remove {}: {}", RECOVERY_FILE, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use space_prefab::editor_registry::{EditorRegistryExt, EditorRegistryPlugin};
+    use space_undo::AddedEntity;
+
+    use super::*;
+
+    fn autosave_world(interval: f32) -> World {
+        let mut world = World::new();
+        world.insert_resource(AutosaveSettings {
+            enabled: true,
+            interval,
+        });
+        world.init_resource::<AutosaveState>();
+        world.init_resource::<ChangeChain>();
+        world.init_resource::<Time>();
+        world
+    }
+
+    fn tick(world: &mut World, seconds: f32) {
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        world.run_system_once(tick_autosave);
+    }
+
+    #[test]
+    fn test_scene_is_dirty_after_undo_chain_change() {
+        let mut world = autosave_world(60.0);
+        tick(&mut world, 0.1);
+        assert!(!world.resource::<AutosaveState>().dirty);
+
+        let entity = world.spawn_empty().id();
+        world
+            .resource_mut::<ChangeChain>()
+            .changes
+            .push(Arc::new(AddedEntity { entity }));
+        tick(&mut world, 0.1);
+        assert!(world.resource::<AutosaveState>().dirty);
+
+        // Unchanged chain keeps autosaved scene clean
+        world.resource_mut::<AutosaveState>().dirty = false;
+        tick(&mut world, 0.1);
+        assert!(!world.resource::<AutosaveState>().dirty);
+
+        // Undo moves change to redo stack, it is a change of scene too
+        let mut chain = world.resource_mut::<ChangeChain>();
+        let change = chain.changes.pop().unwrap();
+        chain.changes_for_redo.push(change);
+        tick(&mut world, 0.1);
+        assert!(world.resource::<AutosaveState>().dirty);
+    }
+
+    #[test]
+    fn test_autosave_waits_for_interval() {
+        let mut world = autosave_world(10.0);
+        world.resource_mut::<AutosaveState>().dirty = true;
+        tick(&mut world, 4.0);
+        assert!(!world.resource::<AutosaveState>().due);
+        tick(&mut world, 4.0);
+        assert!(!world.resource::<AutosaveState>().due);
+        tick(&mut world, 4.0);
+        assert!(world.resource::<AutosaveState>().due);
+
+        // Not changed scene is not stored again
+        let mut world = autosave_world(10.0);
+        tick(&mut world, 20.0);
+        assert!(!world.resource::<AutosaveState>().due);
+
+        // Recovery file is not overwritten before restore is answered
+        let mut world = autosave_world(10.0);
+        let mut state = world.resource_mut::<AutosaveState>();
+        state.dirty = true;
+        state.recovery = Some(RECOVERY_FILE.to_string());
+        tick(&mut world, 20.0);
+        assert!(!world.resource::<AutosaveState>().due);
+    }
+
+    #[test]
+    fn test_restore_recovery_spawns_saved_entities() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(EditorRegistryPlugin)
+            .editor_registry::<Name>();
+        let entity = app.world.spawn((PrefabMarker, Name::new("recovered"))).id();
+        let data = serialize_entities(&app.world, &[entity]).unwrap();
+        app.world.despawn(entity);
+
+        let path = std::env::temp_dir().join("space_editor_restore_recovery_test.scn.ron");
+        std::fs::write(&path, data).unwrap();
+        restore_recovery(&mut app.world, path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+
+        let restored = app
+            .world
+            .query_filtered::<&Name, With<PrefabMarker>>()
+            .iter(&app.world)
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(restored, vec!["recovered".to_string()]);
+    }
+}
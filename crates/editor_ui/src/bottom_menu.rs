@@ -1,8 +1,17 @@
 use bevy::prelude::*;
 use bevy_egui::*;
 use space_editor_core::prelude::*;
-use space_prefab::plugins::PrefabPlugin;
+use space_prefab::{plugins::PrefabPlugin, prelude::PrefabLoaded};
 use space_shared::{ext::egui_file, *};
+use space_undo::despawn_recursive_with_undo;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+use crate::{
+    dock_layout::ResetDockLayout,
+    game_view::{fps_label, smooth_frame_time, DEFAULT_FPS_SMOOTHING},
+};
 
 /// Plugin to activate bottom menu in editor UI
 pub struct BottomMenuPlugin;
@@ -14,8 +23,16 @@ impl Plugin for BottomMenuPlugin {
         }
         app.init_resource::<EditorLoader>();
         app.init_resource::<BottomMenuState>();
+        app.init_resource::<RecentPrefabs>();
+        app.register_type::<RecentPrefabs>()
+            .register_type::<Vec<String>>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<RecentPrefabs>();
+        }
 
         app.add_systems(Update, menu.before(EditorLoadSet).in_set(EditorSet::Editor));
+        app.add_systems(Update, remember_recent_prefab);
         app.add_systems(Update, in_game_menu.in_set(EditorSet::Game));
         app.add_event::<MenuLoadEvent>();
     }
@@ -38,8 +55,9 @@ fn in_game_menu(
                 state.set(EditorState::Editor);
             }
 
-            *smoothed_dt = (*smoothed_dt).mul_add(0.98, time.delta_seconds() * 0.02);
-            ui.label(format!("FPS: {:.0}", 1.0 / *smoothed_dt));
+            *smoothed_dt =
+                smooth_frame_time(*smoothed_dt, time.delta_seconds(), DEFAULT_FPS_SMOOTHING);
+            ui.label(fps_label(*smoothed_dt));
         });
     });
 }
@@ -48,16 +66,82 @@ fn in_game_menu(
 pub struct BottomMenuState {
     pub file_dialog: Option<egui_file::FileDialog>,
     pub gltf_dialog: Option<egui_file::FileDialog>,
+    pub export_dialog: Option<egui_file::FileDialog>,
     pub path: String,
+    /// "Reload current scene" confirmation window is shown
+    pub confirm_reload: bool,
+    /// Asset path of the scene loaded in editor. "Reload" loads it, not the edited save path
+    pub loaded_path: Option<String>,
+}
+
+/// Max count of remembered prefab files in "Recent" menu
+pub const MAX_RECENT_PREFABS: usize = 8;
+
+/// Prefab files loaded in editor, the last loaded one is first
+#[derive(Resource, Reflect, Default, Clone)]
+#[reflect(Resource)]
+pub struct RecentPrefabs {
+    pub paths: Vec<String>,
+}
+
+impl RecentPrefabs {
+    /// Move `path` to the top of the list
+    pub fn push(&mut self, path: &str) {
+        self.paths.retain(|recent| recent != path);
+        self.paths.insert(0, path.to_string());
+        self.paths.truncate(MAX_RECENT_PREFABS);
+    }
+}
+
+fn remember_recent_prefab(
+    mut events: EventReader<PrefabLoaded>,
+    mut recent: ResMut<RecentPrefabs>,
+    mut menu_state: ResMut<BottomMenuState>,
+) {
+    for event in events.read() {
+        // Prefabs spawned by PrefabBundle are not opened scenes
+        if event.root.is_some() {
+            continue;
+        }
+        if let Some(path) = &event.path {
+            recent.push(path);
+            menu_state.loaded_path = Some(path.clone());
+        }
+    }
+}
+
+/// Despawn all prefab entities as one undo step and load scene at asset `path` again from disk.
+/// Scene is kept if the file does not exist
+pub fn reload_scene(world: &mut World, path: String) -> Result<(), String> {
+    if !std::path::Path::new("assets").join(&path).is_file() {
+        return Err(format!("Scene file {} not found", path));
+    }
+    let roots = world
+        .query_filtered::<(Entity, Option<&Parent>), With<PrefabMarker>>()
+        .iter(world)
+        .filter(|(_, parent)| {
+            parent.map_or(true, |parent| {
+                world.get::<PrefabMarker>(parent.get()).is_none()
+            })
+        })
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    despawn_recursive_with_undo(world, &roots, "Reload scene");
+    world.send_event(EditorEvent::Load(EditorPrefabPath::File(path)));
+    Ok(())
 }
 
 pub fn menu(
+    mut commands: Commands,
     mut ctxs: EguiContexts,
     _state: ResMut<NextState<EditorState>>,
     mut events: EventReader<MenuLoadEvent>,
     mut menu_state: ResMut<BottomMenuState>,
     mut editor_events: EventWriter<EditorEvent>,
     background_tasks: Res<BackgroundTaskStorage>,
+    mut reset_layout: EventWriter<ResetDockLayout>,
+    mut export_state: ResMut<gltf_export::GltfExportState>,
+    recent: Res<RecentPrefabs>,
 ) {
     let ctx = ctxs.ctx_mut();
     egui::TopBottomPanel::bottom("bot menu").show(ctx, |ui| {
@@ -132,6 +216,28 @@ pub fn menu(
                 }
             }
 
+            if let Some(export_dialog) = &mut menu_state.export_dialog {
+                if export_dialog.show(ctx).selected() {
+                    if let Some(file) = export_dialog.path() {
+                        // Export event stores path as string, so path must be valid UTF-8
+                        match file.to_str() {
+                            Some(path) => {
+                                let mut path = path.to_string();
+                                if !path.ends_with(".glb") {
+                                    path.push_str(".glb");
+                                }
+                                editor_events.send(EditorEvent::ExportGltf(path));
+                            }
+                            None => {
+                                let err = format!("Export path {:?} is not valid UTF-8", file);
+                                error!("{}", err);
+                                export_state.last_export = Some(Err(err));
+                            }
+                        }
+                    }
+                }
+            }
+
             if ui.button("Save").clicked() {
                 editor_events.send(EditorEvent::Save(EditorPrefabPath::File(format!(
                     "{}.scn.ron",
@@ -149,6 +255,30 @@ pub fn menu(
                 // );
             }
 
+            ui.add_enabled_ui(!recent.paths.is_empty(), |ui| {
+                ui.menu_button("Recent", |ui| {
+                    for path in recent.paths.iter() {
+                        if ui.button(path).clicked() {
+                            menu_state.path = path.trim_end_matches(".scn.ron").to_string();
+                            editor_events
+                                .send(EditorEvent::Load(EditorPrefabPath::File(path.clone())));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+
+            if ui
+                .add_enabled(
+                    menu_state.loaded_path.is_some(),
+                    egui::Button::new("⟲ Reload"),
+                )
+                .on_hover_text("Reload current scene from disk")
+                .clicked()
+            {
+                menu_state.confirm_reload = true;
+            }
+
             if ui.button("Open gltf as prefab").clicked() {
                 let mut gltf_dialog = egui_file::FileDialog::open_file(Some("assets/".into()))
                     .show_files_filter(Box::new(|path| {
@@ -163,10 +293,22 @@ pub fn menu(
                 // ));
             }
 
+            if ui.button("Export glb").clicked() {
+                let mut export_dialog = egui_file::FileDialog::save_file(Some("assets/".into()))
+                    .default_filename("scene.glb")
+                    .title("Export scene (*.glb)");
+                export_dialog.open();
+                menu_state.export_dialog = Some(export_dialog);
+            }
+
             if ui.button("▶").clicked() {
                 editor_events.send(EditorEvent::StartGame);
             }
 
+            if ui.button("Reset layout").clicked() {
+                reset_layout.send_default();
+            }
+
             ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
                 if !background_tasks.tasks.is_empty() {
                     //Spinning circle
@@ -179,10 +321,58 @@ pub fn menu(
                         BackgroundTask::None => {}
                     }
                 }
+
+                match &export_state.last_export {
+                    Some(Ok(path)) => {
+                        ui.label(format!("Exported to {}", path));
+                    }
+                    Some(Err(err)) => {
+                        ui.colored_label(
+                            egui::Color32::LIGHT_RED,
+                            format!("Export failed: {}", err),
+                        );
+                    }
+                    None => {}
+                }
             });
         });
     });
 
+    if let Some(path) = menu_state
+        .loaded_path
+        .clone()
+        .filter(|_| menu_state.confirm_reload)
+    {
+        let mut close = false;
+        egui::Window::new("Reload current scene")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Reload {} from disk? Unsaved changes will be lost",
+                    path
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Reload").clicked() {
+                        let path = path.clone();
+                        commands.add(move |world: &mut World| {
+                            if let Err(err) = reload_scene(world, path) {
+                                error!("Failed to reload scene: {}", err);
+                            }
+                        });
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        if close {
+            menu_state.confirm_reload = false;
+        }
+    }
+
     for event in events.read() {
         menu_state.path = event.path.clone();
         editor_events.send(EditorEvent::Load(EditorPrefabPath::File(format!(
@@ -192,3 +382,17 @@ pub fn menu(
     }
     events.clear();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_of_missing_scene_keeps_entities() {
+        let mut world = World::new();
+        let entity = world.spawn(PrefabMarker).id();
+
+        assert!(reload_scene(&mut world, "missing_scene.scn.ron".to_string()).is_err());
+        assert!(world.get_entity(entity).is_some());
+    }
+}
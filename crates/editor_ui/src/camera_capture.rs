@@ -0,0 +1,341 @@
+use std::sync::mpsc;
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssets,
+        render_graph::{self, NodeRunError, RenderGraph, RenderGraphContext},
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageDataLayout,
+            Maintain, MapMode, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::RenderLayers,
+        Extract, ExtractSchedule, RenderApp,
+    },
+};
+use space_shared::*;
+
+use crate::{
+    camera_view::{CameraViews, ViewCamera},
+    DisableCameraSkip,
+};
+
+/// Frames during which capture camera renders and copies its image to the buffer
+const CAPTURE_COPY_FRAMES: u32 = 3;
+/// Frame when buffer is read back. Gives render world time to finish the last copy
+const CAPTURE_READ_FRAME: u32 = CAPTURE_COPY_FRAMES + 2;
+
+const CAMERA_CAPTURE_NODE: &str = "editor_camera_capture";
+
+/// Folder where captured camera images are stored
+pub const CAPTURE_FOLDER: &str = "screenshots";
+
+/// Plugin to capture image of game camera to png file with custom resolution
+pub struct CameraCapturePlugin;
+
+impl Plugin for CameraCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CaptureCameraEvent>();
+        app.add_systems(
+            Update,
+            (start_camera_capture, finish_camera_capture)
+                .chain()
+                .in_set(EditorSet::Editor),
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<ExtractedCameraCaptures>();
+        render_app.add_systems(ExtractSchedule, extract_camera_captures);
+
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        graph.add_node(CAMERA_CAPTURE_NODE, CameraCaptureNode);
+        graph.add_node_edge(
+            bevy::render::main_graph::node::CAMERA_DRIVER,
+            CAMERA_CAPTURE_NODE,
+        );
+    }
+}
+
+/// Request to render `camera` to offscreen image of `size` and save it to [`CAPTURE_FOLDER`]
+#[derive(Event)]
+pub struct CaptureCameraEvent {
+    pub camera: Entity,
+    pub size: UVec2,
+    /// Index of camera view tab in [`CameraViews`], which will show the result
+    pub view: usize,
+}
+
+/// Temporary camera, which renders capture image
+#[derive(Component)]
+pub struct CameraCapture {
+    pub path: String,
+    pub size: UVec2,
+    pub image: Handle<Image>,
+    pub buffer: Buffer,
+    pub view: usize,
+    frames: u32,
+}
+
+#[derive(Resource, Default)]
+struct ExtractedCameraCaptures(Vec<(Handle<Image>, Buffer, UVec2)>);
+
+fn create_capture_image(size: UVec2) -> Image {
+    let size = Extent3d {
+        width: size.x,
+        height: size.y,
+        ..default()
+    };
+
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("camera_capture"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    image
+}
+
+fn padded_bytes_per_row(width: u32) -> usize {
+    RenderDevice::align_copy_bytes_per_row(width as usize * 4)
+}
+
+fn start_camera_capture(
+    mut commands: Commands,
+    mut events: EventReader<CaptureCameraEvent>,
+    cameras: Query<(
+        &Camera,
+        &GlobalTransform,
+        Option<&Camera3d>,
+        Option<&Projection>,
+        Option<&OrthographicProjection>,
+        Option<&Name>,
+    )>,
+    mut images: ResMut<Assets<Image>>,
+    render_device: Option<Res<RenderDevice>>,
+    mut views: ResMut<CameraViews>,
+) {
+    let Some(render_device) = render_device else {
+        events.clear();
+        return;
+    };
+
+    for event in events.read() {
+        let Ok((camera, transform, camera_3d, projection, orthographic, name)) =
+            cameras.get(event.camera)
+        else {
+            continue;
+        };
+        let size = event.size.max(UVec2::ONE);
+        let image = images.add(create_capture_image(size));
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("camera_capture_buffer"),
+            size: (padded_bytes_per_row(size.x) * size.y as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_millis())
+            .unwrap_or_default();
+        let file_name = name.map_or("camera".to_string(), |name| {
+            name.as_str().replace(|c: char| !c.is_alphanumeric(), "_")
+        });
+        let path = format!("{}/{}_{}.png", CAPTURE_FOLDER, file_name, timestamp);
+
+        // Watched camera is copied without viewport, so projection keeps its own aspect
+        let capture_camera = Camera {
+            viewport: None,
+            is_active: true,
+            target: RenderTarget::Image(image.clone()),
+            ..camera.clone()
+        };
+        let capture = CameraCapture {
+            path,
+            size,
+            image,
+            buffer,
+            view: event.view,
+            frames: 0,
+        };
+        let components = (
+            capture,
+            RenderLayers::layer(0),
+            Name::new("Camera capture"),
+            DisableCameraSkip,
+            ViewCamera,
+        );
+
+        if let Some(camera_3d) = camera_3d {
+            commands.spawn((
+                Camera3dBundle {
+                    camera: capture_camera,
+                    camera_3d: camera_3d.clone(),
+                    projection: projection.cloned().unwrap_or_default(),
+                    transform: transform.compute_transform(),
+                    ..default()
+                },
+                components,
+            ));
+        } else {
+            let mut bundle = Camera2dBundle {
+                camera: capture_camera,
+                transform: transform.compute_transform(),
+                ..default()
+            };
+            if let Some(orthographic) = orthographic {
+                bundle.projection = orthographic.clone();
+            }
+            commands.spawn((bundle, components));
+        }
+
+        if let Some(view) = views.views.get_mut(event.view) {
+            view.last_capture = None;
+        }
+    }
+}
+
+fn finish_camera_capture(
+    mut commands: Commands,
+    mut captures: Query<(Entity, &mut CameraCapture)>,
+    render_device: Option<Res<RenderDevice>>,
+    mut views: ResMut<CameraViews>,
+) {
+    let Some(render_device) = render_device else {
+        return;
+    };
+
+    for (entity, mut capture) in captures.iter_mut() {
+        capture.frames += 1;
+        if capture.frames < CAPTURE_READ_FRAME {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+
+        let result = read_capture(&render_device, &capture).map(|_| capture.path.clone());
+        match &result {
+            Ok(path) => info!("Camera capture saved to {}", path),
+            Err(err) => error!("Failed to capture camera: {}", err),
+        }
+        if let Some(view) = views.views.get_mut(capture.view) {
+            view.last_capture = Some(result);
+        }
+    }
+}
+
+fn read_capture(render_device: &RenderDevice, capture: &CameraCapture) -> Result<(), String> {
+    let slice = capture.buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    render_device.map_buffer(&slice, MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    render_device.poll(Maintain::Wait);
+    receiver
+        .recv()
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+
+    // Rows of the buffer are aligned, so padding must be removed
+    let padded_row = padded_bytes_per_row(capture.size.x);
+    let row = capture.size.x as usize * 4;
+    let mut data = Vec::with_capacity(row * capture.size.y as usize);
+    {
+        let mapped = slice.get_mapped_range();
+        for chunk in mapped.chunks(padded_row) {
+            data.extend_from_slice(&chunk[..row]);
+        }
+    }
+    capture.buffer.unmap();
+
+    let image = Image::new(
+        Extent3d {
+            width: capture.size.x,
+            height: capture.size.y,
+            ..default()
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    let image = image.try_into_dynamic().map_err(|err| err.to_string())?;
+
+    std::fs::create_dir_all(CAPTURE_FOLDER).map_err(|err| err.to_string())?;
+    image
+        .to_rgba8()
+        .save(&capture.path)
+        .map_err(|err| err.to_string())
+}
+
+fn extract_camera_captures(
+    mut extracted: ResMut<ExtractedCameraCaptures>,
+    captures: Extract<Query<&CameraCapture>>,
+) {
+    // Buffer must not be used by render world while it is mapped for reading
+    extracted.0 = captures
+        .iter()
+        .filter(|capture| capture.frames < CAPTURE_COPY_FRAMES)
+        .map(|capture| {
+            (
+                capture.image.clone_weak(),
+                capture.buffer.clone(),
+                capture.size,
+            )
+        })
+        .collect();
+}
+
+/// Render graph node to copy capture images to readable buffers
+struct CameraCaptureNode;
+
+impl render_graph::Node for CameraCaptureNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let Some(captures) = world.get_resource::<ExtractedCameraCaptures>() else {
+            return Ok(());
+        };
+        let gpu_images = world.resource::<RenderAssets<Image>>();
+
+        for (image, buffer, size) in captures.0.iter() {
+            let Some(gpu_image) = gpu_images.get(image) else {
+                continue;
+            };
+            render_context.command_encoder().copy_texture_to_buffer(
+                gpu_image.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_bytes_per_row(size.x) as u32),
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    ..default()
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,25 @@
 use crate::*;
-use bevy::prelude::*;
+use bevy::{prelude::*, render::primitives::Aabb};
+use bevy_egui::EguiContexts;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+/// Keys to fly editor camera while fly button is held: forward, back, left, right, down, up
+const FLY_KEYS: [(KeyCode, Vec3); 6] = [
+    (KeyCode::W, Vec3::NEG_Z),
+    (KeyCode::S, Vec3::Z),
+    (KeyCode::A, Vec3::NEG_X),
+    (KeyCode::D, Vec3::X),
+    (KeyCode::Q, Vec3::NEG_Y),
+    (KeyCode::E, Vec3::Y),
+];
+
+const CAMERA_CONTROLS_PRESETS: [CameraControlsPreset; 3] = [
+    CameraControlsPreset::Default,
+    CameraControlsPreset::Blender,
+    CameraControlsPreset::Unity,
+];
 
 pub struct EditorDefaultCameraPlugin;
 
@@ -25,6 +45,50 @@ impl Plugin for EditorDefaultCameraPlugin {
                 .before(update_pan_orbit)
                 .in_set(EditorSet::Editor),
         );
+        app.editor_hotkey(EditorCameraHotkey::FocusSelected, vec![KeyCode::F]);
+        app.add_systems(
+            Update,
+            focus_selected
+                .after(update_pan_orbit)
+                .before(PanOrbitCameraSystemSet)
+                .in_set(EditorSet::Editor),
+        );
+        app.init_resource::<EditorCameraFollow>();
+
+        app.register_type::<Option<KeyCode>>()
+            .register_type::<EditorCameraSettings>()
+            .init_resource::<EditorCameraSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<EditorCameraSettings>();
+        }
+        app.add_systems(
+            Update,
+            (apply_editor_camera_settings, fly_editor_camera)
+                .chain()
+                .after(update_pan_orbit)
+                .before(PanOrbitCameraSystemSet)
+                .in_set(EditorSet::Editor),
+        );
+        app.add_systems(
+            Update,
+            follow_entity
+                .after(update_pan_orbit)
+                .before(PanOrbitCameraSystemSet),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum EditorCameraHotkey {
+    FocusSelected,
+}
+
+impl Hotkey for EditorCameraHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::FocusSelected => "Focus camera on selected".to_string(),
+        }
     }
 }
 
@@ -48,6 +112,383 @@ pub fn update_pan_orbit(
     }
 }
 
+/// Named set of editor camera controls, see [`EditorCameraSettings::apply_preset`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraControlsPreset {
+    /// Left mouse orbits, right mouse pans
+    Default,
+    /// Middle mouse orbits, Shift + middle mouse pans
+    Blender,
+    /// Alt + left mouse orbits, middle mouse pans, right mouse flies
+    Unity,
+}
+
+impl ToString for CameraControlsPreset {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Default => "Default",
+            Self::Blender => "Blender",
+            Self::Unity => "Unity",
+        }
+        .to_string()
+    }
+}
+
+/// Speeds, buttons and inversion of editor camera controls. Applied to [`PanOrbitCamera`] of editor camera
+#[derive(Resource, Reflect, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct EditorCameraSettings {
+    pub orbit_sensitivity: f32,
+    pub pan_sensitivity: f32,
+    pub zoom_sensitivity: f32,
+    /// Units per second when camera is moved with WASD/QE while fly button is held
+    pub fly_speed: f32,
+    pub invert_orbit: bool,
+    pub invert_pan: bool,
+    pub invert_zoom: bool,
+    pub orbit_button: MouseButton,
+    pub orbit_modifier: Option<KeyCode>,
+    pub pan_button: MouseButton,
+    pub pan_modifier: Option<KeyCode>,
+    pub fly_button: MouseButton,
+}
+
+impl Default for EditorCameraSettings {
+    fn default() -> Self {
+        Self {
+            orbit_sensitivity: 1.0,
+            pan_sensitivity: 1.0,
+            zoom_sensitivity: 1.0,
+            fly_speed: 5.0,
+            invert_orbit: false,
+            invert_pan: false,
+            invert_zoom: false,
+            orbit_button: MouseButton::Left,
+            orbit_modifier: None,
+            pan_button: MouseButton::Right,
+            pan_modifier: None,
+            fly_button: MouseButton::Middle,
+        }
+    }
+}
+
+impl EditorCameraSettings {
+    /// Set all speeds, buttons and inversion at once
+    pub fn apply_preset(&mut self, preset: CameraControlsPreset) {
+        self.invert_orbit = false;
+        self.invert_pan = false;
+        self.invert_zoom = false;
+        match preset {
+            CameraControlsPreset::Default => {
+                self.orbit_sensitivity = 1.0;
+                self.pan_sensitivity = 1.0;
+                self.zoom_sensitivity = 1.0;
+                self.fly_speed = 5.0;
+                self.orbit_button = MouseButton::Left;
+                self.orbit_modifier = None;
+                self.pan_button = MouseButton::Right;
+                self.pan_modifier = None;
+                self.fly_button = MouseButton::Middle;
+            }
+            CameraControlsPreset::Blender => {
+                self.orbit_sensitivity = 1.5;
+                self.pan_sensitivity = 1.0;
+                self.zoom_sensitivity = 1.0;
+                self.fly_speed = 3.0;
+                self.orbit_button = MouseButton::Middle;
+                self.orbit_modifier = None;
+                self.pan_button = MouseButton::Middle;
+                self.pan_modifier = Some(KeyCode::ShiftLeft);
+                self.fly_button = MouseButton::Right;
+            }
+            CameraControlsPreset::Unity => {
+                self.orbit_sensitivity = 1.0;
+                self.pan_sensitivity = 1.5;
+                self.zoom_sensitivity = 0.5;
+                self.fly_speed = 10.0;
+                self.orbit_button = MouseButton::Left;
+                self.orbit_modifier = Some(KeyCode::AltLeft);
+                self.pan_button = MouseButton::Middle;
+                self.pan_modifier = None;
+                self.fly_button = MouseButton::Right;
+            }
+        }
+    }
+
+    fn apply(&self, pan_orbit: &mut PanOrbitCamera) {
+        let sign = |invert: bool| if invert { -1.0 } else { 1.0 };
+        pan_orbit.orbit_sensitivity = self.orbit_sensitivity * sign(self.invert_orbit);
+        pan_orbit.pan_sensitivity = self.pan_sensitivity * sign(self.invert_pan);
+        pan_orbit.zoom_sensitivity = self.zoom_sensitivity;
+        pan_orbit.reversed_zoom = self.invert_zoom;
+        pan_orbit.button_orbit = self.orbit_button;
+        pan_orbit.modifier_orbit = self.orbit_modifier;
+        pan_orbit.button_pan = self.pan_button;
+        pan_orbit.modifier_pan = self.pan_modifier;
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Editor Camera");
+        ui.horizontal(|ui| {
+            ui.label("Preset:");
+            for preset in CAMERA_CONTROLS_PRESETS {
+                if ui.button(preset.to_string()).clicked() {
+                    self.apply_preset(preset);
+                }
+            }
+        });
+        egui::Grid::new("editor_camera_settings")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (label, value, invert) in [
+                    (
+                        "Orbit sensitivity",
+                        &mut self.orbit_sensitivity,
+                        Some(&mut self.invert_orbit),
+                    ),
+                    (
+                        "Pan sensitivity",
+                        &mut self.pan_sensitivity,
+                        Some(&mut self.invert_pan),
+                    ),
+                    (
+                        "Zoom sensitivity",
+                        &mut self.zoom_sensitivity,
+                        Some(&mut self.invert_zoom),
+                    ),
+                    ("Fly speed", &mut self.fly_speed, None),
+                ] {
+                    ui.label(label);
+                    ui.add(
+                        egui::DragValue::new(value)
+                            .speed(0.05)
+                            .clamp_range(0.0..=100.0),
+                    );
+                    if let Some(invert) = invert {
+                        ui.checkbox(invert, "Invert");
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.horizontal(|ui| {
+            ui.label("Orbit:");
+            ui.label(format!(
+                "{}{:?} mouse",
+                modifier_text(self.orbit_modifier),
+                self.orbit_button
+            ));
+            ui.label("Pan:");
+            ui.label(format!(
+                "{}{:?} mouse",
+                modifier_text(self.pan_modifier),
+                self.pan_button
+            ));
+            ui.label("Fly:");
+            ui.label(format!("{:?} mouse + WASD/QE", self.fly_button));
+        });
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+fn modifier_text(modifier: Option<KeyCode>) -> String {
+    modifier.map_or(String::new(), |key| format!("{:?} + ", key))
+}
+
+/// Apply [`EditorCameraSettings`] to editor camera when settings change or camera is respawned
+pub fn apply_editor_camera_settings(
+    settings: Res<EditorCameraSettings>,
+    mut cameras: Query<(Ref<EditorCameraMarker>, &mut PanOrbitCamera)>,
+) {
+    for (marker, mut pan_orbit) in cameras.iter_mut() {
+        if settings.is_changed() || marker.is_added() {
+            settings.apply(&mut pan_orbit);
+        }
+    }
+}
+
+/// Move editor camera with WASD/QE while fly button is held. Camera keeps its orientation
+pub fn fly_editor_camera(
+    mut ctxs: EguiContexts,
+    settings: Res<EditorCameraSettings>,
+    state: Res<EditorCameraEnabled>,
+    time: Res<Time>,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    mut cameras: Query<(&mut Transform, &mut PanOrbitCamera), With<EditorCameraMarker>>,
+) {
+    if !state.0 || !mouse.pressed(settings.fly_button) || ctxs.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+    let direction = FLY_KEYS
+        .iter()
+        .filter(|(key, _)| keys.pressed(*key))
+        .map(|(_, direction)| *direction)
+        .sum::<Vec3>();
+    if direction == Vec3::ZERO {
+        return;
+    }
+
+    for (mut transform, mut pan_orbit) in cameras.iter_mut() {
+        let delta =
+            transform.rotation * direction.normalize() * settings.fly_speed * time.delta_seconds();
+        pan_orbit.focus += delta;
+        pan_orbit.target_focus += delta;
+        transform.translation += delta;
+    }
+}
+
+/// Follow mode of editor camera: camera keeps its offset to the followed entity
+#[derive(Resource, Default)]
+pub struct EditorCameraFollow {
+    pub enabled: bool,
+    /// Follow newly selected entity when selection changes
+    pub retarget_on_selection: bool,
+    pub target: Option<Entity>,
+    last_position: Option<Vec3>,
+}
+
+impl EditorCameraFollow {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.enabled, "Follow selected");
+            if self.enabled {
+                ui.checkbox(&mut self.retarget_on_selection, "Retarget");
+            }
+        });
+    }
+}
+
+/// Moves editor camera by the movement of followed entity. Scene entities are not modified
+pub fn follow_entity(
+    mut follow: ResMut<EditorCameraFollow>,
+    active: Res<ActiveEntity>,
+    transforms: Query<&GlobalTransform>,
+    mut cameras: Query<(&mut Transform, Option<&mut PanOrbitCamera>), With<EditorCameraMarker>>,
+) {
+    if !follow.enabled {
+        if follow.target.is_some() {
+            follow.target = None;
+            follow.last_position = None;
+        }
+        return;
+    }
+
+    if follow.target.is_none()
+        || (follow.retarget_on_selection && active.0.is_some() && active.0 != follow.target)
+    {
+        follow.target = active.0;
+        follow.last_position = None;
+    }
+
+    let Some(position) = follow
+        .target
+        .and_then(|e| transforms.get(e).ok())
+        .map(GlobalTransform::translation)
+    else {
+        follow.target = None;
+        return;
+    };
+
+    let delta = position - follow.last_position.unwrap_or(position);
+    follow.last_position = Some(position);
+    if delta == Vec3::ZERO {
+        return;
+    }
+
+    for (mut transform, pan_orbit) in cameras.iter_mut() {
+        if let Some(mut pan_orbit) = pan_orbit {
+            pan_orbit.focus += delta;
+            pan_orbit.target_focus += delta;
+        }
+        transform.translation += delta;
+    }
+}
+
+/// Move editor camera to frame world space bounds of all selected entities.
+/// Entities without [`Aabb`] are framed by their origin. Movement is smoothed by [`PanOrbitCamera`]
+pub fn focus_selected(
+    mut ctxs: EguiContexts,
+    hotkeys: Res<Input<EditorCameraHotkey>>,
+    selected: Query<(&GlobalTransform, Option<&Aabb>), With<Selected>>,
+    mut cameras: Query<(&mut PanOrbitCamera, Option<&Projection>), With<EditorCameraMarker>>,
+) {
+    if !hotkeys.just_pressed(EditorCameraHotkey::FocusSelected)
+        || ctxs.ctx_mut().wants_keyboard_input()
+    {
+        return;
+    }
+
+    let Some((center, radius)) = framing_sphere(selected.iter()) else {
+        return;
+    };
+    for (mut pan_orbit, projection) in cameras.iter_mut() {
+        frame_sphere(&mut pan_orbit, projection, center, radius);
+    }
+}
+
+/// Move editor camera to frame `entities` in the same way as [`focus_selected`]
+pub fn focus_camera_on(world: &mut World, entities: &[Entity]) {
+    let mut query = world.query::<(&GlobalTransform, Option<&Aabb>)>();
+    let Some((center, radius)) =
+        framing_sphere(entities.iter().filter_map(|e| query.get(world, *e).ok()))
+    else {
+        return;
+    };
+    let mut cameras = world
+        .query_filtered::<(&mut PanOrbitCamera, Option<&Projection>), With<EditorCameraMarker>>();
+    for (mut pan_orbit, projection) in cameras.iter_mut(world) {
+        frame_sphere(&mut pan_orbit, projection, center, radius);
+    }
+}
+
+/// Center and radius of world space bounds of entities. Entities without [`Aabb`] are bounded by their origin
+fn framing_sphere<'a>(
+    entities: impl Iterator<Item = (&'a GlobalTransform, Option<&'a Aabb>)>,
+) -> Option<(Vec3, f32)> {
+    let mut bounds: Option<(Vec3, Vec3)> = None;
+    let mut extend = |point: Vec3| {
+        bounds = Some(bounds.map_or((point, point), |(min, max)| {
+            (min.min(point), max.max(point))
+        }));
+    };
+    for (transform, aabb) in entities {
+        let Some(aabb) = aabb else {
+            extend(transform.translation());
+            continue;
+        };
+        let center = Vec3::from(aabb.center);
+        let half = Vec3::from(aabb.half_extents);
+        for corner in 0..8 {
+            let sign = Vec3::new(
+                if corner & 1 == 0 { -1.0 } else { 1.0 },
+                if corner & 2 == 0 { -1.0 } else { 1.0 },
+                if corner & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            extend(transform.transform_point(center + half * sign));
+        }
+    }
+    let (min, max) = bounds?;
+    Some(((min + max) / 2.0, (max - min).length() / 2.0))
+}
+
+fn frame_sphere(
+    pan_orbit: &mut PanOrbitCamera,
+    projection: Option<&Projection>,
+    center: Vec3,
+    radius: f32,
+) {
+    pan_orbit.target_focus = center;
+    // Selection without renderable bounds is only recentered
+    if radius > f32::EPSILON {
+        let fov = match projection {
+            Some(Projection::Perspective(perspective)) => perspective.fov,
+            _ => PerspectiveProjection::default().fov,
+        };
+        pan_orbit.target_radius = radius / (fov / 2.0).sin();
+    }
+}
+
 type ChangeCameraQueryFilter = (Without<EditorCameraMarker>, With<CameraPlay>);
 
 /// System to change camera from editor camera to game camera (if exist)
@@ -2,54 +2,215 @@ use bevy::{
     prelude::*,
     render::{
         camera::{RenderTarget, TemporalJitter},
+        primitives::Aabb,
         render_resource::{
             Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
         },
     },
-    window::PrimaryWindow,
+    window::WindowRef,
 };
 use bevy_egui::{
     egui::{self, Color32},
     EguiContexts,
 };
 
+use space_editor_core::prelude::{Hotkey, HotkeyAppExt, Selected};
 use space_prefab::component::CameraPlay;
 use space_shared::*;
 
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
 use crate::{
+    camera_capture::{CameraCapturePlugin, CaptureCameraEvent},
+    hierarchy::entity_bounds,
     prelude::{EditorTabName, GameModeSettings},
-    show_editor_ui, DisableCameraSkip, EditorUiAppExt, RenderLayers,
+    show_editor_ui, DisableCameraSkip, EditorUi, EditorUiReg, RenderLayers, LAST_RENDER_LAYER,
 };
 
 use super::editor_tab::EditorTab;
 
-pub struct CameraViewTabPlugin;
+pub struct CameraViewTabPlugin {
+    /// Count of camera view tabs, which can be opened at once
+    pub tabs: usize,
+}
+
+impl Default for CameraViewTabPlugin {
+    fn default() -> Self {
+        Self {
+            tabs: DEFAULT_CAMERA_VIEW_TABS,
+        }
+    }
+}
 
 impl Plugin for CameraViewTabPlugin {
     fn build(&self, app: &mut App) {
-        app.editor_tab_by_trait(EditorTabName::CameraView, CameraViewTab::default());
+        // Each camera view tab is registered separately, so several views can be opened at once
+        app.insert_resource(CameraViews::new(self.tabs));
+        app.add_plugins(CameraCapturePlugin);
+        for index in 0..self.tabs {
+            let tab_id = if index == 0 {
+                EditorTabName::CameraView
+            } else {
+                EditorTabName::Other(format!("Camera view {}", index + 1))
+            };
+            let show_fn = Box::new(
+                move |ui: &mut egui::Ui, commands: &mut Commands, world: &mut World| {
+                    world.resource_scope(|world, mut views: Mut<CameraViews>| {
+                        views.views[index].ui(ui, commands, world);
+                    });
+                },
+            );
+            let reg = EditorUiReg::ResourceBased {
+                show_command: show_fn,
+                title_command: Box::new(move |world| {
+                    world.resource::<CameraViews>().views[index].title()
+                }),
+            };
+            app.world
+                .resource_mut::<EditorUi>()
+                .registry
+                .insert(tab_id, reg);
+        }
+        app.init_resource::<CameraViewSettings>();
+        app.register_type::<CameraViewSettings>()
+            .register_type::<Option<Vec2>>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<CameraViewSettings>();
+        }
         app.add_systems(
             PostUpdate,
             set_camera_viewport
                 .in_set(EditorSet::Editor)
                 .after(show_editor_ui),
         );
+        // Popped out views keep rendering in their windows while game is running
+        app.add_systems(PostUpdate, set_camera_viewport.in_set(EditorSet::Game));
+        app.add_systems(
+            Update,
+            (restore_saved_camera, sync_camera_with_selection)
+                .chain()
+                .in_set(EditorSet::Editor),
+        );
+        app.add_systems(
+            Update,
+            draw_selection_bounds
+                .run_if(any_view_with_overlays)
+                .in_set(EditorSet::Editor),
+        );
         app.add_systems(OnEnter(EditorState::Game), clean_camera_view_tab);
+
+        app.editor_hotkey(SimulationHotkey::TogglePause, vec![KeyCode::Pause]);
+        app.editor_hotkey(SimulationHotkey::Step, vec![KeyCode::F10]);
+        // Works in play mode too, where editor UI is hidden
+        app.add_systems(Update, simulation_hotkeys);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum SimulationHotkey {
+    TogglePause,
+    Step,
+}
+
+impl Hotkey for SimulationHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::TogglePause => "Pause/resume game logic".to_string(),
+            Self::Step => "Step one frame of game logic".to_string(),
+        }
+    }
+}
+
+fn simulation_hotkeys(mut state: ResMut<SimulationState>, hotkeys: Res<Input<SimulationHotkey>>) {
+    if hotkeys.just_pressed(SimulationHotkey::TogglePause) {
+        state.paused = !state.paused;
+    }
+    if hotkeys.just_pressed(SimulationHotkey::Step) && state.paused {
+        state.step = true;
     }
 }
 
 #[derive(Component)]
 pub struct ViewCamera;
 
+/// Default count of camera view tabs, see [`CameraViewTabPlugin::tabs`]
+pub const DEFAULT_CAMERA_VIEW_TABS: usize = 4;
+
+/// States of all camera view tabs. First one is the main [`EditorTabName::CameraView`] tab
+#[derive(Resource)]
+pub struct CameraViews {
+    pub views: Vec<CameraViewTab>,
+}
+
+impl Default for CameraViews {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAMERA_VIEW_TABS)
+    }
+}
+
+impl CameraViews {
+    pub fn new(tabs: usize) -> Self {
+        Self {
+            views: (0..tabs)
+                .map(|index| CameraViewTab {
+                    index,
+                    capture_size: UVec2::new(1920, 1080),
+                    ..default()
+                })
+                .collect(),
+        }
+    }
+}
+
 /// Tab for camera view in editor
-#[derive(Resource, Default)]
+#[derive(Default, Resource)]
 pub struct CameraViewTab {
+    /// Index of tab in [`CameraViews`]
+    pub index: usize,
     pub viewport_rect: Option<egui::Rect>,
     pub camera_entity: Option<Entity>,
     pub real_camera: Option<Entity>,
     pub target_image: Option<Handle<Image>>,
     pub egui_tex_id: Option<(egui::TextureId, Handle<Image>)>,
     pub need_reinit_egui_tex: bool,
+    /// Camera chosen in the tab selector. Shown when no camera is selected
+    pub main_camera: Option<Entity>,
+    /// Target aspect ratio (width, height) of the view. Remaining space is filled with black bars.
+    /// If not set, view keeps proportion of watched camera viewport
+    pub aspect_ratio: Option<Vec2>,
+    /// Resolution of captured image. Independent of tab size
+    pub capture_size: UVec2,
+    /// Saved file path or error of the last capture
+    pub last_capture: Option<Result<String, String>>,
+    /// Render editor overlays (gizmos, grid, selection bounds) in this view.
+    /// Only view camera sees them, so watched game camera output stays clean
+    pub show_editor_overlays: bool,
+    /// Separate OS window, where view is rendered instead of the tab (for example, on second monitor).
+    /// View returns to the tab when window is closed
+    pub window: Option<Entity>,
+    settings_loaded: bool,
+}
+
+const ASPECT_PRESETS: [(&str, Vec2); 5] = [
+    ("16:9", Vec2::new(16.0, 9.0)),
+    ("16:10", Vec2::new(16.0, 10.0)),
+    ("21:9", Vec2::new(21.0, 9.0)),
+    ("4:3", Vec2::new(4.0, 3.0)),
+    ("1:1", Vec2::new(1.0, 1.0)),
+];
+
+/// Persistent settings of camera view tab
+#[derive(Resource, Reflect, Default, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct CameraViewSettings {
+    /// Show selected [`CameraPlay`] camera in the tab
+    pub sync_with_selection: bool,
+    /// Name of camera chosen in the tab selector. Used to restore the choice after scene load
+    pub camera_name: String,
+    /// Aspect ratio of main camera view
+    pub aspect_ratio: Option<Vec2>,
 }
 
 fn create_camera_image(width: u32, height: u32) -> Image {
@@ -78,6 +239,141 @@ fn create_camera_image(width: u32, height: u32) -> Image {
     image
 }
 
+impl CameraViewTab {
+    fn simulation_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+        let mut state = *world.resource::<SimulationState>();
+        ui.horizontal(|ui| {
+            let (icon, hint) = if state.paused {
+                ("▶", "Resume game logic")
+            } else {
+                ("⏸", "Pause game logic")
+            };
+            if ui.button(icon).on_hover_text(hint).clicked() {
+                state.paused = !state.paused;
+            }
+            if ui
+                .add_enabled(state.paused, egui::Button::new("⏭"))
+                .on_hover_text("Run game logic for one frame")
+                .clicked()
+            {
+                state.step = true;
+            }
+            if state.paused {
+                ui.colored_label(Color32::YELLOW, "Paused");
+            }
+        });
+        if *world.resource::<SimulationState>() != state {
+            world.insert_resource(state);
+        }
+    }
+
+    fn capture_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+        ui.horizontal(|ui| {
+            ui.label("Capture");
+            ui.add(
+                egui::DragValue::new(&mut self.capture_size.x)
+                    .clamp_range(1..=8192)
+                    .suffix(" px"),
+            );
+            ui.label("x");
+            ui.add(
+                egui::DragValue::new(&mut self.capture_size.y)
+                    .clamp_range(1..=8192)
+                    .suffix(" px"),
+            );
+            if ui
+                .add_enabled(
+                    self.camera_entity.is_some(),
+                    egui::Button::new("📷 Capture"),
+                )
+                .clicked()
+            {
+                if let Some(camera) = self.camera_entity {
+                    world.send_event(CaptureCameraEvent {
+                        camera,
+                        size: self.capture_size,
+                        view: self.index,
+                    });
+                }
+            }
+        });
+        match &self.last_capture {
+            Some(Ok(path)) => {
+                ui.label(format!("Saved to {}", path));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(Color32::LIGHT_RED, format!("Capture failed: {}", err));
+            }
+            None => {}
+        }
+    }
+
+    fn window_ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        match self.window {
+            Some(window) => {
+                if ui
+                    .button("Dock back")
+                    .on_hover_text("Close separate window and show view in this tab")
+                    .clicked()
+                {
+                    if let Some(window) = world.get_entity_mut(window) {
+                        window.despawn();
+                    }
+                    self.window = None;
+                }
+            }
+            None => {
+                if ui
+                    .button("⧉ Pop out")
+                    .on_hover_text("Show view in separate window")
+                    .clicked()
+                {
+                    self.window = Some(
+                        commands
+                            .spawn(Window {
+                                title: self.title().text().to_string(),
+                                ..default()
+                            })
+                            .id(),
+                    );
+                }
+            }
+        }
+    }
+
+    fn aspect_ratio_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let selected_text = self.aspect_ratio.map_or("Free".to_string(), |aspect| {
+                ASPECT_PRESETS
+                    .iter()
+                    .find(|(_, preset)| *preset == aspect)
+                    .map_or("Custom".to_string(), |(name, _)| name.to_string())
+            });
+            egui::ComboBox::from_label("Aspect")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.aspect_ratio, None, "Free");
+                    for (name, preset) in ASPECT_PRESETS {
+                        ui.selectable_value(&mut self.aspect_ratio, Some(preset), name);
+                    }
+                });
+            if let Some(aspect) = &mut self.aspect_ratio {
+                ui.add(
+                    egui::DragValue::new(&mut aspect.x)
+                        .clamp_range(1.0..=100.0)
+                        .speed(0.1),
+                );
+                ui.label(":");
+                ui.add(
+                    egui::DragValue::new(&mut aspect.y)
+                        .clamp_range(1.0..=100.0)
+                        .speed(0.1),
+                );
+            }
+        });
+    }
+}
+
 impl EditorTab for CameraViewTab {
     fn ui(&mut self, ui: &mut bevy_egui::egui::Ui, commands: &mut Commands, world: &mut World) {
         if self.real_camera.is_none() {
@@ -88,7 +384,7 @@ impl EditorTab for CameraViewTab {
                             Camera3dBundle {
                                 camera: Camera {
                                     is_active: false,
-                                    order: 2,
+                                    order: 2 + self.index as isize,
                                     ..default()
                                 },
                                 camera_3d: Camera3d {
@@ -127,18 +423,35 @@ impl EditorTab for CameraViewTab {
             Without<ViewCamera>,
         )>();
 
+        let mut settings = world.resource::<CameraViewSettings>().clone();
+
         if camera_query.iter(world).count() > 0 {
             egui::ComboBox::from_label("Camera")
                 .selected_text(format!("{:?}", self.camera_entity))
                 .show_ui(ui, |ui| {
                     for entity in camera_query.iter(world) {
-                        ui.selectable_value(
-                            &mut self.camera_entity,
-                            Some(entity),
-                            format!("{:?}", entity),
-                        );
+                        if ui
+                            .selectable_value(
+                                &mut self.camera_entity,
+                                Some(entity),
+                                format!("{:?}", entity),
+                            )
+                            .clicked()
+                        {
+                            self.main_camera = Some(entity);
+                            if self.index == 0 {
+                                settings.camera_name = world
+                                    .get::<Name>(entity)
+                                    .map(|name| name.to_string())
+                                    .unwrap_or_default();
+                            }
+                        }
                     }
                 });
+            // Selection sync and restore are applied only to main view
+            if self.index == 0 {
+                ui.checkbox(&mut settings.sync_with_selection, "Show selected camera");
+            }
             ui.add_space(4.);
             ui.separator();
         } else {
@@ -173,6 +486,30 @@ impl EditorTab for CameraViewTab {
             }
         }
 
+        if self.index == 0 {
+            if !self.settings_loaded {
+                self.aspect_ratio = settings.aspect_ratio;
+                self.settings_loaded = true;
+            }
+            self.aspect_ratio_ui(ui);
+            settings.aspect_ratio = self.aspect_ratio;
+        } else {
+            self.aspect_ratio_ui(ui);
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_editor_overlays, "Editor overlays")
+                .on_hover_text("Show gizmos and selection bounds in this view");
+            self.window_ui(ui, commands, world);
+        });
+
+        self.simulation_ui(ui, world);
+        self.capture_ui(ui, world);
+
+        if world.resource::<CameraViewSettings>() != &settings {
+            world.insert_resource(settings);
+        }
+
         // Moves camera below the selection
         let pos = ui.next_widget_position();
         let mut clipped = ui.clip_rect();
@@ -180,27 +517,27 @@ impl EditorTab for CameraViewTab {
         clipped.set_top(pos.y);
         self.viewport_rect = Some(clipped);
 
+        // Collapsed tab has zero size, but render target texture must have at least one pixel
+        let image_size =
+            UVec2::new(clipped.width() as u32, clipped.height() as u32).max(UVec2::ONE);
         if self.target_image.is_none() {
             let handle = world
                 .resource_mut::<Assets<Image>>()
-                .add(create_camera_image(
-                    clipped.width() as u32,
-                    clipped.height() as u32,
-                ));
+                .add(create_camera_image(image_size.x, image_size.y));
             self.target_image = Some(handle.clone());
             self.need_reinit_egui_tex = true;
         } else if let Some(handle) = &self.target_image {
             if let Some(image) = world.resource::<Assets<Image>>().get(handle) {
-                if image.texture_descriptor.size.width != clipped.width() as u32
-                    || image.texture_descriptor.size.height != clipped.height() as u32
+                if image.texture_descriptor.size.width != image_size.x
+                    || image.texture_descriptor.size.height != image_size.y
                 {
                     world
                         .resource_mut::<Assets<Image>>()
                         .get_mut(handle)
                         .unwrap()
                         .resize(Extent3d {
-                            width: clipped.width() as u32,
-                            height: clipped.height() as u32,
+                            width: image_size.x,
+                            height: image_size.y,
                             ..default()
                         });
                     self.need_reinit_egui_tex = true;
@@ -211,7 +548,14 @@ impl EditorTab for CameraViewTab {
             }
         }
 
+        if self.window.is_some() {
+            ui.label("View is shown in separate window");
+            return;
+        }
+
         if let Some((cam_image, _)) = self.egui_tex_id {
+            // Black bars around letterboxed view
+            ui.painter().rect_filled(clipped, 0.0, egui::Color32::BLACK);
             ui.image(egui::load::SizedTexture {
                 id: cam_image,
                 size: ui.available_size(),
@@ -220,114 +564,230 @@ impl EditorTab for CameraViewTab {
     }
 
     fn title(&self) -> bevy_egui::egui::WidgetText {
-        "Camera view".into()
+        if self.index == 0 {
+            "Camera view".into()
+        } else {
+            format!("Camera view {}", self.index + 1).into()
+        }
     }
 }
 
-fn clean_camera_view_tab(
-    mut ui_state: ResMut<CameraViewTab>,
-    mut cameras: Query<(&mut Camera, &mut Transform), Without<EditorCameraMarker>>,
+/// Restore camera chosen in previous session by its name
+fn restore_saved_camera(
+    mut views: ResMut<CameraViews>,
+    settings: Res<CameraViewSettings>,
+    cameras: Query<
+        (Entity, &Name),
+        (
+            With<Camera>,
+            Without<EditorCameraMarker>,
+            Without<ViewCamera>,
+        ),
+    >,
 ) {
-    let Some(real_cam_entity) = ui_state.real_camera else {
+    let Some(ui_state) = views.views.first_mut() else {
         return;
     };
-
-    let Ok((mut real_cam, _real_cam_transform)) = cameras.get_mut(real_cam_entity) else {
+    if ui_state.camera_entity.is_some() || settings.camera_name.is_empty() {
         return;
-    };
-
-    real_cam.is_active = false;
-    real_cam.viewport = None;
-
-    ui_state.camera_entity = None;
-    ui_state.real_camera = None;
-    ui_state.viewport_rect = None;
+    }
 
-    info!("Clean camera view tab");
+    if let Some((entity, _)) = cameras
+        .iter()
+        .find(|(_, name)| name.as_str() == settings.camera_name)
+    {
+        ui_state.camera_entity = Some(entity);
+        ui_state.main_camera = Some(entity);
+    }
 }
 
-#[derive(Default)]
-struct LastCamTabRect(Option<egui::Rect>);
-
-fn set_camera_viewport(
-    mut local: Local<LastCamTabRect>,
-    mut ui_state: ResMut<CameraViewTab>,
-    primary_window: Query<&mut Window, With<PrimaryWindow>>,
-    egui_settings: Res<bevy_egui::EguiSettings>,
-    mut cameras: Query<(&mut Camera, &mut Transform), Without<EditorCameraMarker>>,
-    mut ctxs: EguiContexts,
+/// Switch camera view to the selected [`CameraPlay`] camera or back to main camera
+fn sync_camera_with_selection(
+    mut views: ResMut<CameraViews>,
+    settings: Res<CameraViewSettings>,
+    selected_cameras: Query<Entity, (With<Selected>, With<CameraPlay>)>,
 ) {
-    let Some(real_cam_entity) = ui_state.real_camera else {
-        return;
-    };
-
-    let Some(camera_entity) = ui_state.camera_entity else {
+    if !settings.sync_with_selection {
         return;
-    };
-
-    let Some(target_image) = ui_state.target_image.clone() else {
+    }
+    let Some(ui_state) = views.views.first_mut() else {
         return;
     };
 
-    if ui_state.egui_tex_id.is_none() {
-        ui_state.egui_tex_id = Some((ctxs.add_image(target_image.clone()), target_image.clone()));
-    }
-
-    if ui_state.need_reinit_egui_tex {
-        ctxs.remove_image(&ui_state.egui_tex_id.as_ref().unwrap().1);
-        ui_state.egui_tex_id = Some((ctxs.add_image(target_image.clone()), target_image.clone()));
-        ui_state.need_reinit_egui_tex = false;
+    let target = selected_cameras.iter().next().or(ui_state.main_camera);
+    if ui_state.camera_entity != target {
+        ui_state.camera_entity = target;
     }
+}
 
-    let Ok([(mut real_cam, mut real_cam_transform), (watch_cam, camera_transform)]) =
-        cameras.get_many_mut([real_cam_entity, camera_entity])
-    else {
-        if let Ok((mut real_cam, _)) = cameras.get_mut(real_cam_entity) {
-            real_cam.is_active = false;
-            ui_state.camera_entity = None;
+fn clean_camera_view_tab(
+    mut views: ResMut<CameraViews>,
+    mut cameras: Query<&mut Camera, Without<EditorCameraMarker>>,
+) {
+    for ui_state in views.views.iter_mut() {
+        // Separate window is not a part of editor UI, so it keeps showing the view in game mode
+        if ui_state.window.is_some() {
+            continue;
         }
-        return;
-    };
 
-    let Ok(window) = primary_window.get_single() else {
-        return;
-    };
+        let Some(real_cam_entity) = ui_state.real_camera else {
+            continue;
+        };
 
-    let Some(viewport_rect) = ui_state.viewport_rect else {
-        return;
-    };
+        let Ok(mut real_cam) = cameras.get_mut(real_cam_entity) else {
+            continue;
+        };
 
-    local.0 = Some(viewport_rect);
+        real_cam.is_active = false;
+        real_cam.viewport = None;
 
-    if watch_cam.is_changed() {
-        *real_cam = watch_cam.clone();
+        ui_state.camera_entity = None;
+        ui_state.real_camera = None;
+        ui_state.viewport_rect = None;
     }
-    // set editor params for real_cam
-    real_cam.order = 2;
-    real_cam.is_active = true;
-    real_cam.target = RenderTarget::Image(target_image.clone());
 
-    *real_cam_transform = *camera_transform;
+    info!("Clean camera view tabs");
+}
 
-    let scale_factor = window.scale_factor() * egui_settings.scale_factor;
+fn any_view_with_overlays(views: Res<CameraViews>) -> bool {
+    views
+        .views
+        .iter()
+        .any(|view| view.show_editor_overlays && view.camera_entity.is_some())
+}
 
-    let mut viewport_pos = viewport_rect.left_top().to_vec2() * scale_factor as f32;
-    let mut viewport_size = viewport_rect.size() * scale_factor as f32;
+/// Selection highlight for camera views. Drawn by gizmos on editor render layer
+fn draw_selection_bounds(
+    mut gizmos: Gizmos,
+    selected: Query<(&GlobalTransform, Option<&Aabb>), With<Selected>>,
+) {
+    for (transform, aabb) in selected.iter() {
+        gizmos.cuboid(entity_bounds(transform, aabb, 1.0), Color::ORANGE);
+    }
+}
 
-    // Fixes camera viewport size to be proportional to main watch camera
-    if let Some(watch_cam_size) = watch_cam.logical_viewport_size() {
-        let wx = watch_cam_size.x;
-        let wy = watch_cam_size.y;
+fn set_camera_viewport(
+    mut views: ResMut<CameraViews>,
+    mut cameras: Query<(&mut Camera, &mut Transform), Without<EditorCameraMarker>>,
+    mut layers: Query<&mut RenderLayers, With<ViewCamera>>,
+    windows: Query<&Window>,
+    mut ctxs: EguiContexts,
+) {
+    for ui_state in views.views.iter_mut() {
+        let Some(real_cam_entity) = ui_state.real_camera else {
+            continue;
+        };
+
+        let Some(camera_entity) = ui_state.camera_entity else {
+            continue;
+        };
+
+        let Some(target_image) = ui_state.target_image.clone() else {
+            continue;
+        };
+
+        if ui_state.egui_tex_id.is_none() {
+            ui_state.egui_tex_id =
+                Some((ctxs.add_image(target_image.clone()), target_image.clone()));
+        }
 
-        viewport_size.y = wy * viewport_size.x / wx
-    }
+        if ui_state.need_reinit_egui_tex {
+            ctxs.remove_image(&ui_state.egui_tex_id.as_ref().unwrap().1);
+            ui_state.egui_tex_id =
+                Some((ctxs.add_image(target_image.clone()), target_image.clone()));
+            ui_state.need_reinit_egui_tex = false;
+        }
+
+        let Ok([(mut real_cam, mut real_cam_transform), (watch_cam, camera_transform)]) =
+            cameras.get_many_mut([real_cam_entity, camera_entity])
+        else {
+            if let Ok((mut real_cam, _)) = cameras.get_mut(real_cam_entity) {
+                real_cam.is_active = false;
+                ui_state.camera_entity = None;
+            }
+            continue;
+        };
+
+        // Closed popped out window returns view to the tab
+        let window_size = ui_state
+            .window
+            .and_then(|window| windows.get(window).ok())
+            .map(|window| {
+                Vec2::new(
+                    window.physical_width() as f32,
+                    window.physical_height() as f32,
+                )
+            });
+        if window_size.is_none() {
+            ui_state.window = None;
+        }
+
+        // Viewport is set in target pixels: window physical size or image of the tab rect size
+        let (target, tab_size) = match (ui_state.window, window_size) {
+            (Some(window), Some(size)) => (RenderTarget::Window(WindowRef::Entity(window)), size),
+            _ => {
+                let Some(viewport_rect) = ui_state.viewport_rect else {
+                    continue;
+                };
+                (
+                    RenderTarget::Image(target_image.clone()),
+                    Vec2::new(viewport_rect.width(), viewport_rect.height()),
+                )
+            }
+        };
+        if tab_size.x < 1.0 || tab_size.y < 1.0 {
+            continue;
+        }
+
+        if watch_cam.is_changed() {
+            *real_cam = watch_cam.clone();
+        }
+        // set editor params for real_cam
+        real_cam.order = 2 + ui_state.index as isize;
+        real_cam.is_active = true;
+        real_cam.target = target;
+
+        let view_layers = if ui_state.show_editor_overlays {
+            RenderLayers::layer(0).with(LAST_RENDER_LAYER)
+        } else {
+            RenderLayers::layer(0)
+        };
+        if let Ok(mut layers) = layers.get_mut(real_cam_entity) {
+            if *layers != view_layers {
+                *layers = view_layers;
+            }
+        }
 
-    // Place viewport in the center of the tab
-    viewport_pos.y += (viewport_rect.size().y - viewport_size.y) / 2.0;
+        *real_cam_transform = *camera_transform;
 
-    real_cam.viewport = Some(bevy::render::camera::Viewport {
-        physical_position: UVec2::new(0, (viewport_rect.size().y - viewport_size.y) as u32 / 2),
-        physical_size: UVec2::new(viewport_size.x as u32, viewport_size.y as u32),
-        depth: 0.0..1.0,
-    });
+        // Keep target aspect ratio or proportion of main watch camera
+        let aspect = if let Some(aspect) = ui_state.aspect_ratio {
+            aspect.x / aspect.y
+        } else if let Some(watch_cam_size) = watch_cam.logical_viewport_size() {
+            watch_cam_size.x / watch_cam_size.y
+        } else {
+            tab_size.x / tab_size.y
+        };
+
+        let viewport_size = if tab_size.x / tab_size.y > aspect {
+            Vec2::new(tab_size.y * aspect, tab_size.y)
+        } else {
+            Vec2::new(tab_size.x, tab_size.x / aspect)
+        };
+
+        // Place viewport in the center of the tab
+        let target_size = tab_size.as_uvec2();
+        let viewport_pos = ((tab_size - viewport_size) / 2.0).as_uvec2();
+        // Extreme aspect ratio can round one side to zero, viewport must stay inside the target
+        let viewport_size = viewport_size
+            .as_uvec2()
+            .max(UVec2::ONE)
+            .min(target_size - viewport_pos);
+
+        real_cam.viewport = Some(bevy::render::camera::Viewport {
+            physical_position: viewport_pos,
+            physical_size: viewport_size,
+            depth: 0.0..1.0,
+        });
+    }
 }
@@ -1,14 +1,15 @@
 use bevy::prelude::*;
 
 use super::{editor_tab::EditorTab, EditorUiAppExt};
-use space_undo::ChangeChain;
+use bevy_egui::egui;
+use space_undo::{ChangeChain, UndoRedo};
 
 pub struct ChangeChainViewPlugin;
 
 impl Plugin for ChangeChainViewPlugin {
     fn build(&self, app: &mut App) {
         app.editor_tab_by_trait(
-            super::editor_tab::EditorTabName::Other("Change Chain".to_string()),
+            super::editor_tab::EditorTabName::UndoHistory,
             ChangeChainView,
         );
     }
@@ -25,13 +26,61 @@ impl EditorTab for ChangeChainView {
         world: &mut bevy::prelude::World,
     ) {
         let change_chain = world.resource::<ChangeChain>();
+        let undo_stack = change_chain.undo_stack();
+        let redo_stack = change_chain.redo_stack();
+        let can_undo = !undo_stack.is_empty();
+        let can_redo = !redo_stack.is_empty();
 
-        for change in change_chain.changes.iter() {
-            ui.label(change.debug_text());
+        let mut events = vec![];
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(can_undo, egui::Button::new("Undo"))
+                .clicked()
+            {
+                events.push(UndoRedo::Undo);
+            }
+            if ui
+                .add_enabled(can_redo, egui::Button::new("Redo"))
+                .clicked()
+            {
+                events.push(UndoRedo::Redo);
+            }
+        });
+        ui.separator();
+
+        // Click on entry undoes or redoes all changes up to this entry
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if ui.selectable_label(!can_undo, "Initial state").clicked() {
+                events.extend((0..undo_stack.len()).map(|_| UndoRedo::Undo));
+            }
+            for (index, change) in undo_stack.iter().enumerate() {
+                let is_current = index + 1 == undo_stack.len();
+                if ui
+                    .selectable_label(is_current, change.describe())
+                    .on_hover_text(change.debug_text())
+                    .clicked()
+                {
+                    events.extend((index + 1..undo_stack.len()).map(|_| UndoRedo::Undo));
+                }
+            }
+            // Redo stack is shown from the next change to redo
+            for (index, change) in redo_stack.iter().rev().enumerate() {
+                if ui
+                    .selectable_label(false, egui::RichText::new(change.describe()).weak())
+                    .on_hover_text(change.debug_text())
+                    .clicked()
+                {
+                    events.extend((0..=index).map(|_| UndoRedo::Redo));
+                }
+            }
+        });
+
+        for event in events {
+            world.send_event(event);
         }
     }
 
     fn title(&self) -> bevy_egui::egui::WidgetText {
-        "Change Chain".into()
+        "Undo History".into()
     }
 }
@@ -0,0 +1,215 @@
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{prelude::*, utils::HashSet, window::PrimaryWindow};
+use bevy_egui::{egui, EguiContext, EguiContexts};
+use space_editor_core::prelude::*;
+use space_prefab::{
+    editor_registry::EditorRegistry,
+    load::spawn_serialized,
+    save::{serialize_entities, ChildrenPrefab},
+};
+use space_shared::*;
+use space_undo::{AddedEntity, NewChange};
+
+/// Plugin to copy/paste selected entities with Ctrl+C / Ctrl+V.
+/// Entities are stored as prefab RON string, so they can be pasted into other editor session
+pub struct EntityClipboardPlugin;
+
+impl Plugin for EntityClipboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EntityClipboard>();
+        app.init_resource::<ComponentClipboard>();
+        app.editor_hotkey(
+            ClipboardHotkey::Copy,
+            vec![KeyCode::ControlLeft, KeyCode::C],
+        );
+        app.editor_hotkey(
+            ClipboardHotkey::Paste,
+            vec![KeyCode::ControlLeft, KeyCode::V],
+        );
+        app.add_systems(Update, clipboard_hotkeys.in_set(EditorSet::Editor));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ClipboardHotkey {
+    Copy,
+    Paste,
+}
+
+impl Hotkey for ClipboardHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::Copy => "Copy entities".to_string(),
+            Self::Paste => "Paste entities".to_string(),
+        }
+    }
+}
+
+/// Last copied entities. Used if system clipboard is not available
+#[derive(Resource, Default)]
+pub struct EntityClipboard {
+    pub data: Option<String>,
+}
+
+/// Component value copied from inspector. Pasting it does not touch other components of target entity
+#[derive(Resource, Default)]
+pub struct ComponentClipboard {
+    pub data: Option<CopiedComponent>,
+}
+
+pub struct CopiedComponent {
+    pub type_id: TypeId,
+    pub name: String,
+    pub value: Box<dyn Reflect>,
+}
+
+impl ComponentClipboard {
+    /// Name of copied component type
+    pub fn name(&self) -> Option<&str> {
+        self.data.as_ref().map(|data| data.name.as_str())
+    }
+}
+
+fn clipboard_hotkeys(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    hotkeys: Res<Input<ClipboardHotkey>>,
+    clipboard: Res<EntityClipboard>,
+    active: Res<ActiveEntity>,
+    selected: Query<Entity, (With<Selected>, With<PrefabMarker>)>,
+    parents: Query<&Parent>,
+) {
+    let ctx = ctxs.ctx_mut();
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+
+    if hotkeys.just_pressed(ClipboardHotkey::Copy) {
+        // Descendants of selected entities are copied with them
+        let roots = selected
+            .iter()
+            .filter(|e| {
+                !parents
+                    .iter_ancestors(*e)
+                    .any(|ancestor| selected.contains(ancestor))
+            })
+            .collect::<Vec<_>>();
+        if !roots.is_empty() {
+            commands.add(move |world: &mut World| copy_entities(world, &roots));
+        }
+    }
+
+    if hotkeys.just_pressed(ClipboardHotkey::Paste) {
+        let system_clipboard = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) | egui::Event::Text(text) if text.len() > 1 => {
+                    Some(text.clone())
+                }
+                _ => None,
+            })
+        });
+        if let Some(data) = system_clipboard.or_else(|| clipboard.data.clone()) {
+            let parent = active.0.filter(|e| selected.contains(*e));
+            commands.add(move |world: &mut World| paste_entities(world, &data, parent));
+        }
+    }
+}
+
+/// Serialize entities to [`EntityClipboard`] and system clipboard
+pub fn copy_entities(world: &mut World, roots: &[Entity]) {
+    let data = match serialize_entities(world, roots) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("Failed to copy entities: {}", err);
+            return;
+        }
+    };
+
+    if let Ok(mut ctx) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    {
+        let data = data.clone();
+        ctx.get_mut().output_mut(|output| output.copied_text = data);
+    }
+    world.resource_mut::<EntityClipboard>().data = Some(data);
+    info!("Copied {} entities", roots.len());
+}
+
+/// Spawn copied entities under `parent` (or at root) and select them
+pub fn paste_entities(world: &mut World, data: &str, parent: Option<Entity>) {
+    let map = match spawn_serialized(world, data) {
+        Ok(map) => map,
+        Err(err) => {
+            warn!("Clipboard does not contain entities: {}", err);
+            return;
+        }
+    };
+
+    let children = map
+        .values()
+        .filter_map(|e| world.get::<ChildrenPrefab>(*e))
+        .flat_map(|children| children.0.iter().copied())
+        .collect::<HashSet<_>>();
+    let roots = map
+        .values()
+        .copied()
+        .filter(|e| !children.contains(e))
+        .collect::<Vec<_>>();
+
+    let mut selected = world.query_filtered::<Entity, With<Selected>>();
+    for e in selected.iter(world).collect::<Vec<_>>() {
+        world.entity_mut(e).remove::<Selected>();
+    }
+    for root in roots.iter() {
+        if let Some(parent) = parent {
+            world.entity_mut(parent).add_child(*root);
+        }
+        world.entity_mut(*root).insert(Selected);
+    }
+
+    for entity in map.values() {
+        world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: *entity }),
+        });
+    }
+    info!("Pasted {} entities", map.len());
+}
+
+/// Store registered component of entity in [`ComponentClipboard`]
+pub fn copy_component(world: &mut World, entity: Entity, type_id: TypeId, name: String) {
+    let registry = world.resource::<EditorRegistry>().clone();
+    let Some(value) = world
+        .get_entity(entity)
+        .and_then(|entity| registry.copy_component(&entity, &type_id))
+    else {
+        warn!("Failed to copy component {}", name);
+        return;
+    };
+    info!("Copied component {}", name);
+    world.resource_mut::<ComponentClipboard>().data = Some(CopiedComponent {
+        type_id,
+        name,
+        value,
+    });
+}
+
+/// Apply component from [`ComponentClipboard`] to entities.
+/// Change is detected by auto undo of registered components, so paste can be undone
+pub fn paste_component(world: &mut World, entities: &[Entity]) {
+    let Some((type_id, value)) = world
+        .resource::<ComponentClipboard>()
+        .data
+        .as_ref()
+        .map(|data| (data.type_id, data.value.clone_value()))
+    else {
+        return;
+    };
+    let registry = world.resource::<EditorRegistry>().clone();
+    for entity in entities {
+        if !registry.paste_component(world, *entity, &type_id, value.as_ref()) {
+            warn!("Failed to paste component to entity {:?}", entity);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use space_editor_core::prelude::*;
+use space_shared::*;
+use space_undo::{AddedEntity, NewChange};
+
+use crate::{
+    bottom_menu::BottomMenuState,
+    camera_plugin::focus_camera_on,
+    editor_tab::EditorTabName,
+    hierarchy::{auto_name_empty, clear_all_entities, spawn_bundle},
+    ui_plugin::EditorUi,
+    ui_registration::BundleReg,
+    UiSystemSet,
+};
+
+/// Max height of matched commands list, longer list is scrolled
+const PALETTE_HEIGHT: f32 = 300.0;
+
+/// Plugin for searchable list of editor actions, opened by Ctrl+P
+pub struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorCommands>();
+        app.init_resource::<CommandPaletteState>();
+        app.editor_hotkey(
+            CommandPaletteHotkey::Open,
+            vec![KeyCode::ControlLeft, KeyCode::P],
+        );
+
+        app.editor_command("Add new entity", |world| {
+            let id = world.spawn(PrefabMarker).id();
+            auto_name_empty(world, id);
+            world.send_event(NewChange {
+                change: Arc::new(AddedEntity { entity: id }),
+            });
+        })
+        .editor_command("Clear all entities", clear_all_entities)
+        .editor_command("Save scene", save_scene)
+        .editor_command("Focus selected", |world| {
+            let selected = world
+                .query_filtered::<Entity, With<Selected>>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            focus_camera_on(world, &selected);
+        });
+
+        app.add_systems(Update, command_palette.in_set(UiSystemSet));
+    }
+}
+
+/// Save scene to the path from bottom menu. Does nothing if the path is not set
+fn save_scene(world: &mut World) {
+    let Some(path) = world
+        .get_resource::<BottomMenuState>()
+        .map(|state| state.path.trim().to_string())
+    else {
+        return;
+    };
+    if path.is_empty() {
+        warn!("Save path is empty, set it in the bottom menu before saving");
+        return;
+    }
+    world.send_event(EditorEvent::Save(EditorPrefabPath::File(format!(
+        "{}.scn.ron",
+        path
+    ))));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum CommandPaletteHotkey {
+    Open,
+}
+
+impl Hotkey for CommandPaletteHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::Open => "Open command palette".to_string(),
+        }
+    }
+}
+
+pub type EditorCommandFn = Arc<dyn Fn(&mut World) + Send + Sync>;
+
+/// Named editor action shown in command palette
+#[derive(Clone)]
+pub struct EditorCommand {
+    pub name: String,
+    pub action: EditorCommandFn,
+}
+
+/// Actions registered by [`EditorCommandExt`]. Spawnable bundles from [`BundleReg`] and
+/// tab toggles are added to palette automatically and are not stored here
+#[derive(Resource, Default)]
+pub struct EditorCommands {
+    pub commands: Vec<EditorCommand>,
+}
+
+impl EditorCommands {
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        action: impl Fn(&mut World) + Send + Sync + 'static,
+    ) {
+        self.commands.push(EditorCommand {
+            name: name.into(),
+            action: Arc::new(action),
+        });
+    }
+}
+
+/// Trait for registering command palette actions via app.**
+pub trait EditorCommandExt {
+    /// Register action which runs with exclusive world access
+    fn editor_command(
+        &mut self,
+        name: &str,
+        action: impl Fn(&mut World) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Register one-shot system as action
+    fn editor_command_system<M>(
+        &mut self,
+        name: &str,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl EditorCommandExt for App {
+    fn editor_command(
+        &mut self,
+        name: &str,
+        action: impl Fn(&mut World) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(EditorCommands::default)
+            .add(name, action);
+        self
+    }
+
+    fn editor_command_system<M>(
+        &mut self,
+        name: &str,
+        system: impl IntoSystem<(), (), M> + 'static,
+    ) -> &mut Self {
+        let id = self.world.register_system(system);
+        let name_str = name.to_string();
+        self.editor_command(name, move |world| {
+            if let Err(err) = world.run_system(id) {
+                error!("Failed to run command {}: {:?}", name_str, err);
+            }
+        })
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CommandPaletteState {
+    pub open: bool,
+    pub query: String,
+    /// Index of highlighted command among matched ones
+    pub selected: usize,
+    /// Text field must grab keyboard focus in the next frame
+    focus: bool,
+}
+
+impl CommandPaletteState {
+    pub fn show(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+        self.focus = true;
+    }
+}
+
+/// Every word of `query` is contained in `name`, case insensitive
+fn command_matches(name: &str, query: &str) -> bool {
+    let name = name.to_lowercase();
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| name.contains(word))
+}
+
+pub(crate) fn tab_label(name: &EditorTabName) -> String {
+    match name {
+        EditorTabName::Other(name) => name.clone(),
+        name => format!("{:?}", name),
+    }
+}
+
+/// Registered commands, then bundle spawns and tab toggles
+fn palette_commands(
+    editor_commands: &EditorCommands,
+    bundles: &BundleReg,
+    editor_ui: &EditorUi,
+) -> Vec<EditorCommand> {
+    let mut commands = editor_commands.commands.clone();
+
+    for (category, category_bundles) in bundles.bundles.iter() {
+        for name in category_bundles.keys() {
+            let (category, bundle) = (category.clone(), name.clone());
+            commands.push(EditorCommand {
+                name: format!("Spawn {}/{}", category, bundle),
+                action: Arc::new(move |world| {
+                    spawn_bundle(world, &category, &bundle);
+                }),
+            });
+        }
+    }
+
+    let mut tabs = editor_ui.registry.keys().cloned().collect::<Vec<_>>();
+    tabs.sort();
+    for tab in tabs {
+        commands.push(EditorCommand {
+            name: format!("Toggle tab {}", tab_label(&tab)),
+            action: Arc::new(move |world| toggle_tab(world, tab.clone())),
+        });
+    }
+    commands
+}
+
+/// Close tab if it is open in dock, otherwise open it in focused node
+pub fn toggle_tab(world: &mut World, name: EditorTabName) {
+    let mut editor_ui = world.resource_mut::<EditorUi>();
+    if let Some(index) = editor_ui.tree.find_tab(&name) {
+        editor_ui.tree.remove_tab(index);
+    } else {
+        editor_ui.tree.push_to_focused_leaf(name);
+    }
+}
+
+fn command_palette(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    hotkeys: Res<Input<CommandPaletteHotkey>>,
+    mut state: ResMut<CommandPaletteState>,
+    editor_commands: Res<EditorCommands>,
+    bundles: Res<BundleReg>,
+    editor_ui: Res<EditorUi>,
+) {
+    if hotkeys.just_pressed(CommandPaletteHotkey::Open) {
+        if state.open {
+            state.open = false;
+        } else {
+            state.show();
+        }
+    }
+    if !state.open {
+        return;
+    }
+
+    let matches = palette_commands(&editor_commands, &bundles, &editor_ui)
+        .into_iter()
+        .filter(|command| command_matches(&command.name, &state.query))
+        .collect::<Vec<_>>();
+
+    let mut run = None;
+    let mut close = false;
+    egui::Window::new("Command palette")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+        .fixed_size([400.0, 0.0])
+        .show(ctxs.ctx_mut(), |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.query)
+                    .hint_text("Type command…")
+                    .desired_width(f32::INFINITY),
+            );
+            if state.focus {
+                response.request_focus();
+                state.focus = false;
+            }
+            if response.changed() {
+                state.selected = 0;
+            }
+
+            let mut moved = false;
+            ui.input(|i| {
+                if i.key_pressed(egui::Key::ArrowDown) {
+                    state.selected += 1;
+                    moved = true;
+                }
+                if i.key_pressed(egui::Key::ArrowUp) {
+                    state.selected = state.selected.saturating_sub(1);
+                    moved = true;
+                }
+                if i.key_pressed(egui::Key::Enter) {
+                    run = Some(state.selected);
+                }
+                if i.key_pressed(egui::Key::Escape) {
+                    close = true;
+                }
+            });
+            state.selected = state.selected.min(matches.len().saturating_sub(1));
+
+            ui.separator();
+            if matches.is_empty() {
+                ui.weak("No matching commands");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(PALETTE_HEIGHT)
+                .show(ui, |ui| {
+                    for (index, command) in matches.iter().enumerate() {
+                        let highlighted = index == state.selected;
+                        let response = ui.selectable_label(highlighted, &command.name);
+                        if highlighted && moved {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            run = Some(index);
+                        }
+                    }
+                });
+        });
+
+    if let Some(command) = run.and_then(|index| matches.get(index)) {
+        let action = command.action.clone();
+        commands.add(move |world: &mut World| action(world));
+        close = true;
+    }
+    if close {
+        state.open = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_matches() {
+        assert!(command_matches("Save scene", ""));
+        assert!(command_matches("Save scene", "save"));
+        assert!(command_matches("Save scene", "SCENE sa"));
+        assert!(!command_matches("Save scene", "load"));
+        assert!(!command_matches("Save scene", "save entity"));
+    }
+
+    #[test]
+    fn test_save_scene_requires_path() {
+        let mut app = App::new();
+        app.add_event::<EditorEvent>()
+            .init_resource::<BottomMenuState>();
+
+        save_scene(&mut app.world);
+        assert!(app.world.resource::<Events<EditorEvent>>().is_empty());
+
+        app.world.resource_mut::<BottomMenuState>().path = "  ".to_string();
+        save_scene(&mut app.world);
+        assert!(app.world.resource::<Events<EditorEvent>>().is_empty());
+
+        app.world.resource_mut::<BottomMenuState>().path = "level".to_string();
+        save_scene(&mut app.world);
+        let events = app.world.resource::<Events<EditorEvent>>();
+        let saved = events
+            .iter_current_update_events()
+            .filter_map(|event| match event {
+                EditorEvent::Save(EditorPrefabPath::File(path)) => Some(path.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(saved, vec!["level.scn.ron".to_string()]);
+    }
+}
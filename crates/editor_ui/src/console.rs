@@ -0,0 +1,324 @@
+use std::{collections::VecDeque, fmt::Write, sync::Mutex};
+
+use bevy::{
+    log::{
+        tracing_subscriber::{layer::Context, layer::SubscriberExt, Layer},
+        Level,
+    },
+    prelude::*,
+    utils::tracing::{
+        field::{Field, Visit},
+        Event, Subscriber,
+    },
+};
+use bevy_egui::egui;
+
+use super::{
+    editor_tab::{EditorTab, EditorTabName},
+    EditorUiAppExt,
+};
+
+/// Max count of stored log lines, the oldest ones are dropped
+pub const CONSOLE_CAPACITY: usize = 5000;
+
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+/// Log lines captured by [`ConsoleLayer`]. Global, because subscriber is created before the app world
+static CONSOLE_LOG: Mutex<ConsoleLog> = Mutex::new(ConsoleLog {
+    lines: VecDeque::new(),
+    next_id: 0,
+});
+
+/// Plugin for "Console" tab with captured `tracing` output.
+/// Events are captured only if [`console_subscriber`] is set in [`LogPlugin`](bevy::log::LogPlugin):
+/// ```ignore
+/// app.add_plugins(DefaultPlugins.set(LogPlugin {
+///     update_subscriber: Some(console_subscriber),
+///     ..default()
+/// }));
+/// ```
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_tab_by_trait(EditorTabName::Console, ConsoleTab::default());
+    }
+}
+
+/// One captured log event
+#[derive(Clone, Debug)]
+pub struct ConsoleLine {
+    /// Sequence number of line since app start, it is not reused after lines are dropped
+    pub id: u64,
+    pub level: Level,
+    pub target: String,
+    /// Message with structured fields appended as `key=value`
+    pub message: String,
+    /// Lowercase target and message, so search does not lowercase lines every frame
+    search_text: String,
+}
+
+impl ConsoleLine {
+    /// Line is shown with `level` filter and lowercase `search` query
+    fn matches(&self, level: Level, search: &str) -> bool {
+        self.level <= level && (search.is_empty() || self.search_text.contains(search))
+    }
+}
+
+impl std::fmt::Display for ConsoleLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.level, self.target, self.message)
+    }
+}
+
+/// `tracing` layer, which copies events to console buffer
+pub struct ConsoleLayer;
+
+impl<S: Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        push_line(
+            *metadata.level(),
+            metadata.target().to_string(),
+            visitor.message + &visitor.fields,
+        );
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+pub type BoxedSubscriber = Box<dyn Subscriber + Send + Sync + 'static>;
+
+/// Add [`ConsoleLayer`] to Bevy log subscriber. Use as `update_subscriber` of [`LogPlugin`](bevy::log::LogPlugin)
+pub fn console_subscriber(subscriber: BoxedSubscriber) -> BoxedSubscriber {
+    Box::new(subscriber.with(ConsoleLayer))
+}
+
+struct ConsoleLog {
+    /// Oldest first, ids are consecutive
+    lines: VecDeque<ConsoleLine>,
+    /// Id of the next pushed line
+    next_id: u64,
+}
+
+impl ConsoleLog {
+    fn get(&self, id: u64) -> Option<&ConsoleLine> {
+        let first = self.lines.front()?.id;
+        self.lines.get(id.checked_sub(first)? as usize)
+    }
+}
+
+fn push_line(level: Level, target: String, message: String) {
+    let Ok(mut log) = CONSOLE_LOG.lock() else {
+        return;
+    };
+    if log.lines.len() >= CONSOLE_CAPACITY {
+        log.lines.pop_front();
+    }
+    let id = log.next_id;
+    log.next_id += 1;
+    log.lines.push_back(ConsoleLine {
+        id,
+        level,
+        search_text: format!("{}\n{}", target, message).to_lowercase(),
+        target,
+        message,
+    });
+}
+
+/// Run `f` with captured lines (oldest first) without copying them.
+/// Console lock is held during the call, so `f` must not log
+pub fn with_console_lines<R>(f: impl FnOnce(&VecDeque<ConsoleLine>) -> R) -> Option<R> {
+    CONSOLE_LOG.lock().ok().map(|log| f(&log.lines))
+}
+
+pub fn clear_console() {
+    if let Ok(mut log) = CONSOLE_LOG.lock() {
+        log.lines.clear();
+    }
+}
+
+/// Tab with captured log lines, level filter and text search
+pub struct ConsoleTab {
+    /// The most verbose shown level
+    pub level: Level,
+    /// Case-insensitive filter by message and target
+    pub search: String,
+    /// Keep view scrolled to the newest line
+    pub follow: bool,
+    /// Level and lowercase search of `shown`
+    filter: Option<(Level, String)>,
+    /// Ids of lines passed the filter
+    shown: Vec<u64>,
+    /// Id of the first line not checked by the filter yet
+    checked: u64,
+}
+
+impl Default for ConsoleTab {
+    fn default() -> Self {
+        Self {
+            level: Level::INFO,
+            search: String::new(),
+            follow: true,
+            filter: None,
+            shown: vec![],
+            checked: 0,
+        }
+    }
+}
+
+impl ConsoleTab {
+    /// Filter only lines pushed since the last call. All lines are filtered again only
+    /// when level or search is changed
+    fn update_shown(&mut self, log: &ConsoleLog) {
+        let filter = (self.level, self.search.to_lowercase());
+        if self.filter.as_ref() != Some(&filter) {
+            self.shown.clear();
+            self.checked = 0;
+        }
+        let (level, search) = self.filter.insert(filter);
+
+        // Lines dropped by capacity limit or cleared
+        let first = log.lines.front().map_or(log.next_id, |line| line.id);
+        let dropped = self.shown.partition_point(|id| *id < first);
+        self.shown.drain(..dropped);
+
+        let skip = self.checked.saturating_sub(first) as usize;
+        self.shown.extend(
+            log.lines
+                .iter()
+                .skip(skip)
+                .filter(|line| line.matches(*level, search))
+                .map(|line| line.id),
+        );
+        self.checked = log.next_id;
+    }
+}
+
+fn level_color(level: Level, ui: &egui::Ui) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::LIGHT_RED,
+        Level::WARN => egui::Color32::YELLOW,
+        Level::INFO => ui.visuals().text_color(),
+        _ => ui.visuals().weak_text_color(),
+    }
+}
+
+impl EditorTab for ConsoleTab {
+    fn ui(&mut self, ui: &mut egui::Ui, _commands: &mut Commands, _world: &mut World) {
+        let mut copy = false;
+        ui.horizontal(|ui| {
+            egui::ComboBox::new("console_level", "")
+                .selected_text(self.level.to_string())
+                .show_ui(ui, |ui| {
+                    for level in LEVELS {
+                        ui.selectable_value(&mut self.level, level, level.to_string());
+                    }
+                });
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut self.search);
+            if !self.search.is_empty() && ui.button("✖").clicked() {
+                self.search.clear();
+            }
+            ui.checkbox(&mut self.follow, "Follow");
+            copy = ui
+                .button("Copy")
+                .on_hover_text("Copy shown lines to clipboard")
+                .clicked();
+            if ui.button("Clear").clicked() {
+                clear_console();
+            }
+        });
+        ui.separator();
+
+        let Ok(log) = CONSOLE_LOG.lock() else {
+            return;
+        };
+        self.update_shown(&log);
+        if copy {
+            let text = self
+                .shown
+                .iter()
+                .filter_map(|id| log.get(*id))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            ui.output_mut(|o| o.copied_text = text);
+        }
+        drop(log);
+
+        if self.shown.is_empty() {
+            ui.weak(
+                "No log lines. Console is filled only if `console_subscriber` is set in LogPlugin",
+            );
+            return;
+        }
+
+        // Only visible rows are built. They are copied out of the buffer, so the lock is
+        // not held while widgets are added
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let shown = &self.shown;
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .stick_to_bottom(self.follow)
+            .show_rows(ui, row_height, shown.len(), |ui, range| {
+                let Ok(log) = CONSOLE_LOG.lock() else {
+                    return;
+                };
+                let lines = shown[range]
+                    .iter()
+                    .filter_map(|id| log.get(*id).cloned())
+                    .collect::<Vec<_>>();
+                drop(log);
+                for line in lines {
+                    let text = egui::RichText::new(line.to_string())
+                        .monospace()
+                        .color(level_color(line.level, ui));
+                    let response = ui
+                        .add(
+                            egui::Label::new(text)
+                                .wrap(false)
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text("Click to copy line");
+                    if response.clicked() {
+                        ui.output_mut(|o| o.copied_text = line.to_string());
+                    }
+                }
+            });
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Console".into()
+    }
+}
@@ -1,6 +1,10 @@
+use std::any::TypeId;
+
 use super::editor_tab::*;
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
 use bevy_egui::egui;
+use space_editor_core::prelude::*;
+use space_prefab::editor_registry::EditorRegistry;
 use space_shared::ext::bevy_inspector_egui;
 
 #[derive(Resource)]
@@ -15,3 +19,160 @@ impl EditorTab for DebugWorldInspector {
         "Debug World Inspector".into()
     }
 }
+
+/// One row of [`EditorRegistryInspector`]
+struct RegistryRow {
+    type_id: TypeId,
+    name: String,
+    path: String,
+    shown: bool,
+    editor_only: bool,
+    clone: bool,
+    save: bool,
+}
+
+/// Read-only view of [`EditorRegistry`]: which component types are shown in inspector,
+/// copied by clone, saved to prefabs and skipped by runtime clone
+#[derive(Resource, Default)]
+pub struct EditorRegistryInspector {
+    filter: String,
+    selected: Option<TypeId>,
+}
+
+impl EditorRegistryInspector {
+    fn rows(world: &World) -> Vec<RegistryRow> {
+        let editor_registry = world.resource::<EditorRegistry>();
+        let registry = editor_registry.registry.read();
+        let app_registry = world.resource::<AppTypeRegistry>().read();
+
+        let mut type_ids = registry.iter().map(|r| r.type_id()).collect::<Vec<_>>();
+        type_ids.extend(editor_registry.clone_components.iter().map(|c| c.type_id));
+        type_ids.extend(editor_registry.editor_only.iter().copied());
+
+        let mut rows = type_ids
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|type_id| {
+                let registration = registry.get(type_id).or_else(|| app_registry.get(type_id));
+                let (name, path) = registration.map_or_else(
+                    || (format!("{:?}", type_id), String::new()),
+                    |r| {
+                        let table = r.type_info().type_path_table();
+                        (table.short_path().to_string(), table.path().to_string())
+                    },
+                );
+                // Prefab save extracts only types of editor registry with reflected component
+                let save = registry
+                    .get(type_id)
+                    .is_some_and(|r| r.data::<ReflectComponent>().is_some());
+                RegistryRow {
+                    type_id,
+                    name,
+                    path,
+                    shown: registry.get(type_id).is_some()
+                        && !editor_registry.silent.contains(&type_id),
+                    editor_only: editor_registry.editor_only.contains(&type_id),
+                    clone: editor_registry
+                        .clone_components
+                        .iter()
+                        .any(|c| c.type_id == type_id),
+                    save,
+                }
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+        rows
+    }
+
+    /// Entities with component of `type_id`
+    fn entities_with(world: &World, type_id: TypeId) -> Vec<(Entity, String)> {
+        let Some(component_id) = world.components().get_id(type_id) else {
+            return vec![];
+        };
+        world
+            .iter_entities()
+            .filter(|entity| entity.contains_id(component_id))
+            .map(|entity| {
+                let name = entity
+                    .get::<Name>()
+                    .map_or_else(|| format!("{:?}", entity.id()), |name| name.to_string());
+                (entity.id(), name)
+            })
+            .collect()
+    }
+}
+
+impl EditorTab for EditorRegistryInspector {
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        if !world.contains_resource::<EditorRegistry>() {
+            ui.label("Editor registry is not initialized");
+            return;
+        }
+        let rows = Self::rows(world);
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.text_edit_singleline(&mut self.filter);
+        });
+        let filter = self.filter.to_lowercase();
+        let rows = rows
+            .into_iter()
+            .filter(|row| {
+                filter.is_empty()
+                    || row.name.to_lowercase().contains(&filter)
+                    || row.path.to_lowercase().contains(&filter)
+            })
+            .collect::<Vec<_>>();
+        ui.label(format!("{} component types", rows.len()));
+        ui.separator();
+
+        let mark = |value: bool| if value { "✔" } else { "" };
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("editor_registry_grid")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Component");
+                    ui.strong("Inspector");
+                    ui.strong("Clone");
+                    ui.strong("Save");
+                    ui.strong("Editor only");
+                    ui.end_row();
+                    for row in rows.iter() {
+                        let is_selected = self.selected == Some(row.type_id);
+                        let mut label = ui.selectable_label(is_selected, &row.name);
+                        if !row.path.is_empty() {
+                            label = label.on_hover_text(&row.path);
+                        }
+                        if label.clicked() {
+                            self.selected = if is_selected { None } else { Some(row.type_id) };
+                        }
+                        ui.label(mark(row.shown));
+                        ui.label(mark(row.clone));
+                        ui.label(mark(row.save));
+                        ui.label(mark(row.editor_only));
+                        ui.end_row();
+                    }
+                });
+
+            let Some(row) = self
+                .selected
+                .and_then(|selected| rows.iter().find(|row| row.type_id == selected))
+            else {
+                return;
+            };
+            ui.separator();
+            let entities = Self::entities_with(world, row.type_id);
+            ui.heading(format!("Entities with {} ({})", row.name, entities.len()));
+            for (entity, name) in entities {
+                if ui.selectable_label(false, name).clicked() {
+                    commands.add(SelectEntities::new([entity], SelectMode::Replace));
+                }
+            }
+        });
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Editor Registry".into()
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+use crate::{editor_tab::EditorTabName, ui_plugin::EditorUi};
+
+/// Plugin to store dock layout (open tabs and split sizes) between editor sessions
+pub struct DockLayoutPlugin;
+
+impl Plugin for DockLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DockLayout>();
+        app.register_type::<DockLayout>();
+        // Persistence events exist only with persistence, layout is not stored without it
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<DockLayout>();
+            app.add_systems(
+                Update,
+                pack_dock_layout.before(PersistenceSet::ResourceProcess),
+            );
+            app.add_systems(
+                Update,
+                unpack_dock_layout.after(PersistenceSet::ResourceProcess),
+            );
+        }
+
+        app.add_event::<ResetDockLayout>();
+
+        app.add_systems(Startup, store_default_layout);
+        app.add_systems(Update, reset_dock_layout);
+    }
+}
+
+/// Serialized dock tree. Updated only before persistence saving
+#[derive(Resource, Reflect, Default, Clone)]
+#[reflect(Resource)]
+pub struct DockLayout {
+    pub layout: String,
+}
+
+/// Dock tree which was set up by plugins at startup. Used to reset layout
+#[derive(Resource)]
+pub struct DefaultDockLayout(pub egui_dock::DockState<EditorTabName>);
+
+/// Event to restore default dock layout
+#[derive(Event, Default)]
+pub struct ResetDockLayout;
+
+fn store_default_layout(mut commands: Commands, editor: Res<EditorUi>) {
+    commands.insert_resource(DefaultDockLayout(editor.tree.clone()));
+}
+
+#[cfg(feature = "persistence_editor")]
+fn pack_dock_layout(
+    mut events: EventReader<PersistenceEvent>,
+    editor: Res<EditorUi>,
+    mut layout: ResMut<DockLayout>,
+) {
+    for event in events.read() {
+        if let PersistenceEvent::Save = event {
+            match ron::to_string(&editor.tree) {
+                Ok(data) => layout.layout = data,
+                Err(err) => error!("Failed to serialize dock layout: {}", err),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "persistence_editor")]
+fn unpack_dock_layout(
+    mut events: EventReader<PersistenceLoaded<DockLayout>>,
+    layout: Res<DockLayout>,
+    mut editor: ResMut<EditorUi>,
+) {
+    if events.read().last().is_none() || layout.layout.is_empty() {
+        return;
+    }
+
+    let mut tree: egui_dock::DockState<EditorTabName> = match ron::from_str(&layout.layout) {
+        Ok(tree) => tree,
+        Err(err) => {
+            warn!(
+                "Failed to load dock layout, default layout is used: {}",
+                err
+            );
+            return;
+        }
+    };
+
+    // Tabs from removed or disabled plugins can be stored in layout
+    while let Some(index) = tree.find_tab_from(|tab| !editor.registry.contains_key(tab)) {
+        let removed = tree.remove_tab(index);
+        warn!(
+            "Tab {:?} is not registered and was removed from layout",
+            removed
+        );
+    }
+
+    editor.tree = tree;
+}
+
+fn reset_dock_layout(
+    mut events: EventReader<ResetDockLayout>,
+    default_layout: Option<Res<DefaultDockLayout>>,
+    mut editor: ResMut<EditorUi>,
+) {
+    if events.read().last().is_none() {
+        return;
+    }
+    if let Some(default_layout) = default_layout {
+        editor.tree = default_layout.0.clone();
+    }
+}
@@ -4,16 +4,24 @@ use bevy::{prelude::*, utils::HashMap};
 use bevy_egui::egui::{self, WidgetText};
 use convert_case::{Case, Casing};
 
-use super::{EditorUiRef, EditorUiReg};
+use super::{system_toggles::EditorSystemToggles, EditorUiRef, EditorUiReg};
 
+/// Trait for struct based editor tabs. Register implementation with
+/// [`EditorUiAppExt::editor_tab_by_trait`] or [`EditorUiAppExt::editor_tab_boxed`]
+///
+/// [`EditorUiAppExt::editor_tab_by_trait`]: crate::ui_plugin::EditorUiAppExt::editor_tab_by_trait
+/// [`EditorUiAppExt::editor_tab_boxed`]: crate::ui_plugin::EditorUiAppExt::editor_tab_boxed
 pub trait EditorTab {
     fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World);
     fn title(&self) -> egui::WidgetText;
 }
 
-#[derive(Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord)]
+#[derive(
+    Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub enum EditorTabName {
     CameraView,
+    Console,
     EventDispatcher,
     GameView,
     Hierarchy,
@@ -22,9 +30,13 @@ pub enum EditorTabName {
     RuntimeAssets,
     Settings,
     ToolBox,
+    UndoHistory,
+    /// User defined tab. String is used as tab name in "add tab" popup
     Other(String),
 }
 
+pub type BoxedEditorTab = Box<dyn EditorTab + Send + Sync>;
+
 pub type EditorTabShowFn = Box<dyn Fn(&mut egui::Ui, &mut Commands, &mut World) + Send + Sync>;
 pub type EditorTabGetTitleFn = Box<dyn Fn(&mut World) -> WidgetText + Send + Sync>;
 
@@ -48,6 +60,17 @@ impl<'a, 'w, 's> egui_dock::TabViewer for EditorTabViewer<'a, 'w, 's> {
     type Tab = EditorTabName;
 
     fn ui(&mut self, ui: &mut egui::Ui, tab_name: &mut Self::Tab) {
+        if let Some(mut toggles) = self.world.get_resource_mut::<EditorSystemToggles>() {
+            let toggles = toggles.bypass_change_detection();
+            toggles.drawn_tabs.insert(tab_name.clone());
+            if toggles.disabled_tabs.contains(tab_name) {
+                ui.weak("Redraw of this tab is disabled in Systems tab");
+                if ui.button("Enable").clicked() {
+                    toggles.disabled_tabs.remove(tab_name);
+                }
+                return;
+            }
+        }
         if let Some(reg) = self.registry.get_mut(tab_name) {
             match reg {
                 EditorUiReg::ResourceBased {
@@ -161,3 +184,9 @@ impl EditorTab for ScheduleEditorTab {
 
 #[derive(Resource, Default)]
 pub struct ScheduleEditorTabStorage(pub HashMap<EditorTabName, ScheduleEditorTab>);
+
+/// Storage for tabs registered with [`EditorUiAppExt::editor_tab_boxed`]
+///
+/// [`EditorUiAppExt::editor_tab_boxed`]: crate::ui_plugin::EditorUiAppExt::editor_tab_boxed
+#[derive(Resource, Default)]
+pub struct BoxedEditorTabStorage(pub HashMap<EditorTabName, BoxedEditorTab>);
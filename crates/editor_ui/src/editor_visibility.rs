@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use space_prefab::save::{serialize_scene, SaveState};
+use space_shared::*;
+use space_undo::OneFrameUndoIgnore;
+
+/// Plugin to hide entities in editor without changing visibility stored in prefab
+pub struct EditorVisibilityPlugin;
+
+impl Plugin for EditorVisibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SaveState::Save),
+            restore_saved_visibility.before(serialize_scene),
+        );
+        app.add_systems(OnEnter(SaveState::Idle), apply_editor_visibility);
+        app.add_systems(OnEnter(EditorState::GamePrepare), restore_saved_visibility);
+    }
+}
+
+/// Editor only visibility override. Entity is hidden in editor if `shown` is [`Visibility::Hidden`].
+/// Children of not cascaded hidden parent are forced to be [`Visibility::Visible`]
+#[derive(Component, Clone, Copy)]
+pub struct EditorVisibility {
+    /// Visibility which will be stored in prefab
+    pub saved: Visibility,
+    /// Visibility which is used while editing
+    pub shown: Visibility,
+}
+
+impl EditorVisibility {
+    pub fn is_hidden(&self) -> bool {
+        self.shown == Visibility::Hidden
+    }
+}
+
+/// Toggle editor only visibility of entity. If `cascade` is false, then children with
+/// [`Visibility::Inherited`] are kept visible and only entity itself is hidden.
+/// Changes are not recorded in undo chain
+pub fn toggle_hidden_in_editor(world: &mut World, entity: Entity, cascade: bool) {
+    let Some(visibility) = world.get::<Visibility>(entity).copied() else {
+        return;
+    };
+    let children = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+
+    if let Some(editor_visibility) = world.get::<EditorVisibility>(entity).copied() {
+        if editor_visibility.is_hidden() {
+            restore_visibility(world, entity, editor_visibility.saved);
+            for child in children {
+                match world.get::<EditorVisibility>(child).copied() {
+                    Some(child_visibility) if !child_visibility.is_hidden() => {
+                        restore_visibility(world, child, child_visibility.saved);
+                    }
+                    _ => {}
+                }
+            }
+            return;
+        }
+    }
+
+    let saved = world
+        .get::<EditorVisibility>(entity)
+        .map_or(visibility, |editor_visibility| editor_visibility.saved);
+    set_editor_visibility(world, entity, saved, Visibility::Hidden);
+
+    if !cascade {
+        for child in children {
+            if world.get::<EditorVisibility>(child).is_some() {
+                continue;
+            }
+            if let Some(Visibility::Inherited) = world.get::<Visibility>(child).copied() {
+                set_editor_visibility(world, child, Visibility::Inherited, Visibility::Visible);
+            }
+        }
+    }
+}
+
+fn set_editor_visibility(world: &mut World, entity: Entity, saved: Visibility, shown: Visibility) {
+    world.entity_mut(entity).insert((
+        EditorVisibility { saved, shown },
+        shown,
+        OneFrameUndoIgnore::default(),
+    ));
+}
+
+fn restore_visibility(world: &mut World, entity: Entity, saved: Visibility) {
+    world
+        .entity_mut(entity)
+        .remove::<EditorVisibility>()
+        .insert((saved, OneFrameUndoIgnore::default()));
+}
+
+/// Write saved visibility back before prefab serialization, so editor only hiding is not stored
+fn restore_saved_visibility(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Visibility, &EditorVisibility)>,
+) {
+    for (entity, mut visibility, editor_visibility) in query.iter_mut() {
+        *visibility = editor_visibility.saved;
+        commands
+            .entity(entity)
+            .insert(OneFrameUndoIgnore::default());
+    }
+}
+
+fn apply_editor_visibility(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Visibility, &EditorVisibility)>,
+) {
+    for (entity, mut visibility, editor_visibility) in query.iter_mut() {
+        if *visibility != editor_visibility.shown {
+            *visibility = editor_visibility.shown;
+            commands
+                .entity(entity)
+                .insert(OneFrameUndoIgnore::default());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use space_editor_core::prelude::*;
+use space_shared::*;
+
+use crate::{camera_plugin::focus_camera_on, UiSystemSet};
+
+/// Max height of matched entities list, longer list is scrolled
+const SEARCH_HEIGHT: f32 = 300.0;
+
+/// Plugin for global jump to prefab entity by name or id, opened by Ctrl+F
+pub struct EntitySearchPlugin;
+
+impl Plugin for EntitySearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EntitySearchState>();
+        app.editor_hotkey(
+            EntitySearchHotkey::Open,
+            vec![KeyCode::ControlLeft, KeyCode::F],
+        );
+
+        app.add_systems(Update, entity_search.in_set(UiSystemSet));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum EntitySearchHotkey {
+    Open,
+}
+
+impl Hotkey for EntitySearchHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::Open => "Jump to entity".to_string(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct EntitySearchState {
+    pub open: bool,
+    pub query: String,
+    /// Index of match which is jumped to by the next Enter
+    pub current: usize,
+    /// Text field must grab keyboard focus in the next frame
+    focus: bool,
+}
+
+impl EntitySearchState {
+    pub fn show(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.current = 0;
+        self.focus = true;
+    }
+}
+
+/// Entity matches `query` by id (`12` or `12v0`) or every word of `query` is contained in its name, case insensitive
+pub fn entity_search_matches(entity: Entity, name: Option<&Name>, query: &str) -> bool {
+    let query = query.trim();
+    if query.is_empty() {
+        return false;
+    }
+    if query == entity.index().to_string() || query == format!("{:?}", entity) {
+        return true;
+    }
+    let Some(name) = name else {
+        return false;
+    };
+    let name = name.as_str().to_lowercase();
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| name.contains(word))
+}
+
+/// Select only `entity` and frame it by editor camera. Hierarchy expands and scrolls to the selected entity by itself
+pub fn jump_to_entity(world: &mut World, entity: Entity) {
+    if world.get_entity(entity).is_none() {
+        return;
+    }
+    select_entities(world, &[entity], SelectMode::Replace);
+    focus_camera_on(world, &[entity]);
+}
+
+fn entity_search(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    hotkeys: Res<Input<EntitySearchHotkey>>,
+    mut state: ResMut<EntitySearchState>,
+    query: Query<(Entity, Option<&Name>), With<PrefabMarker>>,
+) {
+    if hotkeys.just_pressed(EntitySearchHotkey::Open) {
+        if state.open {
+            state.open = false;
+        } else {
+            state.show();
+        }
+    }
+    if !state.open {
+        return;
+    }
+
+    let mut matches = query
+        .iter()
+        .filter(|(entity, name)| entity_search_matches(*entity, *name, &state.query))
+        .map(|(entity, name)| {
+            let label = name.map_or_else(
+                || format!("Entity ({:?})", entity),
+                |name| format!("{} ({:?})", name.as_str(), entity),
+            );
+            (entity, label)
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut jump = None;
+    let mut close = false;
+    egui::Window::new("Jump to entity")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+        .fixed_size([400.0, 0.0])
+        .show(ctxs.ctx_mut(), |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.query)
+                    .hint_text("Entity name or id…")
+                    .desired_width(f32::INFINITY),
+            );
+            if state.focus {
+                response.request_focus();
+                state.focus = false;
+            }
+            if response.changed() {
+                state.current = 0;
+            }
+            state.current = state.current.min(matches.len().saturating_sub(1));
+
+            let mut moved = false;
+            ui.input(|i| {
+                if i.key_pressed(egui::Key::ArrowDown) {
+                    state.current += 1;
+                    moved = true;
+                }
+                if i.key_pressed(egui::Key::ArrowUp) {
+                    state.current = state.current.saturating_sub(1);
+                    moved = true;
+                }
+                if i.key_pressed(egui::Key::Escape) {
+                    close = true;
+                }
+            });
+            state.current = state.current.min(matches.len().saturating_sub(1));
+
+            // Enter moves focus out of the text field, so it is returned to cycle matches by repeated Enter
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                if !matches.is_empty() {
+                    jump = Some(state.current);
+                    let step = if ui.input(|i| i.modifiers.shift) {
+                        matches.len() - 1
+                    } else {
+                        1
+                    };
+                    state.current = (state.current + step) % matches.len();
+                    moved = true;
+                }
+                state.focus = true;
+            }
+
+            ui.horizontal(|ui| {
+                if state.query.trim().is_empty() {
+                    ui.weak("Enter to jump, Shift+Enter to go back, Esc to close");
+                } else if matches.is_empty() {
+                    ui.weak("No matching entities");
+                } else {
+                    ui.label(format!("{}/{} matches", state.current + 1, matches.len()));
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(SEARCH_HEIGHT)
+                .show(ui, |ui| {
+                    for (index, (_, label)) in matches.iter().enumerate() {
+                        let highlighted = index == state.current;
+                        let response = ui.selectable_label(highlighted, label);
+                        if highlighted && moved {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            jump = Some(index);
+                            state.current = index;
+                        }
+                    }
+                });
+        });
+
+    if let Some((entity, _)) = jump.and_then(|index| matches.get(index)) {
+        let entity = *entity;
+        commands.add(move |world: &mut World| jump_to_entity(world, entity));
+    }
+    if close {
+        state.open = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_search_matches() {
+        let entity = Entity::from_raw(12);
+        let name = Name::new("Red Street Lamp");
+
+        // Id and debug form of entity
+        assert!(entity_search_matches(entity, None, "12"));
+        assert!(entity_search_matches(entity, None, " 12v0 "));
+        assert!(!entity_search_matches(entity, None, "1"));
+
+        // Every word in any order, case insensitive
+        assert!(entity_search_matches(entity, Some(&name), "lamp RED"));
+        assert!(entity_search_matches(entity, Some(&name), "street"));
+        assert!(!entity_search_matches(entity, Some(&name), "red car"));
+
+        assert!(!entity_search_matches(entity, Some(&name), "  "));
+    }
+}
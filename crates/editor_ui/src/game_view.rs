@@ -1,19 +1,85 @@
-use bevy::{prelude::*, window::PrimaryWindow};
-use bevy_egui::egui::{self};
+use std::collections::VecDeque;
+
+use bevy::{pbr::wireframe::WireframeConfig, prelude::*, window::PrimaryWindow};
+use bevy_egui::{
+    egui::{self},
+    EguiSettings,
+};
 use egui_gizmo::GizmoMode;
+use space_editor_core::selected::Selected;
 use space_undo::UndoRedo;
 
 use space_shared::*;
 
-use crate::{prelude::EditorTabName, EditorUiAppExt};
+use crate::{
+    camera_plugin::EditorCameraFollow,
+    measure::MeasureState,
+    prelude::{EditorTabName, IsolateState, IsolationSettings},
+    EditorUiAppExt,
+};
 
 use super::{editor_tab::EditorTab, tool::EditorTool};
 
+/// Count of frames in frame time history of game view overlay
+pub const FRAME_HISTORY_LEN: usize = 120;
+
+/// Default weight of previous value in smoothed frame time
+pub const DEFAULT_FPS_SMOOTHING: f32 = 0.98;
+
+/// Exponential moving average of frame time. `smoothing` is weight of `previous` value,
+/// 0.0 shows raw frame time. First frame (`previous` is zero) starts from `dt`
+pub fn smooth_frame_time(previous: f32, dt: f32, smoothing: f32) -> f32 {
+    if previous <= 0.0 {
+        return dt;
+    }
+    let smoothing = smoothing.clamp(0.0, 1.0);
+    previous.mul_add(smoothing, dt * (1.0 - smoothing))
+}
+
+/// FPS label for smoothed frame time. Frame time is zero before the first measured frame
+pub fn fps_label(smoothed_dt: f32) -> String {
+    if smoothed_dt > 0.0 {
+        format!("FPS: {:.0}", 1.0 / smoothed_dt)
+    } else {
+        "FPS: —".to_string()
+    }
+}
+
 pub struct GameViewPlugin;
 
 impl Plugin for GameViewPlugin {
     fn build(&self, app: &mut App) {
         app.editor_tab_by_trait(EditorTabName::GameView, GameViewTab::default());
+        app.init_resource::<EditorViewportInfo>();
+        app.add_systems(Update, track_game_view_cursor.in_set(EditorSet::Editor));
+    }
+}
+
+/// Main editor viewport for custom overlays. Updated each frame when game view tab is drawn
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct EditorViewportInfo {
+    /// Game view rect in egui coordinates. `None` when game view tab is not shown
+    pub rect: Option<egui::Rect>,
+    /// Editor camera, which renders to the viewport
+    pub camera: Option<Entity>,
+    /// Projection and view matrix of editor camera
+    pub clip_from_world: Mat4,
+}
+
+impl EditorViewportInfo {
+    /// Position of world point in egui coordinates. `None` for points behind the camera or without viewport.
+    /// Points outside the camera frustum are projected outside of [`Self::rect`]
+    pub fn world_to_egui(&self, point: Vec3) -> Option<egui::Pos2> {
+        let rect = self.rect?;
+        let clip = self.clip_from_world * point.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        Some(egui::pos2(
+            rect.left() + (ndc.x + 1.0) / 2.0 * rect.width(),
+            rect.top() + (1.0 - ndc.y) / 2.0 * rect.height(),
+        ))
     }
 }
 
@@ -24,6 +90,16 @@ pub struct GameViewTab {
     pub active_tool: Option<usize>,
     pub gizmo_mode: GizmoMode,
     pub smoothed_dt: f32,
+    /// Weight of previous value in [`Self::smoothed_dt`]. Higher is smoother, but slower to react
+    pub fps_smoothing: f32,
+    /// Stop updating FPS and frame time history, so values of a hitch can be read
+    pub freeze_frame_stats: bool,
+    /// Show FPS and frame time overlay
+    pub show_frame_stats: bool,
+    /// Ring buffer with last [`FRAME_HISTORY_LEN`] frame times in seconds
+    pub dt_history: VecDeque<f32>,
+    /// Last cursor position (in egui points) inside game view
+    pub last_cursor: Option<egui::Pos2>,
 }
 
 impl Default for GameViewTab {
@@ -32,6 +108,11 @@ impl Default for GameViewTab {
             viewport_rect: None,
             gizmo_mode: GizmoMode::Translate,
             smoothed_dt: 0.0,
+            fps_smoothing: DEFAULT_FPS_SMOOTHING,
+            freeze_frame_stats: false,
+            show_frame_stats: true,
+            dt_history: VecDeque::with_capacity(FRAME_HISTORY_LEN),
+            last_cursor: None,
             tools: vec![],
             active_tool: None,
         }
@@ -51,14 +132,63 @@ impl EditorTab for GameViewTab {
         }
 
         self.viewport_rect = Some(ui.clip_rect());
+        update_viewport_info(world, self.viewport_rect);
 
         //Draw FPS
         let dt = world.get_resource::<Time>().unwrap().delta_seconds();
-        self.smoothed_dt = self.smoothed_dt.mul_add(0.98, dt * 0.02);
-        ui.colored_label(
-            egui::Color32::WHITE,
-            format!("FPS: {:.0}", 1.0 / self.smoothed_dt),
-        );
+        if !self.freeze_frame_stats {
+            self.smoothed_dt = smooth_frame_time(self.smoothed_dt, dt, self.fps_smoothing);
+            if self.dt_history.len() == FRAME_HISTORY_LEN {
+                self.dt_history.pop_front();
+            }
+            self.dt_history.push_back(dt);
+        }
+
+        ui.toggle_value(&mut self.show_frame_stats, "⏱ Frame stats");
+        if self.show_frame_stats {
+            self.frame_stats_ui(ui, dt);
+        }
+
+        if let Some(mut follow) = world.get_resource_mut::<EditorCameraFollow>() {
+            follow.ui(ui);
+        }
+
+        // Write settings back only on change to not rebuild dimmed materials every frame
+        if let Some(mut isolation) = world.get_resource::<IsolationSettings>().cloned() {
+            isolation.ui(ui);
+            if world.resource::<IsolationSettings>() != &isolation {
+                world.insert_resource(isolation);
+            }
+        }
+
+        if world.contains_resource::<IsolateState>() {
+            let active = world.resource::<IsolateState>().is_active();
+            if ui
+                .selectable_label(active, "◎ Isolate")
+                .on_hover_text("Hide everything except selection (Shift + H)")
+                .clicked()
+            {
+                let selected = world
+                    .query_filtered::<Entity, With<Selected>>()
+                    .iter(world)
+                    .collect::<Vec<_>>();
+                world.resource_mut::<IsolateState>().toggle(selected);
+            }
+        }
+
+        if let Some(mut config) = world.get_resource_mut::<WireframeConfig>() {
+            if ui
+                .selectable_label(config.global, "▦ Wireframe")
+                .on_hover_text("Render all meshes as wireframe")
+                .clicked()
+            {
+                config.global = !config.global;
+            }
+        }
+
+        if let Some(mut measure) = world.get_resource_mut::<MeasureState>() {
+            measure.ui(ui);
+        }
 
         //Tool processing
         if self.tools.is_empty() {
@@ -95,10 +225,121 @@ impl EditorTab for GameViewTab {
     }
 }
 
+impl GameViewTab {
+    fn frame_stats_ui(&mut self, ui: &mut egui::Ui, dt: f32) {
+        let worst_dt = self.dt_history.iter().copied().fold(0.0, f32::max);
+        ui.horizontal(|ui| {
+            ui.colored_label(egui::Color32::WHITE, fps_label(self.smoothed_dt));
+            ui.toggle_value(&mut self.freeze_frame_stats, "❄")
+                .on_hover_text("Freeze FPS and frame time history");
+            ui.add(
+                egui::Slider::new(&mut self.fps_smoothing, 0.0..=0.995)
+                    .text("Smoothing")
+                    .fixed_decimals(3),
+            )
+            .on_hover_text("Weight of previous frames. Higher is more stable, lower reacts faster");
+        });
+        ui.colored_label(
+            egui::Color32::WHITE,
+            format!(
+                "Frame: {:.1} ms  Worst: {:.1} ms",
+                dt * 1000.0,
+                worst_dt * 1000.0
+            ),
+        );
+
+        // Sparkline of frame times, scaled by the worst frame in history
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 24.0), egui::Sense::hover());
+        ui.painter()
+            .rect_filled(rect, 2.0, egui::Color32::from_black_alpha(120));
+        if worst_dt > 0.0 && self.dt_history.len() > 1 {
+            let step = rect.width() / (FRAME_HISTORY_LEN - 1) as f32;
+            let points = self
+                .dt_history
+                .iter()
+                .enumerate()
+                .map(|(index, dt)| {
+                    egui::pos2(
+                        (index as f32).mul_add(step, rect.left()),
+                        rect.bottom() - rect.height() * dt / worst_dt,
+                    )
+                })
+                .collect();
+            ui.painter().add(egui::Shape::line(
+                points,
+                egui::Stroke::new(1.0, egui::Color32::LIGHT_GREEN),
+            ));
+        }
+    }
+}
+
+fn update_viewport_info(world: &mut World, rect: Option<egui::Rect>) {
+    let camera = world
+        .query_filtered::<(Entity, &Camera, &GlobalTransform), With<EditorCameraMarker>>()
+        .iter(world)
+        .find(|(_, camera, _)| camera.is_active)
+        .map(|(entity, camera, transform)| {
+            (
+                entity,
+                camera.projection_matrix() * transform.compute_matrix().inverse(),
+            )
+        });
+    let mut info = world.resource_mut::<EditorViewportInfo>();
+    info.rect = rect;
+    info.camera = camera.map(|(entity, _)| entity);
+    if let Some((_, clip_from_world)) = camera {
+        info.clip_from_world = clip_from_world;
+    }
+}
+
+fn track_game_view_cursor(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    egui_settings: Res<EguiSettings>,
+    mut game_view: ResMut<GameViewTab>,
+) {
+    let Some(cursor) = windows
+        .get_single()
+        .ok()
+        .and_then(|window| egui_cursor_position(window, &egui_settings))
+    else {
+        return;
+    };
+    let Some(viewport) = game_view.viewport_rect else {
+        return;
+    };
+    if viewport.contains(cursor) {
+        game_view.last_cursor = Some(cursor);
+    }
+}
+
+/// Size of egui point in physical pixels, same as used by [`set_camera_viewport`]
+fn egui_scale_factor(window: &Window, egui_settings: &EguiSettings) -> f32 {
+    (window.scale_factor() * egui_settings.scale_factor) as f32
+}
+
+/// Cursor position in egui points, the space of [`GameViewTab::viewport_rect`]
+pub fn egui_cursor_position(window: &Window, egui_settings: &EguiSettings) -> Option<egui::Pos2> {
+    let cursor = window.physical_cursor_position()? / egui_scale_factor(window, egui_settings);
+    Some(egui::pos2(cursor.x, cursor.y))
+}
+
+/// Convert egui position inside game view `viewport` to camera viewport position in logical pixels,
+/// which is expected by [`Camera::viewport_to_world`]
+pub fn egui_to_viewport(
+    pos: egui::Pos2,
+    viewport: egui::Rect,
+    window: &Window,
+    egui_settings: &EguiSettings,
+) -> Vec2 {
+    let offset = (pos - viewport.left_top()) * egui_scale_factor(window, egui_settings);
+    Vec2::new(offset.x, offset.y) / window.scale_factor() as f32
+}
+
 pub fn reset_camera_viewport(
     primary_window: Query<&mut Window, With<PrimaryWindow>>,
     mut cameras: Query<&mut Camera, With<EditorCameraMarker>>,
     mut game_view_tab: ResMut<GameViewTab>,
+    mut viewport_info: ResMut<EditorViewportInfo>,
 ) {
     let mut cam = cameras.single_mut();
 
@@ -107,6 +348,7 @@ pub fn reset_camera_viewport(
     };
 
     game_view_tab.viewport_rect = None;
+    viewport_info.rect = None;
 
     cam.viewport = Some(bevy::render::camera::Viewport {
         physical_position: UVec2::new(0, 0),
@@ -145,16 +387,28 @@ pub fn set_camera_viewport(
     if local.0 == Some(viewport_rect) {
         return;
     }
-    local.0 = Some(viewport_rect);
 
     let scale_factor = window.scale_factor() * egui_settings.scale_factor;
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
 
     let viewport_pos = viewport_rect.left_top().to_vec2() * scale_factor as f32;
     let viewport_size = viewport_rect.size() * scale_factor as f32;
+    let viewport_pos = UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32);
+    let viewport_size = UVec2::new(viewport_size.x as u32, viewport_size.y as u32);
+
+    // Minimized window or collapsed tab has nothing to render to. Old viewport is kept until tab is visible again
+    if window_size.cmplt(UVec2::ONE).any()
+        || viewport_size.cmplt(UVec2::ONE).any()
+        || viewport_pos.cmpge(window_size).any()
+    {
+        return;
+    }
+    local.0 = Some(viewport_rect);
 
     cam.viewport = Some(bevy::render::camera::Viewport {
-        physical_position: UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32),
-        physical_size: UVec2::new(viewport_size.x as u32, viewport_size.y as u32),
+        physical_position: viewport_pos,
+        // Rounding must not move viewport out of the window
+        physical_size: viewport_size.min(window_size - viewport_pos),
         depth: 0.0..1.0,
     });
 }
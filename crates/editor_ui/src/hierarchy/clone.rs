@@ -0,0 +1,762 @@
+use super::*;
+
+/// Event to clone entity with clone all registered components.
+/// By default clone is attached to the parent of `id` as its last child, so cloned child stays a sibling of the original.
+/// With `as_root` clone is spawned without parent and keeps world transform of the original
+#[derive(Event)]
+pub struct CloneEvent {
+    pub id: Entity,
+    /// Skip components marked as editor only in [`EditorRegistry`] to get clean runtime copy
+    pub skip_editor_only: bool,
+    /// Share mesh and material assets with the original instead of copying them, see [`LinkedAssets`]
+    pub linked: bool,
+    /// Spawn clone as root entity instead of attaching it to the parent of the original
+    pub as_root: bool,
+}
+
+/// Settings of "Duplicate in line/grid" dialog
+pub struct DuplicateArrayState {
+    /// Copies are placed in `columns` x `rows` grid, otherwise `count` copies are placed in line
+    pub grid: bool,
+    /// Number of copies in line, original is not counted
+    pub count: usize,
+    /// Grid dimensions, original takes the first cell
+    pub columns: usize,
+    pub rows: usize,
+    /// Translation between neighbour copies in line or grid row
+    pub column_step: Vec3,
+    /// Translation between grid rows
+    pub row_step: Vec3,
+}
+
+impl Default for DuplicateArrayState {
+    fn default() -> Self {
+        Self {
+            grid: false,
+            count: 5,
+            columns: 3,
+            rows: 3,
+            column_step: Vec3::X * 2.0,
+            row_step: Vec3::Z * 2.0,
+        }
+    }
+}
+
+impl DuplicateArrayState {
+    /// Columns and rows passed to [`duplicate_array`]. Line of `count` copies is one row of `count + 1` cells
+    pub fn dimensions(&self) -> (usize, usize) {
+        if self.grid {
+            (self.columns, self.rows)
+        } else {
+            (self.count + 1, 1)
+        }
+    }
+}
+
+pub(super) fn duplicate_array_window(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    state: &mut HierarchyTabState,
+) {
+    let Some(array) = state.duplicate_array.as_mut() else {
+        return;
+    };
+    let mut close = false;
+    egui::Window::new("Duplicate in line/grid")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ui.ctx(), |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut array.grid, false, "Line");
+                ui.selectable_value(&mut array.grid, true, "Grid");
+            });
+            if array.grid {
+                ui.horizontal(|ui| {
+                    ui.label("Columns:");
+                    ui.add(egui::DragValue::new(&mut array.columns).clamp_range(1..=100));
+                    ui.label("Rows:");
+                    ui.add(egui::DragValue::new(&mut array.rows).clamp_range(1..=100));
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    ui.label("Copies:");
+                    ui.add(egui::DragValue::new(&mut array.count).clamp_range(1..=1000));
+                });
+            }
+            step_ui(
+                ui,
+                if array.grid { "Column step:" } else { "Step:" },
+                &mut array.column_step,
+            );
+            if array.grid {
+                step_ui(ui, "Row step:", &mut array.row_step);
+            }
+
+            let (columns, rows) = array.dimensions();
+            let copies = (columns * rows).saturating_sub(1);
+            ui.label(format!("{} copies of each selected entity", copies));
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(copies > 0, egui::Button::new("Duplicate"))
+                    .clicked()
+                {
+                    let (column_step, row_step) = (array.column_step, array.row_step);
+                    commands.add(move |world: &mut World| {
+                        let selected = world
+                            .query_filtered::<Entity, (With<Selected>, With<PrefabMarker>)>()
+                            .iter(world)
+                            .collect::<Vec<_>>();
+                        duplicate_array(world, &selected, columns, rows, column_step, row_step);
+                    });
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+    if close {
+        state.duplicate_array = None;
+    }
+}
+
+fn step_ui(ui: &mut egui::Ui, label: &str, step: &mut Vec3) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(egui::DragValue::new(&mut step.x).speed(0.1).prefix("x: "));
+        ui.add(egui::DragValue::new(&mut step.y).speed(0.1).prefix("y: "));
+        ui.add(egui::DragValue::new(&mut step.z).speed(0.1).prefix("z: "));
+    });
+}
+
+/// Marker of just cloned entity to record it in undo chain.
+/// It is not reflected, so it can't be registered in [`EditorRegistry`] and copied by clone
+#[derive(Component)]
+pub struct ClonedEntity;
+
+pub(super) fn clone_enitites(
+    mut commands: Commands,
+    query: Query<EntityRef>,
+    mut events: EventReader<CloneEvent>,
+    editor_registry: Res<EditorRegistry>,
+) {
+    let mut groups: HashMap<CloneOptions, Vec<Entity>> = HashMap::new();
+    for event in events.read() {
+        if !query
+            .get(event.id)
+            .is_ok_and(|entity| entity.contains::<PrefabMarker>())
+        {
+            warn!("Editor entity {:?} can not be cloned", event.id);
+            continue;
+        }
+        let assets = if event.linked {
+            AssetCloneMode::Linked
+        } else {
+            AssetCloneMode::Deep
+        };
+        let options = CloneOptions {
+            skip_editor_only: event.skip_editor_only,
+            assets,
+            as_root: event.as_root,
+        };
+        groups.entry(options).or_default().push(event.id);
+    }
+
+    for (options, ids) in groups {
+        for root in clone_roots(&query, &ids) {
+            let clone = clone_subtree(&mut commands, &query, &editor_registry, root, options);
+            debug!("Cloned {:?} to {:?}", root, clone);
+        }
+    }
+}
+
+/// Ctrl+D: clone selected entities, move clones by [`DuplicateSettings::offset`] and select them instead of originals.
+/// Alt+D does the same with clones linked to assets of originals
+pub(super) fn duplicate_selected(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    hotkeys: Res<Input<HierarchyHotkey>>,
+    settings: Res<DuplicateSettings>,
+    query: Query<EntityRef>,
+    selected: Query<Entity, (With<Selected>, With<PrefabMarker>)>,
+    editor_registry: Res<EditorRegistry>,
+) {
+    let assets = if hotkeys.just_pressed(HierarchyHotkey::Duplicate) {
+        AssetCloneMode::Deep
+    } else if hotkeys.just_pressed(HierarchyHotkey::DuplicateLinked) {
+        AssetCloneMode::Linked
+    } else {
+        return;
+    };
+    if ctxs.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let ids = selected.iter().collect::<Vec<_>>();
+    for root in clone_roots(&query, &ids) {
+        let new_root = clone_subtree(
+            &mut commands,
+            &query,
+            &editor_registry,
+            root,
+            CloneOptions {
+                assets,
+                ..default()
+            },
+        );
+        if let Some(transform) = query.get(root).ok().and_then(|e| e.get::<Transform>()) {
+            let mut transform = *transform;
+            transform.translation += settings.offset;
+            commands.entity(new_root).insert(transform);
+        }
+        commands.entity(new_root).insert(Selected);
+    }
+    for e in ids {
+        commands.entity(e).remove::<Selected>();
+    }
+}
+
+/// Clone `entities` into `columns` x `rows` grid. Original takes cell (0, 0), copy in cell (column, row)
+/// is moved by `column * column_step + row * row_step` from original. Copies are attached to the parent of original
+/// and recorded as one undo step. [`Locked`] entities are not copied. Returns cloned roots
+pub fn duplicate_array(
+    world: &mut World,
+    entities: &[Entity],
+    columns: usize,
+    rows: usize,
+    column_step: Vec3,
+    row_step: Vec3,
+) -> Vec<Entity> {
+    let entities = entities
+        .iter()
+        .copied()
+        .filter(|entity| world.get::<Locked>(*entity).is_none())
+        .collect::<Vec<_>>();
+    let copies = (columns * rows).saturating_sub(1);
+    if entities.is_empty() || copies == 0 {
+        return vec![];
+    }
+
+    // Cloned entities are recorded by `detect_cloned_entities` in later frames, group latency keeps them in this step
+    begin_change_group(world, format!("Duplicate {} times", copies));
+    let mut clones = vec![];
+    let mut state = SystemState::<(Commands, Query<EntityRef>, Res<EditorRegistry>)>::new(world);
+    {
+        let (mut commands, query, editor_registry) = state.get_mut(world);
+        for root in clone_roots(&query, &entities) {
+            let transform = query
+                .get(root)
+                .ok()
+                .and_then(|e| e.get::<Transform>().copied());
+            for row in 0..rows {
+                for column in 0..columns {
+                    if row == 0 && column == 0 {
+                        continue;
+                    }
+                    let clone = clone_subtree(
+                        &mut commands,
+                        &query,
+                        &editor_registry,
+                        root,
+                        CloneOptions::default(),
+                    );
+                    if let Some(mut transform) = transform {
+                        transform.translation +=
+                            column as f32 * column_step + row as f32 * row_step;
+                        commands.entity(clone).insert(transform);
+                    }
+                    clones.push(clone);
+                }
+            }
+        }
+    }
+    state.apply(world);
+    end_change_group(world);
+    clones
+}
+
+/// Entities inside other cloned entities are cloned with them, so only top level ones are returned
+pub(crate) fn clone_roots(query: &Query<EntityRef>, ids: &[Entity]) -> Vec<Entity> {
+    ids.iter()
+        .copied()
+        .filter(|id| {
+            let mut ancestor = query
+                .get(*id)
+                .ok()
+                .and_then(|e| e.get::<Parent>().map(Parent::get));
+            while let Some(parent) = ancestor {
+                if ids.contains(&parent) {
+                    return false;
+                }
+                ancestor = query
+                    .get(parent)
+                    .ok()
+                    .and_then(|e| e.get::<Parent>().map(Parent::get));
+            }
+            true
+        })
+        .collect()
+}
+
+/// How [`clone_subtree`] copies entities. Default is editor clone with own assets, attached to the original parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) struct CloneOptions {
+    /// Clone by [`EditorRegistry::clone_entity_runtime`] instead of [`EditorRegistry::clone_entity_flat`],
+    /// so editor only components are not copied
+    pub skip_editor_only: bool,
+    /// How mesh and material assets are handled
+    pub assets: AssetCloneMode,
+    /// Spawn clone as root with world transform of original instead of attaching it to the same parent
+    pub as_root: bool,
+}
+
+/// Clone entity with all [`PrefabMarker`] descendants as set by `options`.
+/// [`Entity`] fields of cloned components, which point inside the subtree, are remapped to cloned entities.
+/// Returns id of cloned root
+pub(crate) fn clone_subtree(
+    commands: &mut Commands,
+    query: &Query<EntityRef>,
+    editor_registry: &EditorRegistry,
+    root: Entity,
+    options: CloneOptions,
+) -> Entity {
+    let new_root = commands.spawn_empty().id();
+    if let Some(parent) = query.get(root).ok().and_then(|e| e.get::<Parent>()) {
+        if !options.as_root {
+            commands.entity(parent.get()).add_child(new_root);
+        }
+    }
+    let mut queue = vec![(root, new_root)];
+    let mut map = HashMap::new();
+
+    while let Some((src_id, dst_id)) = queue.pop() {
+        if let Ok(entity) = query.get(src_id) {
+            if entity.contains::<PrefabMarker>() {
+                map.insert(src_id, dst_id);
+                let mut cmds = commands.entity(dst_id);
+                cmds.insert(ClonedEntity);
+
+                if options.skip_editor_only {
+                    editor_registry.clone_entity_runtime(&mut cmds, &entity);
+                    if options.assets == AssetCloneMode::Linked {
+                        link_assets(&mut cmds, &entity);
+                    }
+                } else {
+                    editor_registry.clone_entity_flat_with_mode(&mut cmds, &entity, options.assets);
+                }
+
+                // Cloned children are attached at once to keep order of source children
+                if let Some(children) = entity.get::<Children>() {
+                    let cloned_children = children
+                        .iter()
+                        .filter(|id| {
+                            query
+                                .get(**id)
+                                .map_or(false, |child| child.contains::<PrefabMarker>())
+                        })
+                        .map(|id| (*id, commands.spawn_empty().id()))
+                        .collect::<Vec<_>>();
+                    commands.entity(dst_id).push_children(
+                        &cloned_children
+                            .iter()
+                            .map(|(_, cloned)| *cloned)
+                            .collect::<Vec<_>>(),
+                    );
+                    queue.extend(cloned_children);
+                }
+            }
+        }
+    }
+    // References inside cloned subtree are moved to clones, external references are kept
+    commands.add(move |world: &mut World| {
+        world.resource_scope::<EditorRegistry, _>(|world, editor_registry| {
+            editor_registry.remap_entity_references(world, &map);
+        });
+    });
+    // Cloned local transform is replaced after all cloned components are inserted
+    if options.as_root {
+        if let Some(global) = query
+            .get(root)
+            .ok()
+            .filter(|e| e.contains::<Transform>())
+            .and_then(|e| e.get::<GlobalTransform>())
+        {
+            commands.entity(new_root).insert(global.compute_transform());
+        }
+    }
+    new_root
+}
+
+pub(super) fn detect_cloned_entities(
+    mut commands: Commands,
+    query: Query<Entity, Added<ClonedEntity>>,
+    mut changes: EventWriter<NewChange>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).remove::<ClonedEntity>();
+        changes.send(NewChange {
+            change: Arc::new(AddedEntity { entity }),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_prefab::editor_registry::EditorRegistryPlugin;
+    use space_undo::UndoPlugin;
+
+    #[test]
+    fn test_clone_keeps_children_order() {
+        let mut app = clone_test_app();
+        let names = ["first", "second", "third"];
+        let parent = app.world.spawn((PrefabMarker, Name::new("parent"))).id();
+        for name in names {
+            let child = app.world.spawn((PrefabMarker, Name::new(name))).id();
+            app.world.entity_mut(parent).add_child(child);
+        }
+
+        app.world.send_event(CloneEvent {
+            id: parent,
+            skip_editor_only: false,
+            linked: false,
+            as_root: false,
+        });
+        app.update();
+
+        let clone = find_clone(&mut app, parent, "parent");
+        let children = app.world.get::<Children>(clone).unwrap().to_vec();
+        assert_ne!(
+            children,
+            app.world.get::<Children>(parent).unwrap().to_vec()
+        );
+
+        let cloned_names = children
+            .iter()
+            .map(|child| app.world.get::<Name>(*child).unwrap().as_str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(cloned_names, names);
+    }
+
+    fn clone_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(EditorRegistryPlugin)
+            .add_event::<CloneEvent>()
+            .add_systems(Update, clone_enitites);
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<Name>();
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<Transform>();
+        app
+    }
+
+    fn find_clone(app: &mut App, original: Entity, name: &str) -> Entity {
+        let mut query = app.world.query::<(Entity, &Name)>();
+        query
+            .iter(&app.world)
+            .find(|(entity, entity_name)| *entity != original && entity_name.as_str() == name)
+            .map(|(entity, _)| entity)
+            .expect("Entity must be cloned")
+    }
+
+    #[test]
+    fn test_clone_for_runtime_skips_editor_only_components() {
+        let mut app = clone_test_app();
+        app.editor_clone_registry::<Pinned>()
+            .editor_only::<Pinned>()
+            .editor_clone_registry::<EditorNote>()
+            .editor_only::<EditorNote>();
+        let lamp = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("lamp"),
+                Pinned,
+                EditorNote("check light".to_string()),
+            ))
+            .id();
+
+        app.world.send_event(CloneEvent {
+            id: lamp,
+            skip_editor_only: false,
+            linked: false,
+            as_root: false,
+        });
+        app.update();
+        let clone = find_clone(&mut app, lamp, "lamp");
+        assert!(app.world.get::<Pinned>(clone).is_some());
+        assert_eq!(
+            app.world.get::<EditorNote>(clone),
+            Some(&EditorNote("check light".to_string()))
+        );
+        app.world.despawn(clone);
+
+        app.world.send_event(CloneEvent {
+            id: lamp,
+            skip_editor_only: true,
+            linked: false,
+            as_root: false,
+        });
+        app.update();
+        let runtime_clone = find_clone(&mut app, lamp, "lamp");
+        assert!(app.world.get::<Pinned>(runtime_clone).is_none());
+        assert!(app.world.get::<EditorNote>(runtime_clone).is_none());
+    }
+
+    #[test]
+    fn test_clone_attaches_to_original_parent() {
+        let mut app = clone_test_app();
+        let parent = app.world.spawn((PrefabMarker, Name::new("parent"))).id();
+        let child = app.world.spawn((PrefabMarker, Name::new("child"))).id();
+        app.world.entity_mut(parent).add_child(child);
+
+        app.world.send_event(CloneEvent {
+            id: child,
+            skip_editor_only: false,
+            linked: false,
+            as_root: false,
+        });
+        app.update();
+
+        let clone = find_clone(&mut app, child, "child");
+        assert_eq!(
+            app.world.get::<Parent>(clone).map(Parent::get),
+            Some(parent)
+        );
+        assert_eq!(
+            app.world.get::<Children>(parent).unwrap().to_vec(),
+            vec![child, clone]
+        );
+    }
+
+    #[test]
+    fn test_clone_as_root_keeps_world_transform() {
+        let mut app = clone_test_app();
+        let parent_transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let child_transform = Transform::from_xyz(0.0, 1.0, 0.0);
+        let parent = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("parent"),
+                parent_transform,
+                GlobalTransform::from(parent_transform),
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("child"),
+                child_transform,
+                GlobalTransform::from(parent_transform * child_transform),
+            ))
+            .id();
+        app.world.entity_mut(parent).add_child(child);
+
+        app.world.send_event(CloneEvent {
+            id: child,
+            skip_editor_only: false,
+            linked: false,
+            as_root: true,
+        });
+        app.update();
+
+        let clone = find_clone(&mut app, child, "child");
+        assert!(app.world.get::<Parent>(clone).is_none());
+        assert_eq!(
+            app.world.get::<Children>(parent).unwrap().to_vec(),
+            vec![child]
+        );
+        assert_eq!(
+            app.world.get::<Transform>(clone).unwrap().translation,
+            Vec3::new(1.0, 3.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_array_in_grid() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_systems(Update, detect_cloned_entities);
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<Transform>();
+
+        let parent = app.world.spawn(PrefabMarker).id();
+        let cube = app
+            .world
+            .spawn((PrefabMarker, Transform::from_xyz(1.0, 0.0, 0.0)))
+            .id();
+        let locked = app
+            .world
+            .spawn((PrefabMarker, Locked, Transform::default()))
+            .id();
+        app.world.entity_mut(parent).push_children(&[cube, locked]);
+        app.update();
+        let steps = space_undo::undo_len(&app.world);
+
+        let clones = duplicate_array(&mut app.world, &[cube, locked], 3, 2, Vec3::X, Vec3::Z);
+        app.update();
+
+        assert_eq!(clones.len(), 5);
+        assert_eq!(app.world.get::<Children>(parent).unwrap().len(), 7);
+        let mut translations = clones
+            .iter()
+            .map(|e| {
+                assert_eq!(app.world.get::<Parent>(*e).unwrap().get(), parent);
+                app.world.get::<Transform>(*e).unwrap().translation
+            })
+            .collect::<Vec<_>>();
+        translations.sort_by(|a, b| (a.z, a.x).partial_cmp(&(b.z, b.x)).unwrap());
+        assert_eq!(
+            translations,
+            vec![
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(3.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 1.0),
+                Vec3::new(2.0, 0.0, 1.0),
+                Vec3::new(3.0, 0.0, 1.0),
+            ]
+        );
+
+        // All copies are removed by one undo
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(space_undo::undo_len(&app.world), steps + 1);
+        space_undo::undo(&mut app.world);
+        app.update();
+        assert!(clones.iter().all(|e| app.world.get_entity(*e).is_none()));
+        assert_eq!(app.world.get::<Children>(parent).unwrap().len(), 2);
+    }
+
+    #[derive(Component, Reflect, Clone)]
+    #[reflect(Component)]
+    struct LookAt {
+        target: Entity,
+        others: Vec<Entity>,
+    }
+
+    impl Default for LookAt {
+        fn default() -> Self {
+            Self {
+                target: Entity::PLACEHOLDER,
+                others: vec![],
+            }
+        }
+    }
+
+    #[test]
+    fn test_clone_remaps_entity_references() {
+        let mut app = clone_test_app();
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<LookAt>();
+
+        let external = app.world.spawn(PrefabMarker).id();
+        let parent = app.world.spawn((PrefabMarker, Name::new("parent"))).id();
+        let child = app.world.spawn((PrefabMarker, Name::new("child"))).id();
+        app.world.entity_mut(parent).add_child(child);
+        app.world.entity_mut(parent).insert(LookAt {
+            target: child,
+            others: vec![external, parent],
+        });
+
+        app.world.send_event(CloneEvent {
+            id: parent,
+            skip_editor_only: false,
+            linked: false,
+            as_root: false,
+        });
+        app.update();
+
+        let cloned_parent = find_clone(&mut app, parent, "parent");
+        let cloned_child = find_clone(&mut app, child, "child");
+        let look_at = app.world.get::<LookAt>(cloned_parent).unwrap();
+        assert_eq!(look_at.target, cloned_child);
+        assert_eq!(look_at.others, vec![external, cloned_parent]);
+        // Original is not changed
+        assert_eq!(app.world.get::<LookAt>(parent).unwrap().target, child);
+    }
+
+    #[test]
+    fn test_find_references_to_subtree() {
+        let mut app = clone_test_app();
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<LookAt>();
+
+        let parent = app.world.spawn((PrefabMarker, Name::new("parent"))).id();
+        let child = app.world.spawn((PrefabMarker, Name::new("child"))).id();
+        app.world.entity_mut(parent).add_child(child);
+        app.world.entity_mut(parent).insert(LookAt {
+            target: child,
+            others: vec![],
+        });
+        let camera = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("camera"),
+                LookAt {
+                    target: child,
+                    others: vec![],
+                },
+            ))
+            .id();
+
+        // Reference inside deleted subtree is not reported
+        let references = find_references(&app.world, &[parent]);
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].0.entity, camera);
+        assert_eq!(references[0].0.target, child);
+        assert_eq!(references[0].1, "camera");
+
+        assert!(find_references(&app.world, &[camera]).is_empty());
+    }
+
+    #[test]
+    fn test_delete_of_referenced_entity_waits_for_confirmation() {
+        let mut app = clone_test_app();
+        app.add_plugins(UndoPlugin)
+            .init_resource::<HierarchyTrash>()
+            .init_resource::<HierarchyTabState>();
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<LookAt>();
+
+        let target = app.world.spawn((PrefabMarker, Name::new("target"))).id();
+        let camera = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("camera"),
+                LookAt {
+                    target,
+                    others: vec![],
+                },
+            ))
+            .id();
+        app.update();
+
+        // Delete key and gizmo use the same path as hierarchy menu
+        request_delete(&mut app.world, vec![target], false);
+        app.update();
+        assert!(app.world.get_entity(target).is_some());
+        let state = app.world.resource::<HierarchyTabState>();
+        assert_eq!(state.confirm_delete, Some(vec![target]));
+        assert_eq!(state.delete_references.len(), 1);
+        assert_eq!(state.delete_references[0].0.entity, camera);
+
+        request_delete(&mut app.world, vec![camera], false);
+        app.update();
+        assert!(app.world.get_entity(camera).is_none());
+    }
+}
@@ -0,0 +1,1638 @@
+use super::*;
+
+/// Event to flatten the subtree of entity: all descendants are reparented to `id`
+/// (or to world root if `to_world` is set) with world transforms baked into local ones
+#[derive(Event)]
+pub struct FlattenHierarchyEvent {
+    pub id: Entity,
+    pub to_world: bool,
+}
+
+/// Event to reparent all selected entities to [`ActiveEntity`] with keeping their world transforms
+#[derive(Event)]
+pub struct ParentToActiveEvent;
+
+/// Rename all selected entities by pattern in order of hierarchy tab rows, which follows its [`HierarchySortMode`].
+/// All renames are collected in one undo step
+pub fn batch_rename_selected(world: &mut World, pattern: &str, start: usize, padding: usize) {
+    let mut query = world.query_filtered::<Entity, With<Selected>>();
+    let selected: HashSet<Entity> = query.iter(world).collect();
+    if selected.is_empty() {
+        return;
+    }
+
+    let (sort_mode, spawn_order) = world
+        .get_resource::<HierarchyTabState>()
+        .map(|state| (state.sort_mode, state.spawn_order.clone()))
+        .unwrap_or_default();
+    let mut roots = world
+        .query_filtered::<Entity, Without<Parent>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    let sort_key = |entity: &Entity| sort_mode.sort_key(*entity, world.get(*entity), &spawn_order);
+    roots.sort_by_cached_key(sort_key);
+    let mut ordered = vec![];
+    let mut stack = roots.into_iter().rev().collect::<Vec<_>>();
+    while let Some(entity) = stack.pop() {
+        if selected.contains(&entity) {
+            ordered.push(entity);
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            let mut children = children.to_vec();
+            if sort_mode != HierarchySortMode::Hierarchy {
+                children.sort_by_cached_key(sort_key);
+            }
+            stack.extend(children.into_iter().rev());
+        }
+    }
+
+    begin_change_group(world, "Rename selected");
+    for (idx, entity) in ordered.into_iter().enumerate() {
+        let name = batch_rename_name(pattern, start + idx, padding);
+        world.entity_mut(entity).insert(Name::new(name));
+    }
+    end_change_group(world);
+}
+
+/// Give new empty entity unique name from [`AutoNameSettings`] like "Empty", "Empty.001", ...
+pub fn auto_name_empty(world: &mut World, entity: Entity) {
+    let Some(settings) = world.get_resource::<AutoNameSettings>() else {
+        return;
+    };
+    if !settings.enabled {
+        return;
+    }
+    let base_name = settings.base_name.clone();
+
+    let mut query = world.query_filtered::<&Name, With<PrefabMarker>>();
+    let existing = query
+        .iter(world)
+        .map(|name| name.as_str().to_string())
+        .collect::<HashSet<_>>();
+
+    let mut name = base_name.clone();
+    let mut counter = 0;
+    while existing.contains(&name) {
+        counter += 1;
+        name = format!("{}.{:03}", base_name, counter);
+    }
+
+    if let Some(mut e) = world.get_entity_mut(entity) {
+        e.insert(Name::new(name));
+    }
+}
+
+/// Make entity a child of new parent. Drops which would create a hierarchy loop are rejected.
+/// Parent change is recorded in undo chain as one [`ReparentEntity`] change
+pub(super) fn reparent_entity(world: &mut World, entity: Entity, new_parent: Entity, snap: bool) {
+    if !can_attach(world, new_parent, entity) {
+        warn!("Can not move {:?} inside own descendant", entity);
+        return;
+    }
+
+    record_reparent_in_place(world, entity, snap, |world| {
+        world.entity_mut(new_parent).add_child(entity);
+    });
+}
+
+/// `child` can be made a child of `parent` without hierarchy loop:
+/// `parent` is neither `child` itself nor one of its descendants
+pub fn can_attach(world: &World, parent: Entity, child: Entity) -> bool {
+    let mut ancestor = Some(parent);
+    while let Some(id) = ancestor {
+        if id == child {
+            return false;
+        }
+        ancestor = world.get::<Parent>(id).map(Parent::get);
+    }
+    true
+}
+
+/// "Attach to" action: make `entities` children of `parent`, each reparent is one undo step.
+/// Entities which would create hierarchy loop are skipped and reported in hierarchy tab.
+/// With `snap` entities are placed at parent origin, see [`HierarchySettings::parent_snap_step`]
+pub fn attach_to(world: &mut World, parent: Entity, entities: &[Entity], snap: bool) {
+    let mut rejected = vec![];
+    for e in entities.iter().copied() {
+        if !can_attach(world, parent, e) {
+            rejected.push(entity_label(world, e));
+            continue;
+        }
+        record_reparent_in_place(world, e, snap, |world| {
+            world.entity_mut(parent).add_child(e);
+        });
+    }
+
+    if !rejected.is_empty() {
+        let warning = format!(
+            "Can not attach {} to {}: it would create a hierarchy loop",
+            rejected.join(", "),
+            entity_label(world, parent)
+        );
+        warn!("{}", warning);
+        if let Some(mut state) = world.get_resource_mut::<HierarchyTabState>() {
+            state.attach_warning = Some(warning);
+        }
+    }
+}
+
+fn entity_label(world: &World, entity: Entity) -> String {
+    world.get::<Name>(entity).map_or_else(
+        || format!("Entity ({:?})", entity),
+        |name| name.as_str().to_string(),
+    )
+}
+
+/// Reflected [`Entity`] references to `entities` and their descendants from other prefab entities
+/// with names of referencing entities. Used to warn what will break on delete
+pub fn find_references(world: &World, entities: &[Entity]) -> Vec<(EntityReference, String)> {
+    let mut subtree = HashSet::new();
+    let mut queue = entities.to_vec();
+    while let Some(entity) = queue.pop() {
+        if subtree.insert(entity) {
+            if let Some(children) = world.get::<Children>(entity) {
+                queue.extend(children.iter());
+            }
+        }
+    }
+    world
+        .resource::<EditorRegistry>()
+        .find_entity_references(world, &subtree)
+        .into_iter()
+        .map(|reference| {
+            let name = entity_label(world, reference.entity);
+            (reference, name)
+        })
+        .collect()
+}
+
+/// Rows of referencing entities, click selects the entity
+pub(super) fn references_list(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    references: &[(EntityReference, String)],
+) {
+    egui::ScrollArea::vertical()
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for (reference, name) in references {
+                if ui
+                    .link(format!("{} ({})", name, reference.component))
+                    .on_hover_text(format!("Points to {:?}", reference.target))
+                    .clicked()
+                {
+                    let entity = reference.entity;
+                    commands.add(move |world: &mut World| {
+                        select_entities(world, &[entity], SelectMode::Replace);
+                    });
+                }
+            }
+        });
+}
+
+/// Move entity to the position before/after sibling in parent [`Children`].
+/// If sibling has other parent, then entity will be reparented. Move is recorded as one [`ReparentEntity`] change
+pub(super) fn move_near_sibling(
+    world: &mut World,
+    entity: Entity,
+    sibling: Entity,
+    after: bool,
+    snap: bool,
+) {
+    let mut ancestor = world.get::<Parent>(sibling).map(Parent::get);
+    while let Some(id) = ancestor {
+        if id == entity {
+            warn!("Can not move {:?} inside own descendant", entity);
+            return;
+        }
+        ancestor = world.get::<Parent>(id).map(Parent::get);
+    }
+
+    record_reparent_in_place(world, entity, snap, |world| {
+        if let Some(parent) = world.get::<Parent>(sibling).map(Parent::get) {
+            let siblings = world
+                .get::<Children>(parent)
+                .map(|children| {
+                    children
+                        .iter()
+                        .copied()
+                        .filter(|child| *child != entity)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let mut index = siblings
+                .iter()
+                .position(|child| *child == sibling)
+                .unwrap_or(siblings.len());
+            if after {
+                index += 1;
+            }
+            world.entity_mut(parent).insert_children(index, &[entity]);
+        } else {
+            world.entity_mut(entity).remove_parent();
+        }
+    });
+}
+
+pub(super) fn hierarchy_entity_context(
+    ui: &mut egui::Ui,
+    commands: &mut Commands<'_, '_>,
+    entity: Entity,
+    changes: &mut EventWriter<'_, NewChange>,
+    clone_events: &mut EventWriter<'_, CloneEvent>,
+    selected: &mut Query<'_, '_, Entity, With<Selected>>,
+    parent: Option<&Parent>,
+    children: Option<&Children>,
+    locked: bool,
+    pinned: bool,
+    tag: Option<HierarchyTag>,
+    note: Option<&str>,
+    instance: Option<bool>,
+    linked: bool,
+    scene_entity: bool,
+    read_only: bool,
+    running: bool,
+    copied_component: Option<&str>,
+) {
+    // Content of read-only prefab instance is changed only after unpack
+    let internal_edit = scene_entity && !read_only;
+    let internal_hint = if scene_entity {
+        READ_ONLY_HINT
+    } else {
+        EDITOR_ENTITY_HINT
+    };
+    let editable = internal_edit && !locked;
+    let disabled_hint = if internal_edit {
+        "Entity is locked"
+    } else {
+        internal_hint
+    };
+    // Delete and clone would change scene under running game
+    let scene_edit = editable && !running;
+    let scene_edit_hint = if editable {
+        RUNNING_HINT
+    } else {
+        disabled_hint
+    };
+    if ui.button(if pinned { "Unpin" } else { "Pin" }).clicked() {
+        if pinned {
+            commands.entity(entity).remove::<Pinned>();
+        } else {
+            commands.entity(entity).insert(Pinned);
+        }
+        ui.close_menu();
+    }
+    if ui
+        .button(if note.is_some() {
+            "Edit note…"
+        } else {
+            "Add note…"
+        })
+        .clicked()
+    {
+        let text = note.unwrap_or_default().to_string();
+        commands.add(move |world: &mut World| {
+            world.resource_mut::<HierarchyTabState>().editing_note = Some((entity, text));
+        });
+        ui.close_menu();
+    }
+    ui.add_enabled_ui(internal_edit, |ui| {
+        ui.menu_button("Tag", |ui| {
+            for new_tag in HIERARCHY_TAGS {
+                let text = egui::RichText::new(format!("■ {}", new_tag.to_string()))
+                    .color(new_tag.color());
+                if ui.selectable_label(tag == Some(new_tag), text).clicked() {
+                    let targets = context_targets(entity, selected);
+                    commands.add(move |world: &mut World| {
+                        set_hierarchy_tag(world, &targets, Some(new_tag));
+                    });
+                    ui.close_menu();
+                }
+            }
+            if ui
+                .add_enabled(tag.is_some(), egui::Button::new("None"))
+                .clicked()
+            {
+                let targets = context_targets(entity, selected);
+                commands.add(move |world: &mut World| set_hierarchy_tag(world, &targets, None));
+                ui.close_menu();
+            }
+        })
+    })
+    .response
+    .on_disabled_hover_text(internal_hint);
+    if ui.button("Toggle wireframe").clicked() {
+        let targets = context_targets(entity, selected);
+        commands.add(move |world: &mut World| toggle_wireframe(world, &targets));
+        ui.close_menu();
+    }
+    if ui.button("Focus camera").clicked() {
+        let targets = context_targets(entity, selected);
+        commands.add(move |world: &mut World| focus_camera_on(world, &targets));
+        ui.close_menu();
+    }
+    if ui
+        .button("Ping in viewport")
+        .on_hover_text("Highlight entity in viewport without moving camera")
+        .clicked()
+    {
+        for target in context_targets(entity, selected) {
+            commands.entity(target).insert(Ping::default());
+        }
+        ui.close_menu();
+    }
+    if ui
+        .button("Find references")
+        .on_hover_text("List entities, which components point to this entity or its children")
+        .clicked()
+    {
+        commands.add(move |world: &mut World| {
+            let references = find_references(world, &[entity]);
+            world.resource_mut::<HierarchyTabState>().references =
+                Some((entity_label(world, entity), references));
+        });
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(internal_edit, egui::Button::new("Add child"))
+        .on_disabled_hover_text(internal_hint)
+        .clicked()
+    {
+        commands.add(move |world: &mut World| {
+            add_child_with_undo(world, entity);
+        });
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(internal_edit, egui::Button::new("Add sibling"))
+        .on_disabled_hover_text(internal_hint)
+        .clicked()
+    {
+        let new_id = commands.spawn_empty().insert(PrefabMarker).id();
+        if let Some(parent) = parent {
+            commands.entity(parent.get()).add_child(new_id);
+        }
+        commands.add(move |world: &mut World| auto_name_empty(world, new_id));
+        changes.send(NewChange {
+            change: Arc::new(AddedEntity { entity: new_id }),
+        });
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(scene_edit, egui::Button::new("Delete"))
+        .on_disabled_hover_text(scene_edit_hint)
+        .clicked()
+    {
+        let targets = context_targets(entity, selected);
+        commands.add(move |world: &mut World| request_delete(world, targets, true));
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(scene_edit, egui::Button::new("Clone"))
+        .on_disabled_hover_text(scene_edit_hint)
+        .clicked()
+    {
+        for id in context_targets(entity, selected) {
+            clone_events.send(CloneEvent {
+                id,
+                skip_editor_only: false,
+                linked: false,
+                as_root: false,
+            });
+        }
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(scene_edit, egui::Button::new("Clone for runtime"))
+        .on_hover_text("Clone without editor only components, such as pins and notes")
+        .on_disabled_hover_text(scene_edit_hint)
+        .clicked()
+    {
+        for id in context_targets(entity, selected) {
+            clone_events.send(CloneEvent {
+                id,
+                skip_editor_only: true,
+                linked: false,
+                as_root: false,
+            });
+        }
+        ui.close_menu();
+    }
+    if parent.is_some()
+        && ui
+            .add_enabled(scene_edit, egui::Button::new("Clone as root"))
+            .on_hover_text("Clone without parent, keeping world position")
+            .on_disabled_hover_text(scene_edit_hint)
+            .clicked()
+    {
+        for id in context_targets(entity, selected) {
+            clone_events.send(CloneEvent {
+                id,
+                skip_editor_only: false,
+                linked: false,
+                as_root: true,
+            });
+        }
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(scene_edit, egui::Button::new("Duplicate (linked)"))
+        .on_hover_text("Clone sharing mesh and material with the original")
+        .on_disabled_hover_text(scene_edit_hint)
+        .clicked()
+    {
+        for id in context_targets(entity, selected) {
+            clone_events.send(CloneEvent {
+                id,
+                skip_editor_only: false,
+                linked: true,
+                as_root: false,
+            });
+        }
+        ui.close_menu();
+    }
+    if linked && ui.button("Unlink assets").clicked() {
+        commands.add(move |world: &mut World| unlink_assets(world, entity));
+        ui.close_menu();
+    }
+    if !selected.is_empty()
+        && !selected.contains(entity)
+        && ui
+            .add_enabled(internal_edit, egui::Button::new("Attach to"))
+            .on_hover_text("Hold Alt to snap attached entities to parent origin")
+            .on_disabled_hover_text(internal_hint)
+            .clicked()
+    {
+        let targets = selected.iter().collect::<Vec<_>>();
+        let snap = ui.input(|i| i.modifiers.alt);
+        commands.add(move |world: &mut World| attach_to(world, entity, &targets, snap));
+    }
+    if ui
+        .add_enabled(parent.is_some(), egui::Button::new("Select parent"))
+        .on_hover_text("Hold Shift to add parent to selection")
+        .clicked()
+    {
+        let add = ui.input(|i| i.modifiers.shift);
+        let targets = context_targets(entity, selected);
+        commands.add(move |world: &mut World| {
+            select_parent(world, &targets, add);
+        });
+        ui.close_menu();
+    }
+    if children.is_some_and(|children| !children.is_empty())
+        && ui.button("Select subtree").clicked()
+    {
+        let add = ui.input(|i| i.modifiers.shift);
+        commands.add(move |world: &mut World| select_subtree(world, entity, add));
+        ui.close_menu();
+    }
+    if selected.contains(entity)
+        && selected.iter().nth(1).is_some()
+        && ui
+            .add_enabled(internal_edit, egui::Button::new("Rename selected…"))
+            .on_disabled_hover_text(internal_hint)
+            .clicked()
+    {
+        commands.add(|world: &mut World| {
+            world.resource_mut::<HierarchyTabState>().batch_rename =
+                Some(BatchRenameState::default());
+        });
+        ui.close_menu();
+    }
+    if selected.contains(entity)
+        && ui
+            .add_enabled(scene_edit, egui::Button::new("Duplicate in line/grid…"))
+            .on_hover_text("Spawn copies of selected entities with incremented position")
+            .on_disabled_hover_text(scene_edit_hint)
+            .clicked()
+    {
+        commands.add(|world: &mut World| {
+            world.resource_mut::<HierarchyTabState>().duplicate_array =
+                Some(DuplicateArrayState::default());
+        });
+        ui.close_menu();
+    }
+    if let Some(editable) = instance {
+        instance_menu(ui, commands, entity, editable);
+    }
+    if ui
+        .add_enabled(scene_entity, egui::Button::new("Save as prefab…"))
+        .on_disabled_hover_text(EDITOR_ENTITY_HINT)
+        .clicked()
+    {
+        commands.add(move |world: &mut World| {
+            let mut dialog = egui_file::FileDialog::save_file(Some("assets/".into()))
+                .default_filename(format!("{}.scn.ron", prefab_file_name(world, entity)))
+                .title("Save as prefab (*.scn.ron)");
+            dialog.open();
+            world.resource_mut::<HierarchyTabState>().save_prefab = Some((entity, dialog));
+        });
+        ui.close_menu();
+    }
+    if selected.contains(entity)
+        && ui
+            .add_enabled(internal_edit, egui::Button::new("Group selected"))
+            .on_disabled_hover_text(internal_hint)
+            .clicked()
+    {
+        commands.add(group_selected);
+        ui.close_menu();
+    }
+    if selected.iter().nth(1).is_some() && ui.button("Parent selected to active").clicked() {
+        commands.add(|world: &mut World| world.send_event(ParentToActiveEvent));
+        ui.close_menu();
+    }
+    if let Some(copied) = copied_component {
+        if ui
+            .add_enabled(editable, egui::Button::new(format!("Paste {}", copied)))
+            .on_disabled_hover_text(disabled_hint)
+            .clicked()
+        {
+            let targets = context_targets(entity, selected);
+            commands.add(move |world: &mut World| paste_component(world, &targets));
+            ui.close_menu();
+        }
+    }
+    if selected.contains(entity) && selected.iter().nth(1).is_some() {
+        ui.menu_button("Align", |ui| {
+            for (axis, axis_name) in ["X", "Y", "Z"].into_iter().enumerate() {
+                ui.menu_button(axis_name, |ui| {
+                    for mode in ALIGN_MODES {
+                        if ui.button(mode.to_string()).clicked() {
+                            commands.add(move |world: &mut World| {
+                                align_selected(world, axis, mode);
+                            });
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
+        });
+    }
+    // Reparent actions are the last ones, so they are greyed out together for editor entities
+    // and content of read-only prefab instances
+    ui.set_enabled(internal_edit);
+    if parent.is_some() {
+        ui.menu_button("Detach", |ui| {
+            if ui.button("Detach (keep world position)").clicked() {
+                commands.add(move |world: &mut World| {
+                    record_reparent(world, entity, |world| {
+                        let global = world.get::<GlobalTransform>(entity).copied();
+                        world.entity_mut(entity).remove_parent();
+                        if let Some(global) = global {
+                            world.entity_mut(entity).insert(global.compute_transform());
+                        }
+                    });
+                });
+                ui.close_menu();
+            }
+            if ui.button("Detach (keep local)").clicked() {
+                commands.add(move |world: &mut World| {
+                    record_reparent(world, entity, |world| {
+                        world.entity_mut(entity).remove_parent();
+                    });
+                });
+                ui.close_menu();
+            }
+        });
+    }
+    if children.is_some_and(|children| !children.is_empty()) {
+        ui.menu_button("Flatten hierarchy", |ui| {
+            let to_world = if ui.button("To this entity").clicked() {
+                Some(false)
+            } else if ui.button("To world root").clicked() {
+                Some(true)
+            } else {
+                None
+            };
+            if let Some(to_world) = to_world {
+                commands.add(move |world: &mut World| {
+                    world.send_event(FlattenHierarchyEvent {
+                        id: entity,
+                        to_world,
+                    });
+                });
+                ui.close_menu();
+            }
+        });
+    }
+}
+
+/// Hover text of actions, which are disabled outside of [`EditorRunState::Editing`]
+pub(super) const RUNNING_HINT: &str = "Stop the game to delete or clone entities";
+
+/// Hover text of context actions, which would change content of read-only prefab instance
+pub(super) const READ_ONLY_HINT: &str = "Unpack instance to edit";
+
+/// Hover text of context actions, which are disabled for entities without [`PrefabMarker`]
+const EDITOR_ENTITY_HINT: &str =
+    "Editor entity is not part of the scene and can not be edited. Switch to \"Prefab\" view to edit scene entities";
+
+/// Entities for Delete/Clone context actions: whole selection if clicked entity is selected, otherwise clicked entity only
+fn context_targets(entity: Entity, selected: &Query<Entity, With<Selected>>) -> Vec<Entity> {
+    if selected.contains(entity) {
+        selected.iter().collect()
+    } else {
+        vec![entity]
+    }
+}
+
+/// Spawn new named prefab entity as the last child of `parent`. Spawn and parenting are one undo step,
+/// so undo removes the child without leaving it in children of `parent`. Returns the new entity
+pub fn add_child_with_undo(world: &mut World, parent: Entity) -> Entity {
+    begin_change_group(world, "Add child");
+    let child = world.spawn(PrefabMarker).id();
+    let reparent = ReparentEntity::record(world, child, |world| {
+        world.entity_mut(parent).add_child(child);
+    });
+    // Group is reverted in order, so child is detached before it is despawned
+    world.send_event(NewChange {
+        change: Arc::new(reparent),
+    });
+    world.send_event(NewChange {
+        change: Arc::new(AddedEntity { entity: child }),
+    });
+    auto_name_empty(world, child);
+    end_change_group(world);
+    child
+}
+
+/// Despawn entities with their descendants. Entities inside other deleted entities are skipped.
+/// All removals are collected in one named undo step, which restores the whole subtrees
+pub fn delete_entities(world: &mut World, entities: &[Entity]) {
+    if !destructive_edits_allowed(world) {
+        return;
+    }
+    let entities = editable_entities(world, scene_entities(world, entities, "deleted"), "deleted");
+    let roots = entities
+        .iter()
+        .copied()
+        .filter(|e| !has_ancestor_in(world, *e, &entities))
+        .collect::<Vec<_>>();
+    if roots.is_empty() {
+        return;
+    }
+    let name = format!("Delete {} entities", roots.len());
+    info!("Delete entities {:?}", roots);
+    move_to_trash(world, &roots);
+    despawn_recursive_with_undo(world, &roots, name);
+}
+
+/// Delete entities from hierarchy menu, Delete key or gizmo. Entities referenced by other entities are deleted
+/// only after confirmation in hierarchy, which lists references to be broken.
+/// `confirm_children` asks confirmation for entities with children too
+pub fn request_delete(world: &mut World, targets: Vec<Entity>, confirm_children: bool) {
+    let has_children = confirm_children
+        && targets.iter().any(|entity| {
+            world
+                .get::<Children>(*entity)
+                .is_some_and(|children| !children.is_empty())
+        });
+    let references = find_references(world, &targets);
+    if !has_children && references.is_empty() {
+        delete_entities(world, &targets);
+        return;
+    }
+    if let Some(mut state) = world.get_resource_mut::<HierarchyTabState>() {
+        state.confirm_delete = Some(targets);
+        state.delete_references = references;
+    } else {
+        // No hierarchy to confirm in
+        warn!(
+            "{} references from other entities are broken by delete",
+            references.len()
+        );
+        delete_entities(world, &targets);
+    }
+}
+
+/// Delete and clear are blocked outside of [`EditorRunState::Editing`], so play mode entities are not removed from scene
+fn destructive_edits_allowed(world: &World) -> bool {
+    let allowed = world
+        .get_resource::<State<EditorRunState>>()
+        .map_or(true, |state| state.is_editing());
+    if !allowed {
+        warn!("Entities can not be deleted while game is running");
+    }
+    allowed
+}
+
+/// Existing [`PrefabMarker`] entities from `entities`. Editor entities (cameras, gizmos, ui) are skipped with a warning,
+/// so they can be selected in "Editor" and "All" views, but scene edit actions are not applied to them
+fn scene_entities(world: &World, entities: &[Entity], action: &str) -> Vec<Entity> {
+    entities
+        .iter()
+        .copied()
+        .filter(|entity| match world.get_entity(*entity) {
+            Some(entity_ref) if entity_ref.contains::<PrefabMarker>() => true,
+            Some(_) => {
+                warn!("Editor entity {:?} can not be {}", entity, action);
+                false
+            }
+            None => false,
+        })
+        .collect()
+}
+
+/// `entities` without content of read-only prefab instances, which is skipped with a warning.
+/// Instance roots are kept, so whole instance can still be edited
+fn editable_entities(world: &World, entities: Vec<Entity>, action: &str) -> Vec<Entity> {
+    entities
+        .into_iter()
+        .filter(|entity| match read_only_instance_root(world, *entity) {
+            Some(root) => {
+                warn!(
+                    "Entity {:?} of prefab instance {:?} can not be {}, unpack instance to edit",
+                    entity, root, action
+                );
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Despawn all [`PrefabMarker`] entities in one undo step. Parent-children links are restored on undo
+pub fn clear_all_entities(world: &mut World) {
+    if !destructive_edits_allowed(world) {
+        return;
+    }
+    let entities = world
+        .query_filtered::<Entity, With<PrefabMarker>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    let roots = entities
+        .iter()
+        .copied()
+        .filter(|e| !has_ancestor_in(world, *e, &entities))
+        .collect::<Vec<_>>();
+    despawn_recursive_with_undo(world, &roots, "Clear all entities");
+    world.send_event(PrefabUnloaded { entities: roots });
+}
+
+pub(crate) fn has_ancestor_in(world: &World, entity: Entity, entities: &[Entity]) -> bool {
+    let mut ancestor = world.get::<Parent>(entity).map(Parent::get);
+    while let Some(id) = ancestor {
+        if entities.contains(&id) {
+            return true;
+        }
+        ancestor = world.get::<Parent>(id).map(Parent::get);
+    }
+    false
+}
+
+/// Apply hierarchy change of entity and send it to undo chain as one [`ReparentEntity`] change
+fn record_reparent(world: &mut World, entity: Entity, reparent: impl FnOnce(&mut World)) {
+    let change = ReparentEntity::record(world, entity, reparent);
+    world.send_event(NewChange {
+        change: Arc::new(change),
+    });
+}
+
+/// Apply parent change like [`record_reparent`]. With [`HierarchySettings::keep_world_transform`] local [`Transform`]
+/// is recomputed for the new parent, so entity keeps its world position. With `snap` local translation is moved to
+/// parent origin or to the nearest [`HierarchySettings::parent_snap_step`] in parent space.
+/// Adjusted transform is a part of recorded change
+pub(super) fn record_reparent_in_place(
+    world: &mut World,
+    entity: Entity,
+    snap: bool,
+    reparent: impl FnOnce(&mut World),
+) {
+    let (keep_world, snap_step) = world
+        .get_resource::<HierarchySettings>()
+        .map_or((true, 0.0), |settings| {
+            (settings.keep_world_transform, settings.parent_snap_step)
+        });
+    let global = world
+        .get::<GlobalTransform>(entity)
+        .copied()
+        .filter(|_| keep_world && world.get::<Transform>(entity).is_some());
+
+    record_reparent(world, entity, |world| {
+        let old_parent = world.get::<Parent>(entity).map(Parent::get);
+        reparent(world);
+        let new_parent = world.get::<Parent>(entity).map(Parent::get);
+        if new_parent == old_parent {
+            return;
+        }
+        let mut transform = match global {
+            Some(global) => {
+                match new_parent.and_then(|parent| world.get::<GlobalTransform>(parent)) {
+                    Some(parent_global) => global.reparented_to(parent_global),
+                    None => global.compute_transform(),
+                }
+            }
+            None => match world.get::<Transform>(entity) {
+                Some(transform) if snap => *transform,
+                _ => return,
+            },
+        };
+        if snap {
+            transform.translation = snap_translation(transform.translation, snap_step);
+        }
+        world.entity_mut(entity).insert(transform);
+    });
+}
+
+/// Round translation to the nearest multiple of `step`. Zero step gives origin
+fn snap_translation(translation: Vec3, step: f32) -> Vec3 {
+    if step > 0.0 {
+        (translation / step).round() * step
+    } else {
+        Vec3::ZERO
+    }
+}
+
+/// Select entity with all its descendants. Selection is extended if `add` is set, otherwise it is replaced.
+/// [`Locked`] entities are skipped, but their descendants are still selected
+pub fn select_subtree(world: &mut World, entity: Entity, add: bool) {
+    if !add {
+        let mut query = world.query_filtered::<Entity, With<Selected>>();
+        for e in query.iter(world).collect::<Vec<_>>() {
+            world.entity_mut(e).remove::<Selected>();
+        }
+    }
+
+    let mut queue = vec![entity];
+    while let Some(e) = queue.pop() {
+        let Some(mut entity_mut) = world.get_entity_mut(e) else {
+            continue;
+        };
+        if !entity_mut.contains::<Locked>() {
+            entity_mut.insert(Selected);
+        }
+        if let Some(children) = entity_mut.get::<Children>() {
+            queue.extend(children.iter().rev());
+        }
+    }
+}
+
+/// Select parents of `entities` instead of them, or in addition to them if `add` is set.
+/// Root entities stay selected, so repeated calls stop at the top of the tree. Returns selected parents
+pub fn select_parent(world: &mut World, entities: &[Entity], add: bool) -> Vec<Entity> {
+    let mut parents = vec![];
+    let mut targets = vec![];
+    for entity in entities.iter().copied() {
+        let target = match world.get::<Parent>(entity) {
+            Some(parent) => {
+                parents.push(parent.get());
+                parent.get()
+            }
+            None => entity,
+        };
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    let mode = if add {
+        SelectMode::Add
+    } else {
+        SelectMode::Replace
+    };
+    select_entities(world, &targets, mode);
+    parents
+}
+
+/// Spawn new empty entity at the center of selection and reparent selected entities to it with keeping their world transforms.
+/// Group is placed under common parent of selection (or world root) and becomes the only selected entity.
+/// All changes are collected in one undo step
+pub fn group_selected(world: &mut World) {
+    let mut query = world.query_filtered::<Entity, With<Selected>>();
+    let selected = query.iter(world).collect::<Vec<_>>();
+    let roots = selected
+        .iter()
+        .copied()
+        .filter(|e| !has_ancestor_in(world, *e, &selected))
+        .collect::<Vec<_>>();
+    if roots.is_empty() {
+        return;
+    }
+
+    let globals = roots
+        .iter()
+        .map(|e| {
+            world
+                .get::<GlobalTransform>(*e)
+                .copied()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let center = globals
+        .iter()
+        .map(GlobalTransform::translation)
+        .sum::<Vec3>()
+        / globals.len() as f32;
+    let parents = roots
+        .iter()
+        .map(|e| world.get::<Parent>(*e).map(Parent::get))
+        .collect::<Vec<_>>();
+    let common_parent = parents
+        .iter()
+        .all(|parent| *parent == parents[0])
+        .then_some(parents[0])
+        .flatten();
+    let parent_global = common_parent
+        .and_then(|parent| world.get::<GlobalTransform>(parent).copied())
+        .unwrap_or_default();
+    let group_global = GlobalTransform::from_translation(center);
+
+    begin_change_group(world, "Group selected");
+    let group = world
+        .spawn((
+            PrefabMarker,
+            Name::new("Group"),
+            TransformBundle {
+                local: group_global.reparented_to(&parent_global),
+                global: group_global,
+            },
+            VisibilityBundle::default(),
+        ))
+        .id();
+    if let Some(parent) = common_parent {
+        world.entity_mut(parent).add_child(group);
+    }
+
+    // Reparent changes are sent before group spawn, so undo moves children back before group despawn
+    for (entity, global) in roots.iter().zip(globals) {
+        record_reparent(world, *entity, |world| {
+            world
+                .entity_mut(*entity)
+                .set_parent(group)
+                .insert(global.reparented_to(&group_global));
+        });
+    }
+    world.send_event(NewChange {
+        change: Arc::new(AddedEntity { entity: group }),
+    });
+    end_change_group(world);
+
+    for e in selected {
+        world.entity_mut(e).remove::<Selected>();
+    }
+    world.entity_mut(group).insert(Selected);
+}
+
+/// How selected entities are placed along one axis by [`align_selected`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AlignMode {
+    Min,
+    Max,
+    Average,
+    /// Keep first and last entities in place and space others evenly between them
+    Distribute,
+}
+
+const ALIGN_MODES: [AlignMode; 4] = [
+    AlignMode::Min,
+    AlignMode::Max,
+    AlignMode::Average,
+    AlignMode::Distribute,
+];
+
+impl ToString for AlignMode {
+    fn to_string(&self) -> String {
+        match self {
+            AlignMode::Min => "Align to min".to_string(),
+            AlignMode::Max => "Align to max".to_string(),
+            AlignMode::Average => "Align to average".to_string(),
+            AlignMode::Distribute => "Distribute evenly".to_string(),
+        }
+    }
+}
+
+/// Align world translations of selected entities on `axis` (0 - X, 1 - Y, 2 - Z).
+/// Entities inside other selected entities are moved with their ancestors only.
+/// Local transforms are recomputed from parent world transform, and all moves are one undo step
+pub fn align_selected(world: &mut World, axis: usize, mode: AlignMode) {
+    let mut query = world.query_filtered::<Entity, With<Selected>>();
+    let selected = query.iter(world).collect::<Vec<_>>();
+    let mut targets = selected
+        .iter()
+        .copied()
+        .filter(|e| !has_ancestor_in(world, *e, &selected))
+        .filter_map(|e| {
+            let global = world.get::<GlobalTransform>(e)?.compute_transform();
+            world.get::<Transform>(e)?;
+            Some((e, global))
+        })
+        .collect::<Vec<_>>();
+    if targets.len() < 2 {
+        return;
+    }
+
+    let values = targets
+        .iter()
+        .map(|(_, global)| global.translation[axis])
+        .collect::<Vec<_>>();
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let average = values.iter().sum::<f32>() / values.len() as f32;
+
+    if mode == AlignMode::Distribute {
+        targets.sort_by(|(_, a), (_, b)| a.translation[axis].total_cmp(&b.translation[axis]));
+    }
+    let step = (max - min) / (targets.len() - 1) as f32;
+
+    begin_change_group(world, mode.to_string());
+    for (idx, (entity, mut global)) in targets.into_iter().enumerate() {
+        global.translation[axis] = match mode {
+            AlignMode::Min => min,
+            AlignMode::Max => max,
+            AlignMode::Average => average,
+            AlignMode::Distribute => min + step * idx as f32,
+        };
+        let parent_global = world
+            .get::<Parent>(entity)
+            .and_then(|parent| world.get::<GlobalTransform>(parent.get()))
+            .copied();
+        let new_transform = match parent_global {
+            Some(parent_global) => GlobalTransform::from(global).reparented_to(&parent_global),
+            None => global,
+        };
+
+        let old_transform = *world.get::<Transform>(entity).unwrap();
+        if old_transform == new_transform {
+            continue;
+        }
+        world
+            .entity_mut(entity)
+            .insert((new_transform, OneFrameUndoIgnore::default()));
+        world.send_event(NewChange {
+            change: Arc::new(ReflectedComponentChange::new(
+                entity,
+                old_transform,
+                new_transform,
+            )),
+        });
+    }
+    end_change_group(world);
+}
+
+/// Reparent all descendants of flattened entity with keeping their world transforms.
+/// All parent and transform changes are done in one frame, so they will be collected in one undo step
+pub(super) fn flatten_hierarchy(
+    mut commands: Commands,
+    mut events: EventReader<FlattenHierarchyEvent>,
+    children_query: Query<&Children>,
+    parent_query: Query<&Parent>,
+) {
+    for event in events.read() {
+        let descendants = children_query
+            .iter_descendants(event.id)
+            .collect::<Vec<_>>();
+
+        let releveled = descendants
+            .iter()
+            .filter(|e| {
+                children_query
+                    .get(**e)
+                    .is_ok_and(|children| !children.is_empty())
+            })
+            .count();
+        if releveled > 0 {
+            warn!(
+                "Flatten hierarchy of {:?}: {} descendants with own children will be re-leveled",
+                event.id, releveled
+            );
+        }
+
+        for e in descendants {
+            if event.to_world {
+                commands.entity(e).remove_parent_in_place();
+            } else if parent_query.get(e).ok().map(Parent::get) != Some(event.id) {
+                commands.entity(e).set_parent_in_place(event.id);
+            }
+        }
+    }
+}
+
+/// Reparent selected entities to the active one in one frame, so all changes will be collected in one undo step.
+/// Entities which are ancestors of active entity are skipped to avoid hierarchy loops
+pub(super) fn parent_to_active(
+    mut commands: Commands,
+    mut events: EventReader<ParentToActiveEvent>,
+    active: Res<ActiveEntity>,
+    selected: Query<Entity, With<Selected>>,
+    parent_query: Query<&Parent>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    events.clear();
+
+    let Some(active) = active.0 else {
+        warn!("No active entity to parent selection to");
+        return;
+    };
+
+    let ancestors = parent_query.iter_ancestors(active).collect::<HashSet<_>>();
+    for e in selected.iter() {
+        if e == active {
+            continue;
+        }
+        if ancestors.contains(&e) {
+            warn!(
+                "Skip parenting {:?} to {:?}: it would create a hierarchy loop",
+                e, active
+            );
+            continue;
+        }
+        if parent_query.get(e).ok().map(Parent::get) != Some(active) {
+            commands.entity(e).set_parent_in_place(active);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_prefab::editor_registry::EditorRegistryPlugin;
+    use space_undo::UndoPlugin;
+
+    #[test]
+    fn test_undo_add_child_leaves_no_stray_child() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
+        let parent = app.world.spawn(PrefabMarker).id();
+        let sibling = app.world.spawn(PrefabMarker).id();
+        app.world.entity_mut(parent).add_child(sibling);
+        app.update();
+
+        let child = add_child_with_undo(&mut app.world, parent);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(
+            app.world.get::<Children>(parent).unwrap().to_vec(),
+            vec![sibling, child]
+        );
+
+        app.world.send_event(space_undo::UndoRedo::Undo);
+        for _ in 0..5 {
+            app.update();
+        }
+
+        assert!(app.world.get_entity(child).is_none());
+        assert_eq!(
+            app.world.get::<Children>(parent).unwrap().to_vec(),
+            vec![sibling]
+        );
+    }
+
+    #[test]
+    fn test_batch_rename_in_hierarchy_order() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
+        app.auto_reflected_undo::<Name>();
+
+        let parent = app.world.spawn((Selected, Name::new("crate"))).id();
+        let first = app.world.spawn((Selected, Name::new("crate"))).id();
+        let skipped = app.world.spawn(Name::new("crate")).id();
+        let second = app.world.spawn(Selected).id();
+        app.world
+            .entity_mut(parent)
+            .push_children(&[first, skipped, second]);
+        let root = app.world.spawn((Selected, Name::new("crate"))).id();
+        app.update();
+
+        batch_rename_selected(&mut app.world, "Crate_{n}", 8, 2);
+
+        let name = |app: &App, entity: Entity| app.world.get::<Name>(entity).unwrap().to_string();
+        assert_eq!(name(&app, parent), "Crate_08");
+        assert_eq!(name(&app, first), "Crate_09");
+        assert_eq!(name(&app, second), "Crate_10");
+        assert_eq!(name(&app, root), "Crate_11");
+        assert_eq!(name(&app, skipped), "crate");
+        assert_eq!(batch_rename_name("Prop", 3, 0), "Prop3");
+
+        // One undo restores all names
+        for _ in 0..10 {
+            app.update();
+        }
+        app.world.send_event(space_undo::UndoRedo::Undo);
+        for _ in 0..5 {
+            app.update();
+        }
+        for entity in [parent, first, skipped, root] {
+            assert_eq!(name(&app, entity), "crate");
+        }
+        assert!(app.world.get::<Name>(second).is_none());
+    }
+
+    #[test]
+    fn test_batch_rename_follows_hierarchy_sort_mode() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
+        app.insert_resource(HierarchyTabState {
+            sort_mode: HierarchySortMode::Name,
+            ..default()
+        });
+
+        let parent = app.world.spawn((Selected, Name::new("b"))).id();
+        let child_b = app.world.spawn((Selected, Name::new("y"))).id();
+        let child_a = app.world.spawn((Selected, Name::new("x"))).id();
+        app.world
+            .entity_mut(parent)
+            .push_children(&[child_b, child_a]);
+        let root = app.world.spawn((Selected, Name::new("a"))).id();
+        app.update();
+
+        batch_rename_selected(&mut app.world, "Item_{n}", 1, 0);
+
+        let name = |app: &App, entity: Entity| app.world.get::<Name>(entity).unwrap().to_string();
+        assert_eq!(name(&app, root), "Item_1");
+        assert_eq!(name(&app, parent), "Item_2");
+        assert_eq!(name(&app, child_a), "Item_3");
+        assert_eq!(name(&app, child_b), "Item_4");
+    }
+
+    #[test]
+    fn test_align_selected_in_world_space() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(TransformPlugin)
+            .add_plugins(UndoPlugin);
+
+        let spawn = |app: &mut App, x: f32, y: f32| {
+            app.world
+                .spawn((
+                    Selected,
+                    TransformBundle::from_transform(Transform::from_xyz(x, y, 0.0)),
+                ))
+                .id()
+        };
+        let parent = app
+            .world
+            .spawn(TransformBundle::from_transform(Transform::from_xyz(
+                10.0, 0.0, 0.0,
+            )))
+            .id();
+        let child = spawn(&mut app, 0.0, 0.0);
+        app.world.entity_mut(parent).add_child(child);
+        let first = spawn(&mut app, 0.0, 1.0);
+        let second = spawn(&mut app, 4.0, 2.0);
+        app.update();
+
+        let local =
+            |app: &App, entity: Entity| app.world.get::<Transform>(entity).unwrap().translation;
+
+        align_selected(&mut app.world, 0, AlignMode::Distribute);
+        app.update();
+        assert_eq!(local(&app, first).x, 0.0);
+        assert_eq!(local(&app, second).x, 5.0);
+        assert_eq!(local(&app, child).x, 0.0);
+
+        align_selected(&mut app.world, 1, AlignMode::Max);
+        app.update();
+        for entity in [first, second, child] {
+            assert_eq!(local(&app, entity).y, 2.0);
+        }
+
+        align_selected(&mut app.world, 0, AlignMode::Min);
+        assert_eq!(local(&app, second).x, 0.0);
+        assert_eq!(local(&app, child).x, -10.0);
+    }
+
+    #[test]
+    fn test_delete_skips_editor_entities() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(space_prefab::editor_uuid::EditorUuidPlugin)
+            .init_resource::<HierarchyTrash>();
+
+        let scene = app.world.spawn(PrefabMarker).id();
+        let editor = app.world.spawn(Name::new("editor camera")).id();
+        app.update();
+        app.update();
+
+        delete_entities(&mut app.world, &[scene, editor]);
+        app.update();
+        assert!(app.world.get_entity(scene).is_none());
+        assert!(app.world.get_entity(editor).is_some());
+
+        // Only editor entities: nothing is deleted and nothing is moved to trash
+        delete_entities(&mut app.world, &[editor]);
+        app.update();
+        assert!(app.world.get_entity(editor).is_some());
+        assert_eq!(app.world.resource::<HierarchyTrash>().items.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_skips_read_only_instance_content() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(space_prefab::editor_uuid::EditorUuidPlugin)
+            .init_resource::<HierarchyTrash>();
+
+        let root = app
+            .world
+            .spawn((
+                PrefabMarker,
+                InstanceOf {
+                    path: "cube.scn.ron".into(),
+                },
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((PrefabMarker, PrefabInstance::default()))
+            .set_parent(root)
+            .id();
+        app.update();
+        app.update();
+
+        delete_entities(&mut app.world, &[child]);
+        app.update();
+        assert!(app.world.get_entity(child).is_some());
+        assert!(app.world.resource::<HierarchyTrash>().items.is_empty());
+
+        // Whole instance is still deleted from its root
+        delete_entities(&mut app.world, &[root]);
+        app.update();
+        assert!(app.world.get_entity(root).is_none());
+        assert!(app.world.get_entity(child).is_none());
+    }
+
+    #[test]
+    fn test_undone_delete_is_not_restored_from_trash() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(space_prefab::editor_uuid::EditorUuidPlugin)
+            .init_resource::<HierarchyTrash>();
+
+        let lamp = app.world.spawn((PrefabMarker, Name::new("lamp"))).id();
+        app.update();
+        app.update();
+
+        delete_entities(&mut app.world, &[lamp]);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<HierarchyTrash>().items.len(), 1);
+
+        space_undo::undo(&mut app.world);
+        app.update();
+        let restored = app
+            .world
+            .resource::<space_undo::ChangeChain>()
+            .current_entity(lamp);
+        assert!(app.world.get_entity(restored).is_some());
+
+        // Item of undone delete can not be restored second time
+        assert!(app.world.resource::<HierarchyTrash>().items[0].is_alive(&app.world));
+        assert!(crate::trash::restore_from_trash(&mut app.world, 0).is_empty());
+
+        delete_entities(&mut app.world, &[restored]);
+        for _ in 0..10 {
+            app.update();
+        }
+        space_undo::undo(&mut app.world);
+        app.update();
+        prune_trash(&mut app.world);
+        assert!(app.world.resource::<HierarchyTrash>().items.is_empty());
+    }
+
+    #[test]
+    fn test_restore_from_trash_keeps_parent() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(space_prefab::editor_uuid::EditorUuidPlugin)
+            .init_resource::<HierarchyTrash>();
+        app.register_type::<Name>();
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<Name>();
+
+        let parent = app.world.spawn((PrefabMarker, Name::new("parent"))).id();
+        let child = app.world.spawn((PrefabMarker, Name::new("lamp"))).id();
+        app.world.entity_mut(parent).add_child(child);
+        app.update();
+        app.update();
+
+        delete_entities(&mut app.world, &[child]);
+        app.update();
+        assert!(app.world.get_entity(child).is_none());
+        assert_eq!(app.world.resource::<HierarchyTrash>().items.len(), 1);
+
+        let restored = crate::trash::restore_from_trash(&mut app.world, 0);
+        app.update();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(app.world.get::<Name>(restored[0]).unwrap().as_str(), "lamp");
+        assert_eq!(app.world.get::<Parent>(restored[0]).unwrap().get(), parent);
+        assert!(app.world.resource::<HierarchyTrash>().items.is_empty());
+    }
+
+    #[test]
+    fn test_note_survives_delete_undo_and_trash() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(space_undo::SyncUndoMarkersPlugin::<PrefabMarker>::default())
+            .add_plugins(space_prefab::editor_uuid::EditorUuidPlugin)
+            .init_resource::<HierarchyTrash>();
+        app.editor_clone_registry::<EditorNote>()
+            .editor_only::<EditorNote>()
+            .auto_reflected_undo::<EditorNote>();
+        let note = EditorNote("check light".to_string());
+
+        let lamp = app
+            .world
+            .spawn((PrefabMarker, Name::new("lamp"), note.clone()))
+            .id();
+        app.update();
+        app.update();
+
+        delete_entities(&mut app.world, &[lamp]);
+        for _ in 0..10 {
+            app.update();
+        }
+        space_undo::undo(&mut app.world);
+        for _ in 0..3 {
+            app.update();
+        }
+        let restored = app
+            .world
+            .resource::<space_undo::ChangeChain>()
+            .current_entity(lamp);
+        assert_eq!(app.world.get::<EditorNote>(restored), Some(&note));
+
+        delete_entities(&mut app.world, &[restored]);
+        app.update();
+        let trash = app.world.resource::<HierarchyTrash>();
+        let index = trash.items.len() - 1;
+        let from_trash = crate::trash::restore_from_trash(&mut app.world, index);
+        assert_eq!(from_trash.len(), 1);
+        assert_eq!(app.world.get::<EditorNote>(from_trash[0]), Some(&note));
+    }
+
+    fn attach_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .init_resource::<HierarchyTabState>();
+        app
+    }
+
+    #[test]
+    fn test_attach_to_self_is_rejected() {
+        let mut app = attach_test_app();
+        let entity = app.world.spawn((PrefabMarker, Name::new("cube"))).id();
+
+        assert!(!can_attach(&app.world, entity, entity));
+        attach_to(&mut app.world, entity, &[entity], false);
+        app.update();
+
+        assert!(app.world.get::<Parent>(entity).is_none());
+        assert!(app.world.get::<Children>(entity).is_none());
+        assert!(app
+            .world
+            .resource::<HierarchyTabState>()
+            .attach_warning
+            .is_some());
+    }
+
+    #[test]
+    fn test_attach_ancestor_to_grandchild_is_rejected() {
+        let mut app = attach_test_app();
+        let root = app.world.spawn((PrefabMarker, Name::new("root"))).id();
+        let child = app.world.spawn(PrefabMarker).id();
+        let grandchild = app.world.spawn(PrefabMarker).id();
+        let other = app.world.spawn(PrefabMarker).id();
+        app.world.entity_mut(root).add_child(child);
+        app.world.entity_mut(child).add_child(grandchild);
+
+        assert!(!can_attach(&app.world, grandchild, root));
+        assert!(can_attach(&app.world, root, grandchild));
+
+        // Valid entities are still attached
+        attach_to(&mut app.world, grandchild, &[root, other], false);
+        app.update();
+
+        assert!(app.world.get::<Parent>(root).is_none());
+        assert_eq!(app.world.get::<Parent>(grandchild).unwrap().get(), child);
+        assert_eq!(app.world.get::<Parent>(other).unwrap().get(), grandchild);
+        let warning = app
+            .world
+            .resource::<HierarchyTabState>()
+            .attach_warning
+            .clone()
+            .unwrap();
+        assert!(warning.contains("root"));
+    }
+
+    #[test]
+    fn test_select_parent() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        let root = app.world.spawn(PrefabMarker).id();
+        let child = app.world.spawn((PrefabMarker, Selected)).id();
+        app.world.entity_mut(root).add_child(child);
+
+        assert_eq!(select_parent(&mut app.world, &[child], true), vec![root]);
+        assert!(app.world.get::<Selected>(child).is_some());
+        assert!(app.world.get::<Selected>(root).is_some());
+
+        assert_eq!(select_parent(&mut app.world, &[child], false), vec![root]);
+        assert!(app.world.get::<Selected>(child).is_none());
+
+        // Root stays selected
+        assert!(select_parent(&mut app.world, &[root], false).is_empty());
+        assert!(app.world.get::<Selected>(root).is_some());
+    }
+
+    #[test]
+    fn test_attach_keeps_world_transform() {
+        let mut app = attach_test_app();
+        app.insert_resource(HierarchySettings::default());
+        let parent = app
+            .world
+            .spawn((
+                PrefabMarker,
+                TransformBundle::from_transform(Transform::from_xyz(10.0, 0.0, 0.0)),
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((
+                PrefabMarker,
+                TransformBundle::from_transform(Transform::from_xyz(12.0, 1.0, 0.0)),
+            ))
+            .id();
+        app.update();
+
+        attach_to(&mut app.world, parent, &[child], false);
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::new(2.0, 1.0, 0.0)
+        );
+
+        app.world
+            .resource_mut::<HierarchySettings>()
+            .keep_world_transform = false;
+        let other = app
+            .world
+            .spawn((PrefabMarker, TransformBundle::default()))
+            .id();
+        app.update();
+        attach_to(&mut app.world, other, &[child], false);
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::new(2.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_attach_snaps_to_parent() {
+        let mut app = attach_test_app();
+        app.insert_resource(HierarchySettings::default());
+        let parent = app
+            .world
+            .spawn((
+                PrefabMarker,
+                TransformBundle::from_transform(Transform::from_xyz(10.0, 0.0, 0.0)),
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((
+                PrefabMarker,
+                TransformBundle::from_transform(Transform::from_xyz(12.3, 0.9, 0.0)),
+            ))
+            .id();
+        app.update();
+
+        attach_to(&mut app.world, parent, &[child], true);
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::ZERO
+        );
+        app.update();
+        app.update();
+
+        space_undo::undo(&mut app.world);
+        assert!(app.world.get::<Parent>(child).is_none());
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::new(12.3, 0.9, 0.0)
+        );
+
+        app.world
+            .resource_mut::<HierarchySettings>()
+            .parent_snap_step = 1.0;
+        attach_to(&mut app.world, parent, &[child], true);
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::new(2.0, 1.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use super::*;
+
+/// "Prefab" submenu of prefab instance root. `editable` is set if instance has [`EditableInstance`]
+pub(super) fn instance_menu(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    entity: Entity,
+    editable: bool,
+) {
+    ui.menu_button("Prefab", |ui| {
+        let toggle = if editable {
+            "Make internals read-only"
+        } else {
+            "Allow editing internals"
+        };
+        if ui
+            .button(toggle)
+            .on_hover_text("Edits of instance content are stored as overrides")
+            .clicked()
+        {
+            if editable {
+                commands.entity(entity).remove::<EditableInstance>();
+            } else {
+                commands.entity(entity).insert(EditableInstance);
+            }
+            ui.close_menu();
+        }
+        if ui
+            .button("Unpack")
+            .on_hover_text("Break link with prefab file, content becomes ordinary entities")
+            .clicked()
+        {
+            commands.add(move |world: &mut World| unpack_instance(world, entity));
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button("Revert to prefab").clicked() {
+            commands.add(move |world: &mut World| {
+                let result = revert_to_prefab(world, entity).map(|_| "Prefab reverted".into());
+                report_prefab_result(world, result);
+            });
+            ui.close_menu();
+        }
+        if ui.button("Apply overrides to prefab").clicked() {
+            commands.add(move |world: &mut World| {
+                let result = apply_overrides_to_prefab(world, entity)
+                    .map(|path| format!("Prefab saved to {}", path));
+                report_prefab_result(world, result);
+            });
+            ui.close_menu();
+        }
+    });
+}
+
+/// Log result of prefab action and show it in hierarchy
+pub(super) fn report_prefab_result(world: &mut World, result: Result<String, String>) {
+    let result = result.map_err(|err| format!("Prefab action failed: {}", err));
+    match &result {
+        Ok(message) => info!("{}", message),
+        Err(err) => error!("{}", err),
+    }
+    world.resource_mut::<HierarchyTabState>().prefab_result = Some(result);
+}
+
+/// Default prefab file name from entity name
+pub(super) fn prefab_file_name(world: &World, entity: Entity) -> String {
+    world
+        .get::<Name>(entity)
+        .map(|name| name.as_str().replace(|c: char| !c.is_alphanumeric(), "_"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "prefab".to_string())
+}
+
+/// Save entity with its descendants to prefab file without changing the scene.
+/// Saved prefab is added to "Prefabs" category of spawnable bundles, which spawns its instances. Returns the written path
+pub fn save_subtree_as_prefab(
+    world: &mut World,
+    entity: Entity,
+    path: &str,
+) -> Result<String, String> {
+    // Same validators as for scene save. There is no report window, so warnings are only logged
+    let issues = world
+        .get_resource::<PrefabValidators>()
+        .map(|validators| validators.validate_subtree(world, entity))
+        .unwrap_or_default();
+    let mut errors = vec![];
+    for issue in issues {
+        if issue.is_error() {
+            errors.push(issue.message);
+        } else {
+            warn!("Prefab validation: {}", issue.message);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(format!("Prefab validation failed: {}", errors.join(", ")));
+    }
+
+    let data = serialize_subtree(world, entity).map_err(|err| err.to_string())?;
+    if let Some(dir) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(path, data).map_err(|err| err.to_string())?;
+
+    // Prefab loader uses asset path, which is relative to assets folder
+    let asset_path = path
+        .split_once("assets/")
+        .map_or(path, |(_, asset_path)| asset_path)
+        .to_string();
+    let name = asset_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(&asset_path)
+        .trim_end_matches(".scn.ron")
+        .to_string();
+    if let Some(mut reg) = world.get_resource_mut::<BundleReg>() {
+        let bundle = EditorBundleUntyped::from_fn(name.clone(), move |cmds| {
+            let path = asset_path.clone();
+            cmds.insert((
+                TransformBundle::default(),
+                VisibilityBundle::default(),
+                Name::new(path.clone()),
+            ))
+            .add(move |entity: Entity, world: &mut World| {
+                if let Err(err) = instantiate_prefab(world, entity, &path) {
+                    error!("Failed to spawn prefab {}: {}", path, err);
+                }
+            });
+        });
+        reg.bundles
+            .entry("Prefabs".to_string())
+            .or_default()
+            .insert(name, bundle);
+    }
+    Ok(path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_prefab::{
+        editor_registry::EditorRegistryPlugin,
+        instance::{read_only_instance_root, PrefabInstance, PrefabInstancePlugin},
+    };
+    use space_undo::UndoPlugin;
+
+    #[test]
+    fn test_instance_content_read_only_until_unpacked() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
+        let root = app
+            .world
+            .spawn((
+                PrefabMarker,
+                InstanceOf {
+                    path: "cube.scn.ron".into(),
+                },
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((PrefabMarker, PrefabInstance::default()))
+            .set_parent(root)
+            .id();
+
+        assert_eq!(read_only_instance_root(&app.world, child), Some(root));
+        assert_eq!(read_only_instance_root(&app.world, root), None);
+
+        app.world.entity_mut(root).insert(EditableInstance);
+        assert_eq!(read_only_instance_root(&app.world, child), None);
+
+        unpack_instance(&mut app.world, root);
+        app.update();
+        assert!(app.world.get::<InstanceOf>(root).is_none());
+        assert!(app.world.get::<EditableInstance>(root).is_none());
+        assert!(app.world.get::<PrefabInstance>(child).is_none());
+        assert_eq!(read_only_instance_root(&app.world, child), None);
+    }
+
+    #[test]
+    fn test_unpack_and_editable_toggle_are_undoable() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(space_undo::SyncUndoMarkersPlugin::<PrefabMarker>::default())
+            .add_plugins(EditorRegistryPlugin)
+            .add_state::<EditorState>()
+            .add_plugins(PrefabInstancePlugin);
+        let root = app
+            .world
+            .spawn((
+                PrefabMarker,
+                InstanceOf {
+                    path: "cube.scn.ron".into(),
+                },
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((PrefabMarker, PrefabInstance::default()))
+            .set_parent(root)
+            .id();
+        for _ in 0..10 {
+            app.update();
+        }
+        let steps = space_undo::undo_len(&app.world);
+
+        app.world.entity_mut(root).insert(EditableInstance);
+        for _ in 0..10 {
+            app.update();
+        }
+        unpack_instance(&mut app.world, root);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(space_undo::undo_len(&app.world), steps + 2);
+
+        // Unpack is undone in one step, toggle in the next one
+        space_undo::undo(&mut app.world);
+        app.update();
+        assert!(app.world.get::<InstanceOf>(root).is_some());
+        assert!(app.world.get::<PrefabInstance>(child).is_some());
+        assert!(app.world.get::<EditableInstance>(root).is_some());
+
+        space_undo::undo(&mut app.world);
+        app.update();
+        assert!(app.world.get::<EditableInstance>(root).is_none());
+        assert_eq!(read_only_instance_root(&app.world, child), Some(root));
+    }
+}
@@ -0,0 +1,2260 @@
+#![allow(clippy::too_many_arguments)]
+mod clone;
+mod context_menu;
+mod instances;
+mod repair;
+
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{
+    ecs::{
+        query::{Has, ReadOnlyWorldQuery},
+        system::{CommandQueue, SystemState},
+    },
+    prelude::*,
+    render::primitives::Aabb,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use bevy_egui::{egui::collapsing_header::CollapsingState, *};
+use bevy_mod_picking::backends::raycast::bevy_mod_raycast::prelude::{
+    Ray3d, Raycast, RaycastSettings,
+};
+use bevy_panorbit_camera::PanOrbitCamera;
+use space_editor_core::prelude::*;
+use space_prefab::{
+    editor_registry::{
+        AssetCloneMode, ComponentRequirement, EditorRegistry, EditorRegistryExt, EntityReference,
+    },
+    instance::{
+        apply_overrides_to_prefab, instantiate_prefab, read_only_instance_root, revert_to_prefab,
+        unpack_instance, EditableInstance, InstanceOf, PrefabInstance, PrefabOverrides,
+    },
+    linked::{link_assets, unlink_assets, LinkedAssets},
+    load::PrefabUnloaded,
+    save::serialize_subtree,
+};
+use space_undo::{
+    begin_change_group, despawn_recursive_with_undo, end_change_group, AddedEntity, AppAutoUndo,
+    NewChange, OneFrameUndoIgnore, ReflectedComponentChange, ReparentEntity, UndoSet,
+};
+
+use crate::{
+    clipboard::{paste_component, ComponentClipboard},
+    editor_visibility::{toggle_hidden_in_editor, EditorVisibility},
+    game_view::egui_to_viewport,
+    prelude::{
+        focus_camera_on, AutoNameSettings, DuplicateSettings, GameViewTab, HierarchyDoubleClick,
+        HierarchySettings, SpawnPlacement, SpawnSettings, HIERARCHY_ROW_SCALE,
+    },
+    trash::{move_to_trash, prune_trash, HierarchyTrash},
+    ui_registration::{
+        generate_bundle_previews, register_bundle_previews, BundleReg, EditorBundleUntyped,
+    },
+    wireframe::toggle_wireframe,
+};
+use space_shared::{ext::egui_file, *};
+
+use super::{editor_tab::EditorTabName, EditorUiAppExt, EditorUiRef};
+
+pub use self::{clone::*, context_menu::*, instances::*, repair::*};
+
+/// Plugin to activate hierarchy UI in editor UI
+#[derive(Default)]
+pub struct SpaceHierarchyPlugin {}
+
+impl Plugin for SpaceHierarchyPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<SelectedPlugin>() {
+            app.add_plugins(SelectedPlugin);
+        }
+
+        app.init_resource::<HierarchyTabState>();
+        app.init_resource::<HierarchyIcons>();
+        app.init_resource::<HierarchyTrash>();
+        app.add_systems(Update, prune_trash.in_set(EditorSet::Editor));
+        // Pins and notes are copied by editor clone, but not by clone for runtime
+        app.editor_clone_registry::<Pinned>()
+            .editor_only::<Pinned>()
+            .editor_clone_registry::<EditorNote>()
+            .editor_only::<EditorNote>()
+            .auto_reflected_undo::<EditorNote>();
+        app.hierarchy_icon::<Camera>("📷")
+            .hierarchy_icon::<PointLight>("💡")
+            .hierarchy_icon::<DirectionalLight>("💡")
+            .hierarchy_icon::<SpotLight>("💡")
+            .hierarchy_icon::<Handle<Mesh>>("🔺")
+            .hierarchy_icon::<InstanceOf>("📦")
+            .hierarchy_icon::<LinkedAssets>("🔗");
+        app.editor_tab(EditorTabName::Hierarchy, "Hierarchy".into(), show_hierarchy);
+
+        // app.add_systems(Update, show_hierarchy.before(crate::editor::ui_camera_block).in_set(EditorSet::Editor));
+        app.add_systems(Update, clone_enitites.in_set(EditorSet::Editor));
+        app.add_systems(
+            Update,
+            (generate_bundle_previews, register_bundle_previews)
+                .chain()
+                .run_if(resource_changed::<BundleReg>())
+                .in_set(EditorSet::Editor),
+        );
+        app.editor_hotkey(
+            HierarchyHotkey::Duplicate,
+            vec![KeyCode::ControlLeft, KeyCode::D],
+        );
+        app.editor_hotkey(
+            HierarchyHotkey::DuplicateLinked,
+            vec![KeyCode::AltLeft, KeyCode::D],
+        );
+        app.add_systems(Update, duplicate_selected.in_set(EditorSet::Editor));
+        app.add_systems(Update, flatten_hierarchy.in_set(EditorSet::Editor));
+        app.add_systems(Update, parent_to_active.in_set(EditorSet::Editor));
+        app.init_resource::<SelectionFlash>();
+        app.add_systems(
+            Update,
+            (sync_hierarchy_selection, draw_selection_flash, draw_ping).in_set(EditorSet::Editor),
+        );
+        app.add_systems(
+            PostUpdate,
+            detect_cloned_entities
+                .in_set(EditorSet::Editor)
+                .before(UndoSet::PerType),
+        );
+        app.add_event::<CloneEvent>();
+        app.add_event::<FlattenHierarchyEvent>();
+        app.add_event::<ParentToActiveEvent>();
+    }
+}
+
+/// Icons shown before entity name in hierarchy for entities with registered component types
+#[derive(Resource, Default)]
+pub struct HierarchyIcons {
+    pub icons: Vec<(TypeId, String)>,
+}
+
+impl HierarchyIcons {
+    /// All icons of entity components in registration order
+    pub fn icons_for(&self, entity: &EntityRef) -> String {
+        self.icons
+            .iter()
+            .filter(|(type_id, _)| entity.contains_type_id(*type_id))
+            .map(|(_, icon)| icon.as_str())
+            .collect()
+    }
+}
+
+/// Extension to register hierarchy icons for component types
+pub trait HierarchyIconExt {
+    fn hierarchy_icon<T: Component>(&mut self, icon: &str) -> &mut Self;
+}
+
+impl HierarchyIconExt for App {
+    fn hierarchy_icon<T: Component>(&mut self, icon: &str) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(HierarchyIcons::default)
+            .icons
+            .push((TypeId::of::<T>(), icon.to_string()));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum HierarchyHotkey {
+    Duplicate,
+    DuplicateLinked,
+}
+
+impl Hotkey for HierarchyHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::Duplicate => "Duplicate selected".to_string(),
+            Self::DuplicateLinked => "Duplicate selected (linked)".to_string(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct HierarchyTabState {
+    /// Which entities are shown
+    pub show_mode: HierarchyShowMode,
+    /// Show only entities with this component (and their ancestors). Type is chosen from [`EditorRegistry`]
+    component_filter: Option<(TypeId, String)>,
+    dragged_entity: Option<Entity>,
+    repair_report: Vec<String>,
+    /// Case-insensitive filter by entity name
+    filter: String,
+    renaming: Option<RenameState>,
+    /// Drawn rows in display order with their collapsing state ids. Used for keyboard navigation
+    rows: Vec<(Entity, Option<egui::Id>)>,
+    /// Start of range selection with Shift + Up/Down
+    range_anchor: Option<Entity>,
+    pub sort_mode: HierarchySortMode,
+    /// Order in which entities were first shown in hierarchy. Used for [`HierarchySortMode::SpawnOrder`]
+    spawn_order: HashMap<Entity, usize>,
+    /// Component icons of shown entities from [`HierarchyIcons`]
+    icons: HashMap<Entity, String>,
+    /// Confirmation window of "Clear all entities" is open
+    confirm_clear: bool,
+    /// Entities with children or referenced by other entities waiting for delete confirmation
+    confirm_delete: Option<Vec<Entity>>,
+    /// References to entities waiting for delete confirmation
+    delete_references: Vec<(EntityReference, String)>,
+    /// Open "Find references" window with name of the entity and found references
+    references: Option<(String, Vec<(EntityReference, String)>)>,
+    /// Case-insensitive filter of spawnable bundles by name
+    bundle_filter: String,
+    /// Broken requirement rules of shown entities from [`EditorRegistry`]
+    missing: HashMap<Entity, Vec<ComponentRequirement>>,
+    /// Shown entities with [`Pinned`] marker
+    pinned: HashSet<Entity>,
+    /// Pinned entity which is drawn in "Pinned" section now. Other pinned entities are skipped in tree
+    pinned_root: Option<Entity>,
+    /// Name of component in [`ComponentClipboard`] for "Paste" context action
+    copied_component: Option<String>,
+    /// Game is running, so delete, clone and clear actions are disabled
+    running: bool,
+    /// [`HierarchyTag`] of shown entities
+    tags: HashMap<Entity, HierarchyTag>,
+    /// Show only entities with this tag (and their ancestors)
+    tag_filter: Option<HierarchyTag>,
+    /// Open "Rename selected" dialog
+    batch_rename: Option<BatchRenameState>,
+    /// Open "Duplicate in line/grid" dialog
+    duplicate_array: Option<DuplicateArrayState>,
+    /// Entity selected outside of hierarchy with count of frames left to scroll to its row
+    reveal: Option<(Entity, u32)>,
+    /// Selection was changed by click or keyboard in hierarchy, so it will be flashed in viewport
+    selected_in_hierarchy: bool,
+    /// Open "Save as prefab" dialog with the entity to save
+    save_prefab: Option<(Entity, egui_file::FileDialog)>,
+    /// Result of the last "Save as prefab", "Revert to prefab" or "Apply overrides to prefab"
+    prefab_result: Option<Result<String, String>>,
+    /// Shown prefab instance roots with [`InstanceOf`] and whether their content is editable
+    instances: HashMap<Entity, bool>,
+    /// Shown content of prefab instances without [`EditableInstance`]. It can not be renamed or dragged
+    read_only: HashSet<Entity>,
+    /// Shown entities without [`PrefabMarker`]. Scene edit actions are disabled for them
+    editor_entities: HashSet<Entity>,
+    /// Shown entities with not empty [`PrefabOverrides`]
+    overridden: HashSet<Entity>,
+    /// Shown entities with [`LinkedAssets`]
+    linked: HashSet<Entity>,
+    /// Shown entity with [`Hovered`] marker. Its row is tinted
+    hovered: Option<Entity>,
+    /// Entity of row under pointer in the last drawn frame. Taken by [`update_hovered`](crate::selection::update_hovered),
+    /// so it is `None` when hierarchy tab is not drawn
+    pub(crate) hovered_row: Option<Entity>,
+    /// Use small indentation and stop indenting rows deeper than [`COMPACT_INDENT_DEPTH`]
+    compact_indent: bool,
+    /// Horizontal offset of tree requested by middle mouse drag
+    pan_offset: Option<f32>,
+    /// Category and name of bundle dragged from "Spawnable bundles" to game view
+    dragged_bundle: Option<(String, String)>,
+    /// Warning of the last rejected "Attach to"
+    attach_warning: Option<String>,
+    /// [`EditorNote`] text of shown entities
+    notes: HashMap<Entity, String>,
+    /// Show only entities with [`EditorNote`] (and their ancestors)
+    notes_filter: bool,
+    /// Entity and text of opened note editor popup
+    editing_note: Option<(Entity, String)>,
+}
+
+/// Side of bundle thumbnail in "Spawnable bundles" menu
+const BUNDLE_PREVIEW_SIZE: f32 = 32.0;
+
+/// Scale text and row spacing of hierarchy tree by [`HierarchySettings::row_scale`]
+fn scale_tree_style(style: &mut egui::Style, scale: f32) {
+    if scale == 1.0 {
+        return;
+    }
+    for font in style.text_styles.values_mut() {
+        font.size *= scale;
+    }
+    style.spacing.indent *= scale;
+    style.spacing.interact_size.y *= scale;
+    style.spacing.icon_width *= scale;
+    style.spacing.icon_width_inner *= scale;
+}
+
+/// Indentation of children rows in compact mode
+const COMPACT_INDENT: f32 = 8.0;
+/// Rows deeper than this are not indented in compact mode and show their depth instead
+const COMPACT_INDENT_DEPTH: usize = 8;
+
+/// Frames during which revealed row is kept in view while its ancestors are expanding
+const REVEAL_FRAMES: u32 = 10;
+/// Seconds of selection flash in viewport after selecting in hierarchy
+const FLASH_TIME: f32 = 0.6;
+
+/// Seconds of "Ping in viewport" highlight
+const PING_TIME: f32 = 1.5;
+/// Count of expanding rings during [`PING_TIME`]
+const PING_PULSES: f32 = 3.0;
+
+/// Editor only highlight of entity pinged from hierarchy. It is removed when timer is finished.
+/// Unlike "Focus camera", the camera is not moved
+#[derive(Component)]
+pub struct Ping {
+    pub timer: Timer,
+}
+
+impl Default for Ping {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(PING_TIME, TimerMode::Once),
+        }
+    }
+}
+
+/// Entities selected in hierarchy, which bounds are flashed in viewport
+#[derive(Resource, Default)]
+pub struct SelectionFlash {
+    pub entities: Vec<Entity>,
+    /// Elapsed seconds of [`Time`] when flash was started
+    pub start: f32,
+}
+
+/// Entities shown in hierarchy
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HierarchyShowMode {
+    /// Only [`PrefabMarker`] entities, which are saved to prefab
+    #[default]
+    Prefab,
+    /// Only entities without [`PrefabMarker`] (cameras, gizmos and other editor internals)
+    Editor,
+    /// All entities in world
+    All,
+}
+
+const HIERARCHY_SHOW_MODES: [HierarchyShowMode; 3] = [
+    HierarchyShowMode::Prefab,
+    HierarchyShowMode::Editor,
+    HierarchyShowMode::All,
+];
+
+impl ToString for HierarchyShowMode {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Prefab => "Prefab",
+            Self::Editor => "Editor only",
+            Self::All => "All",
+        }
+        .to_string()
+    }
+}
+
+/// Order of sibling rows in hierarchy
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HierarchySortMode {
+    /// Children are shown in [`Children`] order like in prefab. Roots are sorted by id
+    #[default]
+    Hierarchy,
+    /// Alphabetical by [`Name`], unnamed entities are shown last
+    Name,
+    /// Order of appearance in hierarchy in current session
+    SpawnOrder,
+    /// Raw [`Entity`] id order
+    EntityId,
+}
+
+const HIERARCHY_SORT_MODES: [HierarchySortMode; 4] = [
+    HierarchySortMode::Hierarchy,
+    HierarchySortMode::Name,
+    HierarchySortMode::SpawnOrder,
+    HierarchySortMode::EntityId,
+];
+
+impl ToString for HierarchySortMode {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Hierarchy => "Hierarchy",
+            Self::Name => "Name",
+            Self::SpawnOrder => "Spawn order",
+            Self::EntityId => "Entity id",
+        }
+        .to_string()
+    }
+}
+
+impl HierarchySortMode {
+    fn sort_key(
+        self,
+        entity: Entity,
+        name: Option<&Name>,
+        spawn_order: &HashMap<Entity, usize>,
+    ) -> (bool, String, usize, Entity) {
+        match self {
+            Self::Name => (
+                name.is_none(),
+                name.map(|name| name.as_str().to_lowercase())
+                    .unwrap_or_default(),
+                0,
+                entity,
+            ),
+            Self::SpawnOrder => (
+                false,
+                String::new(),
+                spawn_order.get(&entity).copied().unwrap_or(usize::MAX),
+                entity,
+            ),
+            Self::Hierarchy | Self::EntityId => (false, String::new(), 0, entity),
+        }
+    }
+}
+
+/// State of inline entity rename in hierarchy
+pub struct RenameState {
+    pub entity: Entity,
+    pub text: String,
+    focused: bool,
+}
+
+/// Settings of "Rename selected" dialog
+pub struct BatchRenameState {
+    /// Name pattern, `{n}` is replaced by index
+    pub pattern: String,
+    pub start: usize,
+    /// Minimal count of index digits, index is padded with zeros
+    pub padding: usize,
+}
+
+impl Default for BatchRenameState {
+    fn default() -> Self {
+        Self {
+            pattern: "Entity_{n}".to_string(),
+            start: 1,
+            padding: 0,
+        }
+    }
+}
+
+/// Name for batch rename: `{n}` in pattern is replaced by zero padded index.
+/// Index is appended to the end if pattern has no `{n}`, so names stay unique
+pub fn batch_rename_name(pattern: &str, index: usize, padding: usize) -> String {
+    let index = format!("{:0width$}", index, width = padding);
+    if pattern.contains("{n}") {
+        pattern.replace("{n}", &index)
+    } else {
+        format!("{}{}", pattern, index)
+    }
+}
+
+type HierarchyQueryIter<'a> = (
+    Entity,
+    Option<&'a Name>,
+    Option<&'a Children>,
+    Option<&'a Parent>,
+    Option<&'a LoadError>,
+    Option<&'a EditorVisibility>,
+    Has<Locked>,
+);
+
+/// System to show hierarchy
+pub fn show_hierarchy(
+    mut commands: Commands,
+    query: Query<HierarchyQueryIter, With<PrefabMarker>>,
+    all_entites: Query<HierarchyQueryIter>,
+    mut selected: Query<Entity, With<Selected>>,
+    mut clone_events: EventWriter<CloneEvent>,
+    ui_reg: Res<BundleReg>,
+    mut ui: NonSendMut<EditorUiRef>,
+    mut changes: EventWriter<NewChange>,
+    mut state: ResMut<HierarchyTabState>,
+    mut settings: ResMut<HierarchySettings>,
+    mut active: ResMut<ActiveEntity>,
+    icons: Res<HierarchyIcons>,
+    entity_refs: Query<EntityRef>,
+    editor_registry: Res<EditorRegistry>,
+    component_clipboard: Res<ComponentClipboard>,
+    (trash, run_state): (Res<HierarchyTrash>, Option<Res<State<EditorRunState>>>),
+) {
+    state.copied_component = component_clipboard.name().map(str::to_string);
+    state.running = run_state.is_some_and(|state| !state.is_editing());
+    // Escape which cancels inline rename must not clear selection
+    let was_renaming = state.renaming.is_some();
+
+    let mut all: Vec<_> = if state.show_mode != HierarchyShowMode::Prefab {
+        all_entites.iter().collect()
+    } else {
+        query.iter().collect()
+    };
+    all.sort_by_key(|a| a.0);
+
+    // Entities appeared in the same frame get spawn order by id
+    state
+        .spawn_order
+        .retain(|entity, _| all_entites.contains(*entity));
+    let mut next_order = state
+        .spawn_order
+        .values()
+        .max()
+        .map_or(0, |order| order + 1);
+    for (entity, ..) in all.iter() {
+        state.spawn_order.entry(*entity).or_insert_with(|| {
+            next_order += 1;
+            next_order - 1
+        });
+    }
+    let sort_mode = state.sort_mode;
+    all.sort_by_cached_key(|a| sort_mode.sort_key(a.0, a.1, &state.spawn_order));
+
+    state.icons.clear();
+    state.missing.clear();
+    state.pinned.clear();
+    state.tags.clear();
+    state.notes.clear();
+    state.instances.clear();
+    state.read_only.clear();
+    state.editor_entities.clear();
+    state.overridden.clear();
+    state.linked.clear();
+    state.hovered = None;
+    for (entity, ..) in all.iter() {
+        if let Ok(entity_ref) = entity_refs.get(*entity) {
+            let icons = icons.icons_for(&entity_ref);
+            if !icons.is_empty() {
+                state.icons.insert(*entity, icons);
+            }
+            let missing = editor_registry.missing_components(&entity_ref);
+            if !missing.is_empty() {
+                state.missing.insert(*entity, missing);
+            }
+            if entity_ref.contains::<Pinned>() {
+                state.pinned.insert(*entity);
+            }
+            if let Some(tag) = entity_ref.get::<HierarchyTag>() {
+                state.tags.insert(*entity, *tag);
+            }
+            if let Some(note) = entity_ref.get::<EditorNote>() {
+                state.notes.insert(*entity, note.0.clone());
+            }
+            if entity_ref.contains::<InstanceOf>() {
+                state
+                    .instances
+                    .insert(*entity, entity_ref.contains::<EditableInstance>());
+            } else if entity_ref.contains::<PrefabInstance>() {
+                // Content is read-only, if the nearest instance root is not editable
+                let mut ancestor = entity_ref.get::<Parent>().map(Parent::get);
+                while let Some(Ok(ancestor_ref)) = ancestor.map(|id| entity_refs.get(id)) {
+                    if ancestor_ref.contains::<InstanceOf>() {
+                        if !ancestor_ref.contains::<EditableInstance>() {
+                            state.read_only.insert(*entity);
+                        }
+                        break;
+                    }
+                    ancestor = ancestor_ref.get::<Parent>().map(Parent::get);
+                }
+            }
+            if !entity_ref.contains::<PrefabMarker>() {
+                state.editor_entities.insert(*entity);
+            }
+            if entity_ref.contains::<LinkedAssets>() {
+                state.linked.insert(*entity);
+            }
+            if entity_ref.contains::<Hovered>() {
+                state.hovered = Some(*entity);
+            }
+            if entity_ref
+                .get::<PrefabOverrides>()
+                .is_some_and(|overrides| !overrides.is_empty())
+            {
+                state.overridden.insert(*entity);
+            }
+        }
+    }
+
+    // Entities matching the filters and all their ancestors, so hierarchy context is kept
+    let filter = state.filter.to_lowercase();
+    let editor_only = state.show_mode == HierarchyShowMode::Editor;
+    let component_filter = state.component_filter.as_ref().map(|(type_id, _)| *type_id);
+    let visible = if filter.is_empty()
+        && state.tag_filter.is_none()
+        && !state.notes_filter
+        && !editor_only
+        && component_filter.is_none()
+    {
+        None
+    } else {
+        let mut visible = HashSet::new();
+        for (entity, name, _, parent, _, _, _) in all.iter() {
+            if let Ok(entity_ref) = entity_refs.get(*entity) {
+                if editor_only && entity_ref.contains::<PrefabMarker>() {
+                    continue;
+                }
+                if component_filter.is_some_and(|type_id| !entity_ref.contains_type_id(type_id)) {
+                    continue;
+                }
+            }
+            let text = name.map_or_else(
+                || format!("Entity ({:?})", entity),
+                |name| name.as_str().to_string(),
+            );
+            if !text.to_lowercase().contains(&filter) {
+                continue;
+            }
+            if state
+                .tag_filter
+                .is_some_and(|tag| state.tags.get(entity) != Some(&tag))
+            {
+                continue;
+            }
+            if state.notes_filter && !state.notes.contains_key(entity) {
+                continue;
+            }
+            visible.insert(*entity);
+            let mut ancestor = parent.map(Parent::get);
+            while let Some(id) = ancestor {
+                if !visible.insert(id) {
+                    break;
+                }
+                ancestor = all_entites
+                    .get(id)
+                    .ok()
+                    .and_then(|(_, _, _, parent, _, _, _)| parent.map(Parent::get));
+            }
+        }
+        Some(visible)
+    };
+
+    let ui = &mut ui.0;
+
+    // Path to entity selected in viewport is expanded, so its row can be scrolled to
+    if let Some((reveal, frames)) = state.reveal {
+        if frames == REVEAL_FRAMES {
+            let mut ancestor = all_entites
+                .get(reveal)
+                .ok()
+                .and_then(|row| row.3.map(Parent::get));
+            while let Some(id) = ancestor {
+                let mut collapsing = CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    hierarchy_collapsing_id(id),
+                    true,
+                );
+                collapsing.set_open(true);
+                collapsing.store(ui.ctx());
+                ancestor = all_entites
+                    .get(id)
+                    .ok()
+                    .and_then(|row| row.3.map(Parent::get));
+            }
+        }
+        state.reveal = frames.checked_sub(1).map(|frames| (reveal, frames));
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.text_edit_singleline(&mut state.filter);
+            if ui.button("x").clicked() {
+                state.filter.clear();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Sort by:");
+            egui::ComboBox::new("hierarchy_sort_mode", "")
+                .selected_text(state.sort_mode.to_string())
+                .show_ui(ui, |ui| {
+                    for mode in HIERARCHY_SORT_MODES.into_iter() {
+                        ui.selectable_value(&mut state.sort_mode, mode, mode.to_string());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Show:");
+            egui::ComboBox::new("hierarchy_show_mode", "")
+                .selected_text(state.show_mode.to_string())
+                .show_ui(ui, |ui| {
+                    for mode in HIERARCHY_SHOW_MODES.into_iter() {
+                        ui.selectable_value(&mut state.show_mode, mode, mode.to_string());
+                    }
+                });
+            component_filter_combo(ui, &mut state, &editor_registry);
+        });
+        select_with_component_menu(ui, &mut commands, &editor_registry);
+        tag_legend(ui, &mut state, &mut settings);
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut state.compact_indent, "Reduce indentation");
+            ui.checkbox(&mut state.notes_filter, "📝 Only with notes");
+        });
+
+        // Deep trees are scrolled horizontally, middle mouse drag pans them
+        let mut tree_scroll = egui::ScrollArea::horizontal()
+            .id_source("hierarchy_tree")
+            .auto_shrink([false, true]);
+        if let Some(offset) = state.pan_offset.take() {
+            tree_scroll = tree_scroll.horizontal_scroll_offset(offset);
+        }
+        let row_scale = settings.row_scale;
+        let tree = tree_scroll.show(ui, |ui| {
+            scale_tree_style(ui.style_mut(), row_scale);
+            if state.compact_indent {
+                ui.spacing_mut().indent = COMPACT_INDENT * row_scale;
+            }
+            state.rows.clear();
+            let pinned = all
+                .iter()
+                .map(|row| row.0)
+                .filter(|entity| state.pinned.contains(entity))
+                .collect::<Vec<_>>();
+            if !pinned.is_empty() {
+                egui::CollapsingHeader::new("📌 Pinned")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for entity in pinned {
+                            state.pinned_root = Some(entity);
+                            if state.show_mode != HierarchyShowMode::Prefab {
+                                draw_entity::<()>(
+                                    &mut commands,
+                                    ui,
+                                    &all_entites,
+                                    entity,
+                                    &mut selected,
+                                    &mut clone_events,
+                                    &mut changes,
+                                    &mut state,
+                                    &settings,
+                                    visible.as_ref(),
+                                );
+                            } else {
+                                draw_entity::<With<PrefabMarker>>(
+                                    &mut commands,
+                                    ui,
+                                    &query,
+                                    entity,
+                                    &mut selected,
+                                    &mut clone_events,
+                                    &mut changes,
+                                    &mut state,
+                                    &settings,
+                                    visible.as_ref(),
+                                );
+                            }
+                        }
+                        state.pinned_root = None;
+                    });
+                ui.separator();
+            }
+
+            for (entity, _name, _children, parent, _, _, _) in all.iter() {
+                if parent.is_none() {
+                    if state.show_mode != HierarchyShowMode::Prefab {
+                        draw_entity::<()>(
+                            &mut commands,
+                            ui,
+                            &all_entites,
+                            *entity,
+                            &mut selected,
+                            &mut clone_events,
+                            &mut changes,
+                            &mut state,
+                            &settings,
+                            visible.as_ref(),
+                        );
+                    } else {
+                        draw_entity::<With<PrefabMarker>>(
+                            &mut commands,
+                            ui,
+                            &query,
+                            *entity,
+                            &mut selected,
+                            &mut clone_events,
+                            &mut changes,
+                            &mut state,
+                            &settings,
+                            visible.as_ref(),
+                        );
+                    }
+                }
+            }
+        });
+        let pan = ui.input(|i| {
+            let over_tree = i
+                .pointer
+                .hover_pos()
+                .is_some_and(|pos| tree.inner_rect.contains(pos));
+            (i.pointer.middle_down() && over_tree).then(|| i.pointer.delta().x)
+        });
+        if let Some(delta) = pan.filter(|delta| *delta != 0.0) {
+            state.pan_offset = Some((tree.state.offset.x - delta).max(0.0));
+        }
+        // Ctrl+Scroll is reported by egui as zoom
+        let zoom = ui.input(|i| {
+            let over_tree = i
+                .pointer
+                .hover_pos()
+                .is_some_and(|pos| tree.inner_rect.contains(pos));
+            if over_tree {
+                i.zoom_delta()
+            } else {
+                1.0
+            }
+        });
+        if zoom != 1.0 {
+            settings.row_scale = (settings.row_scale * zoom)
+                .clamp(*HIERARCHY_ROW_SCALE.start(), *HIERARCHY_ROW_SCALE.end());
+        }
+
+        if ui.ui_contains_pointer() && !ui.ctx().wants_keyboard_input() {
+            keyboard_navigation(ui, &mut commands, &mut state, &mut active, was_renaming);
+        }
+
+        if ui.input(|i| i.pointer.any_released()) {
+            // Dropped not on entity row: move dragged entity to world root
+            if let Some(dragged_entity) = state.dragged_entity.take() {
+                let in_area = ui
+                    .input(|i| i.pointer.interact_pos())
+                    .is_some_and(|pointer| ui.clip_rect().contains(pointer));
+                if in_area {
+                    let snap = ui.input(|i| i.modifiers.alt);
+                    commands.add(move |world: &mut World| {
+                        record_reparent_in_place(world, dragged_entity, snap, |world| {
+                            world.entity_mut(dragged_entity).remove_parent();
+                        });
+                    });
+                }
+            }
+        }
+
+        ui.separator();
+        trash.ui(ui, &mut commands);
+
+        ui.spacing();
+        ui.separator();
+        ui.vertical_centered_justified(|ui| {
+            ui.columns(2, |columns| {
+                let open = if columns[0].button("Expand all").clicked() {
+                    Some(true)
+                } else if columns[1].button("Collapse all").clicked() {
+                    Some(false)
+                } else {
+                    None
+                };
+                if let Some(open) = open {
+                    for (entity, _, children, _, _, _, _) in all.iter() {
+                        if children.is_some_and(|children| !children.is_empty()) {
+                            let mut collapsing = CollapsingState::load_with_default_open(
+                                columns[0].ctx(),
+                                hierarchy_collapsing_id(*entity),
+                                true,
+                            );
+                            collapsing.set_open(open);
+                            collapsing.store(columns[0].ctx());
+                        }
+                    }
+                }
+            });
+            if ui.button("+ Add new entity").clicked() {
+                let id = commands.spawn_empty().insert(PrefabMarker).id();
+                commands.add(move |world: &mut World| auto_name_empty(world, id));
+                changes.send(NewChange {
+                    change: Arc::new(AddedEntity { entity: id }),
+                });
+            }
+            if ui
+                .add_enabled(!state.running, egui::Button::new("Clear all entities"))
+                .on_disabled_hover_text(RUNNING_HINT)
+                .clicked()
+            {
+                state.confirm_clear = true;
+            }
+            if ui.button("Repair hierarchy").clicked() {
+                commands.add(|world: &mut World| {
+                    let report = repair_hierarchy(world);
+                    world.resource_mut::<HierarchyTabState>().repair_report = report;
+                });
+            }
+        });
+
+        if state.confirm_clear {
+            let mut close = false;
+            egui::Window::new("Clear all entities?")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "All {} prefab entities will be removed. This can be undone with Ctrl+Z",
+                        query.iter().count()
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Clear").clicked() {
+                            commands.add(clear_all_entities);
+                            close = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+            if close {
+                state.confirm_clear = false;
+            }
+        }
+
+        if let Some(targets) = state.confirm_delete.clone() {
+            let mut close = false;
+            let mut descendants = 0;
+            let mut queue = targets.clone();
+            while let Some(entity) = queue.pop() {
+                if let Some(children) = all_entites.get(entity).ok().and_then(|row| row.2) {
+                    descendants += children.len();
+                    queue.extend(children.iter());
+                }
+            }
+            let references = &state.delete_references;
+            egui::Window::new("Delete entities?")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "{} entities will be removed with {} descendants. This can be undone with Ctrl+Z",
+                        targets.len(),
+                        descendants
+                    ));
+                    if !references.is_empty() {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!(
+                                "⚠ {} references from other entities will be broken:",
+                                references.len()
+                            ),
+                        );
+                        references_list(ui, &mut commands, references);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Delete").clicked() {
+                            commands.add(move |world: &mut World| delete_entities(world, &targets));
+                            close = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+            if close {
+                state.confirm_delete = None;
+                state.delete_references.clear();
+            }
+        }
+
+        let mut open = true;
+        if let Some((name, references)) = &state.references {
+            egui::Window::new(format!("References to {}", name))
+                .id(egui::Id::new("hierarchy_references"))
+                .collapsible(false)
+                .open(&mut open)
+                .show(ui.ctx(), |ui| {
+                    if references.is_empty() {
+                        ui.label("No references, entity can be deleted safely");
+                    } else {
+                        references_list(ui, &mut commands, references);
+                    }
+                });
+        }
+        if !open {
+            state.references = None;
+        }
+
+        if state.editing_note.is_some() {
+            note_window(ui, &mut commands, &mut state);
+        }
+        if state.batch_rename.is_some() {
+            batch_rename_window(ui, &mut commands, &mut state, selected.iter().count());
+        }
+        if state.duplicate_array.is_some() {
+            duplicate_array_window(ui, &mut commands, &mut state);
+        }
+
+        let mut saved = false;
+        if let Some((entity, dialog)) = &mut state.save_prefab {
+            if dialog.show(ui.ctx()).selected() {
+                saved = true;
+                if let Some(file) = dialog.path() {
+                    let mut path = file.to_string_lossy().to_string();
+                    if !path.ends_with(".scn.ron") {
+                        path.push_str(".scn.ron");
+                    }
+                    let entity = *entity;
+                    commands.add(move |world: &mut World| {
+                        let result = save_subtree_as_prefab(world, entity, &path)
+                            .map(|path| format!("Prefab saved to {}", path));
+                        report_prefab_result(world, result);
+                    });
+                }
+            }
+        }
+        if saved {
+            state.save_prefab = None;
+        }
+
+        if let Some(result) = &state.prefab_result {
+            let mut close = false;
+            ui.horizontal(|ui| {
+                match result {
+                    Ok(message) => ui.label(message),
+                    Err(err) => ui.colored_label(egui::Color32::RED, err),
+                };
+                close = ui.button("✖").clicked();
+            });
+            if close {
+                state.prefab_result = None;
+            }
+        }
+
+        if let Some(warning) = &state.attach_warning {
+            let mut close = false;
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::YELLOW, warning);
+                close = ui.button("✖").clicked();
+            });
+            if close {
+                state.attach_warning = None;
+            }
+        }
+
+        if !state.repair_report.is_empty() {
+            let mut close = false;
+            ui.collapsing("Last hierarchy repair", |ui| {
+                for line in state.repair_report.iter() {
+                    ui.label(line);
+                }
+                close = ui.button("Close report").clicked();
+            });
+            if close {
+                state.repair_report.clear();
+            }
+        }
+
+        ui.spacing();
+
+        if let Some((category_name, name)) = state.dragged_bundle.clone() {
+            let pointer = ui.input(|i| i.pointer.interact_pos());
+            if let Some(pointer) = pointer {
+                egui::Area::new("dragged_bundle")
+                    .fixed_pos(pointer + egui::vec2(12.0, 12.0))
+                    .interactable(false)
+                    .order(egui::Order::Tooltip)
+                    .show(ui.ctx(), |ui| {
+                        let preview = ui_reg
+                            .bundles
+                            .get(&category_name)
+                            .and_then(|bundles| bundles.get(&name))
+                            .and_then(|bundle| bundle.preview_texture());
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                if let Some(texture) = preview {
+                                    ui.image(preview_image(texture));
+                                }
+                                ui.label(&name);
+                            });
+                        });
+                    });
+            }
+            if ui.input(|i| i.pointer.any_released()) {
+                state.dragged_bundle = None;
+                if let Some(pointer) = pointer {
+                    commands.add(move |world: &mut World| {
+                        drop_bundle(world, &category_name, &name, pointer);
+                    });
+                }
+            }
+        }
+
+        ui.label("Spawnable bundles");
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut state.bundle_filter);
+            if !state.bundle_filter.is_empty() && ui.button("✖").clicked() {
+                state.bundle_filter.clear();
+            }
+        });
+
+        // With filter matched bundles of all categories are shown as one flat list
+        if !state.bundle_filter.is_empty() {
+            let filter = state.bundle_filter.to_lowercase();
+            let mut matches: Vec<(&String, &String, &EditorBundleUntyped)> = ui_reg
+                .bundles
+                .iter()
+                .flat_map(|(category_name, category_bundle)| {
+                    category_bundle
+                        .iter()
+                        .map(move |(name, dyn_bundle)| (category_name, name, dyn_bundle))
+                })
+                .filter(|(_, name, _)| name.to_lowercase().contains(&filter))
+                .collect();
+            matches.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)));
+
+            if matches.is_empty() {
+                ui.weak("No matching bundles");
+            }
+            for (category_name, name, dyn_bundle) in matches {
+                let response = bundle_button(ui, name, dyn_bundle.preview_texture())
+                    .on_hover_text(category_name.as_str());
+                if response.drag_started() {
+                    state.dragged_bundle = Some((category_name.clone(), name.clone()));
+                }
+                if response.clicked() {
+                    let entity = dyn_bundle.spawn(&mut commands);
+                    commands.add(move |world: &mut World| place_spawned_entity(world, entity));
+                    changes.send(NewChange {
+                        change: Arc::new(AddedEntity { entity }),
+                    });
+                }
+            }
+            return;
+        }
+
+        for (category_name, category_bundle) in ui_reg.bundles.iter() {
+            ui.menu_button(category_name, |ui| {
+                let mut categories_vec: Vec<(&String, &EditorBundleUntyped)> =
+                    category_bundle.iter().collect();
+                categories_vec.sort_by(|a, b| a.0.cmp(b.0));
+
+                for (name, dyn_bundle) in categories_vec {
+                    let response = bundle_button(ui, name, dyn_bundle.preview_texture());
+                    if response.drag_started() {
+                        state.dragged_bundle = Some((category_name.clone(), name.clone()));
+                    }
+                    if response.clicked() {
+                        let entity = dyn_bundle.spawn(&mut commands);
+                        commands.add(move |world: &mut World| place_spawned_entity(world, entity));
+                        changes.send(NewChange {
+                            change: Arc::new(AddedEntity { entity }),
+                        });
+                    }
+                }
+            });
+        }
+    });
+}
+
+/// Choose component type from [`EditorRegistry`] to show only entities with it
+fn component_filter_combo(
+    ui: &mut egui::Ui,
+    state: &mut HierarchyTabState,
+    editor_registry: &EditorRegistry,
+) {
+    let selected_text = state
+        .component_filter
+        .as_ref()
+        .map_or("Any component", |(_, name)| name.as_str())
+        .to_string();
+    egui::ComboBox::new("hierarchy_component_filter", "")
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            if ui
+                .selectable_label(state.component_filter.is_none(), "Any component")
+                .clicked()
+            {
+                state.component_filter = None;
+            }
+            for (type_id, name) in registered_component_types(editor_registry) {
+                let is_selected = state
+                    .component_filter
+                    .as_ref()
+                    .is_some_and(|(selected, _)| *selected == type_id);
+                if ui.selectable_label(is_selected, &name).clicked() {
+                    state.component_filter = Some((type_id, name));
+                }
+            }
+        });
+}
+
+/// Menu to select all prefab entities with chosen component. Shift extends current selection
+fn select_with_component_menu(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    editor_registry: &EditorRegistry,
+) {
+    ui.menu_button("Select all with component…", |ui| {
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for (type_id, name) in registered_component_types(editor_registry) {
+                    if ui.button(&name).clicked() {
+                        let extend = ui.input(|i| i.modifiers.shift);
+                        commands.add(move |world: &mut World| {
+                            let count = select_all_with_component(world, type_id, extend);
+                            info!("Selected {} entities with {}", count, name);
+                        });
+                        ui.close_menu();
+                    }
+                }
+            });
+    })
+    .response
+    .on_hover_text("Hold Shift to add to current selection");
+}
+
+/// Reflected component types from [`EditorRegistry`] with their short names, sorted by name
+fn registered_component_types(editor_registry: &EditorRegistry) -> Vec<(TypeId, String)> {
+    let registry = editor_registry.registry.read();
+    let mut types = registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .map(|registration| {
+            (
+                registration.type_id(),
+                registration
+                    .type_info()
+                    .type_path_table()
+                    .short_path()
+                    .to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    types.sort_by(|a, b| a.1.cmp(&b.1));
+    types
+}
+
+/// Select all [`PrefabMarker`] entities with registered component `type_id`.
+/// If `extend`, entities are added to current selection, otherwise selection is replaced.
+/// Returns count of matched entities
+pub fn select_all_with_component(world: &mut World, type_id: TypeId, extend: bool) -> usize {
+    let editor_registry = world.resource::<EditorRegistry>().clone();
+    let Some(reflect_component) = editor_registry
+        .registry
+        .read()
+        .get_type_data::<ReflectComponent>(type_id)
+        .cloned()
+    else {
+        return 0;
+    };
+    let entities = world
+        .query_filtered::<EntityRef, With<PrefabMarker>>()
+        .iter(world)
+        .filter(|entity| reflect_component.contains(*entity))
+        .map(|entity| entity.id())
+        .collect::<Vec<_>>();
+    let mode = if extend {
+        SelectMode::Add
+    } else {
+        SelectMode::Replace
+    };
+    select_entities(world, &entities, mode);
+    entities.len()
+}
+
+/// Bundle button, which spawns bundle on click and can be dragged to game view
+fn bundle_button(
+    ui: &mut egui::Ui,
+    name: &str,
+    preview: Option<egui::TextureId>,
+) -> egui::Response {
+    let button = match preview {
+        Some(texture) => egui::Button::image_and_text(preview_image(texture), name),
+        None => egui::Button::new(name),
+    };
+    ui.add(button.sense(egui::Sense::click_and_drag()))
+        .on_hover_cursor(egui::CursorIcon::Grab)
+}
+
+fn preview_image<'a>(texture: egui::TextureId) -> egui::Image<'a> {
+    egui::Image::new(egui::load::SizedTexture::new(
+        texture,
+        egui::Vec2::splat(BUNDLE_PREVIEW_SIZE),
+    ))
+}
+
+/// Spawn bundle dropped to game view at the surface under the cursor (or at the ground plane).
+/// Dropping outside of game view does nothing
+fn drop_bundle(world: &mut World, category: &str, name: &str, pointer: egui::Pos2) {
+    let Some(viewport) = world.resource::<GameViewTab>().viewport_rect else {
+        return;
+    };
+    if !viewport.contains(pointer) {
+        return;
+    }
+
+    let mut queue = CommandQueue::default();
+    let entity = {
+        let Some(dyn_bundle) = world
+            .resource::<BundleReg>()
+            .bundles
+            .get(category)
+            .and_then(|category| category.get(name))
+        else {
+            return;
+        };
+        let mut commands = Commands::new(&mut queue, world);
+        dyn_bundle.spawn(&mut commands)
+    };
+    queue.apply(world);
+
+    let point = game_view_to_viewport(world, pointer, viewport)
+        .and_then(|cursor| cursor_surface_point(world, cursor, entity));
+    if let Some(point) = point {
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            transform.translation = point;
+        }
+    }
+    world.send_event(NewChange {
+        change: Arc::new(AddedEntity { entity }),
+    });
+}
+
+/// Spawn registered bundle from [`BundleReg`] in the same way as click on it in "Spawnable bundles" list.
+/// Spawn is recorded in undo. Returns `None` if there is no such bundle
+pub fn spawn_bundle(world: &mut World, category: &str, name: &str) -> Option<Entity> {
+    let mut queue = CommandQueue::default();
+    let entity = {
+        let dyn_bundle = world
+            .resource::<BundleReg>()
+            .bundles
+            .get(category)
+            .and_then(|category| category.get(name))?;
+        let mut commands = Commands::new(&mut queue, world);
+        dyn_bundle.spawn(&mut commands)
+    };
+    queue.apply(world);
+
+    place_spawned_entity(world, entity);
+    world.send_event(NewChange {
+        change: Arc::new(AddedEntity { entity }),
+    });
+    Some(entity)
+}
+
+/// Closest mesh hit under cursor in game view or ground plane point. `ignore` entity is not hit
+fn cursor_surface_point(world: &mut World, cursor: Vec2, ignore: Entity) -> Option<Vec3> {
+    let ray = world
+        .query_filtered::<(&Camera, &GlobalTransform), With<EditorCameraMarker>>()
+        .iter(world)
+        .next()
+        .and_then(|(camera, transform)| camera.viewport_to_world(transform, cursor))?;
+
+    let mut raycast = SystemState::<Raycast>::new(world);
+    let filter = |entity: Entity| entity != ignore;
+    let settings = RaycastSettings::default().with_filter(&filter);
+    let hit = raycast
+        .get_mut(world)
+        .cast_ray(Ray3d::new(ray.origin, ray.direction), &settings)
+        .first()
+        .map(|(_, hit)| hit.position());
+
+    hit.or_else(|| {
+        ray.intersect_plane(Vec3::ZERO, Vec3::Y)
+            .map(|distance| ray.get_point(distance))
+    })
+}
+
+/// Camera viewport position of egui point inside game view `viewport`
+fn game_view_to_viewport(world: &mut World, pos: egui::Pos2, viewport: egui::Rect) -> Option<Vec2> {
+    let window = world
+        .query_filtered::<&Window, With<PrimaryWindow>>()
+        .get_single(world)
+        .ok()?;
+    let egui_settings = world.get_resource::<EguiSettings>()?;
+    Some(egui_to_viewport(pos, viewport, window, egui_settings))
+}
+
+/// Move just spawned bundle to the point chosen by [`SpawnSettings::placement`]
+fn place_spawned_entity(world: &mut World, entity: Entity) {
+    let point = match world.resource::<SpawnSettings>().placement {
+        SpawnPlacement::Origin => None,
+        SpawnPlacement::CameraFocus => world
+            .query_filtered::<&PanOrbitCamera, With<EditorCameraMarker>>()
+            .iter(world)
+            .next()
+            .map(|camera| camera.focus),
+        SpawnPlacement::CursorGround => {
+            let game_view = world.resource::<GameViewTab>();
+            let cursor = game_view.last_cursor.zip(game_view.viewport_rect);
+            let position = cursor
+                .and_then(|(cursor, viewport)| game_view_to_viewport(world, cursor, viewport));
+            world
+                .query_filtered::<(&Camera, &GlobalTransform), With<EditorCameraMarker>>()
+                .iter(world)
+                .next()
+                .zip(position)
+                .and_then(|((camera, transform), position)| {
+                    let ray = camera.viewport_to_world(transform, position)?;
+                    let distance = ray.intersect_plane(Vec3::ZERO, Vec3::Y)?;
+                    Some(ray.get_point(distance))
+                })
+        }
+    };
+
+    let Some(point) = point else {
+        return;
+    };
+    if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+        transform.translation = point;
+    }
+}
+
+/// Id of entity row [`CollapsingState`]. Based on [`Entity`] bits, so it is kept after rename or hierarchy change
+pub(crate) fn hierarchy_collapsing_id(entity: Entity) -> egui::Id {
+    egui::Id::new(("hierarchy_entity", entity.to_bits()))
+}
+
+type DrawIter<'a> = (
+    Entity,
+    Option<&'a Name>,
+    Option<&'a Children>,
+    Option<&'a Parent>,
+    Option<&'a LoadError>,
+    Option<&'a EditorVisibility>,
+    Has<Locked>,
+);
+
+/// Count of entity ancestors shown in hierarchy
+fn hierarchy_depth<F: ReadOnlyWorldQuery>(query: &Query<DrawIter, F>, entity: Entity) -> usize {
+    let mut depth = 0;
+    let mut current = entity;
+    while let Some(parent) = query.get(current).ok().and_then(|row| row.3) {
+        depth += 1;
+        current = parent.get();
+    }
+    depth
+}
+
+fn draw_entity<F: ReadOnlyWorldQuery>(
+    commands: &mut Commands,
+    ui: &mut egui::Ui,
+    query: &Query<DrawIter, F>,
+    entity: Entity,
+    selected: &mut Query<Entity, With<Selected>>,
+    clone_events: &mut EventWriter<CloneEvent>,
+    changes: &mut EventWriter<NewChange>,
+    state: &mut HierarchyTabState,
+    settings: &HierarchySettings,
+    visible: Option<&HashSet<Entity>>,
+) {
+    if visible.is_some_and(|visible| !visible.contains(&entity)) {
+        return;
+    }
+    let pinned = state.pinned.contains(&entity);
+    if pinned && state.pinned_root != Some(entity) {
+        return;
+    }
+    let copied_component = state.copied_component.clone();
+    let running = state.running;
+    let tag = state.tags.get(&entity).copied();
+    let note = state.notes.get(&entity).cloned();
+    let instance = state.instances.get(&entity).copied();
+    let read_only = state.read_only.contains(&entity);
+    let scene_entity = !state.editor_entities.contains(&entity);
+    let linked = state.linked.contains(&entity);
+    let overridden = state.overridden.contains(&entity);
+    let Ok((_, name, children, parent, load_error, editor_visibility, locked)) = query.get(entity)
+    else {
+        return;
+    };
+    let hovered = state.hovered == Some(entity);
+    // Tag or hover tint is painted under the row after its size is known
+    let row_background = (tag.is_some() || hovered).then(|| ui.painter().add(egui::Shape::Noop));
+
+    let mut entity_name = match state.icons.get(&entity) {
+        Some(icons) => format!("{} {}", icons, settings.id_display.format(name, entity)),
+        None => settings.id_display.format(name, entity),
+    };
+    if note.is_some() {
+        entity_name = format!("{} 📝", entity_name);
+    }
+    if state.compact_indent {
+        let depth = hierarchy_depth(query, entity);
+        if depth >= COMPACT_INDENT_DEPTH {
+            entity_name = format!("{}› {}", depth, entity_name);
+        }
+        // Children of this row share depth limit with it, so rest of the parent body is not affected
+        if depth + 1 >= COMPACT_INDENT_DEPTH {
+            ui.spacing_mut().indent = 0.0;
+        }
+    }
+    let is_hidden = editor_visibility.is_some_and(EditorVisibility::is_hidden);
+    let missing = state.missing.get(&entity).cloned().unwrap_or_default();
+    let label_text = |text: String| {
+        let mut text = egui::RichText::new(text);
+        // Overridden prefab instance entities are shown in italics
+        if overridden {
+            text = text.italics();
+        }
+        if load_error.is_some() {
+            text.color(egui::Color32::RED)
+        } else if is_hidden || read_only {
+            text.weak()
+        } else {
+            text
+        }
+    };
+
+    let is_selected = selected.contains(entity);
+    let is_renaming = state
+        .renaming
+        .as_ref()
+        .is_some_and(|rename| rename.entity == entity);
+
+    // Only children shown by current hierarchy query are counted
+    let shown_children = children.map_or(0, |children| {
+        children
+            .iter()
+            .filter(|child| query.get(**child).is_ok())
+            .count()
+    });
+    let has_children = shown_children > 0;
+    let collapsing_id = has_children.then(|| hierarchy_collapsing_id(entity));
+    if has_children && settings.show_child_count {
+        entity_name = match visible {
+            Some(visible) => {
+                let matched = children
+                    .unwrap()
+                    .iter()
+                    .filter(|child| visible.contains(*child))
+                    .count();
+                format!("{} ({}/{})", entity_name, matched, shown_children)
+            }
+            None => format!("{} ({})", entity_name, shown_children),
+        };
+    }
+    // Locked rows can not be selected, so keyboard navigation skips them
+    if !locked {
+        state.rows.push((entity, collapsing_id));
+    }
+
+    let label = if let Some(collapsing_id) = collapsing_id {
+        // Prefab instances are shown as a single node until expanded
+        CollapsingState::load_with_default_open(ui.ctx(), collapsing_id, instance.is_none())
+            .show_header(ui, |ui| {
+                visibility_toggle(ui, commands, entity, is_hidden, settings.cascade_hide);
+                lock_toggle(ui, commands, entity, locked);
+                missing_components_warning(ui, commands, entity, &missing);
+                if is_renaming {
+                    rename_field(ui, commands, &mut state.renaming, name)
+                } else {
+                    ui.selectable_label(is_selected, label_text(entity_name))
+                        .context_menu(|ui| {
+                            hierarchy_entity_context(
+                                ui,
+                                commands,
+                                entity,
+                                changes,
+                                clone_events,
+                                selected,
+                                parent,
+                                children,
+                                locked,
+                                pinned,
+                                tag,
+                                note.as_deref(),
+                                instance,
+                                linked,
+                                scene_entity,
+                                read_only,
+                                running,
+                                copied_component.as_deref(),
+                            );
+                        })
+                }
+            })
+            .body(|ui| {
+                let mut children = children.unwrap().to_vec();
+                if state.sort_mode != HierarchySortMode::Hierarchy {
+                    let sort_mode = state.sort_mode;
+                    children.sort_by_cached_key(|child| {
+                        let name = query.get(*child).ok().and_then(|row| row.1);
+                        sort_mode.sort_key(*child, name, &state.spawn_order)
+                    });
+                }
+                for child in children.iter() {
+                    draw_entity(
+                        commands,
+                        ui,
+                        query,
+                        *child,
+                        selected,
+                        clone_events,
+                        changes,
+                        state,
+                        settings,
+                        visible,
+                    );
+                }
+            })
+            .1
+            .inner
+    } else if is_renaming {
+        ui.horizontal(|ui| {
+            ui.add_space(18.0);
+            visibility_toggle(ui, commands, entity, is_hidden, settings.cascade_hide);
+            lock_toggle(ui, commands, entity, locked);
+            rename_field(ui, commands, &mut state.renaming, name)
+        })
+        .inner
+    } else {
+        ui.horizontal(|ui| {
+            ui.add_space(18.0);
+            visibility_toggle(ui, commands, entity, is_hidden, settings.cascade_hide);
+            lock_toggle(ui, commands, entity, locked);
+            missing_components_warning(ui, commands, entity, &missing);
+            ui.selectable_label(is_selected, label_text(entity_name))
+                .context_menu(|ui| {
+                    hierarchy_entity_context(
+                        ui,
+                        commands,
+                        entity,
+                        changes,
+                        clone_events,
+                        selected,
+                        parent,
+                        children,
+                        locked,
+                        pinned,
+                        tag,
+                        note.as_deref(),
+                        instance,
+                        linked,
+                        scene_entity,
+                        read_only,
+                        running,
+                        copied_component.as_deref(),
+                    );
+                })
+        })
+        .inner
+    };
+
+    if let Some(background) = row_background {
+        let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), label.rect.y_range());
+        let color = if hovered {
+            ui.visuals().widgets.hovered.weak_bg_fill
+        } else {
+            tag.map_or(egui::Color32::TRANSPARENT, |tag| {
+                tag.color().gamma_multiply(0.3)
+            })
+        };
+        ui.painter()
+            .set(background, egui::Shape::rect_filled(rect, 2.0, color));
+    }
+    if label.hovered() {
+        state.hovered_row = Some(entity);
+    }
+
+    if state.reveal.is_some_and(|(reveal, _)| reveal == entity) {
+        ui.scroll_to_rect(label.rect, Some(egui::Align::Center));
+    }
+
+    if is_renaming {
+        return;
+    }
+
+    let label = if let Some(load_error) = load_error {
+        label.on_hover_text(load_error.errors.join("\n"))
+    } else {
+        label
+    };
+    let label = if let Some(note) = &note {
+        label.on_hover_text(format!("📝 {}", note))
+    } else {
+        label
+    };
+    let label = if read_only {
+        label.on_hover_text("Part of prefab instance. Unpack it or allow editing to change")
+    } else {
+        label
+    };
+    let label = label.interact(egui::Sense::drag());
+
+    if label.drag_started() && !read_only {
+        state.dragged_entity = Some(entity);
+    }
+
+    if label.double_clicked() {
+        let action = if ui.input(|i| i.modifiers.alt) {
+            settings.double_click.alternative()
+        } else {
+            settings.double_click
+        };
+        match action {
+            HierarchyDoubleClick::Rename if read_only => {}
+            HierarchyDoubleClick::Rename => {
+                state.renaming = Some(RenameState {
+                    entity,
+                    text: name.map(|name| name.to_string()).unwrap_or_default(),
+                    focused: false,
+                });
+            }
+            HierarchyDoubleClick::Focus => {
+                commands.add(move |world: &mut World| focus_camera_on(world, &[entity]));
+            }
+        }
+    }
+
+    // Drop dragged entity before/after this row (row edges) or inside it as a child (row center)
+    if let Some(dragged_entity) = state.dragged_entity {
+        if let Some(pointer) = ui.input(|i| i.pointer.interact_pos()) {
+            if label.rect.contains(pointer) {
+                let released = ui.input(|i| i.pointer.any_released());
+                let snap = ui.input(|i| i.modifiers.alt);
+                let quarter = label.rect.height() / 4.0;
+                let stroke = egui::Stroke::new(2.0, egui::Color32::LIGHT_BLUE);
+                let on_edge = pointer.y < label.rect.top() + quarter
+                    || pointer.y > label.rect.bottom() - quarter;
+                if dragged_entity == entity || read_only {
+                    // Dropped on itself or into read-only prefab instance, nothing to do
+                } else if on_edge {
+                    let after = pointer.y > label.rect.center().y;
+                    let y = if after {
+                        label.rect.bottom()
+                    } else {
+                        label.rect.top()
+                    };
+                    ui.painter().hline(label.rect.x_range(), y, stroke);
+
+                    if released {
+                        commands.add(move |world: &mut World| {
+                            move_near_sibling(world, dragged_entity, entity, after, snap);
+                        });
+                    }
+                } else {
+                    ui.painter().rect_stroke(label.rect, 2.0, stroke);
+
+                    if released {
+                        commands.add(move |world: &mut World| {
+                            reparent_entity(world, dragged_entity, entity, snap);
+                        });
+                    }
+                }
+                if released {
+                    state.dragged_entity = None;
+                }
+            }
+        }
+    }
+
+    if label.clicked() && !locked {
+        state.selected_in_hierarchy = true;
+        let mode = if is_selected {
+            SelectMode::Remove
+        } else if ui.input(|i| i.modifiers.shift) {
+            SelectMode::Add
+        } else {
+            SelectMode::Replace
+        };
+        commands.add(SelectEntities::new([entity], mode));
+    }
+}
+
+/// Legend of [`HierarchyTag`] colors with editable names. Click on color filters hierarchy by tag
+fn tag_legend(ui: &mut egui::Ui, state: &mut HierarchyTabState, settings: &mut HierarchySettings) {
+    let header = match state.tag_filter {
+        Some(tag) => format!("Tags (showing {})", settings.tag_name(tag)),
+        None => "Tags".to_string(),
+    };
+    ui.collapsing(header, |ui| {
+        settings
+            .tag_names
+            .resize(HIERARCHY_TAGS.len(), String::new());
+        for tag in HIERARCHY_TAGS {
+            ui.horizontal(|ui| {
+                let count = state.tags.values().filter(|t| **t == tag).count();
+                let swatch = egui::RichText::new("■").color(tag.color());
+                if ui
+                    .selectable_label(state.tag_filter == Some(tag), swatch)
+                    .on_hover_text("Show only entities with this tag")
+                    .clicked()
+                {
+                    state.tag_filter = if state.tag_filter == Some(tag) {
+                        None
+                    } else {
+                        Some(tag)
+                    };
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut settings.tag_names[tag as usize])
+                        .hint_text(tag.to_string())
+                        .desired_width(120.0),
+                );
+                ui.label(format!("({})", count));
+            });
+        }
+        if state.tag_filter.is_some() && ui.button("Show all tags").clicked() {
+            state.tag_filter = None;
+        }
+    });
+}
+
+/// Selection from hierarchy is flashed in viewport, selection from other places is revealed in hierarchy
+fn sync_hierarchy_selection(
+    mut events: EventReader<SelectionChanged>,
+    mut state: ResMut<HierarchyTabState>,
+    mut flash: ResMut<SelectionFlash>,
+    time: Res<Time>,
+) {
+    for event in events.read() {
+        let from_hierarchy = std::mem::take(&mut state.selected_in_hierarchy);
+        let Some(last) = event.added.last() else {
+            continue;
+        };
+        if from_hierarchy {
+            flash.entities = event.added.clone();
+            flash.start = time.elapsed_seconds();
+        } else {
+            state.reveal = Some((*last, REVEAL_FRAMES));
+        }
+    }
+}
+
+fn draw_selection_flash(
+    mut gizmos: Gizmos,
+    mut flash: ResMut<SelectionFlash>,
+    time: Res<Time>,
+    query: Query<(&GlobalTransform, Option<&Aabb>)>,
+) {
+    if flash.entities.is_empty() {
+        return;
+    }
+    let elapsed = time.elapsed_seconds() - flash.start;
+    if elapsed > FLASH_TIME {
+        flash.entities.clear();
+        return;
+    }
+
+    // Bounds grow a little and fade out
+    let progress = elapsed / FLASH_TIME;
+    let color = Color::YELLOW.with_a(1.0 - progress);
+    for entity in flash.entities.iter() {
+        let Ok((transform, aabb)) = query.get(*entity) else {
+            continue;
+        };
+        gizmos.cuboid(entity_bounds(transform, aabb, 1.0 + 0.2 * progress), color);
+    }
+}
+
+/// Pulsing bounds and expanding rings around pinged entities
+fn draw_ping(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Ping, &GlobalTransform, Option<&Aabb>)>,
+) {
+    for (entity, mut ping, transform, aabb) in query.iter_mut() {
+        if ping.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Ping>();
+            continue;
+        }
+        let pulse = (ping.timer.percent() * PING_PULSES).fract();
+        let bounds = entity_bounds(
+            transform,
+            aabb,
+            1.0 + 0.1 * (pulse * std::f32::consts::TAU).sin(),
+        );
+        gizmos.cuboid(bounds, Color::ORANGE);
+        let radius = bounds.scale.max_element() * (0.5 + pulse);
+        gizmos.sphere(
+            bounds.translation,
+            Quat::IDENTITY,
+            radius,
+            Color::ORANGE.with_a(1.0 - pulse),
+        );
+    }
+}
+
+/// World bounds of entity mesh scaled by `grow`, small box for entities without [`Aabb`]
+pub(crate) fn entity_bounds(
+    transform: &GlobalTransform,
+    aabb: Option<&Aabb>,
+    grow: f32,
+) -> Transform {
+    let (center, size) = aabb.map_or((Vec3::ZERO, Vec3::splat(0.2)), |aabb| {
+        (Vec3::from(aabb.center), Vec3::from(aabb.half_extents) * 2.0)
+    });
+    transform
+        .mul_transform(Transform::from_translation(center).with_scale(size * grow))
+        .compute_transform()
+}
+
+/// Eye button to hide entity in editor. Selection is not affected
+fn visibility_toggle(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    entity: Entity,
+    is_hidden: bool,
+    cascade: bool,
+) {
+    let icon = if is_hidden { "◌" } else { "👁" };
+    let hover = if is_hidden {
+        "Show in editor"
+    } else {
+        "Hide in editor"
+    };
+    if ui.small_button(icon).on_hover_text(hover).clicked() {
+        commands.add(move |world: &mut World| toggle_hidden_in_editor(world, entity, cascade));
+    }
+}
+
+/// Padlock button to protect entity from selection, deletion and cloning
+fn lock_toggle(ui: &mut egui::Ui, commands: &mut Commands, entity: Entity, locked: bool) {
+    let icon = if locked { "🔒" } else { "🔓" };
+    let hover = if locked { "Unlock" } else { "Lock" };
+    if ui.small_button(icon).on_hover_text(hover).clicked() {
+        if locked {
+            commands.entity(entity).remove::<Locked>();
+        } else {
+            commands.entity(entity).insert(Locked);
+        }
+    }
+}
+
+/// Warning icon for entity with broken [`EditorRegistry`] requirement rules.
+/// Click inserts missing components with default values
+fn missing_components_warning(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    entity: Entity,
+    missing: &[ComponentRequirement],
+) {
+    if missing.is_empty() {
+        return;
+    }
+    let hover = missing
+        .iter()
+        .map(|rule| format!("{} requires {}", rule.component_name, rule.required_name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if ui
+        .small_button(egui::RichText::new("⚠").color(egui::Color32::YELLOW))
+        .on_hover_text(format!("{}\nClick to add missing components", hover))
+        .clicked()
+    {
+        for rule in missing {
+            commands.entity(entity).add(rule.add_required.clone());
+        }
+    }
+}
+
+/// Up/Down moves selection between drawn rows (Shift extends selection range),
+/// Left/Right collapses/expands selected row, Backspace selects parents (Shift extends), Escape clears selection
+fn keyboard_navigation(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    state: &mut HierarchyTabState,
+    active: &mut ActiveEntity,
+    was_renaming: bool,
+) {
+    let (up, down, left, right, backspace, escape, shift) = ui.input(|i| {
+        (
+            i.key_pressed(egui::Key::ArrowUp),
+            i.key_pressed(egui::Key::ArrowDown),
+            i.key_pressed(egui::Key::ArrowLeft),
+            i.key_pressed(egui::Key::ArrowRight),
+            i.key_pressed(egui::Key::Backspace),
+            i.key_pressed(egui::Key::Escape),
+            i.modifiers.shift,
+        )
+    });
+    if escape && !was_renaming {
+        state.range_anchor = None;
+        commands.add(SelectEntities::new(
+            Vec::<Entity>::new(),
+            SelectMode::Replace,
+        ));
+        return;
+    }
+    if backspace && !was_renaming {
+        state.range_anchor = None;
+        state.selected_in_hierarchy = true;
+        commands.add(move |world: &mut World| {
+            let selected = world
+                .query_filtered::<Entity, With<Selected>>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            select_parent(world, &selected, shift);
+        });
+        return;
+    }
+    if !(up || down || left || right) || state.rows.is_empty() {
+        return;
+    }
+
+    let current = active
+        .0
+        .and_then(|e| state.rows.iter().position(|(row, _)| *row == e));
+
+    if left || right {
+        if let Some((_, Some(id))) = current.map(|index| state.rows[index]) {
+            let mut collapsing = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+            collapsing.set_open(right);
+            collapsing.store(ui.ctx());
+        }
+        return;
+    }
+
+    let next = match current {
+        Some(index) if up => index.saturating_sub(1),
+        Some(index) => (index + 1).min(state.rows.len() - 1),
+        None if up => state.rows.len() - 1,
+        None => 0,
+    };
+    let next_entity = state.rows[next].0;
+
+    if shift {
+        let anchor = state
+            .range_anchor
+            .and_then(|e| state.rows.iter().position(|(row, _)| *row == e))
+            .unwrap_or(current.unwrap_or(next));
+        state.range_anchor = Some(state.rows[anchor].0);
+        let range = anchor.min(next)..=anchor.max(next);
+        let rows = state.rows[range].iter().map(|(row, _)| *row);
+        state.selected_in_hierarchy = true;
+        commands.add(SelectEntities::new(rows, SelectMode::Replace));
+    } else {
+        state.range_anchor = None;
+        state.selected_in_hierarchy = true;
+        commands.add(SelectEntities::new([next_entity], SelectMode::Replace));
+    }
+    active.0 = Some(next_entity);
+}
+
+/// Text field for inline entity rename. Name is written on Enter or focus loss, Escape cancels rename.
+/// [`Name`] change is recorded in undo chain by auto undo of registered components
+fn rename_field(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    renaming: &mut Option<RenameState>,
+    name: Option<&Name>,
+) -> egui::Response {
+    let Some(rename) = renaming else {
+        return ui.label("");
+    };
+
+    let response = ui.text_edit_singleline(&mut rename.text);
+    if !rename.focused {
+        response.request_focus();
+        rename.focused = true;
+    }
+
+    if response.lost_focus() {
+        let entity = rename.entity;
+        let text = rename.text.trim().to_string();
+        let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+        let unchanged = name.map_or(text.is_empty(), |name| name.as_str() == text);
+        if !cancelled && !unchanged && !text.is_empty() {
+            commands.entity(entity).insert(Name::new(text));
+        }
+        *renaming = None;
+    }
+    response
+}
+
+fn batch_rename_window(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    state: &mut HierarchyTabState,
+    selected_count: usize,
+) {
+    let Some(rename) = state.batch_rename.as_mut() else {
+        return;
+    };
+    let mut close = false;
+    egui::Window::new("Rename selected")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ui.ctx(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Pattern:");
+                ui.text_edit_singleline(&mut rename.pattern);
+            });
+            ui.label("{n} is replaced by index");
+            ui.horizontal(|ui| {
+                ui.label("Start index:");
+                ui.add(egui::DragValue::new(&mut rename.start));
+                ui.label("Zero padding:");
+                ui.add(egui::DragValue::new(&mut rename.padding).clamp_range(0..=8));
+            });
+            let last = rename.start + selected_count.saturating_sub(1);
+            ui.label(format!(
+                "{} entities: {} … {}",
+                selected_count,
+                batch_rename_name(&rename.pattern, rename.start, rename.padding),
+                batch_rename_name(&rename.pattern, last, rename.padding)
+            ));
+            ui.horizontal(|ui| {
+                let can_rename = !rename.pattern.trim().is_empty() && selected_count > 0;
+                if ui
+                    .add_enabled(can_rename, egui::Button::new("Rename"))
+                    .clicked()
+                {
+                    let pattern = rename.pattern.trim().to_string();
+                    let start = rename.start;
+                    let padding = rename.padding;
+                    commands.add(move |world: &mut World| {
+                        batch_rename_selected(world, &pattern, start, padding);
+                    });
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+    if close {
+        state.batch_rename = None;
+    }
+}
+
+/// Editor only marker of entity shown in "Pinned" section at the top of hierarchy.
+/// It is not in editor registry, so it is never saved to prefab
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct Pinned;
+
+/// Editor only color tag to visually group entities in hierarchy.
+/// It is not reflected, so it is never saved to prefab
+#[derive(
+    Component, Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum HierarchyTag {
+    Red,
+    Amber,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+pub const HIERARCHY_TAGS: [HierarchyTag; 6] = [
+    HierarchyTag::Red,
+    HierarchyTag::Amber,
+    HierarchyTag::Yellow,
+    HierarchyTag::Green,
+    HierarchyTag::Blue,
+    HierarchyTag::Purple,
+];
+
+impl ToString for HierarchyTag {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Red => "Red",
+            Self::Amber => "Amber",
+            Self::Yellow => "Yellow",
+            Self::Green => "Green",
+            Self::Blue => "Blue",
+            Self::Purple => "Purple",
+        }
+        .to_string()
+    }
+}
+
+impl HierarchyTag {
+    pub const fn color(self) -> egui::Color32 {
+        match self {
+            Self::Red => egui::Color32::from_rgb(220, 60, 60),
+            Self::Amber => egui::Color32::from_rgb(230, 150, 30),
+            Self::Yellow => egui::Color32::from_rgb(220, 210, 60),
+            Self::Green => egui::Color32::from_rgb(70, 180, 80),
+            Self::Blue => egui::Color32::from_rgb(60, 120, 220),
+            Self::Purple => egui::Color32::from_rgb(160, 80, 210),
+        }
+    }
+}
+
+/// Editor only free text note of entity, shown as hierarchy row tooltip.
+/// It is not in editor registry, so it is never saved to prefab. Notes are stored in scene state file,
+/// note edits and removal are recorded in undo
+#[derive(Component, Reflect, Debug, Clone, Default, PartialEq)]
+#[reflect(Component)]
+pub struct EditorNote(pub String);
+
+/// Set note of entity. Empty (or whitespace only) text removes [`EditorNote`]
+pub fn set_editor_note(world: &mut World, entity: Entity, text: &str) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if text.trim().is_empty() {
+        entity_mut.remove::<EditorNote>();
+    } else {
+        entity_mut.insert(EditorNote(text.to_string()));
+    }
+}
+
+/// Popup to edit [`EditorNote`] of entity
+fn note_window(ui: &mut egui::Ui, commands: &mut Commands, state: &mut HierarchyTabState) {
+    let Some((entity, text)) = state.editing_note.as_mut() else {
+        return;
+    };
+    let entity = *entity;
+    let mut close = false;
+    egui::Window::new("Entity note")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ui.ctx(), |ui| {
+            ui.add(
+                egui::TextEdit::multiline(text)
+                    .hint_text("For example: needs art pass")
+                    .desired_rows(4),
+            );
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    let text = text.clone();
+                    commands.add(move |world: &mut World| set_editor_note(world, entity, &text));
+                    close = true;
+                }
+                if ui.button("Remove note").clicked() {
+                    commands.add(move |world: &mut World| set_editor_note(world, entity, ""));
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+    if close {
+        state.editing_note = None;
+    }
+}
+
+/// Set or remove (with `None`) [`HierarchyTag`] of entities
+fn set_hierarchy_tag(world: &mut World, entities: &[Entity], tag: Option<HierarchyTag>) {
+    for entity in entities.iter() {
+        let Some(mut entity_mut) = world.get_entity_mut(*entity) else {
+            continue;
+        };
+        match tag {
+            Some(tag) => {
+                entity_mut.insert(tag);
+            }
+            None => {
+                entity_mut.remove::<HierarchyTag>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_prefab::editor_registry::EditorRegistryPlugin;
+
+    #[test]
+    fn test_select_all_with_component() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(SelectedPlugin);
+        app.world
+            .resource_mut::<EditorRegistry>()
+            .register::<Transform>();
+
+        let first = app.world.spawn((PrefabMarker, Transform::default())).id();
+        let second = app.world.spawn((PrefabMarker, Transform::default())).id();
+        let other = app.world.spawn((PrefabMarker, Selected)).id();
+        let not_prefab = app.world.spawn(Transform::default()).id();
+
+        let count = select_all_with_component(&mut app.world, TypeId::of::<Transform>(), true);
+        assert_eq!(count, 2);
+        for entity in [first, second, other] {
+            assert!(app.world.get::<Selected>(entity).is_some());
+        }
+        assert!(app.world.get::<Selected>(not_prefab).is_none());
+
+        select_all_with_component(&mut app.world, TypeId::of::<Transform>(), false);
+        assert!(app.world.get::<Selected>(first).is_some());
+        assert!(app.world.get::<Selected>(other).is_none());
+    }
+}
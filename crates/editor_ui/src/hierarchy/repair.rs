@@ -0,0 +1,94 @@
+use super::*;
+
+/// Fix dangling [`Parent`] references (orphans become roots) and stale [`Children`] entries.
+/// All changes are done in one frame, so they will be collected in one undo step.
+/// Returns description of all fixes
+pub fn repair_hierarchy(world: &mut World) -> Vec<String> {
+    let mut report = vec![];
+
+    let mut parents = world.query::<(Entity, &Parent)>();
+    let parents = parents
+        .iter(world)
+        .map(|(e, parent)| (e, parent.get()))
+        .collect::<Vec<_>>();
+    for (entity, parent) in parents {
+        if world.get_entity(parent).is_none() {
+            world.entity_mut(entity).remove::<Parent>();
+            report.push(format!(
+                "{:?}: parent {:?} does not exist, moved to root",
+                entity, parent
+            ));
+        } else if !world
+            .get::<Children>(parent)
+            .is_some_and(|children| children.contains(&entity))
+        {
+            world.entity_mut(parent).add_child(entity);
+            report.push(format!(
+                "{:?}: added to children list of parent {:?}",
+                entity, parent
+            ));
+        }
+    }
+
+    let mut children = world.query::<(Entity, &Children)>();
+    let stale = children
+        .iter(world)
+        .filter_map(|(e, children)| {
+            let (valid, stale): (Vec<Entity>, Vec<Entity>) = children
+                .iter()
+                .partition(|child| world.get::<Parent>(**child).map(Parent::get) == Some(e));
+            (!stale.is_empty()).then_some((e, valid, stale))
+        })
+        .collect::<Vec<_>>();
+    for (entity, valid, stale) in stale {
+        // `remove_children` would touch stale children: it panics on despawned ones
+        // and strips `Parent` of children moved to other entity. Children list is rebuilt instead
+        let mut entity_mut = world.entity_mut(entity);
+        entity_mut.remove::<Children>();
+        if !valid.is_empty() {
+            entity_mut.push_children(&valid);
+        }
+        report.push(format!("{:?}: removed stale children {:?}", entity, stale));
+    }
+
+    if report.is_empty() {
+        report.push("Hierarchy is consistent, nothing to repair".to_string());
+    }
+    for line in report.iter() {
+        info!("Repair hierarchy: {}", line);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair_hierarchy_stale_children() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        let other = world.spawn_empty().id();
+        let kept = world.spawn_empty().id();
+        let despawned = world.spawn_empty().id();
+        let moved = world.spawn_empty().id();
+        let donor = world.spawn_empty().id();
+        world
+            .entity_mut(parent)
+            .push_children(&[kept, despawned, moved]);
+        world.entity_mut(other).add_child(donor);
+
+        // Broken state: despawned child is still listed, moved child points to other parent
+        world.despawn(despawned);
+        let other_parent = world.entity_mut(donor).take::<Parent>().unwrap();
+        world.entity_mut(moved).insert(other_parent);
+
+        let report = repair_hierarchy(&mut world);
+        assert!(!report.is_empty());
+        assert_eq!(world.get::<Children>(parent).unwrap().to_vec(), vec![kept]);
+        assert_eq!(world.get::<Parent>(kept).unwrap().get(), parent);
+        // Child of other entity keeps its parent and is listed there
+        assert_eq!(world.get::<Parent>(moved).unwrap().get(), other);
+        assert_eq!(world.get::<Children>(other).unwrap().to_vec(), vec![moved]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_egui::egui;
+
+/// Custom ui of component value. Returns true if value was changed
+pub type ComponentEditorFn = Arc<dyn Fn(&mut egui::Ui, &mut dyn Reflect) -> bool + Send + Sync>;
+
+/// Custom inspector widgets by component type. Inspector draws them instead of reflected fields,
+/// changes are stored in undo chain in the same way as reflected edits
+#[derive(Resource, Default, Clone)]
+pub struct ComponentEditors {
+    editors: HashMap<TypeId, ComponentEditorFn>,
+}
+
+impl ComponentEditors {
+    /// Register `editor` for component `T`. Previous editor of `T` is replaced
+    pub fn insert<T: Component + Reflect>(
+        &mut self,
+        editor: impl Fn(&mut egui::Ui, &mut T) -> bool + Send + Sync + 'static,
+    ) {
+        self.editors.insert(
+            TypeId::of::<T>(),
+            Arc::new(move |ui, value| {
+                value
+                    .downcast_mut::<T>()
+                    .is_some_and(|value| editor(ui, value))
+            }),
+        );
+    }
+
+    pub fn contains(&self, type_id: TypeId) -> bool {
+        self.editors.contains_key(&type_id)
+    }
+
+    /// Draw custom editor of `value`. Returns `None` if type has no custom editor and must be drawn by reflection
+    pub fn ui(&self, type_id: TypeId, ui: &mut egui::Ui, value: &mut dyn Reflect) -> Option<bool> {
+        self.editors.get(&type_id).map(|editor| editor(ui, value))
+    }
+}
+
+pub trait ComponentEditorExt {
+    /// registers custom inspector ui for a component
+    fn editor_component_ui<T: Component + Reflect>(
+        &mut self,
+        editor: impl Fn(&mut egui::Ui, &mut T) -> bool + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl ComponentEditorExt for App {
+    fn editor_component_ui<T: Component + Reflect>(
+        &mut self,
+        editor: impl Fn(&mut egui::Ui, &mut T) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(ComponentEditors::default)
+            .insert(editor);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use space_editor_core::prelude::*;
+    use space_prefab::prelude::{EditorRegistryExt, EditorRegistryPlugin};
+    use space_shared::PrefabMarker;
+    use space_undo::{SyncUndoMarkersPlugin, UndoPlugin, UndoRedo};
+
+    use super::*;
+    use crate::inspector::{
+        components_order::ComponentsOrder, inspect, transform_panel::TransformPanelState,
+        FilterComponentState, InspectState,
+    };
+
+    /// Example of plugin component with custom inspector ui instead of reflected fields
+    #[derive(Component, Reflect, Default, Clone)]
+    struct TeamColor {
+        team: usize,
+    }
+
+    const TEAMS: [(&str, egui::Color32); 2] =
+        [("Red", egui::Color32::RED), ("Blue", egui::Color32::BLUE)];
+
+    fn team_color_ui(ui: &mut egui::Ui, color: &mut TeamColor) -> bool {
+        // Out of range team from old scene is reset, so editor reports change
+        let mut changed = color.team >= TEAMS.len();
+        color.team = color.team.min(TEAMS.len() - 1);
+        ui.horizontal(|ui| {
+            for (index, (name, team_color)) in TEAMS.iter().enumerate() {
+                let text = egui::RichText::new(*name).color(*team_color);
+                changed |= ui.selectable_value(&mut color.team, index, text).changed();
+            }
+        });
+        changed
+    }
+
+    #[test]
+    fn test_custom_component_editor() {
+        let mut app = App::new();
+        app.editor_component_ui::<TeamColor>(team_color_ui);
+        let editors = app.world.resource::<ComponentEditors>().clone();
+        assert!(editors.contains(std::any::TypeId::of::<TeamColor>()));
+        assert!(!editors.contains(std::any::TypeId::of::<Transform>()));
+
+        let mut color = TeamColor { team: 5 };
+        let mut unknown = Transform::default();
+        let ctx = egui::Context::default();
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let changed = editors.ui(std::any::TypeId::of::<TeamColor>(), ui, &mut color);
+                assert_eq!(changed, Some(true));
+                // Types without custom editor are drawn by reflection
+                let fallback = editors.ui(std::any::TypeId::of::<Transform>(), ui, &mut unknown);
+                assert_eq!(fallback, None);
+            });
+        });
+        assert_eq!(color.team, TEAMS.len() - 1);
+    }
+
+    fn show_inspector(app: &mut App, time: f64) {
+        let mut open_components = HashMap::new();
+        open_components.insert("TeamColor".to_string(), true);
+        let ctx = egui::Context::default();
+        let input = egui::RawInput {
+            time: Some(time),
+            ..default()
+        };
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                inspect(ui, &mut app.world, &mut open_components);
+            });
+        });
+    }
+
+    /// Edit made by custom editor is stored as one undo step, same as reflected edit
+    #[test]
+    fn test_custom_component_editor_edit_is_undoable() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(SyncUndoMarkersPlugin::<PrefabMarker>::default())
+            .add_plugins(EditorRegistryPlugin)
+            .init_resource::<InspectState>()
+            .init_resource::<FilterComponentState>()
+            .init_resource::<ComponentsOrder>()
+            .init_resource::<TransformPanelState>();
+        app.editor_registry::<TeamColor>();
+        app.editor_component_ui::<TeamColor>(team_color_ui);
+
+        let entity = app
+            .world
+            .spawn((PrefabMarker, TeamColor { team: 5 }, Selected))
+            .id();
+        app.update();
+        app.update();
+
+        // Out of range team is reset by editor, edit is stored after coalesce time
+        show_inspector(&mut app, 0.0);
+        assert_eq!(app.world.get::<TeamColor>(entity).unwrap().team, 1);
+        app.update();
+        show_inspector(&mut app, 1.0);
+        app.update();
+        app.update();
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+        assert_eq!(app.world.get::<TeamColor>(entity).unwrap().team, 5);
+    }
+}
@@ -0,0 +1,437 @@
+use bevy::{
+    prelude::*,
+    reflect::{
+        DynamicEnum, DynamicStruct, DynamicTuple, DynamicVariant, Enum, ReflectMut, ReflectRef,
+        TypeInfo, TypeRegistry, VariantInfo,
+    },
+};
+use bevy_egui::egui;
+use space_shared::ext::bevy_inspector_egui::{
+    inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
+};
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+/// Lists, arrays and maps longer than this are shown by pages, so widgets are built only for the shown page
+pub const LIST_PAGE_LEN: usize = 32;
+
+/// Plugin for reflection inspector depth limit settings
+pub struct InspectorDepthPlugin;
+
+impl Plugin for InspectorDepthPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InspectorSettings>()
+            .register_type::<InspectorSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<InspectorSettings>();
+        }
+    }
+}
+
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct InspectorSettings {
+    /// Nested structs, tuples, lists and enums deeper than this are collapsed to "…" header.
+    /// Its content is built only when it is expanded
+    pub max_depth: usize,
+}
+
+impl Default for InspectorSettings {
+    fn default() -> Self {
+        Self { max_depth: 5 }
+    }
+}
+
+impl InspectorSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Inspector");
+        ui.add(
+            egui::DragValue::new(&mut self.max_depth)
+                .clamp_range(1..=32)
+                .prefix("Max nesting depth: "),
+        )
+        .on_hover_text("Deeper values are collapsed and built only when expanded");
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+enum ValueKind {
+    Struct,
+    TupleStruct,
+    Tuple,
+    List,
+    Array,
+    /// Enum with fields in current variant
+    Enum,
+    Map,
+    /// Values with own inspector ui, enums without fields and primitive values
+    Delegated,
+}
+
+/// Reflection ui of component value with nesting depth limit. Structs, tuples, sequences, maps and enum
+/// variants are walked here, other values and types with custom ui are drawn by `bevy_inspector_egui`.
+/// Values at `max_depth` are collapsed under "…" header, expanding it allows next `max_depth` levels.
+/// Returns `true` if value was changed
+pub fn ui_for_reflect_limited(
+    env: &mut InspectorUi<'_, '_>,
+    value: &mut dyn Reflect,
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    depth: usize,
+    max_depth: usize,
+) -> bool {
+    let has_custom_ui = env
+        .type_registry
+        .get_type_data::<InspectorEguiImpl>(value.as_any().type_id())
+        .is_some();
+    let kind = match value.reflect_ref() {
+        _ if has_custom_ui => ValueKind::Delegated,
+        ReflectRef::Struct(_) => ValueKind::Struct,
+        ReflectRef::TupleStruct(_) => ValueKind::TupleStruct,
+        ReflectRef::Tuple(_) => ValueKind::Tuple,
+        ReflectRef::List(_) => ValueKind::List,
+        ReflectRef::Array(_) => ValueKind::Array,
+        ReflectRef::Enum(e) if e.field_len() > 0 => ValueKind::Enum,
+        ReflectRef::Map(_) => ValueKind::Map,
+        _ => return env.ui_for_reflect_with_options(value, ui, id, &()),
+    };
+
+    if depth >= max_depth && !has_custom_ui {
+        return egui::CollapsingHeader::new(format!("… {}", value.reflect_short_type_path()))
+            .id_source(id.with("depth_limit"))
+            .default_open(false)
+            .show(ui, |ui| {
+                ui_for_reflect_limited(env, value, ui, id, 0, max_depth)
+            })
+            .body_returned
+            .unwrap_or(false);
+    }
+
+    if matches!(kind, ValueKind::Delegated) {
+        return env.ui_for_reflect_with_options(value, ui, id, &());
+    }
+
+    let mut changed = false;
+    match (kind, value.reflect_mut()) {
+        (ValueKind::Struct, ReflectMut::Struct(value)) => {
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                for index in 0..value.field_len() {
+                    let name = value.name_at(index).unwrap_or_default().to_string();
+                    ui.label(name);
+                    if let Some(field) = value.field_at_mut(index) {
+                        changed |= ui_for_reflect_limited(
+                            env,
+                            field,
+                            ui,
+                            id.with(index),
+                            depth + 1,
+                            max_depth,
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        (ValueKind::TupleStruct, ReflectMut::TupleStruct(value)) => {
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                for index in 0..value.field_len() {
+                    ui.label(index.to_string());
+                    if let Some(field) = value.field_mut(index) {
+                        changed |= ui_for_reflect_limited(
+                            env,
+                            field,
+                            ui,
+                            id.with(index),
+                            depth + 1,
+                            max_depth,
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        (ValueKind::Tuple, ReflectMut::Tuple(value)) => {
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                for index in 0..value.field_len() {
+                    ui.label(index.to_string());
+                    if let Some(field) = value.field_mut(index) {
+                        changed |= ui_for_reflect_limited(
+                            env,
+                            field,
+                            ui,
+                            id.with(index),
+                            depth + 1,
+                            max_depth,
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        // Short lists keep add/remove buttons of default list ui
+        (ValueKind::List, ReflectMut::List(list)) if list.len() <= LIST_PAGE_LEN => {
+            changed = env.ui_for_reflect_with_options(list.as_reflect_mut(), ui, id, &());
+        }
+        (ValueKind::List, ReflectMut::List(list)) => {
+            let len = list.len();
+            changed = paged_items(ui, id, len, |ui, index| {
+                ui.label(index.to_string());
+                list.get_mut(index).is_some_and(|item| {
+                    ui_for_reflect_limited(env, item, ui, id.with(index), depth + 1, max_depth)
+                })
+            });
+        }
+        (ValueKind::Array, ReflectMut::Array(array)) => {
+            let len = array.len();
+            changed = paged_items(ui, id, len, |ui, index| {
+                ui.label(index.to_string());
+                array.get_mut(index).is_some_and(|item| {
+                    ui_for_reflect_limited(env, item, ui, id.with(index), depth + 1, max_depth)
+                })
+            });
+        }
+        (ValueKind::Map, ReflectMut::Map(map)) => {
+            let len = map.len();
+            changed = paged_items(ui, id, len, |ui, index| {
+                map.get_at_mut(index).is_some_and(|(key, item)| {
+                    ui.label(format!("{:?}", key));
+                    ui_for_reflect_limited(env, item, ui, id.with(index), depth + 1, max_depth)
+                })
+            });
+        }
+        (ValueKind::Enum, ReflectMut::Enum(value)) => {
+            changed = variant_select(env.type_registry, value, ui, id);
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                for index in 0..value.field_len() {
+                    let name = value
+                        .name_at(index)
+                        .map_or_else(|| index.to_string(), str::to_string);
+                    ui.label(name);
+                    if let Some(field) = value.field_at_mut(index) {
+                        changed |= ui_for_reflect_limited(
+                            env,
+                            field,
+                            ui,
+                            id.with(index),
+                            depth + 1,
+                            max_depth,
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// Combo box of enum variants. Variant is switched with default field values,
+/// so variants with fields without [`ReflectDefault`] can not be selected
+fn variant_select(
+    registry: &TypeRegistry,
+    value: &mut dyn Enum,
+    ui: &mut egui::Ui,
+    id: egui::Id,
+) -> bool {
+    let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() else {
+        return false;
+    };
+    let current = value.variant_name().to_string();
+    let mut selected = None;
+    egui::ComboBox::from_id_source(id.with("variant"))
+        .selected_text(&current)
+        .show_ui(ui, |ui| {
+            for variant in info.iter() {
+                let default = default_variant(registry, variant);
+                let label = egui::SelectableLabel::new(variant.name() == current, variant.name());
+                if ui.add_enabled(default.is_some(), label).clicked() && variant.name() != current {
+                    selected = default;
+                }
+            }
+        });
+    let Some(variant) = selected else {
+        return false;
+    };
+    value.apply(&variant);
+    true
+}
+
+/// Variant with default values of all fields. None if any field type has no [`ReflectDefault`]
+fn default_variant(registry: &TypeRegistry, variant: &VariantInfo) -> Option<DynamicEnum> {
+    let default = |type_id| {
+        registry
+            .get_type_data::<ReflectDefault>(type_id)
+            .map(ReflectDefault::default)
+    };
+    let dynamic = match variant {
+        VariantInfo::Unit(_) => DynamicVariant::Unit,
+        VariantInfo::Tuple(info) => {
+            let mut tuple = DynamicTuple::default();
+            for field in info.iter() {
+                tuple.insert_boxed(default(field.type_id())?);
+            }
+            DynamicVariant::Tuple(tuple)
+        }
+        VariantInfo::Struct(info) => {
+            let mut fields = DynamicStruct::default();
+            for field in info.iter() {
+                fields.insert_boxed(field.name(), default(field.type_id())?);
+            }
+            DynamicVariant::Struct(fields)
+        }
+    };
+    Some(DynamicEnum::new(variant.name(), dynamic))
+}
+
+/// Items of sequence or map, `item_ui` draws label and value of item. Long sequences are split to pages
+/// of [`LIST_PAGE_LEN`] items, shown page is stored in egui memory
+fn paged_items(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    len: usize,
+    mut item_ui: impl FnMut(&mut egui::Ui, usize) -> bool,
+) -> bool {
+    let page_id = id.with("list_page");
+    let pages = len.div_ceil(LIST_PAGE_LEN).max(1);
+    let mut page = ui
+        .data(|data| data.get_temp::<usize>(page_id))
+        .unwrap_or_default()
+        .min(pages - 1);
+
+    if pages > 1 {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(page > 0, egui::Button::new("◀")).clicked() {
+                page -= 1;
+            }
+            let start = page * LIST_PAGE_LEN;
+            ui.label(format!(
+                "{}–{} of {}",
+                start,
+                (start + LIST_PAGE_LEN).min(len) - 1,
+                len
+            ));
+            if ui
+                .add_enabled(page + 1 < pages, egui::Button::new("▶"))
+                .clicked()
+            {
+                page += 1;
+            }
+        });
+        ui.data_mut(|data| data.insert_temp(page_id, page));
+    }
+
+    let mut changed = false;
+    egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+        let start = page * LIST_PAGE_LEN;
+        for index in start..(start + LIST_PAGE_LEN).min(len) {
+            changed |= item_ui(ui, index);
+            ui.end_row();
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        any::Any,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use space_shared::ext::bevy_inspector_egui::reflect_inspector::Context;
+
+    use super::*;
+
+    /// Count of drawn [`Probe`] values
+    static PROBES_DRAWN: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Reflect, Default)]
+    struct Probe;
+
+    fn probe_ui(
+        _: &mut dyn Any,
+        _: &mut egui::Ui,
+        _: &dyn Any,
+        _: egui::Id,
+        _: InspectorUi<'_, '_>,
+    ) -> bool {
+        PROBES_DRAWN.fetch_add(1, Ordering::SeqCst);
+        false
+    }
+
+    fn probe_ui_readonly(
+        _: &dyn Any,
+        _: &mut egui::Ui,
+        _: &dyn Any,
+        _: egui::Id,
+        _: InspectorUi<'_, '_>,
+    ) {
+    }
+
+    fn probe_ui_many(
+        _: &mut egui::Ui,
+        _: &dyn Any,
+        _: egui::Id,
+        _: InspectorUi<'_, '_>,
+        _: &mut [&mut dyn Reflect],
+        _: &dyn Fn(&mut dyn Reflect) -> &mut dyn Reflect,
+    ) -> bool {
+        false
+    }
+
+    #[derive(Reflect)]
+    enum Wrapper {
+        Empty,
+        Named { inner: Option<Option<Probe>> },
+    }
+
+    /// Draw `value` and return count of probes drawn inside it
+    fn drawn_probes(value: &mut dyn Reflect, max_depth: usize) -> usize {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Probe>();
+        registry
+            .get_mut(std::any::TypeId::of::<Probe>())
+            .unwrap()
+            .insert(InspectorEguiImpl::new(
+                probe_ui,
+                probe_ui_readonly,
+                probe_ui_many,
+            ));
+
+        PROBES_DRAWN.store(0, Ordering::SeqCst);
+        let ctx = egui::Context::default();
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let mut cx = Context {
+                    world: None,
+                    queue: None,
+                };
+                let mut env = InspectorUi::for_bevy(&registry, &mut cx);
+                ui_for_reflect_limited(&mut env, value, ui, egui::Id::new("value"), 0, max_depth);
+            });
+        });
+        PROBES_DRAWN.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_depth_limit_applies_to_enums_and_maps() {
+        // Probe is at depth 3: variant field, two options
+        let mut value = Wrapper::Named {
+            inner: Some(Some(Probe)),
+        };
+        assert_eq!(drawn_probes(&mut value, 2), 0);
+        assert_eq!(drawn_probes(&mut value, 4), 1);
+
+        let mut map = bevy::utils::HashMap::<String, Option<Probe>>::default();
+        map.insert("probe".to_string(), Some(Probe));
+        assert_eq!(drawn_probes(&mut map, 1), 0);
+        assert_eq!(drawn_probes(&mut map, 3), 1);
+
+        let mut empty = Wrapper::Empty;
+        assert_eq!(drawn_probes(&mut empty, 1), 0);
+    }
+}
@@ -1,13 +1,20 @@
+pub mod component_editors;
 pub mod components_order;
+pub mod depth_limit;
 pub mod events_dispatcher;
 pub mod refl_impl;
 pub mod resources;
 pub mod runtime_assets;
+pub mod transform_panel;
 
-use std::any::TypeId;
+use std::{any::TypeId, sync::Arc};
 
 use bevy::{
-    ecs::{change_detection::MutUntyped, system::CommandQueue},
+    ecs::{
+        change_detection::MutUntyped,
+        component::ComponentId,
+        system::{CommandQueue, EntityCommand},
+    },
     prelude::*,
     ptr::PtrMut,
     reflect::ReflectFromPtr,
@@ -17,21 +24,35 @@ use bevy::{
 use bevy_egui::*;
 
 use space_editor_core::prelude::*;
-use space_prefab::{component::EntityLink, editor_registry::EditorRegistry};
+use space_prefab::{
+    component::EntityLink,
+    editor_registry::EditorRegistry,
+    instance::{read_only_instance_root, unpack_instance, EditableInstance, PrefabOverrides},
+};
 use space_shared::ext::bevy_inspector_egui::{
     self, inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
 };
+use space_undo::{
+    begin_change_group, end_change_group, ComponentModified, NewChange, OneFrameUndoIgnore,
+};
+
+pub use self::component_editors::{ComponentEditorExt, ComponentEditors};
 
 use self::{
     components_order::{ComponentsOrder, ComponentsPriority},
+    depth_limit::{ui_for_reflect_limited, InspectorDepthPlugin, InspectorSettings},
     events_dispatcher::EventDispatcherTab,
     refl_impl::{entity_ref_ui, entity_ref_ui_readonly, many_unimplemented},
     resources::ResourceTab,
     runtime_assets::RuntimeAssetsTab,
+    transform_panel::{repeat_transform_by, transform_panel, TransformPanelState},
 };
 
 use super::{
+    clipboard::{copy_component, paste_component, ComponentClipboard},
+    command_palette::EditorCommandExt,
     editor_tab::{EditorTab, EditorTabName},
+    prelude::UnitsSettings,
     EditorUiAppExt,
 };
 
@@ -47,6 +68,10 @@ impl Plugin for SpaceInspectorPlugin {
         app.init_resource::<InspectState>();
         app.init_resource::<FilterComponentState>();
         app.init_resource::<ComponentsOrder>();
+        app.init_resource::<ComponentEditors>();
+        app.init_resource::<TransformPanelState>();
+        app.editor_command("Repeat last transform by", repeat_transform_by);
+        app.add_plugins(InspectorDepthPlugin);
         app.editor_component_priority::<Name>(0);
         app.editor_component_priority::<Transform>(1);
 
@@ -101,11 +126,30 @@ pub fn mut_untyped_split(mut mut_untyped: MutUntyped<'_>) -> (PtrMut<'_>, impl F
     (ptr, move || mut_untyped.set_changed())
 }
 
+/// Seconds without changes after which inspector edit is stored as one undo step
+const EDIT_COALESCE_TIME: f64 = 0.5;
+
 /// Just state of inspector panel
 #[derive(Resource, Default)]
 struct InspectState {
     commands: Vec<InspectCommand>,
     show_add_component_window: bool,
+    /// "Add component to selection" window for multiple selected entities
+    show_selection_add_window: bool,
+    /// Result of the last "Add component to selection" action
+    selection_add_report: Option<String>,
+    /// Component edit, which is not stored in undo chain yet
+    pending_edit: Option<PendingEdit>,
+}
+
+/// Value of component before the first of series of edits (slider drag, typing)
+struct PendingEdit {
+    entity: Entity,
+    type_id: TypeId,
+    type_name: String,
+    old_value: Box<dyn Reflect>,
+    /// Egui time of the last edit
+    last_edit: f64,
 }
 
 #[derive(Resource, Default)]
@@ -116,6 +160,12 @@ struct FilterComponentState {
 enum InspectCommand {
     AddComponent(Entity, TypeId),
     RemoveComponent(Entity, TypeId),
+    CopyComponent(Entity, TypeId, String),
+    PasteComponent(Entity),
+    /// Break link of prefab instance root with its file
+    UnpackInstance(Entity),
+    /// Allow editing of prefab instance root content as overrides
+    EditInstance(Entity),
 }
 
 fn execute_inspect_command(
@@ -132,50 +182,129 @@ fn execute_inspect_command(
             InspectCommand::RemoveComponent(e, id) => {
                 registration.remove_by_id(&mut commands.entity(*e), id);
             }
+            InspectCommand::CopyComponent(e, id, name) => {
+                let (e, id, name) = (*e, *id, name.clone());
+                commands.add(move |world: &mut World| copy_component(world, e, id, name));
+            }
+            InspectCommand::PasteComponent(e) => {
+                let e = *e;
+                commands.add(move |world: &mut World| paste_component(world, &[e]));
+            }
+            InspectCommand::UnpackInstance(root) => {
+                let root = *root;
+                commands.add(move |world: &mut World| unpack_instance(world, root));
+            }
+            InspectCommand::EditInstance(root) => {
+                commands.entity(*root).insert(EditableInstance);
+            }
         }
     }
     state.commands.clear();
 }
 
+/// Notice for content of read-only prefab instance with actions to make it editable
+fn read_only_instance_ui(
+    ui: &mut egui::Ui,
+    root: Entity,
+    root_name: &str,
+    commands: &mut Vec<InspectCommand>,
+) {
+    ui.group(|ui| {
+        ui.label(format!("🔒 Part of prefab instance {}", root_name));
+        ui.horizontal(|ui| {
+            if ui
+                .button("Unpack")
+                .on_hover_text("Break link with prefab file, content becomes ordinary entities")
+                .clicked()
+            {
+                commands.push(InspectCommand::UnpackInstance(root));
+            }
+            if ui
+                .button("Edit as override")
+                .on_hover_text("Allow editing of instance content, changes are stored as overrides")
+                .clicked()
+            {
+                commands.push(InspectCommand::EditInstance(root));
+            }
+        });
+    });
+}
+
+/// List components of prefab instance entity, which differ from prefab file
+fn prefab_overrides_ui(ui: &mut egui::Ui, overrides: &PrefabOverrides) {
+    if overrides.is_empty() {
+        return;
+    }
+    ui.collapsing("Prefab overrides", |ui| {
+        for (label, components) in [
+            ("Changed", &overrides.changed),
+            ("Added", &overrides.added),
+            ("Removed", &overrides.removed),
+        ] {
+            if !components.is_empty() {
+                ui.label(format!("{}: {}", label, components.join(", ")));
+            }
+        }
+    });
+}
+
 /// System to show inspector panel
 pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashMap<String, bool>) {
+    transform_panel(ui, world);
+
     let selected_entity = world
         .query_filtered::<Entity, With<Selected>>()
         .get_single(world);
 
     let Ok(selected_entity) = selected_entity else {
+        let selected_count = world
+            .query_filtered::<(), With<Selected>>()
+            .iter(world)
+            .count();
+        if selected_count > 1 {
+            selection_add_component_ui(ui, world, selected_count);
+        }
         return;
     };
 
     let editor_registry = world.resource::<EditorRegistry>().clone();
+    let component_editors = world
+        .get_resource::<ComponentEditors>()
+        .cloned()
+        .unwrap_or_default();
     let all_registry = editor_registry.registry.clone();
     let registry = all_registry.read();
     let app_registry = world.resource::<AppTypeRegistry>().clone();
     let world_registry = app_registry.read();
     let mut disable_pan_orbit = false;
+    let max_depth = world
+        .get_resource::<InspectorSettings>()
+        .map_or(InspectorSettings::default().max_depth, |settings| {
+            settings.max_depth
+        });
+    let units = world
+        .get_resource::<UnitsSettings>()
+        .cloned()
+        .unwrap_or_default();
 
     //Collet data about all components
-    let components_priority = world.resource::<ComponentsOrder>().clone().components;
-    let mut components_id = Vec::new();
-    for reg in registry.iter() {
-        if let Some(c_id) = world.components().get_id(reg.type_id()) {
-            let name = pretty_type_name::pretty_type_name_str(
-                world.components().get_info(c_id).unwrap().name(),
-            );
-            let priority = components_priority.get(&name).unwrap_or(&u8::MAX);
-            components_id.push((c_id, reg.type_id(), name, priority));
-        }
-    }
-    components_id.sort_by(|(.., name_a, priority_a), (.., name_b, priority_b)| {
-        priority_a.cmp(priority_b).then(name_a.cmp(name_b))
+    let components_id = registered_components(world);
+    // Content of prefab instance is read-only until the instance is unpacked or made editable
+    let read_only_root = read_only_instance_root(world, selected_entity).map(|root| {
+        let name = world
+            .get::<Name>(root)
+            .map_or_else(|| format!("{:?}", root), |name| name.to_string());
+        (root, name)
     });
 
-    // println!("{:#?}\n", components_id);
-
     let cell = world.as_unsafe_world_cell();
     let mut state = unsafe { cell.get_resource_mut::<InspectState>().unwrap() };
+    let copied_component = unsafe { cell.get_resource::<ComponentClipboard>() }
+        .and_then(|clipboard| clipboard.name().map(str::to_string));
 
     let mut commands: Vec<InspectCommand> = vec![];
+    // Components changed in this frame with values before change
+    let mut edited: Vec<(TypeId, String, Box<dyn Reflect>)> = vec![];
     let mut queue = CommandQueue::default();
     let mut cx = unsafe {
         bevy_inspector_egui::reflect_inspector::Context {
@@ -190,6 +319,7 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
     if ui
         .interact(ui.min_rect(), "painter".into(), egui::Sense::click())
         .secondary_clicked()
+        && read_only_root.is_none()
     {
         state.show_add_component_window = true;
     }
@@ -206,8 +336,15 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
                 name = format!("{:?}", e.id());
             }
             ui.heading(&name);
+            if let Some((root, root_name)) = &read_only_root {
+                read_only_instance_ui(ui, *root, root_name, &mut commands);
+            }
+            if let Some(overrides) = unsafe { e.get::<PrefabOverrides>() } {
+                prefab_overrides_ui(ui, overrides);
+            }
             ui.label("Components:");
             let e_id = e.id().index();
+            ui.set_enabled(read_only_root.is_none());
             egui::Grid::new(format!("{e_id}")).show(ui, |ui| {
                 for (c_id, t_id, name, _) in &components_id {
                     if let Some(data) = unsafe { e.get_mut_by_id(*c_id) } {
@@ -225,17 +362,54 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
                                             ui.push_id(
                                                 format!("content-{:?}-{}", &e.id(), &name),
                                                 |ui| {
-                                                    if env.ui_for_reflect_with_options(
-                                                        value,
-                                                        ui,
-                                                        ui.id(),
-                                                        &(),
-                                                    ) {
+                                                    let before = value.clone_value();
+                                                    // Custom editor replaces reflected fields of the component
+                                                    let changed = match component_editors
+                                                        .ui(*t_id, ui, value)
+                                                    {
+                                                        Some(changed) => changed,
+                                                        None if value.is::<Transform>() => {
+                                                            transform_ui(
+                                                                &mut env,
+                                                                value.downcast_mut().unwrap(),
+                                                                ui,
+                                                                &units,
+                                                                max_depth,
+                                                            )
+                                                        }
+                                                        None => ui_for_reflect_limited(
+                                                            &mut env,
+                                                            value,
+                                                            ui,
+                                                            ui.id(),
+                                                            0,
+                                                            max_depth,
+                                                        ),
+                                                    };
+                                                    if changed {
                                                         set_changed();
+                                                        edited.push((*t_id, name.clone(), before));
                                                     }
                                                 },
                                             );
                                         });
+                                    header.header_response.context_menu(|ui| {
+                                        if ui.button("Copy component").clicked() {
+                                            commands.push(InspectCommand::CopyComponent(
+                                                e.id(),
+                                                *t_id,
+                                                name.clone(),
+                                            ));
+                                            ui.close_menu();
+                                        }
+                                        if let Some(copied) = &copied_component {
+                                            if ui.button(format!("Paste {}", copied)).clicked() {
+                                                commands
+                                                    .push(InspectCommand::PasteComponent(e.id()));
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    });
                                     if header.header_response.clicked() {
                                         let open_name =
                                             open_components.entry(name.clone()).or_default();
@@ -275,7 +449,10 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
     //Open context window by button
     ui.vertical_centered(|ui| {
         ui.add_space(8.);
-        if ui.button("Add component").clicked() {
+        if ui
+            .add_enabled(read_only_root.is_none(), egui::Button::new("Add component"))
+            .clicked()
+        {
             state.show_add_component_window = true;
         }
     });
@@ -318,7 +495,224 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_components: &mut HashM
 
     state.commands = commands;
 
+    let now = ui.input(|i| i.time);
+    let pointer_down = ui.input(|i| i.pointer.any_down());
+    let mut to_store = vec![];
+    for (type_id, type_name, old_value) in edited {
+        match &mut state.pending_edit {
+            Some(pending) if pending.entity == selected_entity && pending.type_id == type_id => {
+                pending.last_edit = now;
+            }
+            pending => {
+                to_store.extend(pending.take());
+                *pending = Some(PendingEdit {
+                    entity: selected_entity,
+                    type_id,
+                    type_name,
+                    old_value,
+                    last_edit: now,
+                });
+            }
+        }
+    }
+    let settled = state
+        .pending_edit
+        .as_ref()
+        .is_some_and(|pending| !pointer_down && now - pending.last_edit > EDIT_COALESCE_TIME);
+    if settled {
+        to_store.extend(state.pending_edit.take());
+    }
+    let editing = state.pending_edit.as_ref().map(|pending| pending.entity);
+
+    // Auto undo must not split edit series to many steps
+    if let Some(entity) = editing {
+        if let Some(mut entity) = world.get_entity_mut(entity) {
+            entity.insert(OneFrameUndoIgnore::default());
+        }
+    }
+    for pending in to_store {
+        store_component_edit(world, pending);
+    }
+
     if disable_pan_orbit {
         world.resource_mut::<crate::EditorCameraEnabled>().0 = false;
     }
 }
+
+/// Reflected [`Transform`] fields with translation in [`UnitsSettings`] display units
+fn transform_ui(
+    env: &mut InspectorUi<'_, '_>,
+    transform: &mut Transform,
+    ui: &mut egui::Ui,
+    units: &UnitsSettings,
+    max_depth: usize,
+) -> bool {
+    let id = ui.id();
+    let mut changed = false;
+    egui::Grid::new(id.with("transform")).show(ui, |ui| {
+        ui.label("translation");
+        ui.horizontal(|ui| {
+            let translation = &mut transform.translation;
+            for value in [&mut translation.x, &mut translation.y, &mut translation.z] {
+                changed |= units.drag_value(ui, value).changed();
+            }
+        });
+        ui.end_row();
+        ui.label("rotation");
+        changed |= ui_for_reflect_limited(
+            env,
+            &mut transform.rotation,
+            ui,
+            id.with("rotation"),
+            1,
+            max_depth,
+        );
+        ui.end_row();
+        ui.label("scale");
+        changed |= ui_for_reflect_limited(
+            env,
+            &mut transform.scale,
+            ui,
+            id.with("scale"),
+            1,
+            max_depth,
+        );
+        ui.end_row();
+    });
+    changed
+}
+
+/// Editor registry components, which are known to world, sorted by [`ComponentsOrder`] priority and name
+fn registered_components(world: &World) -> Vec<(ComponentId, TypeId, String, u8)> {
+    let editor_registry = world.resource::<EditorRegistry>().clone();
+    let registry = editor_registry.registry.read();
+    let components_priority = &world.resource::<ComponentsOrder>().components;
+    let mut components_id = Vec::new();
+    for reg in registry.iter() {
+        if let Some(c_id) = world.components().get_id(reg.type_id()) {
+            let name = pretty_type_name::pretty_type_name_str(
+                world.components().get_info(c_id).unwrap().name(),
+            );
+            let priority = *components_priority.get(&name).unwrap_or(&u8::MAX);
+            components_id.push((c_id, reg.type_id(), name, priority));
+        }
+    }
+    components_id.sort_by(|(.., name_a, priority_a), (.., name_b, priority_b)| {
+        priority_a.cmp(priority_b).then(name_a.cmp(name_b))
+    });
+    components_id
+}
+
+/// Inspector content for multiple selected entities
+fn selection_add_component_ui(ui: &mut egui::Ui, world: &mut World, selected_count: usize) {
+    ui.vertical_centered(|ui| {
+        ui.add_space(8.);
+        ui.label(format!("{} entities selected", selected_count));
+        if ui.button("Add component to selection").clicked() {
+            world
+                .resource_mut::<InspectState>()
+                .show_selection_add_window = true;
+        }
+        if let Some(report) = &world.resource::<InspectState>().selection_add_report {
+            ui.label(report);
+        }
+    });
+
+    let components_id = registered_components(world);
+    let mut open = world.resource::<InspectState>().show_selection_add_window;
+    let mut chosen = None;
+    egui::Window::new("Add component to selection")
+        .open(&mut open)
+        .resizable(true)
+        .scroll2([false, true])
+        .default_width(120.)
+        .default_height(300.)
+        .show(ui.ctx(), |ui: &mut egui::Ui| {
+            let mut state = world.resource_mut::<FilterComponentState>();
+            ui.text_edit_singleline(&mut state.component_add_filter);
+            let lower_filter = state.component_add_filter.to_lowercase();
+            egui::Grid::new("Selection component grid").show(ui, |ui| {
+                for (_c_id, t_id, name, _) in &components_id {
+                    if name.to_lowercase().contains(&lower_filter) {
+                        ui.label(name);
+                        if ui.button("+").clicked() {
+                            chosen = Some((*t_id, name.clone()));
+                        }
+                        ui.end_row();
+                    }
+                }
+            });
+        });
+
+    let report = chosen.map(|(type_id, name)| {
+        let added = add_component_to_selected(world, type_id);
+        format!("{} added to {} of {} entities", name, added, selected_count)
+    });
+    let mut state = world.resource_mut::<InspectState>();
+    state.show_selection_add_window = open;
+    if report.is_some() {
+        state.selection_add_report = report;
+    }
+}
+
+/// Insert default value of registered component to all [`Selected`] entities, which do not have it yet.
+/// All insertions are one undo step. Returns count of entities, which got the component
+pub fn add_component_to_selected(world: &mut World, type_id: TypeId) -> usize {
+    let registry = world.resource::<EditorRegistry>().clone();
+    if !registry.spawn_components.contains_key(&type_id) {
+        warn!("Component {:?} is not registered in editor", type_id);
+        return 0;
+    }
+    let component_id = world.components().get_id(type_id);
+    let entities = world
+        .query_filtered::<EntityRef, With<Selected>>()
+        .iter(world)
+        .filter(|entity| !component_id.is_some_and(|id| entity.contains_id(id)))
+        .map(|entity| entity.id())
+        .collect::<Vec<_>>();
+    if entities.is_empty() {
+        return 0;
+    }
+
+    begin_change_group(world, "Add component to selection");
+    for entity in entities.iter() {
+        registry.get_spawn_command(&type_id).apply(*entity, world);
+    }
+    end_change_group(world);
+    info!("Component added to {} selected entities", entities.len());
+    entities.len()
+}
+
+/// Send finished inspector edit to undo chain, if component value was changed
+fn store_component_edit(world: &mut World, pending: PendingEdit) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let new_value = {
+        let registry = registry.read();
+        let Some(reflect_component) = registry
+            .get(pending.type_id)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+        else {
+            return;
+        };
+        let Some(value) = world
+            .get_entity(pending.entity)
+            .and_then(|entity| reflect_component.reflect(entity))
+        else {
+            return;
+        };
+        value.clone_value()
+    };
+    if new_value.reflect_partial_eq(pending.old_value.as_ref()) == Some(true) {
+        return;
+    }
+
+    world.send_event(NewChange {
+        change: Arc::new(ComponentModified {
+            entity: pending.entity,
+            type_id: pending.type_id,
+            type_name: pending.type_name,
+            old_value: pending.old_value,
+            new_value,
+        }),
+    });
+}
@@ -0,0 +1,594 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use space_editor_core::prelude::*;
+use space_prefab::instance::read_only_instance_root;
+use space_undo::{
+    begin_change_group, end_change_group, NewChange, OneFrameUndoIgnore, ReflectedComponentChange,
+};
+
+use crate::{
+    hierarchy::has_ancestor_in,
+    prelude::UnitsSettings,
+    tools::gizmo::{GizmoSettings, TransformSpace},
+};
+
+use super::EDIT_COALESCE_TIME;
+
+/// Values closer than this are shown as one value in multi-selection
+const MIXED_EPSILON: f32 = 1e-4;
+
+/// Transforms of selected entities before the first of series of edits in transform panel
+#[derive(Resource, Default)]
+pub struct TransformPanelState {
+    pending: Option<PendingTransformEdit>,
+    /// Open "Transform by…" dialog with edited delta
+    transform_by: Option<TransformDelta>,
+    /// Delta of the last applied "Transform by…" for "Repeat last"
+    pub last_transform_by: Option<TransformDelta>,
+}
+
+/// Change relative to current [`Transform`] of each entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransformDelta {
+    /// Added to translation
+    pub translation: Vec3,
+    /// XYZ euler angles in degrees, added to rotation
+    pub rotation: Vec3,
+    /// Multiplies scale
+    pub scale: Vec3,
+    /// Axes of translation and rotation. Scale is always applied along entity axes
+    pub space: TransformSpace,
+}
+
+impl Default for TransformDelta {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Vec3::ZERO,
+            scale: Vec3::ONE,
+            space: TransformSpace::World,
+        }
+    }
+}
+
+impl TransformDelta {
+    /// Apply delta to `transform` of entity with `parent` global transform
+    pub fn apply(&self, transform: &Transform, parent: Option<&GlobalTransform>) -> Transform {
+        let rotation = Quat::from_euler(
+            EulerRot::XYZ,
+            self.rotation.x.to_radians(),
+            self.rotation.y.to_radians(),
+            self.rotation.z.to_radians(),
+        );
+        let mut result = *transform;
+        match self.space {
+            TransformSpace::Local => {
+                result.translation += transform.rotation * self.translation;
+                result.rotation = transform.rotation * rotation;
+            }
+            TransformSpace::World => {
+                // World axes are converted to parent space for parented entities
+                let (translation, rotation) = match parent {
+                    Some(parent) => {
+                        let parent_rotation = parent.compute_transform().rotation;
+                        (
+                            parent
+                                .affine()
+                                .inverse()
+                                .transform_vector3(self.translation),
+                            parent_rotation.inverse() * rotation * parent_rotation,
+                        )
+                    }
+                    None => (self.translation, rotation),
+                };
+                result.translation += translation;
+                result.rotation = rotation * transform.rotation;
+            }
+        }
+        result.scale *= self.scale;
+        result
+    }
+}
+
+/// Apply `delta` to each of `entities` relative to its own transform. Children of other entities in the list
+/// are skipped, so they are not moved twice. Content of read-only prefab instances is skipped too.
+/// Stored as one undo step
+pub fn transform_by(world: &mut World, entities: &[Entity], delta: &TransformDelta) {
+    let changes = entities
+        .iter()
+        .copied()
+        .filter(|entity| !has_ancestor_in(world, *entity, entities))
+        .filter(|entity| read_only_instance_root(world, *entity).is_none())
+        .filter_map(|entity| {
+            let old = *world.get::<Transform>(entity)?;
+            let parent = world
+                .get::<Parent>(entity)
+                .and_then(|parent| world.get::<GlobalTransform>(parent.get()));
+            let new = delta.apply(&old, parent);
+            (new != old).then_some((entity, old, new))
+        })
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        return;
+    }
+
+    begin_change_group(world, "Transform by");
+    for (entity, old, new) in changes {
+        world
+            .entity_mut(entity)
+            .insert((new, OneFrameUndoIgnore::default()));
+        world.send_event(NewChange {
+            change: Arc::new(ReflectedComponentChange::new(entity, old, new)),
+        });
+    }
+    end_change_group(world);
+}
+
+/// Repeat the last "Transform by…" for current selection
+pub fn repeat_transform_by(world: &mut World) {
+    let Some(delta) = world.resource::<TransformPanelState>().last_transform_by else {
+        return;
+    };
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    transform_by(world, &selected, &delta);
+}
+
+fn transform_by_window(ui: &mut egui::Ui, world: &mut World, units: &UnitsSettings) {
+    let Some(mut delta) = world.resource::<TransformPanelState>().transform_by else {
+        return;
+    };
+    let mut apply = false;
+    let mut close = false;
+    egui::Window::new("Transform by")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ui.ctx(), |ui| {
+            egui::ComboBox::new("transform_by_space", "Space")
+                .selected_text(delta.space.to_string())
+                .show_ui(ui, |ui| {
+                    for space in [TransformSpace::World, TransformSpace::Local] {
+                        ui.selectable_value(&mut delta.space, space, space.to_string());
+                    }
+                });
+            egui::Grid::new("transform_by_grid").show(ui, |ui| {
+                ui.label("Offset");
+                for axis in 0..3 {
+                    let mut display = units.to_display(delta.translation[axis]);
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut display)
+                                .speed(0.1)
+                                .suffix(units.suffix()),
+                        )
+                        .changed()
+                    {
+                        delta.translation[axis] = units.from_display(display);
+                    }
+                }
+                ui.end_row();
+                ui.label("Rotate");
+                for axis in 0..3 {
+                    ui.add(
+                        egui::DragValue::new(&mut delta.rotation[axis])
+                            .speed(1.0)
+                            .suffix("°"),
+                    );
+                }
+                ui.end_row();
+                ui.label("Scale ×");
+                for axis in 0..3 {
+                    ui.add(egui::DragValue::new(&mut delta.scale[axis]).speed(0.01));
+                }
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                apply = ui.button("Apply").clicked();
+                if ui.button("Reset").clicked() {
+                    delta = TransformDelta {
+                        space: delta.space,
+                        ..default()
+                    };
+                }
+                close = ui.button("Close").clicked();
+            });
+        });
+
+    world.resource_mut::<TransformPanelState>().transform_by = (!close).then_some(delta);
+    if apply {
+        world
+            .resource_mut::<TransformPanelState>()
+            .last_transform_by = Some(delta);
+        repeat_transform_by(world);
+    }
+}
+
+/// Transform shown in panel. In world space translation and rotation are global, scale is always local
+fn to_space(
+    transform: &Transform,
+    parent: Option<&GlobalTransform>,
+    space: TransformSpace,
+) -> Transform {
+    match (space, parent) {
+        (TransformSpace::World, Some(parent)) => Transform {
+            translation: parent.transform_point(transform.translation),
+            rotation: parent.compute_transform().rotation * transform.rotation,
+            scale: transform.scale,
+        },
+        _ => *transform,
+    }
+}
+
+/// Inverse of [`to_space`]
+fn from_space(
+    shown: &Transform,
+    parent: Option<&GlobalTransform>,
+    space: TransformSpace,
+) -> Transform {
+    match (space, parent) {
+        (TransformSpace::World, Some(parent)) => Transform {
+            translation: parent
+                .affine()
+                .inverse()
+                .transform_point3(shown.translation),
+            rotation: parent.compute_transform().rotation.inverse() * shown.rotation,
+            scale: shown.scale,
+        },
+        _ => *shown,
+    }
+}
+
+fn parent_global(world: &World, entity: Entity) -> Option<GlobalTransform> {
+    let parent = world.get::<Parent>(entity)?;
+    world.get::<GlobalTransform>(parent.get()).copied()
+}
+
+struct PendingTransformEdit {
+    old_transforms: Vec<(Entity, Transform)>,
+    /// Egui time of the last edit
+    last_edit: f64,
+}
+
+/// One number of [`Transform`] in transform panel. Rotation is XYZ euler angle in degrees
+#[derive(Clone, Copy)]
+enum TransformField {
+    Translation(usize),
+    Rotation(usize),
+    Scale(usize),
+}
+
+impl TransformField {
+    fn get(self, transform: &Transform) -> f32 {
+        match self {
+            Self::Translation(axis) => transform.translation[axis],
+            Self::Rotation(axis) => {
+                let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+                [x, y, z][axis].to_degrees()
+            }
+            Self::Scale(axis) => transform.scale[axis],
+        }
+    }
+
+    fn set(self, transform: &mut Transform, value: f32) {
+        match self {
+            Self::Translation(axis) => transform.translation[axis] = value,
+            Self::Rotation(axis) => {
+                let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+                let mut angles = [x, y, z];
+                angles[axis] = value.to_radians();
+                transform.rotation =
+                    Quat::from_euler(EulerRot::XYZ, angles[0], angles[1], angles[2]);
+            }
+            Self::Scale(axis) => transform.scale[axis] = value,
+        }
+    }
+
+    fn to_display(self, value: f32, units: &UnitsSettings) -> f32 {
+        match self {
+            Self::Translation(_) => units.to_display(value),
+            _ => value,
+        }
+    }
+
+    fn from_display(self, value: f32, units: &UnitsSettings) -> f32 {
+        match self {
+            Self::Translation(_) => units.from_display(value),
+            _ => value,
+        }
+    }
+
+    fn suffix(self, units: &UnitsSettings) -> String {
+        match self {
+            Self::Translation(_) => units.suffix().to_string(),
+            Self::Rotation(_) => "°".to_string(),
+            Self::Scale(_) => String::new(),
+        }
+    }
+
+    const fn speed(self) -> f64 {
+        match self {
+            Self::Translation(_) => 0.1,
+            Self::Rotation(_) => 1.0,
+            Self::Scale(_) => 0.01,
+        }
+    }
+}
+
+const TRANSFORM_ROWS: [(&str, [TransformField; 3]); 3] = [
+    (
+        "Position",
+        [
+            TransformField::Translation(0),
+            TransformField::Translation(1),
+            TransformField::Translation(2),
+        ],
+    ),
+    (
+        "Rotation",
+        [
+            TransformField::Rotation(0),
+            TransformField::Rotation(1),
+            TransformField::Rotation(2),
+        ],
+    ),
+    (
+        "Scale",
+        [
+            TransformField::Scale(0),
+            TransformField::Scale(1),
+            TransformField::Scale(2),
+        ],
+    ),
+];
+
+/// Numeric [`Transform`] fields of all selected entities. Fields with different values are shown as "—",
+/// typed value is applied to all selected entities. Series of edits is stored as one undo step.
+/// Position and rotation are shown in [`GizmoSettings::space`], shared with gizmo.
+/// Content of read-only prefab instances is not shown
+pub fn transform_panel(ui: &mut egui::Ui, world: &mut World) {
+    let transforms = world
+        .query_filtered::<(Entity, &Transform), With<Selected>>()
+        .iter(world)
+        .filter(|(e, _)| read_only_instance_root(world, *e).is_none())
+        .map(|(e, transform)| (e, *transform))
+        .collect::<Vec<_>>();
+
+    let now = ui.input(|i| i.time);
+    let pointer_down = ui.input(|i| i.pointer.any_down());
+    let selection_changed = world
+        .resource::<TransformPanelState>()
+        .pending
+        .as_ref()
+        .is_some_and(|pending| {
+            pending.old_transforms.len() != transforms.len()
+                || pending
+                    .old_transforms
+                    .iter()
+                    .zip(transforms.iter())
+                    .any(|((a, _), (b, _))| a != b)
+        });
+    let settled = world
+        .resource::<TransformPanelState>()
+        .pending
+        .as_ref()
+        .is_some_and(|pending| !pointer_down && now - pending.last_edit > EDIT_COALESCE_TIME);
+    if selection_changed || settled {
+        if let Some(pending) = world.resource_mut::<TransformPanelState>().pending.take() {
+            store_transform_edit(world, pending);
+        }
+    }
+
+    if transforms.is_empty() {
+        return;
+    }
+
+    let units = world.resource::<UnitsSettings>().clone();
+    let space = world
+        .get_resource::<GizmoSettings>()
+        .map_or(TransformSpace::Local, |settings| settings.space);
+    let parents = transforms
+        .iter()
+        .map(|(entity, _)| parent_global(world, *entity))
+        .collect::<Vec<_>>();
+    let shown = transforms
+        .iter()
+        .zip(parents.iter())
+        .map(|((_, transform), parent)| to_space(transform, parent.as_ref(), space))
+        .collect::<Vec<_>>();
+    let first = &shown[0];
+    let has_last = world
+        .resource::<TransformPanelState>()
+        .last_transform_by
+        .is_some();
+    let mut open_transform_by = false;
+    let mut repeat = false;
+    let mut toggle_space = false;
+    let mut edit = None;
+    let space_name = space.to_string().to_lowercase();
+    let header = if transforms.len() > 1 {
+        format!("Transform ({} entities, {})", transforms.len(), space_name)
+    } else {
+        format!("Transform ({})", space_name)
+    };
+    egui::CollapsingHeader::new(header)
+        .id_source("transform_panel")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new("transform_panel_grid").show(ui, |ui| {
+                for (label, fields) in TRANSFORM_ROWS {
+                    ui.label(label);
+                    for field in fields {
+                        let value = field.get(first);
+                        let mixed = shown
+                            .iter()
+                            .any(|transform| (field.get(transform) - value).abs() > MIXED_EPSILON);
+                        let mut display = field.to_display(value, &units);
+                        let mut drag = egui::DragValue::new(&mut display)
+                            .speed(field.speed())
+                            .suffix(field.suffix(&units));
+                        if mixed {
+                            drag = drag.custom_formatter(|_, _| "—".to_string());
+                        }
+                        if ui.add(drag).changed() {
+                            edit = Some((field, field.from_display(display, &units)));
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                toggle_space = ui
+                    .button(space.to_string())
+                    .on_hover_text("Space of position and rotation, shared with gizmo (T)")
+                    .clicked();
+                open_transform_by = ui
+                    .button("Transform by…")
+                    .on_hover_text(
+                        "Offset, rotate or scale each entity relative to its own transform",
+                    )
+                    .clicked();
+                repeat = ui
+                    .add_enabled(has_last, egui::Button::new("Repeat last"))
+                    .clicked();
+            });
+        });
+    ui.separator();
+
+    if toggle_space {
+        if let Some(mut settings) = world.get_resource_mut::<GizmoSettings>() {
+            settings.space = space.toggled();
+        }
+    }
+    if open_transform_by {
+        let mut state = world.resource_mut::<TransformPanelState>();
+        // Dialog starts in the space of gizmo and panel
+        let delta = TransformDelta {
+            space,
+            ..state.last_transform_by.unwrap_or_default()
+        };
+        state.transform_by = Some(delta);
+    }
+    if repeat {
+        repeat_transform_by(world);
+    }
+    transform_by_window(ui, world, &units);
+
+    let mut state = world.resource_mut::<TransformPanelState>();
+    if let Some((field, value)) = edit {
+        match &mut state.pending {
+            Some(pending) => pending.last_edit = now,
+            pending => {
+                *pending = Some(PendingTransformEdit {
+                    old_transforms: transforms.clone(),
+                    last_edit: now,
+                })
+            }
+        }
+        for (((entity, _), parent), shown) in transforms.iter().zip(parents.iter()).zip(shown) {
+            let mut shown = shown;
+            field.set(&mut shown, value);
+            let transform = from_space(&shown, parent.as_ref(), space);
+            world.entity_mut(*entity).insert(transform);
+        }
+    }
+
+    // Auto undo must not split edit series to many steps
+    if world.resource::<TransformPanelState>().pending.is_some() {
+        for (entity, _) in transforms.iter() {
+            world
+                .entity_mut(*entity)
+                .insert(OneFrameUndoIgnore::default());
+        }
+    }
+}
+
+fn store_transform_edit(world: &mut World, pending: PendingTransformEdit) {
+    let changes = pending
+        .old_transforms
+        .into_iter()
+        .filter_map(|(entity, old)| {
+            let new = *world.get::<Transform>(entity)?;
+            (new != old).then_some((entity, old, new))
+        })
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        return;
+    }
+
+    begin_change_group(world, "Transform");
+    for (entity, old, new) in changes {
+        world.send_event(NewChange {
+            change: Arc::new(ReflectedComponentChange::new(entity, old, new)),
+        });
+    }
+    end_change_group(world);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space_shared::PrefabMarker;
+    use space_undo::{AppAutoUndo, UndoPlugin};
+
+    #[test]
+    fn test_transform_by_is_relative_and_undoable() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
+        app.auto_reflected_undo::<Transform>();
+        let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        let parent = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Transform::from_rotation(rotation),
+                GlobalTransform::from(Transform::from_rotation(rotation)),
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((PrefabMarker, Transform::from_xyz(0.0, 1.0, 0.0)))
+            .set_parent(parent)
+            .id();
+        let other = app
+            .world
+            .spawn((PrefabMarker, Transform::from_xyz(5.0, 0.0, 0.0)))
+            .id();
+        app.update();
+
+        let delta = TransformDelta {
+            translation: Vec3::X,
+            scale: Vec3::splat(2.0),
+            ..default()
+        };
+        transform_by(&mut app.world, &[child, other], &delta);
+
+        // World offset is converted to parent space
+        let child_transform = *app.world.get::<Transform>(child).unwrap();
+        assert!(
+            (rotation * child_transform.translation).abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-5)
+        );
+        assert_eq!(child_transform.scale, Vec3::splat(2.0));
+        assert_eq!(
+            app.world.get::<Transform>(other).unwrap().translation,
+            Vec3::new(6.0, 0.0, 0.0)
+        );
+
+        app.update();
+        app.update();
+        space_undo::undo(&mut app.world);
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            app.world.get::<Transform>(other).unwrap().translation,
+            Vec3::new(5.0, 0.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use bevy::{
+    prelude::*,
+    render::view::VisibilitySystems,
+    utils::{HashMap, HashSet},
+};
+use bevy_egui::egui;
+use space_editor_core::prelude::*;
+use space_shared::*;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+/// Plugin to dim all not selected meshes in editor view ("inspect in isolation")
+/// and to hide everything except selection with [`IsolateState`]
+pub struct IsolationPlugin;
+
+impl Plugin for IsolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IsolationSettings>();
+        app.register_type::<IsolationSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<IsolationSettings>();
+        }
+
+        app.init_resource::<IsolateState>();
+        app.editor_hotkey(IsolateHotkey::Toggle, vec![KeyCode::ShiftLeft, KeyCode::H]);
+
+        app.add_systems(Update, dim_not_selected.in_set(EditorSet::Editor));
+        app.add_systems(Update, isolate_hotkey.in_set(EditorSet::Editor));
+        app.add_systems(
+            PostUpdate,
+            apply_isolate
+                .after(VisibilitySystems::VisibilityPropagate)
+                .before(VisibilitySystems::CheckVisibility),
+        );
+        app.add_systems(
+            OnEnter(EditorState::GamePrepare),
+            (restore_dimmed, exit_isolate),
+        );
+    }
+}
+
+/// Settings of isolation view effect
+#[derive(Resource, Reflect, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct IsolationSettings {
+    pub enabled: bool,
+    /// 0.0 - no dimming, 1.0 - not selected meshes are black
+    pub intensity: f32,
+}
+
+impl Default for IsolationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.7,
+        }
+    }
+}
+
+impl IsolationSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.enabled, "Dim unselected");
+            if self.enabled {
+                ui.add(egui::Slider::new(&mut self.intensity, 0.0..=1.0));
+            }
+        });
+    }
+}
+
+fn dimmed_material(material: &StandardMaterial, intensity: f32) -> StandardMaterial {
+    let [r, g, b, a] = material.base_color.as_rgba_f32();
+    let grey = 0.3 * r + 0.59 * g + 0.11 * b;
+    let mix = |c: f32| (c + (grey - c) * intensity) * (1.0 - intensity);
+
+    let mut dimmed = material.clone();
+    dimmed.base_color = Color::rgba(mix(r), mix(g), mix(b), a);
+    dimmed.emissive = material.emissive * (1.0 - intensity);
+    dimmed
+}
+
+fn is_selected_or_child(
+    entity: Entity,
+    selected: &Query<(), With<Selected>>,
+    parents: &Query<&Parent>,
+) -> bool {
+    if selected.contains(entity) {
+        return true;
+    }
+    parents
+        .iter_ancestors(entity)
+        .any(|ancestor| selected.contains(ancestor))
+}
+
+fn dim_not_selected(
+    mut commands: Commands,
+    mut cache: Local<HashMap<AssetId<StandardMaterial>, Handle<StandardMaterial>>>,
+    settings: Res<IsolationSettings>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    selected: Query<(), With<Selected>>,
+    parents: Query<&Parent>,
+    // Editor meshes (gizmos, icons, camera previews) are not dimmed
+    mut meshes: Query<
+        (
+            Entity,
+            &mut Handle<StandardMaterial>,
+            Option<&IsolationDimmed>,
+        ),
+        With<PrefabMarker>,
+    >,
+) {
+    if settings.is_changed() {
+        cache.clear();
+    }
+    let active = settings.enabled && !selected.is_empty();
+
+    for (entity, mut handle, dimmed) in meshes.iter_mut() {
+        if active && !is_selected_or_child(entity, &selected, &parents) {
+            if dimmed.is_some_and(|dimmed| *handle == dimmed.dimmed && !settings.is_changed()) {
+                continue;
+            }
+            // Material could be replaced while entity was dimmed
+            let original = match dimmed {
+                Some(dimmed) if *handle == dimmed.dimmed => dimmed.original.clone(),
+                _ => handle.clone(),
+            };
+            let dimmed_handle = if let Some(cached) = cache.get(&original.id()) {
+                cached.clone()
+            } else {
+                let Some(material) = materials.get(&original) else {
+                    continue;
+                };
+                let new_material = dimmed_material(material, settings.intensity);
+                let new_handle = materials.add(new_material);
+                cache.insert(original.id(), new_handle.clone());
+                new_handle
+            };
+
+            *handle = dimmed_handle.clone();
+            commands.entity(entity).insert(IsolationDimmed {
+                original,
+                dimmed: dimmed_handle,
+            });
+        } else if let Some(dimmed) = dimmed {
+            if *handle == dimmed.dimmed {
+                *handle = dimmed.original.clone();
+            }
+            commands.entity(entity).remove::<IsolationDimmed>();
+        }
+    }
+}
+
+fn restore_dimmed(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Handle<StandardMaterial>, &IsolationDimmed), With<PrefabMarker>>,
+) {
+    for (entity, mut handle, dimmed) in query.iter_mut() {
+        if *handle == dimmed.dimmed {
+            *handle = dimmed.original.clone();
+        }
+        commands.entity(entity).remove::<IsolationDimmed>();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum IsolateHotkey {
+    Toggle,
+}
+
+impl Hotkey for IsolateHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::Toggle => "Isolate selected".to_string(),
+        }
+    }
+}
+
+/// Isolate mode: all prefab entities except subtrees of selection taken on enter are hidden in editor.
+/// Only [`InheritedVisibility`] is overridden, so [`Visibility`] is not changed and no undo changes are sent
+#[derive(Resource, Default)]
+pub struct IsolateState {
+    /// Entities which subtrees stay visible. `None` if isolate mode is off
+    pub isolated: Option<HashSet<Entity>>,
+    /// Entities hidden by isolate mode in the last frame
+    hidden: HashSet<Entity>,
+}
+
+impl IsolateState {
+    pub const fn is_active(&self) -> bool {
+        self.isolated.is_some()
+    }
+
+    /// Exit isolate mode or isolate given entities. Nothing happens if there is nothing to isolate
+    pub fn toggle(&mut self, selected: impl IntoIterator<Item = Entity>) {
+        if self.isolated.is_some() {
+            self.isolated = None;
+        } else {
+            let selected: HashSet<Entity> = selected.into_iter().collect();
+            if !selected.is_empty() {
+                self.isolated = Some(selected);
+            }
+        }
+    }
+}
+
+fn isolate_hotkey(
+    input: Res<Input<IsolateHotkey>>,
+    mut state: ResMut<IsolateState>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    if input.just_pressed(IsolateHotkey::Toggle) {
+        state.toggle(selected.iter());
+    }
+}
+
+fn exit_isolate(mut state: ResMut<IsolateState>) {
+    state.isolated = None;
+}
+
+fn apply_isolate(
+    mut state: ResMut<IsolateState>,
+    prefabs: Query<Entity, With<PrefabMarker>>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    mut visibility: Query<(&Visibility, &mut InheritedVisibility)>,
+) {
+    let mut hidden = HashSet::new();
+    if let Some(isolated) = &state.isolated {
+        let kept = |entity: Entity| {
+            isolated.contains(&entity)
+                || parents
+                    .iter_ancestors(entity)
+                    .any(|ancestor| isolated.contains(&ancestor))
+        };
+        for entity in prefabs.iter() {
+            if kept(entity) {
+                continue;
+            }
+            hidden.insert(entity);
+            // Not prefab descendants (like meshes of gltf scene) are hidden with their prefab
+            hidden.extend(
+                children
+                    .iter_descendants(entity)
+                    .filter(|child| !kept(*child)),
+            );
+        }
+    }
+
+    for entity in hidden.iter() {
+        if let Ok((_, mut inherited)) = visibility.get_mut(*entity) {
+            if inherited.get() {
+                *inherited = InheritedVisibility::HIDDEN;
+            }
+        }
+    }
+
+    // Visibility propagation updates only changed subtrees, so not hidden anymore entities
+    // get inherited visibility computed from their own and ancestors visibility
+    for entity in state.hidden.difference(&hidden) {
+        let mut chain = vec![*entity];
+        chain.extend(parents.iter_ancestors(*entity));
+        let visible = chain.iter().rev().fold(true, |parent_visible, entity| {
+            match visibility.get(*entity).map(|(visibility, _)| *visibility) {
+                Ok(Visibility::Visible) => true,
+                Ok(Visibility::Hidden) => false,
+                _ => parent_visible,
+            }
+        });
+        if let Ok((_, mut inherited)) = visibility.get_mut(*entity) {
+            if inherited.get() != visible {
+                *inherited = if visible {
+                    InheritedVisibility::VISIBLE
+                } else {
+                    InheritedVisibility::HIDDEN
+                };
+            }
+        }
+    }
+
+    state.hidden = hidden;
+}
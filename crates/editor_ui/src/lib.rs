@@ -6,18 +6,36 @@ mod mouse_check;
 /// This module will be used to create Unity like project file dialog. Currently NOT USED
 pub mod asset_inspector;
 
+/// This module contains periodic autosave of scene for crash recovery
+pub mod autosave;
+
 /// This module contains logic for bottom menu
 pub mod bottom_menu;
 
 /// This module contains UI logic for undo/redo functionality
 pub mod change_chain;
 
+/// This module contains searchable command palette of editor actions
+pub mod command_palette;
+
+/// This module contains Console tab with captured log output
+pub mod console;
+
+/// This module contains copy/paste of entities and component values through clipboard
+pub mod clipboard;
+
 /// This module contains UI logic for debug panels (like WorldInspector)
 pub mod debug_panels;
 
+/// This module contains saving/loading of editor dock layout
+pub mod dock_layout;
+
 /// This module contains traits and logic for editor dock tabs. Also it contains logic to run all editor dock ui
 pub mod editor_tab;
 
+/// This module contains global jump to entity by name or id
+pub mod entity_search;
+
 /// This module contains Game view tab logic
 pub mod game_view;
 
@@ -27,12 +45,42 @@ pub mod hierarchy;
 /// This module contains Inspector tab logic
 pub mod inspector;
 
+/// This module contains editor view effect to dim not selected entities
+pub mod isolation;
+
+/// This module contains editor only hiding of entities
+pub mod editor_visibility;
+
+/// This module contains editor only wireframe view of meshes
+pub mod wireframe;
+
+/// This module contains editor only outline of selected meshes
+pub mod selection_outline;
+
+/// This module contains viewport distance measurement
+pub mod measure;
+
+/// This module contains UI logic for prefab load errors summary
+pub mod load_errors;
+
 /// This module contains methods to visualize entities without a mesh attached
 pub mod meshless_visualizer;
 
+/// This module contains modal with results of prefab validation before save
+pub mod save_validation;
+
+/// This module contains saving/restoring of editor hierarchy state next to saved scenes
+pub mod scene_state;
+
+/// This module contains debug toggles of editor system sets and tab redraw
+pub mod system_toggles;
+
 /// This module contains Settings tab logic
 pub mod settings;
 
+/// This module contains trash bin of entities deleted through hierarchy
+pub mod trash;
+
 /// This module contains traits and methods to register tools in game view tab
 pub mod tool;
 
@@ -45,6 +93,9 @@ pub mod ui_registration;
 /// This module contains UI logic for view game camera image
 pub mod camera_view;
 
+/// This module contains offscreen capture of game camera image to file
+pub mod camera_capture;
+
 /// UI plugin and common systems
 pub mod ui_plugin;
 
@@ -82,15 +133,19 @@ use bevy_egui::{egui, EguiContext};
 
 use game_view::{has_window_changed, GameViewPlugin};
 use prelude::{
-    clean_meshless, reset_camera_viewport, set_camera_viewport, ChangeChainViewPlugin, EditorTab,
-    EditorTabCommand, EditorTabGetTitleFn, EditorTabName, EditorTabShowFn, EditorTabViewer,
-    GameModeSettings, GameViewTab, MeshlessVisualizerPlugin, NewTabBehaviour, NewWindowSettings,
+    clean_meshless, reset_camera_viewport, set_camera_viewport, BoxedEditorTab,
+    BoxedEditorTabStorage, ChangeChainViewPlugin, EditorTab, EditorTabCommand, EditorTabGetTitleFn,
+    EditorTabName, EditorTabShowFn, EditorTabViewer, GameModeSettings, GameViewTab,
+    MeshlessVisualizerPlugin, NewTabBehaviour, NewWindowSettings, PlayModeSettings,
     ScheduleEditorTab, ScheduleEditorTabStorage, SpaceHierarchyPlugin, SpaceInspectorPlugin,
 };
 use space_prefab::prelude::*;
 use space_shared::{
+    apply_run_requests,
     ext::bevy_inspector_egui::{quick::WorldInspectorPlugin, DefaultInspectorConfigPlugin},
-    EditorCameraMarker, EditorSet, EditorState, PrefabMarker, PrefabMemoryCache, SelectParent,
+    simulation_running, update_editor_run_state, EditorCameraMarker, EditorRunRequest,
+    EditorRunState, EditorRunStateChanged, EditorSet, EditorState, PrefabMarker, PrefabMemoryCache,
+    SelectParent, SimulationState,
 };
 use space_undo::{SyncUndoMarkersPlugin, UndoPlugin, UndoSet};
 use ui_registration::BundleReg;
@@ -104,9 +159,11 @@ pub const LAST_RENDER_LAYER: u8 = RenderLayers::TOTAL_LAYERS as u8 - 1;
 
 pub mod prelude {
     pub use super::{
-        asset_inspector::*, bottom_menu::*, change_chain::*, debug_panels::*, editor_tab::*,
-        game_view::*, hierarchy::*, inspector::*, meshless_visualizer::*, settings::*, tool::*,
-        tools::*, ui_registration::*,
+        asset_inspector::*, bottom_menu::*, change_chain::*, clipboard::*, command_palette::*,
+        console::*, debug_panels::*, dock_layout::*, editor_tab::*, editor_visibility::*,
+        entity_search::*, game_view::*, hierarchy::*, inspector::*, isolation::*, load_errors::*,
+        meshless_visualizer::*, selection_outline::*, settings::*, system_toggles::*, tool::*,
+        tools::*, ui_registration::*, wireframe::*,
     };
 
     pub use space_editor_core::prelude::*;
@@ -212,6 +269,40 @@ impl Plugin for EditorSetsPlugin {
             Update,
             EditorSet::Editor.run_if(in_state(EditorState::Editor)),
         );
+
+        // Game logic can be paused and stepped, editor systems keep running
+        app.init_resource::<SimulationState>();
+        app.init_resource::<SimulationStepRan>();
+        app.configure_sets(Update, EditorSet::Game.run_if(simulation_running));
+        app.add_systems(Update, mark_simulation_step.in_set(EditorSet::Game));
+        app.add_systems(Last, consume_simulation_step);
+
+        // Public run state is applied in the same frame, before `Update` systems
+        app.add_state::<EditorRunState>();
+        app.add_event::<EditorRunRequest>();
+        app.add_event::<EditorRunStateChanged>();
+        app.add_systems(
+            PreUpdate,
+            (apply_run_requests, update_editor_run_state).chain(),
+        );
+    }
+}
+
+/// Paused game systems ran this frame because of [`SimulationState::step`]
+#[derive(Resource, Default)]
+struct SimulationStepRan(bool);
+
+fn mark_simulation_step(state: Res<SimulationState>, mut ran: ResMut<SimulationStepRan>) {
+    if state.step {
+        ran.0 = true;
+    }
+}
+
+/// Step is cleared only after game systems ran, so step requested later in the frame is not lost
+fn consume_simulation_step(mut state: ResMut<SimulationState>, mut ran: ResMut<SimulationStepRan>) {
+    if ran.0 {
+        state.step = false;
+        ran.0 = false;
     }
 }
 
@@ -256,11 +347,18 @@ fn set_start_state(mut state: ResMut<NextState<EditorState>>) {
     state.set(EditorState::Loading);
 }
 
+/// Load the last saved scene on return to editor. After play mode it is the scene stored before play,
+/// so game changes are discarded unless [`PlayModeSettings::restore_on_stop`] is disabled.
+/// Without restore, scenes spawned by [`PlayerStart`] are still removed, so next play does not spawn them twice
 fn clear_and_load_on_start(
+    mut commands: Commands,
     mut load_server: ResMut<EditorLoader>,
     save_confg: Res<SaveConfig>,
     assets: Res<AssetServer>,
     cache: Res<PrefabMemoryCache>,
+    play_settings: Option<Res<PlayModeSettings>>,
+    player_starts: Query<&Children, With<PlayerStart>>,
+    game_scenes: Query<(), (With<Handle<DynamicScene>>, Without<PrefabMarker>)>,
 ) {
     if save_confg.path.is_none() {
         return;
@@ -271,6 +369,18 @@ fn clear_and_load_on_start(
             load_server.scene = Some(assets.load(format!("{}.scn.ron", path)));
         }
         space_shared::EditorPrefabPath::MemoryCahce => {
+            if play_settings.is_some_and(|settings| !settings.restore_on_stop) {
+                info!("Keeping scene changed in play mode");
+                for children in player_starts.iter() {
+                    for child in children
+                        .iter()
+                        .filter(|child| game_scenes.contains(**child))
+                    {
+                        commands.entity(*child).despawn_recursive();
+                    }
+                }
+                return;
+            }
             info!("Loading prefab from cache");
             load_server.scene = cache.scene.clone();
         }
@@ -385,3 +495,150 @@ pub fn game_mode_changed(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use space_prefab::load::LoadPlugin;
+    use space_shared::EditorPrefabPath;
+
+    use super::*;
+
+    fn play_mode_app(restore_on_stop: bool) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), HierarchyPlugin))
+            .init_asset::<DynamicScene>()
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins((SavePrefabPlugin, LoadPlugin))
+            .init_resource::<EditorLoader>()
+            .init_resource::<PrefabMemoryCache>()
+            .insert_resource(PlayModeSettings { restore_on_stop })
+            .add_systems(Update, load_listener);
+        app.editor_registry::<Name>();
+        app.editor_registry::<Transform>();
+        app
+    }
+
+    /// Store scene to memory as before play, change it as game logic would and return to editor
+    fn play_and_stop(app: &mut App) -> (Entity, Entity) {
+        let parent = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("parent"),
+                Transform::from_xyz(1.0, 2.0, 3.0),
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("child"),
+                Transform::from_xyz(0.0, 1.0, 0.0),
+            ))
+            .id();
+        app.world.entity_mut(parent).add_child(child);
+
+        app.world.resource_mut::<SaveConfig>().path = Some(EditorPrefabPath::MemoryCahce);
+        app.world
+            .resource_mut::<NextState<SaveState>>()
+            .set(SaveState::Save);
+        app.update();
+        assert!(app.world.resource::<PrefabMemoryCache>().scene.is_some());
+
+        app.world.get_mut::<Transform>(parent).unwrap().translation = Vec3::splat(10.0);
+        app.world.entity_mut(child).despawn_recursive();
+        app.world.spawn((
+            PrefabMarker,
+            Name::new("spawned by game"),
+            Transform::default(),
+        ));
+
+        app.world.run_system_once(clear_and_load_on_start);
+        app.update();
+        app.update();
+        (parent, child)
+    }
+
+    fn prefab_names(app: &mut App) -> Vec<String> {
+        let mut names = app
+            .world
+            .query_filtered::<&Name, With<PrefabMarker>>()
+            .iter(&app.world)
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    fn find_by_name(app: &mut App, name: &str) -> Entity {
+        app.world
+            .query::<(Entity, &Name)>()
+            .iter(&app.world)
+            .find(|(_, entity_name)| entity_name.as_str() == name)
+            .map(|(entity, _)| entity)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_stop_restores_scene_stored_before_play() {
+        let mut app = play_mode_app(true);
+        play_and_stop(&mut app);
+
+        assert_eq!(prefab_names(&mut app), vec!["child", "parent"]);
+        let parent = find_by_name(&mut app, "parent");
+        let child = find_by_name(&mut app, "child");
+        assert_eq!(
+            app.world.get::<Parent>(child).map(Parent::get),
+            Some(parent)
+        );
+        assert_eq!(
+            app.world.get::<Transform>(parent).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::Y
+        );
+    }
+
+    #[test]
+    fn test_stop_keeps_play_changes_without_restore() {
+        let mut app = play_mode_app(false);
+        let (parent, child) = play_and_stop(&mut app);
+
+        assert!(app.world.resource::<EditorLoader>().scene.is_none());
+        assert_eq!(prefab_names(&mut app), vec!["parent", "spawned by game"]);
+        assert!(app.world.get_entity(child).is_none());
+        assert_eq!(
+            app.world.get::<Transform>(parent).unwrap().translation,
+            Vec3::splat(10.0)
+        );
+    }
+
+    #[test]
+    fn test_stop_without_restore_removes_player_start_scene() {
+        let mut app = play_mode_app(false);
+        app.world.resource_mut::<SaveConfig>().path = Some(EditorPrefabPath::MemoryCahce);
+        let start = app.world.spawn((PrefabMarker, PlayerStart::default())).id();
+        // Spawned by `spawn_player_start` on play
+        let scene = app
+            .world
+            .spawn(Handle::<DynamicScene>::default())
+            .set_parent(start)
+            .id();
+        let game_entity = app.world.spawn(Name::new("player")).set_parent(scene).id();
+        let prefab_child = app
+            .world
+            .spawn((PrefabMarker, Name::new("spawn point")))
+            .set_parent(start)
+            .id();
+
+        app.world.run_system_once(clear_and_load_on_start);
+
+        assert!(app.world.get_entity(scene).is_none());
+        assert!(app.world.get_entity(game_entity).is_none());
+        assert!(app.world.get_entity(start).is_some());
+        assert!(app.world.get_entity(prefab_child).is_some());
+    }
+}
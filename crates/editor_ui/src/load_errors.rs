@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use space_editor_core::prelude::*;
+
+use super::{
+    editor_tab::{EditorTab, EditorTabName},
+    settings::HierarchySettings,
+    show_editor_ui, EditorUiAppExt, UiSystemSet,
+};
+
+pub struct LoadErrorsViewPlugin;
+
+impl Plugin for LoadErrorsViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_tab_by_trait(
+            EditorTabName::Other("Load Errors".to_string()),
+            LoadErrorsView,
+        );
+        app.init_resource::<LoadReportWindowState>();
+        app.add_systems(
+            Update,
+            show_load_report.after(show_editor_ui).in_set(UiSystemSet),
+        );
+    }
+}
+
+/// State of "Load summary" window, which is opened after each prefab load
+#[derive(Resource, Default)]
+pub struct LoadReportWindowState {
+    pub open: bool,
+}
+
+fn show_load_report(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    mut state: ResMut<LoadReportWindowState>,
+    report: Option<Res<LoadReport>>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let Some(report) = report else {
+        return;
+    };
+    if report.is_changed() {
+        state.open = true;
+    }
+    if !state.open {
+        return;
+    }
+
+    let mut select = None;
+    egui::Window::new("Load summary")
+        .open(&mut state.open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label(format!("Entities loaded: {}", report.entities));
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Entities with load errors: {}",
+                    report.entities_with_errors.len()
+                ));
+                if !report.entities_with_errors.is_empty() && ui.button("Select").clicked() {
+                    select = Some(report.entities_with_errors.clone());
+                }
+            });
+            ui.label(format!(
+                "Unregistered components dropped: {}",
+                report.dropped_components
+            ));
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Broken entity references: {}",
+                    report.broken_references.len()
+                ));
+                if !report.broken_references.is_empty() && ui.button("Select").clicked() {
+                    select = Some(report.broken_references.clone());
+                }
+            });
+        });
+
+    if let Some(entities) = select {
+        for e in selected.iter() {
+            commands.entity(e).remove::<Selected>();
+        }
+        for e in entities {
+            if let Some(mut e) = commands.get_entity(e) {
+                e.insert(Selected);
+            }
+        }
+    }
+}
+
+/// Tab with summary of all entities marked with [`LoadError`]
+#[derive(Resource, Default)]
+pub struct LoadErrorsView;
+
+impl EditorTab for LoadErrorsView {
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        let id_display = world
+            .get_resource::<HierarchySettings>()
+            .map(|settings| settings.id_display)
+            .unwrap_or_default();
+        let mut query = world.query::<(Entity, Option<&Name>, &LoadError)>();
+
+        let mut errors = query
+            .iter(world)
+            .map(|(entity, name, error)| {
+                let name = id_display.format(name, entity);
+                (entity, name, error.errors.clone())
+            })
+            .collect::<Vec<_>>();
+        errors.sort_by_key(|(entity, ..)| *entity);
+
+        if world.contains_resource::<LoadReport>() && ui.button("Show load summary").clicked() {
+            world.resource_mut::<LoadReportWindowState>().open = true;
+        }
+
+        if errors.is_empty() {
+            ui.label("No load errors");
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (entity, name, entity_errors) in errors {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::RED, name);
+                    if ui.button("Select").clicked() {
+                        commands.entity(entity).insert(Selected);
+                    }
+                    if ui.button("Clear").clicked() {
+                        commands.entity(entity).remove::<LoadError>();
+                    }
+                });
+                for error in entity_errors {
+                    ui.label(format!("    {}", error));
+                }
+                ui.separator();
+            }
+        });
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Load Errors".into()
+    }
+}
@@ -0,0 +1,210 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{egui, EguiContexts, EguiSettings};
+use bevy_mod_picking::{focus::HoverMap, pointer::PointerId};
+use space_shared::*;
+
+use crate::{
+    camera_plugin::update_pan_orbit,
+    game_view::{EditorViewportInfo, GameViewTab},
+    prelude::UnitsSettings,
+    show_editor_ui, UiSystemSet,
+};
+
+/// Max distance in egui points from cursor to entity origin, at which measure point snaps to origin
+pub const MEASURE_SNAP_DISTANCE: f32 = 12.0;
+
+/// Max cursor movement in logical pixels between press and release, which is still a click
+const CLICK_MAX_DISTANCE: f32 = 4.0;
+
+/// Plugin for viewport distance measurement. Measure mode is toggled in game view toolbar
+pub struct MeasurePlugin;
+
+impl Plugin for MeasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeasureState>();
+        app.add_systems(
+            Update,
+            (measure_click, draw_measurements)
+                .chain()
+                .after(show_editor_ui)
+                .before(update_pan_orbit)
+                .in_set(UiSystemSet),
+        );
+    }
+}
+
+/// Distance measurements in game view. While [`Self::active`], clicks in game view place measure points
+/// instead of selecting entities
+#[derive(Resource, Default)]
+pub struct MeasureState {
+    pub active: bool,
+    /// First point of measurement in progress
+    pub pending: Option<Vec3>,
+    /// Finished measurements, kept until cleared
+    pub measurements: Vec<(Vec3, Vec3)>,
+}
+
+impl MeasureState {
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+        self.pending = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.pending = None;
+        self.measurements.clear();
+    }
+
+    /// Start new measurement or finish pending one
+    pub fn add_point(&mut self, point: Vec3) {
+        match self.pending.take() {
+            Some(start) => self.measurements.push((start, point)),
+            None => self.pending = Some(point),
+        }
+    }
+
+    /// Toolbar buttons for game view
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .selectable_label(self.active, "📏 Measure")
+            .on_hover_text("Click two points in game view to measure distance between them")
+            .clicked()
+        {
+            self.toggle();
+        }
+        if !self.measurements.is_empty() && ui.button("Clear measurements").clicked() {
+            self.clear();
+        }
+    }
+}
+
+/// World point under cursor: origin of prefab entity close to cursor or the closest raycast hit
+fn measure_point(
+    cursor: egui::Pos2,
+    viewport: &EditorViewportInfo,
+    hover_map: &HoverMap,
+    prefabs: &Query<(Entity, &GlobalTransform), With<PrefabMarker>>,
+) -> Option<Vec3> {
+    let origin = prefabs
+        .iter()
+        .filter_map(|(_, transform)| {
+            let point = transform.translation();
+            let distance = viewport.world_to_egui(point)?.distance(cursor);
+            (distance <= MEASURE_SNAP_DISTANCE).then_some((point, distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(point, _)| point);
+
+    origin.or_else(|| {
+        hover_map
+            .get(&PointerId::Mouse)?
+            .values()
+            .filter(|hit| hit.position.is_some())
+            .min_by(|a, b| a.depth.total_cmp(&b.depth))
+            .and_then(|hit| hit.position)
+    })
+}
+
+fn measure_click(
+    mut state: ResMut<MeasureState>,
+    mut press: Local<Option<Vec2>>,
+    game_view: Res<GameViewTab>,
+    viewport: Res<EditorViewportInfo>,
+    egui_settings: Res<EguiSettings>,
+    hover_map: Res<HoverMap>,
+    mouse: Res<Input<MouseButton>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    prefabs: Query<(Entity, &GlobalTransform), With<PrefabMarker>>,
+) {
+    if !state.active {
+        *press = None;
+        return;
+    }
+    let Some(cursor) = window.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let scale = egui_settings.scale_factor as f32;
+    let egui_cursor = egui::pos2(cursor.x / scale, cursor.y / scale);
+
+    if mouse.just_pressed(MouseButton::Left) {
+        *press = game_view
+            .viewport_rect
+            .is_some_and(|rect| rect.contains(egui_cursor))
+            .then_some(cursor);
+        return;
+    }
+    if mouse.just_pressed(MouseButton::Right) {
+        state.pending = None;
+        return;
+    }
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    // Longer drags rotate camera
+    if press
+        .take()
+        .map_or(true, |start| start.distance(cursor) > CLICK_MAX_DISTANCE)
+    {
+        return;
+    }
+    if let Some(point) = measure_point(egui_cursor, &viewport, &hover_map, &prefabs) {
+        state.add_point(point);
+    }
+}
+
+fn draw_measurements(
+    mut ctxs: EguiContexts,
+    state: Res<MeasureState>,
+    viewport: Res<EditorViewportInfo>,
+    units: Res<UnitsSettings>,
+    egui_settings: Res<EguiSettings>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Some(rect) = viewport.rect else {
+        return;
+    };
+    if state.measurements.is_empty() && state.pending.is_none() {
+        return;
+    }
+    let painter = ctxs
+        .ctx_mut()
+        .layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("measurements"),
+        ))
+        .with_clip_rect(rect);
+    let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 200, 60));
+
+    for (start, end) in state.measurements.iter() {
+        let (Some(a), Some(b)) = (viewport.world_to_egui(*start), viewport.world_to_egui(*end))
+        else {
+            continue;
+        };
+        painter.line_segment([a, b], stroke);
+        painter.circle_filled(a, 3.0, stroke.color);
+        painter.circle_filled(b, 3.0, stroke.color);
+        painter.text(
+            a + (b - a) / 2.0,
+            egui::Align2::CENTER_BOTTOM,
+            units.format(start.distance(*end)),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+    }
+
+    // Line from pending point follows cursor
+    let Some(a) = state
+        .pending
+        .and_then(|start| viewport.world_to_egui(start))
+    else {
+        return;
+    };
+    painter.circle_filled(a, 3.0, stroke.color);
+    let scale = egui_settings.scale_factor as f32;
+    if let Some(cursor) = window.get_single().ok().and_then(Window::cursor_position) {
+        painter.line_segment(
+            [a, egui::pos2(cursor.x / scale, cursor.y / scale)],
+            egui::Stroke::new(1.0, stroke.color),
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use space_editor_core::prelude::*;
+use space_shared::EditorPrefabPath;
+
+use super::{show_editor_ui, UiSystemSet};
+
+pub struct SaveValidationPlugin;
+
+impl Plugin for SaveValidationPlugin {
+    fn build(&self, app: &mut App) {
+        // Report window is shown, so warnings are confirmed by user
+        app.world
+            .get_resource_or_insert_with(SaveValidation::default)
+            .confirm_warnings = true;
+        app.add_systems(
+            Update,
+            show_save_validation
+                .after(show_editor_ui)
+                .in_set(UiSystemSet),
+        );
+    }
+}
+
+/// Modal window with issues found by prefab validators. Errors block save, warnings can be ignored by "Save anyway"
+fn show_save_validation(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    mut validation: ResMut<SaveValidation>,
+) {
+    let Some(report) = validation.report.as_ref() else {
+        return;
+    };
+
+    let mut select = None;
+    let mut save = false;
+    let mut close = false;
+    egui::Window::new("Save validation")
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label(format!("Issues found before saving {}:", report.path));
+            ui.separator();
+            for issue in report.issues.iter() {
+                ui.horizontal(|ui| {
+                    let (icon, color) = match issue.severity {
+                        ValidationSeverity::Warning => ("⚠", egui::Color32::YELLOW),
+                        ValidationSeverity::Error => ("❌", egui::Color32::LIGHT_RED),
+                    };
+                    ui.colored_label(color, icon);
+                    ui.label(&issue.message);
+                    if !issue.entities.is_empty() && ui.button("Select").clicked() {
+                        select = Some(issue.entities.clone());
+                    }
+                });
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!report.has_errors(), egui::Button::new("Save anyway"))
+                    .on_disabled_hover_text("Fix errors before saving")
+                    .clicked()
+                {
+                    save = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if let Some(entities) = select {
+        commands.add(move |world: &mut World| {
+            select_entities(world, &entities, SelectMode::Replace);
+        });
+    }
+    if save || close {
+        let report = validation.report.take();
+        if let Some(report) = report.filter(|_| save) {
+            commands.add(move |world: &mut World| {
+                start_save(world, EditorPrefabPath::File(report.path));
+            });
+        }
+    }
+}
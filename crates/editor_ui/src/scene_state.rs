@@ -0,0 +1,191 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, Uuid},
+};
+use bevy_egui::{egui::collapsing_header::CollapsingState, EguiContexts};
+use serde::{Deserialize, Serialize};
+use space_editor_core::prelude::*;
+use space_prefab::{
+    prelude::{EditorUuid, PrefabLoaded},
+    save::{SaveConfig, SaveState},
+};
+use space_shared::*;
+
+use crate::{
+    hierarchy::{hierarchy_collapsing_id, EditorNote, HierarchyTag, Pinned},
+    settings::HierarchySettings,
+};
+
+/// Plugin to store editor only hierarchy state (selection, expanded nodes, pins, tags and notes)
+/// in `*.editor.ron` file next to saved scene and restore it when scene is loaded
+pub struct SceneStatePlugin;
+
+impl Plugin for SceneStatePlugin {
+    fn build(&self, app: &mut App) {
+        // Saves blocked by validation or cancelled by user never enter `SaveState::Save`,
+        // so state file stays in sync with prefab file
+        app.add_systems(OnEnter(SaveState::Save), save_scene_state);
+        app.add_systems(Update, restore_scene_state);
+    }
+}
+
+/// Editor state of one scene entity. Entity is stored by its [`EditorUuid`], which is saved in prefab file
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct EntitySceneState {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub selected: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    /// `None` if node was never expanded or collapsed by user
+    #[serde(default)]
+    pub expanded: Option<bool>,
+    #[serde(default)]
+    pub tag: Option<HierarchyTag>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Content of `*.editor.ron` file. It is never read by prefab loader, so scene file stays clean
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct SceneState {
+    pub entities: Vec<EntitySceneState>,
+}
+
+/// Path of editor state file for prefab asset path. `level.scn.ron` is stored as `assets/level.editor.ron`
+pub fn scene_state_path(prefab_path: &str) -> String {
+    let stem = prefab_path
+        .strip_suffix(".scn.ron")
+        .or_else(|| prefab_path.strip_suffix(".ron"))
+        .unwrap_or(prefab_path);
+    format!("assets/{}.editor.ron", stem)
+}
+
+fn save_scene_state(
+    save_config: Res<SaveConfig>,
+    mut ctxs: EguiContexts,
+    settings: Res<HierarchySettings>,
+    query: Query<
+        (
+            Entity,
+            &EditorUuid,
+            Has<Selected>,
+            Has<Pinned>,
+            Option<&HierarchyTag>,
+            Option<&EditorNote>,
+        ),
+        With<PrefabMarker>,
+    >,
+) {
+    let Some(EditorPrefabPath::File(path)) = &save_config.path else {
+        return;
+    };
+    if !settings.store_scene_state {
+        return;
+    }
+
+    let ctx = ctxs.ctx_mut();
+    let entities = query
+        .iter()
+        .map(
+            |(entity, uuid, selected, pinned, tag, note)| EntitySceneState {
+                uuid: uuid.0,
+                selected,
+                pinned,
+                expanded: CollapsingState::load(ctx, hierarchy_collapsing_id(entity))
+                    .map(|state| state.is_open()),
+                tag: tag.copied(),
+                note: note.map(|note| note.0.clone()),
+            },
+        )
+        .filter(|state| {
+            state.selected
+                || state.pinned
+                || state.expanded.is_some()
+                || state.tag.is_some()
+                || state.note.is_some()
+        })
+        .collect();
+
+    let path = scene_state_path(path);
+    let res = ron::ser::to_string_pretty(&SceneState { entities }, Default::default())
+        .map_err(|err| err.to_string())
+        .and_then(|data| std::fs::write(&path, data).map_err(|err| err.to_string()));
+    if let Err(err) = res {
+        error!("Failed to save editor state to {}: {}", path, err);
+    }
+}
+
+fn restore_scene_state(
+    mut commands: Commands,
+    mut events: EventReader<PrefabLoaded>,
+    mut ctxs: EguiContexts,
+    settings: Res<HierarchySettings>,
+    uuids: Query<&EditorUuid>,
+) {
+    for event in events.read() {
+        let Some(path) = &event.path else {
+            continue;
+        };
+        if !settings.store_scene_state || event.entity_map.is_empty() {
+            continue;
+        }
+
+        let path = scene_state_path(path);
+        // Scene can be saved without editor state file
+        let Ok(data) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let state: SceneState = match ron::from_str(&data) {
+            Ok(state) => state,
+            Err(err) => {
+                warn!("Failed to read editor state from {}: {}", path, err);
+                continue;
+            }
+        };
+
+        let loaded = event
+            .entity_map
+            .values()
+            .filter_map(|entity| uuids.get(*entity).ok().map(|uuid| (uuid.0, *entity)))
+            .collect::<HashMap<_, _>>();
+
+        let ctx = ctxs.ctx_mut();
+        let mut selected = vec![];
+        for stored in state.entities {
+            // Entities removed from scene after state was saved are ignored
+            let Some(entity) = loaded.get(&stored.uuid).copied() else {
+                continue;
+            };
+
+            if let Some(open) = stored.expanded {
+                let mut collapsing = CollapsingState::load_with_default_open(
+                    ctx,
+                    hierarchy_collapsing_id(entity),
+                    true,
+                );
+                collapsing.set_open(open);
+                collapsing.store(ctx);
+            }
+
+            let Some(mut cmds) = commands.get_entity(entity) else {
+                continue;
+            };
+            if stored.selected {
+                selected.push(entity);
+            }
+            if stored.pinned {
+                cmds.insert(Pinned);
+            }
+            if let Some(tag) = stored.tag {
+                cmds.insert(tag);
+            }
+            if let Some(note) = stored.note {
+                cmds.insert(EditorNote(note));
+            }
+        }
+        if !selected.is_empty() {
+            commands.add(SelectEntities::new(selected, SelectMode::Replace));
+        }
+    }
+}
@@ -1,5 +1,12 @@
-use crate::*;
-use bevy::prelude::*;
+use crate::{
+    hierarchy::{entity_bounds, request_delete, HierarchyTabState},
+    measure::MeasureState,
+    tools::gizmo::GizmoDragState,
+    *,
+};
+use bevy::{prelude::*, render::primitives::Aabb};
+use bevy_egui::{EguiContexts, EguiSettings};
+use bevy_mod_picking::{focus::HoverMap, pointer::PointerId};
 
 pub struct EditorPickingPlugin;
 
@@ -17,9 +24,50 @@ impl Plugin for EditorPickingPlugin {
                 .run_if(in_state(EditorState::Editor)),
         );
         app.add_systems(PostUpdate, auto_add_picking_dummy);
+
+        app.init_resource::<BoxSelectState>();
+        app.editor_hotkey(SelectionHotkey::BoxSelect, vec![KeyCode::ControlLeft]);
+        app.editor_hotkey(SelectionHotkey::Delete, vec![KeyCode::Delete]);
+        app.add_systems(
+            Update,
+            (start_box_select, box_select, click_select)
+                .chain()
+                .after(show_editor_ui)
+                .before(update_pan_orbit)
+                .in_set(UiSystemSet),
+        );
+        app.add_systems(
+            Update,
+            (update_hovered, draw_hovered)
+                .chain()
+                .after(show_editor_ui)
+                .in_set(UiSystemSet),
+        );
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum SelectionHotkey {
+    BoxSelect,
+    Delete,
+}
+
+impl Hotkey for SelectionHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::BoxSelect => "Box select".to_string(),
+            Self::Delete => "Delete selected".to_string(),
+        }
+    }
+}
+
+/// State of box (marquee) selection in game view
+#[derive(Resource, Default)]
+pub struct BoxSelectState {
+    /// Start of drag in window logical coordinates
+    pub start: Option<Vec2>,
+}
+
 pub fn auto_add_picking(
     mut commands: Commands,
     query: Query<Entity, (With<PrefabMarker>, Without<Pickable>)>,
@@ -54,30 +102,39 @@ pub fn auto_add_picking_dummy(
 
 pub fn select_listener(
     mut commands: Commands,
-    query: Query<Entity, With<Selected>>,
     // may need to be optimized a bit so that there is less overlap
     query_parent: Query<&SelectParent>,
+    locked: Query<(), With<Locked>>,
     mut events: EventReader<SelectEvent>,
     pan_orbit_state: ResMut<EditorCameraEnabled>,
     keyboard: Res<Input<KeyCode>>,
+    measure: Option<Res<MeasureState>>,
 ) {
     if !pan_orbit_state.0 {
         return;
     }
+    // Clicks place measure points instead
+    if measure.is_some_and(|measure| measure.active) {
+        events.clear();
+        return;
+    }
     for event in events.read() {
         info!("Select Event: {:?}", event.e);
         let entity = match query_parent.get(event.e) {
             Ok(a) => a.parent,
             Err(_) => event.e,
         };
+        if locked.contains(entity) {
+            continue;
+        }
         match event.event.button {
             PointerButton::Primary => {
-                commands.entity(entity).insert(Selected);
-                if !keyboard.pressed(KeyCode::ShiftLeft) {
-                    for e in query.iter() {
-                        commands.entity(e).remove::<Selected>();
-                    }
-                }
+                let mode = if keyboard.pressed(KeyCode::ShiftLeft) {
+                    SelectMode::Add
+                } else {
+                    SelectMode::Replace
+                };
+                commands.add(SelectEntities::new([entity], mode));
             }
             PointerButton::Secondary => { /*Show context menu?*/ }
             PointerButton::Middle => {}
@@ -85,21 +142,24 @@ pub fn select_listener(
     }
 }
 
+/// Delete all selected not [`Locked`] entities in one undo step.
+/// Ignored while egui text field has keyboard focus
 pub fn delete_selected(
     mut commands: Commands,
-    query: Query<Entity, With<Selected>>,
-    keyboard: Res<Input<KeyCode>>,
+    mut ctxs: EguiContexts,
+    query: Query<Entity, (With<Selected>, Without<Locked>)>,
+    hotkeys: Res<Input<SelectionHotkey>>,
 ) {
-    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
-    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
-    let delete = keyboard.just_pressed(KeyCode::Back) || keyboard.just_pressed(KeyCode::Delete);
-
-    if ctrl && shift && delete {
-        for entity in query.iter() {
-            info!("Delete Entity: {entity:?}");
-            commands.entity(entity).despawn_recursive();
-        }
+    if !hotkeys.just_pressed(SelectionHotkey::Delete) || ctxs.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let targets = query.iter().collect::<Vec<_>>();
+    if targets.is_empty() {
+        return;
     }
+    info!("Delete {} selected entities", targets.len());
+    commands.add(move |world: &mut World| request_delete(world, targets, false));
 }
 
 impl From<ListenerInput<Pointer<Down>>> for SelectEvent {
@@ -118,3 +178,382 @@ pub struct SelectEvent {
     e: Entity,
     event: ListenerInput<Pointer<Down>>,
 }
+
+/// Move [`Hovered`] marker to the entity of hierarchy row under pointer or to the closest prefab entity
+/// under pointer in game view. Marker is removed when pointer is over neither of them
+pub fn update_hovered(
+    mut commands: Commands,
+    hierarchy: Option<ResMut<HierarchyTabState>>,
+    game_view: Res<GameViewTab>,
+    egui_settings: Res<EguiSettings>,
+    hover_map: Res<HoverMap>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    select_parents: Query<&SelectParent>,
+    prefabs: Query<(), With<PrefabMarker>>,
+    hovered: Query<Entity, With<Hovered>>,
+) {
+    let from_hierarchy = hierarchy.and_then(|mut state| state.hovered_row.take());
+    let from_viewport = || {
+        let cursor = window.get_single().ok()?.cursor_position()?;
+        let scale = egui_settings.scale_factor as f32;
+        let viewport = game_view.viewport_rect?;
+        if !viewport.contains(egui::pos2(cursor.x / scale, cursor.y / scale)) {
+            return None;
+        }
+        hover_map
+            .get(&PointerId::Mouse)?
+            .iter()
+            .map(|(entity, hit)| {
+                let entity = select_parents
+                    .get(*entity)
+                    .map_or(*entity, |select_parent| select_parent.parent);
+                (entity, hit.depth)
+            })
+            .filter(|(entity, _)| prefabs.contains(*entity))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity)
+    };
+    let target = from_hierarchy.or_else(from_viewport);
+
+    for entity in hovered.iter() {
+        if Some(entity) != target {
+            commands.entity(entity).remove::<Hovered>();
+        }
+    }
+    if let Some(target) = target.filter(|target| !hovered.contains(*target)) {
+        if let Some(mut entity) = commands.get_entity(target) {
+            entity.insert(Hovered);
+        }
+    }
+}
+
+/// Outline bounds of [`Hovered`] entity in game view
+pub fn draw_hovered(
+    mut gizmos: Gizmos,
+    query: Query<(&GlobalTransform, Option<&Aabb>), With<Hovered>>,
+) {
+    for (transform, aabb) in query.iter() {
+        gizmos.cuboid(
+            entity_bounds(transform, aabb, 1.02),
+            Color::rgb(0.4, 0.8, 1.0),
+        );
+    }
+}
+
+/// Start box selection on press with [`SelectionHotkey::BoxSelect`] pressed on empty space of game view.
+/// Box is not started while transform gizmo is dragged: snap hotkey of gizmo is the same key
+pub fn start_box_select(
+    mut state: ResMut<BoxSelectState>,
+    game_view: Res<GameViewTab>,
+    egui_settings: Res<EguiSettings>,
+    hover_map: Res<HoverMap>,
+    mouse: Res<Input<MouseButton>>,
+    hotkeys: Res<Input<SelectionHotkey>>,
+    gizmo_drag: Option<Res<GizmoDragState>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if state.start.is_some() || gizmo_drag.is_some_and(|drag| drag.dragging) {
+        return;
+    }
+    let Some(cursor) = window.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Some(viewport) = game_view.viewport_rect else {
+        return;
+    };
+    let scale = egui_settings.scale_factor as f32;
+    let nothing_hovered = hover_map
+        .get(&PointerId::Mouse)
+        .map_or(true, |hovered| hovered.is_empty());
+    if mouse.just_pressed(MouseButton::Left)
+        && hotkeys.pressed(SelectionHotkey::BoxSelect)
+        && nothing_hovered
+        && viewport.contains(egui::pos2(cursor.x / scale, cursor.y / scale))
+    {
+        state.start = Some(cursor);
+    }
+}
+
+/// System to select all not [`Locked`] prefab entities, which screen position is inside dragged rectangle
+/// started by [`start_box_select`]. With shift pressed entities are added to selection, otherwise selection is replaced.
+/// Box is dropped without selection change if gizmo drag starts under it
+pub fn box_select(
+    mut commands: Commands,
+    mut state: ResMut<BoxSelectState>,
+    mut ctxs: EguiContexts,
+    mut camera_state: ResMut<EditorCameraEnabled>,
+    egui_settings: Res<EguiSettings>,
+    mouse: Res<Input<MouseButton>>,
+    keyboard: Res<Input<KeyCode>>,
+    gizmo_drag: Option<Res<GizmoDragState>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<EditorCameraMarker>>,
+    prefabs: Query<
+        (Entity, &GlobalTransform, Option<&Aabb>),
+        (With<PrefabMarker>, Without<Locked>),
+    >,
+) {
+    let Some(start) = state.start else {
+        return;
+    };
+    if gizmo_drag.is_some_and(|drag| drag.dragging) {
+        state.start = None;
+        return;
+    }
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let scale = egui_settings.scale_factor as f32;
+
+    let rect = Rect::from_corners(start, cursor);
+    camera_state.0 = false;
+
+    if mouse.pressed(MouseButton::Left) {
+        let painter = ctxs.ctx_mut().layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("box_select"),
+        ));
+        let egui_rect = egui::Rect::from_min_max(
+            egui::pos2(rect.min.x / scale, rect.min.y / scale),
+            egui::pos2(rect.max.x / scale, rect.max.y / scale),
+        );
+        painter.rect(
+            egui_rect,
+            0.0,
+            egui::Color32::from_rgba_unmultiplied(100, 150, 255, 30),
+            egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
+        );
+        return;
+    }
+
+    state.start = None;
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let viewport_min = camera
+        .logical_viewport_rect()
+        .map_or(Vec2::ZERO, |viewport| viewport.min);
+
+    let mode = if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        SelectMode::Add
+    } else {
+        SelectMode::Replace
+    };
+
+    // Box without area works as click on empty space: only deselect
+    if rect.width() < 1.0 || rect.height() < 1.0 {
+        commands.add(SelectEntities::new([], mode));
+        return;
+    }
+
+    let mut hits = vec![];
+    for (e, transform, aabb) in prefabs.iter() {
+        let Some(screen_rect) = screen_space_rect(camera, camera_transform, transform, aabb) else {
+            continue;
+        };
+        let screen_rect = Rect::from_corners(
+            screen_rect.min + viewport_min,
+            screen_rect.max + viewport_min,
+        );
+        if !rect.intersect(screen_rect).is_empty() || rect.contains(screen_rect.min) {
+            hits.push(e);
+        }
+    }
+    commands.add(SelectEntities::new(hits, mode));
+}
+
+/// Max cursor movement in logical pixels between press and release, which is still a click.
+/// Longer drags rotate camera and do not change selection
+const CLICK_MAX_DISTANCE: f32 = 4.0;
+
+/// Click in game view on place without pickable mesh. Selects the closest not [`Locked`] prefab entity,
+/// which bounds are hit by ray from cursor (for example, entities without meshes).
+/// Click on empty space clears selection. With shift pressed entity is added to selection
+pub fn click_select(
+    mut commands: Commands,
+    mut press: Local<Option<Vec2>>,
+    camera_state: Res<EditorCameraEnabled>,
+    box_state: Res<BoxSelectState>,
+    measure: Option<Res<MeasureState>>,
+    game_view: Res<GameViewTab>,
+    egui_settings: Res<EguiSettings>,
+    hover_map: Res<HoverMap>,
+    mouse: Res<Input<MouseButton>>,
+    keyboard: Res<Input<KeyCode>>,
+    hotkeys: Res<Input<SelectionHotkey>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<EditorCameraMarker>>,
+    prefabs: Query<(Entity, &GlobalTransform, &Aabb), (With<PrefabMarker>, Without<Locked>)>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        let scale = egui_settings.scale_factor as f32;
+        let in_viewport = game_view.viewport_rect.is_some_and(|viewport| {
+            viewport.contains(egui::pos2(cursor.x / scale, cursor.y / scale))
+        });
+        let nothing_hovered = hover_map
+            .get(&PointerId::Mouse)
+            .map_or(true, |hovered| hovered.is_empty());
+        let measuring = measure.is_some_and(|measure| measure.active);
+        *press = (in_viewport
+            && nothing_hovered
+            && !measuring
+            && camera_state.0
+            && !hotkeys.pressed(SelectionHotkey::BoxSelect))
+        .then_some(cursor);
+        return;
+    }
+
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(start) = press.take() else {
+        return;
+    };
+    // Gizmo or box selection used this click
+    if start.distance(cursor) > CLICK_MAX_DISTANCE || !camera_state.0 || box_state.start.is_some() {
+        return;
+    }
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let viewport_min = camera
+        .logical_viewport_rect()
+        .map_or(Vec2::ZERO, |viewport| viewport.min);
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor - viewport_min) else {
+        return;
+    };
+
+    let closest = prefabs
+        .iter()
+        .filter_map(|(e, transform, aabb)| {
+            ray_aabb_distance(ray.origin, ray.direction, transform, aabb).map(|dist| (e, dist))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(e, _)| e);
+
+    let add = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    match (closest, add) {
+        (Some(e), true) => commands.add(SelectEntities::new([e], SelectMode::Add)),
+        (Some(e), false) => commands.add(SelectEntities::new([e], SelectMode::Replace)),
+        (None, true) => {}
+        (None, false) => commands.add(SelectEntities::new([], SelectMode::Replace)),
+    }
+}
+
+/// Distance along world ray to the oriented [`Aabb`] of entity, if ray hits it
+fn ray_aabb_distance(
+    origin: Vec3,
+    direction: Vec3,
+    transform: &GlobalTransform,
+    aabb: &Aabb,
+) -> Option<f32> {
+    // Ray is moved to entity local space, so ray parameter stays the world distance
+    let inverse = transform.affine().inverse();
+    let local_origin = inverse.transform_point3(origin);
+    let local_direction = inverse.transform_vector3(direction);
+    let min = Vec3::from(aabb.min());
+    let max = Vec3::from(aabb.max());
+
+    let mut near = f32::NEG_INFINITY;
+    let mut far = f32::INFINITY;
+    for axis in 0..3 {
+        if local_direction[axis].abs() < f32::EPSILON {
+            if local_origin[axis] < min[axis] || local_origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - local_origin[axis]) / local_direction[axis];
+        let t2 = (max[axis] - local_origin[axis]) / local_direction[axis];
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+
+    (near <= far && far >= 0.0).then_some(near.max(0.0))
+}
+
+/// Screen space bounds of entity [`Aabb`] corners in camera viewport coordinates.
+/// Entities without [`Aabb`] are represented by their origin
+fn screen_space_rect(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    transform: &GlobalTransform,
+    aabb: Option<&Aabb>,
+) -> Option<Rect> {
+    let Some(aabb) = aabb else {
+        let pos = camera.world_to_viewport(camera_transform, transform.translation())?;
+        return Some(Rect::from_corners(pos, pos));
+    };
+
+    let center = Vec3::from(aabb.center);
+    let half = Vec3::from(aabb.half_extents);
+    let mut result: Option<Rect> = None;
+    for corner in 0..8 {
+        let sign = Vec3::new(
+            if corner & 1 == 0 { -1.0 } else { 1.0 },
+            if corner & 2 == 0 { -1.0 } else { 1.0 },
+            if corner & 4 == 0 { -1.0 } else { 1.0 },
+        );
+        let world = transform.transform_point(center + half * sign);
+        let Some(pos) = camera.world_to_viewport(camera_transform, world) else {
+            continue;
+        };
+        result = Some(result.map_or(Rect::from_corners(pos, pos), |rect| rect.union_point(pos)));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ctrl_drag_on_gizmo_does_not_start_box_select() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<BoxSelectState>()
+            .init_resource::<GameViewTab>()
+            .init_resource::<EguiSettings>()
+            .init_resource::<HoverMap>()
+            .init_resource::<Input<MouseButton>>()
+            .init_resource::<Input<SelectionHotkey>>()
+            .init_resource::<GizmoDragState>()
+            .add_systems(Update, start_box_select);
+        app.world.resource_mut::<GameViewTab>().viewport_rect = Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(800.0, 600.0),
+        ));
+        let mut window = Window::default();
+        window.set_cursor_position(Some(Vec2::new(100.0, 100.0)));
+        app.world.spawn((window, PrimaryWindow));
+
+        // Ctrl+press which started snapped gizmo drag
+        app.world.resource_mut::<GizmoDragState>().dragging = true;
+        app.world
+            .resource_mut::<Input<SelectionHotkey>>()
+            .press(SelectionHotkey::BoxSelect);
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        assert!(app.world.resource::<BoxSelectState>().start.is_none());
+
+        // The same press on empty space starts box selection
+        app.world.resource_mut::<GizmoDragState>().dragging = false;
+        let mut mouse = app.world.resource_mut::<Input<MouseButton>>();
+        mouse.reset(MouseButton::Left);
+        mouse.press(MouseButton::Left);
+        app.update();
+        assert!(app.world.resource::<BoxSelectState>().start.is_some());
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use bevy_mod_outline::{
+    ComputedOutlineDepth, OutlineBundle, OutlineMode, OutlinePlugin, OutlineStencil, OutlineVolume,
+};
+use space_editor_core::prelude::*;
+use space_shared::*;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+/// Plugin to outline meshes of selected entities in editor view
+pub struct SelectionOutlinePlugin;
+
+impl Plugin for SelectionOutlinePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<OutlinePlugin>() {
+            app.add_plugins(OutlinePlugin);
+        }
+        app.init_resource::<SelectionOutlineSettings>();
+        app.register_type::<SelectionOutlineSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<SelectionOutlineSettings>();
+        }
+
+        app.add_systems(Update, update_selection_outline.in_set(EditorSet::Editor));
+        app.add_systems(OnEnter(EditorState::GamePrepare), remove_selection_outline);
+    }
+}
+
+/// Settings of selection outline. Color can be changed to keep outline visible on different backgrounds
+#[derive(Resource, Reflect, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct SelectionOutlineSettings {
+    pub enabled: bool,
+    pub color: Color,
+    /// Outline width in logical pixels
+    pub width: f32,
+}
+
+impl Default for SelectionOutlineSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            color: Color::rgb(1.0, 0.6, 0.1),
+            width: 3.0,
+        }
+    }
+}
+
+impl SelectionOutlineSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Selection outline");
+        ui.checkbox(&mut self.enabled, "Outline selected meshes");
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                let mut color = self.color.as_rgba_f32();
+                if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                    self.color = Color::rgba(color[0], color[1], color[2], color[3]);
+                }
+            });
+            ui.add(
+                egui::DragValue::new(&mut self.width)
+                    .clamp_range(0.5..=20.0)
+                    .speed(0.1)
+                    .suffix(" px")
+                    .prefix("Width: "),
+            );
+        });
+        ui.spacing();
+        ui.separator();
+    }
+
+    fn volume(&self) -> OutlineVolume {
+        OutlineVolume {
+            visible: true,
+            width: self.width,
+            colour: self.color,
+        }
+    }
+}
+
+/// Editor only marker of meshes outlined by selection.
+/// Outline components are not reflected, so they are never saved to prefab and materials are not changed.
+/// Mesh with its own outline keeps it in `previous`, so only outline inserted by editor is removed
+#[derive(Component, Default, Clone)]
+pub struct SelectionOutline {
+    previous: Option<OutlineVolume>,
+}
+
+/// Restore outline of mesh before selection or remove outline components inserted by editor
+fn remove_outline(commands: &mut Commands, entity: Entity, outline: &SelectionOutline) {
+    let mut entity = commands.entity(entity);
+    entity.remove::<SelectionOutline>();
+    if let Some(previous) = &outline.previous {
+        entity.insert(previous.clone());
+    } else {
+        entity.remove::<(
+            OutlineVolume,
+            OutlineStencil,
+            ComputedOutlineDepth,
+            OutlineMode,
+        )>();
+    }
+}
+
+/// Outline meshes of selected entities and their descendants, remove outline from deselected ones
+fn update_selection_outline(
+    mut commands: Commands,
+    settings: Res<SelectionOutlineSettings>,
+    selected: Query<(), With<Selected>>,
+    parents: Query<&Parent>,
+    meshes: Query<
+        (Entity, Option<&OutlineVolume>),
+        (With<Handle<Mesh>>, Without<SelectionOutline>),
+    >,
+    mut outlined: Query<(Entity, &SelectionOutline, &mut OutlineVolume)>,
+) {
+    let is_selected = |entity: Entity| {
+        selected.contains(entity)
+            || parents
+                .iter_ancestors(entity)
+                .any(|ancestor| selected.contains(ancestor))
+    };
+
+    let volume = settings.volume();
+    for (entity, marker, mut outline) in outlined.iter_mut() {
+        if settings.enabled && is_selected(entity) {
+            // Compared to not trigger change detection each frame
+            if outline.colour != volume.colour || outline.width != volume.width {
+                *outline = volume.clone();
+            }
+        } else {
+            remove_outline(&mut commands, entity, marker);
+        }
+    }
+
+    if !settings.enabled || selected.is_empty() {
+        return;
+    }
+    for (entity, previous) in meshes.iter() {
+        if !is_selected(entity) {
+            continue;
+        }
+        if let Some(previous) = previous {
+            // Stencil and mode of own outline are kept, only its volume is replaced while selected
+            commands.entity(entity).insert((
+                SelectionOutline {
+                    previous: Some(previous.clone()),
+                },
+                volume.clone(),
+            ));
+        } else {
+            commands.entity(entity).insert((
+                SelectionOutline::default(),
+                OutlineBundle {
+                    outline: volume.clone(),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+/// Outline is editor view effect, so it is not kept in play mode
+fn remove_selection_outline(mut commands: Commands, outlined: Query<(Entity, &SelectionOutline)>) {
+    for (entity, outline) in outlined.iter() {
+        remove_outline(&mut commands, entity, outline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn test_deselect_keeps_own_outline() {
+        let mut world = World::new();
+        world.init_resource::<SelectionOutlineSettings>();
+        let own = OutlineVolume {
+            visible: true,
+            width: 1.0,
+            colour: Color::BLUE,
+        };
+        let outlined = world
+            .spawn((Handle::<Mesh>::default(), own.clone(), Selected))
+            .id();
+        let plain = world.spawn((Handle::<Mesh>::default(), Selected)).id();
+
+        world.run_system_once(update_selection_outline);
+        let selection_colour = world.resource::<SelectionOutlineSettings>().color;
+        for entity in [outlined, plain] {
+            assert!(world.get::<SelectionOutline>(entity).is_some());
+            assert_eq!(
+                world.get::<OutlineVolume>(entity).unwrap().colour,
+                selection_colour
+            );
+        }
+
+        world.entity_mut(outlined).remove::<Selected>();
+        world.entity_mut(plain).remove::<Selected>();
+        world.run_system_once(update_selection_outline);
+        assert!(world.get::<SelectionOutline>(outlined).is_none());
+        let restored = world.get::<OutlineVolume>(outlined).unwrap();
+        assert_eq!(restored.colour, own.colour);
+        assert_eq!(restored.width, own.width);
+        assert!(world.get::<OutlineVolume>(plain).is_none());
+        assert!(world.get::<OutlineStencil>(plain).is_none());
+
+        // Play mode removes only selection outline too
+        world.entity_mut(outlined).insert(Selected);
+        world.run_system_once(update_selection_outline);
+        world.run_system_once(remove_selection_outline);
+        assert!(world.get::<SelectionOutline>(outlined).is_none());
+        assert_eq!(
+            world.get::<OutlineVolume>(outlined).unwrap().colour,
+            own.colour
+        );
+    }
+}
@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use bevy::{
     prelude::*,
     utils::{HashMap, HashSet},
+    winit::{UpdateMode, WinitSettings},
 };
 use bevy_egui::*;
 use space_editor_core::hotkeys::AllHotkeys;
@@ -10,7 +13,13 @@ use space_undo::ChangeChainSettings;
 use space_persistence::*;
 
 use super::{
+    autosave::AutosaveSettings,
+    camera_plugin::EditorCameraSettings,
     editor_tab::{EditorTab, EditorTabName},
+    hierarchy::{HierarchyTag, HIERARCHY_TAGS},
+    inspector::depth_limit::InspectorSettings,
+    selection_outline::SelectionOutlineSettings,
+    tools::gizmo::GizmoSettings,
     EditorUiAppExt,
 };
 
@@ -29,6 +38,22 @@ impl Plugin for SettingsWindowPlugin {
         app.editor_tab_by_trait(EditorTabName::Settings, SettingsWindow::default());
         app.register_type::<GameMode>()
             .init_resource::<GameModeSettings>();
+        app.init_resource::<UnfocusedRenderSettings>();
+        app.register_type::<PlayModeSettings>()
+            .init_resource::<PlayModeSettings>();
+        app.register_type::<DisplayUnit>()
+            .init_resource::<UnitsSettings>();
+        app.init_resource::<AutoNameSettings>();
+        app.init_resource::<DuplicateSettings>();
+        app.register_type::<SpawnPlacement>()
+            .init_resource::<SpawnSettings>();
+        app.register_type::<EntityIdDisplay>()
+            .register_type::<HierarchyDoubleClick>()
+            .init_resource::<HierarchySettings>();
+        app.add_systems(
+            Update,
+            apply_unfocused_render_settings.run_if(resource_changed::<UnfocusedRenderSettings>()),
+        );
         #[cfg(feature = "persistence_editor")]
         {
             app.persistence_resource::<NewWindowSettings>()
@@ -36,6 +61,13 @@ impl Plugin for SettingsWindowPlugin {
                 .init_resource::<NewWindowSettings>();
             app.persistence_resource::<ChangeChainSettings>();
             app.persistence_resource::<GameModeSettings>();
+            app.persistence_resource::<UnfocusedRenderSettings>();
+            app.persistence_resource::<PlayModeSettings>();
+            app.persistence_resource::<UnitsSettings>();
+            app.persistence_resource::<AutoNameSettings>();
+            app.persistence_resource::<DuplicateSettings>();
+            app.persistence_resource::<SpawnSettings>();
+            app.persistence_resource::<HierarchySettings>();
         }
 
         // #[cfg(feature = "bevy_xpbd_3d")]
@@ -106,6 +138,528 @@ impl GameModeSettings {
     }
 }
 
+/// Settings of play-in-editor. Scene is stored to memory before play mode starts
+#[derive(Resource, Reflect, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct PlayModeSettings {
+    /// Restore the stored scene on stop, so changes made by game logic are discarded.
+    /// When disabled, runtime state of entities is kept for editing. Scenes spawned by `PlayerStart` are removed anyway,
+    /// other entities spawned by game systems without `PrefabMarker` are kept
+    pub restore_on_stop: bool,
+}
+
+impl Default for PlayModeSettings {
+    fn default() -> Self {
+        Self {
+            restore_on_stop: true,
+        }
+    }
+}
+
+impl PlayModeSettings {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Play mode");
+        ui.checkbox(&mut self.restore_on_stop, "Restore scene on stop")
+            .on_hover_text("Discard changes made while playing. Disable to keep runtime changes");
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+const DISPLAY_UNITS: [DisplayUnit; 3] = [
+    DisplayUnit::Meters,
+    DisplayUnit::Centimeters,
+    DisplayUnit::Custom,
+];
+
+/// Unit in which distances are shown in editor ui
+#[derive(Default, Reflect, PartialEq, Eq, Clone, Copy)]
+pub enum DisplayUnit {
+    /// 1 bevy unit is 1 meter
+    #[default]
+    Meters,
+    /// 1 bevy unit is 100 centimeters
+    Centimeters,
+    /// Custom conversion factor from [`UnitsSettings`]
+    Custom,
+}
+
+impl ToString for DisplayUnit {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Meters => "Meters",
+            Self::Centimeters => "Centimeters",
+            Self::Custom => "Custom",
+        }
+        .to_string()
+    }
+}
+
+/// Display units for transform values and measurements.
+/// Only ui values are converted, underlying data stays in bevy units
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct UnitsSettings {
+    pub unit: DisplayUnit,
+    /// How many custom units are in one bevy unit
+    pub custom_factor: f32,
+    pub custom_suffix: String,
+}
+
+impl Default for UnitsSettings {
+    fn default() -> Self {
+        Self {
+            unit: DisplayUnit::Meters,
+            custom_factor: 1.0,
+            custom_suffix: "u".to_string(),
+        }
+    }
+}
+
+impl UnitsSettings {
+    /// How many display units are in one bevy unit
+    pub fn factor(&self) -> f32 {
+        match self.unit {
+            DisplayUnit::Meters => 1.0,
+            DisplayUnit::Centimeters => 100.0,
+            DisplayUnit::Custom => self.custom_factor,
+        }
+    }
+
+    pub fn suffix(&self) -> &str {
+        match self.unit {
+            DisplayUnit::Meters => " m",
+            DisplayUnit::Centimeters => " cm",
+            DisplayUnit::Custom => &self.custom_suffix,
+        }
+    }
+
+    /// Convert bevy units to display units
+    pub fn to_display(&self, value: f32) -> f32 {
+        value * self.factor()
+    }
+
+    /// Convert display units back to bevy units
+    pub fn from_display(&self, value: f32) -> f32 {
+        if self.factor() == 0.0 {
+            value
+        } else {
+            value / self.factor()
+        }
+    }
+
+    /// Bevy units value as display units text with suffix
+    pub fn format(&self, value: f32) -> String {
+        format!("{:.3}{}", self.to_display(value), self.suffix())
+    }
+
+    /// Drag value which shows bevy units value in display units
+    pub fn drag_value(&self, ui: &mut egui::Ui, value: &mut f32) -> egui::Response {
+        let mut display = self.to_display(*value);
+        let response = ui.add(
+            egui::DragValue::new(&mut display)
+                .speed(0.1)
+                .suffix(self.suffix()),
+        );
+        if response.changed() {
+            *value = self.from_display(display);
+        }
+        response
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Units");
+        ui.horizontal(|ui| {
+            ui.label("Display unit:");
+            egui::ComboBox::new("display_unit", "")
+                .selected_text(self.unit.to_string())
+                .show_ui(ui, |ui| {
+                    for unit in DISPLAY_UNITS.into_iter() {
+                        ui.selectable_value(&mut self.unit, unit, unit.to_string());
+                    }
+                });
+        });
+        if self.unit == DisplayUnit::Custom {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut self.custom_factor)
+                        .clamp_range(0.0001..=f32::MAX)
+                        .prefix("Units per bevy unit: "),
+                );
+                ui.label("Suffix:");
+                ui.text_edit_singleline(&mut self.custom_suffix);
+            });
+        }
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+const ENTITY_ID_DISPLAYS: [EntityIdDisplay; 3] = [
+    EntityIdDisplay::Full,
+    EntityIdDisplay::Index,
+    EntityIdDisplay::Hidden,
+];
+
+/// How entity id is shown next to entity name in hierarchy
+#[derive(Default, Reflect, PartialEq, Eq, Clone, Copy)]
+pub enum EntityIdDisplay {
+    /// Debug format like `Name (12v3)`
+    #[default]
+    Full,
+    /// Only index like `Name (12)`
+    Index,
+    /// Only name. Unnamed entities are shown with index
+    Hidden,
+}
+
+impl ToString for EntityIdDisplay {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Full => "Full",
+            Self::Index => "Index only",
+            Self::Hidden => "Hidden",
+        }
+        .to_string()
+    }
+}
+
+impl EntityIdDisplay {
+    /// Format entity label with respect to id display mode
+    pub fn format(&self, name: Option<&Name>, entity: Entity) -> String {
+        match (self, name) {
+            (Self::Full, Some(name)) => format!("{} ({:?})", name.as_str(), entity),
+            (Self::Full, None) => format!("Entity ({:?})", entity),
+            (Self::Index, Some(name)) => format!("{} ({})", name.as_str(), entity.index()),
+            (Self::Hidden, Some(name)) => name.as_str().to_string(),
+            (Self::Index | Self::Hidden, None) => format!("Entity ({})", entity.index()),
+        }
+    }
+}
+
+const HIERARCHY_DOUBLE_CLICKS: [HierarchyDoubleClick; 2] =
+    [HierarchyDoubleClick::Rename, HierarchyDoubleClick::Focus];
+
+/// Action of double click on hierarchy row. Alt + double click runs the other action
+#[derive(Default, Reflect, PartialEq, Eq, Clone, Copy)]
+pub enum HierarchyDoubleClick {
+    /// Start inline rename of entity
+    #[default]
+    Rename,
+    /// Frame entity with editor camera
+    Focus,
+}
+
+impl HierarchyDoubleClick {
+    /// Action of double click with Alt pressed
+    pub fn alternative(self) -> Self {
+        match self {
+            Self::Rename => Self::Focus,
+            Self::Focus => Self::Rename,
+        }
+    }
+}
+
+impl ToString for HierarchyDoubleClick {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Rename => "Rename",
+            Self::Focus => "Focus camera",
+        }
+        .to_string()
+    }
+}
+
+/// Limits of [`HierarchySettings::row_scale`]
+pub const HIERARCHY_ROW_SCALE: std::ops::RangeInclusive<f32> = 0.5..=2.5;
+
+/// Hierarchy tab display settings
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct HierarchySettings {
+    pub id_display: EntityIdDisplay,
+    /// Hiding entity in editor also hides its children
+    pub cascade_hide: bool,
+    /// User names of [`HierarchyTag`] colors shown in tag legend
+    pub tag_names: Vec<String>,
+    pub double_click: HierarchyDoubleClick,
+    /// Save selection, expanded nodes, pins and tags to `*.editor.ron` file next to saved scene
+    pub store_scene_state: bool,
+    /// Show count of children after name of entity. With active filter it is shown as `matched/total`
+    pub show_child_count: bool,
+    /// "Attach to" and drag reparent recompute local [`Transform`] from [`GlobalTransform`], so entity stays in place.
+    /// Otherwise local transform is kept and entity moves with new parent
+    pub keep_world_transform: bool,
+    /// Grid step in parent space used by Alt + "Attach to"/drop. Zero places entity exactly at parent origin
+    pub parent_snap_step: f32,
+    /// Scale of hierarchy row text and indentation. Changed by Ctrl+Scroll over hierarchy tree
+    pub row_scale: f32,
+}
+
+impl Default for HierarchySettings {
+    fn default() -> Self {
+        Self {
+            id_display: EntityIdDisplay::default(),
+            cascade_hide: true,
+            tag_names: HIERARCHY_TAGS.iter().map(ToString::to_string).collect(),
+            double_click: HierarchyDoubleClick::default(),
+            store_scene_state: true,
+            show_child_count: true,
+            keep_world_transform: true,
+            parent_snap_step: 0.0,
+            row_scale: 1.0,
+        }
+    }
+}
+
+impl HierarchySettings {
+    /// Name of tag from legend, or its color name if it was not set
+    pub fn tag_name(&self, tag: HierarchyTag) -> String {
+        self.tag_names
+            .get(tag as usize)
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| tag.to_string())
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Hierarchy");
+        ui.horizontal(|ui| {
+            ui.label("Entity id:");
+            egui::ComboBox::new("entity_id_display", "")
+                .selected_text(self.id_display.to_string())
+                .show_ui(ui, |ui| {
+                    for display in ENTITY_ID_DISPLAYS.into_iter() {
+                        ui.selectable_value(&mut self.id_display, display, display.to_string());
+                    }
+                });
+        });
+        ui.checkbox(&mut self.cascade_hide, "Hide children with parent");
+        ui.checkbox(&mut self.show_child_count, "Show children count");
+        ui.horizontal(|ui| {
+            ui.label("Row size:");
+            ui.add(egui::Slider::new(&mut self.row_scale, HIERARCHY_ROW_SCALE))
+                .on_hover_text("Ctrl+Scroll over hierarchy tree also changes it");
+        });
+        ui.checkbox(
+            &mut self.keep_world_transform,
+            "Keep world transform when reparenting",
+        );
+        ui.horizontal(|ui| {
+            ui.label("Snap to parent step:");
+            ui.add(
+                egui::DragValue::new(&mut self.parent_snap_step)
+                    .speed(0.05)
+                    .clamp_range(0.0..=f32::MAX),
+            )
+            .on_hover_text("Used when attaching with Alt held. 0 places entity at parent origin");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Double click:");
+            egui::ComboBox::new("hierarchy_double_click", "")
+                .selected_text(self.double_click.to_string())
+                .show_ui(ui, |ui| {
+                    for action in HIERARCHY_DOUBLE_CLICKS.into_iter() {
+                        ui.selectable_value(&mut self.double_click, action, action.to_string());
+                    }
+                });
+            ui.label(format!(
+                "(Alt: {})",
+                self.double_click.alternative().to_string()
+            ));
+        });
+        ui.checkbox(
+            &mut self.store_scene_state,
+            "Restore selection and expanded nodes with scene",
+        );
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+/// Automatic naming of new empty entities, spawned from hierarchy
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct AutoNameSettings {
+    pub enabled: bool,
+    /// Name of new entity. Numeric suffix will be added to avoid collisions
+    pub base_name: String,
+}
+
+impl Default for AutoNameSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            base_name: "Empty".to_string(),
+        }
+    }
+}
+
+impl AutoNameSettings {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("New entities");
+        ui.checkbox(&mut self.enabled, "Auto name new entities");
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Base name:");
+                ui.text_edit_singleline(&mut self.base_name);
+            });
+        });
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+/// Duplicate (Ctrl+D) behaviour
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DuplicateSettings {
+    /// Translation offset of duplicated entity from original
+    pub offset: Vec3,
+}
+
+impl Default for DuplicateSettings {
+    fn default() -> Self {
+        Self {
+            offset: Vec3::new(1.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl DuplicateSettings {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Duplicate");
+        ui.horizontal(|ui| {
+            ui.label("Offset:");
+            ui.add(
+                egui::DragValue::new(&mut self.offset.x)
+                    .speed(0.1)
+                    .prefix("x: "),
+            );
+            ui.add(
+                egui::DragValue::new(&mut self.offset.y)
+                    .speed(0.1)
+                    .prefix("y: "),
+            );
+            ui.add(
+                egui::DragValue::new(&mut self.offset.z)
+                    .speed(0.1)
+                    .prefix("z: "),
+            );
+        });
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+const SPAWN_PLACEMENTS: [SpawnPlacement; 3] = [
+    SpawnPlacement::Origin,
+    SpawnPlacement::CameraFocus,
+    SpawnPlacement::CursorGround,
+];
+
+/// Where bundles spawned from hierarchy are placed
+#[derive(Default, Reflect, PartialEq, Eq, Clone, Copy)]
+pub enum SpawnPlacement {
+    /// Keep default transform of bundle
+    Origin,
+    /// Focus point of editor camera
+    #[default]
+    CameraFocus,
+    /// Point on ground plane (y = 0) under the last cursor position in game view
+    CursorGround,
+}
+
+impl ToString for SpawnPlacement {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Origin => "Origin",
+            Self::CameraFocus => "Camera focus",
+            Self::CursorGround => "Ground under cursor",
+        }
+        .to_string()
+    }
+}
+
+/// Placement of bundles spawned from hierarchy
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct SpawnSettings {
+    pub placement: SpawnPlacement,
+}
+
+impl SpawnSettings {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Spawn");
+        ui.horizontal(|ui| {
+            ui.label("Place new bundles at:");
+            egui::ComboBox::new("spawn_placement", "")
+                .selected_text(self.placement.to_string())
+                .show_ui(ui, |ui| {
+                    for placement in SPAWN_PLACEMENTS {
+                        ui.selectable_value(&mut self.placement, placement, placement.to_string());
+                    }
+                });
+        });
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+/// Determine whether editor and camera view keep rendering when window lost focus
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct UnfocusedRenderSettings {
+    /// Keep render loop active in unfocused window (useful for multi-monitor workflows)
+    pub keep_rendering: bool,
+    /// Update rate of unfocused window if `keep_rendering` is enabled
+    pub unfocused_fps: f32,
+}
+
+impl Default for UnfocusedRenderSettings {
+    fn default() -> Self {
+        Self {
+            keep_rendering: false,
+            unfocused_fps: 30.0,
+        }
+    }
+}
+
+impl UnfocusedRenderSettings {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Unfocused window");
+        ui.checkbox(&mut self.keep_rendering, "Keep rendering when unfocused");
+        ui.add_enabled(
+            self.keep_rendering,
+            egui::DragValue::new(&mut self.unfocused_fps)
+                .clamp_range(1.0..=240.0)
+                .prefix("Unfocused FPS: "),
+        );
+        ui.spacing();
+        ui.separator();
+    }
+}
+
+fn apply_unfocused_render_settings(
+    mut default_mode: Local<Option<UpdateMode>>,
+    settings: Res<UnfocusedRenderSettings>,
+    winit_settings: Option<ResMut<WinitSettings>>,
+) {
+    let Some(mut winit_settings) = winit_settings else {
+        return;
+    };
+    // Remember app unfocused mode to restore it when setting is disabled
+    let default_mode = *default_mode.get_or_insert(winit_settings.unfocused_mode);
+
+    winit_settings.unfocused_mode = if settings.keep_rendering {
+        UpdateMode::Reactive {
+            wait: Duration::from_secs_f32(1.0 / settings.unfocused_fps.max(1.0)),
+        }
+    } else {
+        default_mode
+    };
+}
+
 #[derive(Default, Reflect, PartialEq, Eq, Clone)]
 pub enum NewTabBehaviour {
     Pop,
@@ -187,10 +741,54 @@ impl EditorTab for SettingsWindow {
         game_mode_setting.ui(ui);
         ui.spacing();
 
+        world.resource_mut::<PlayModeSettings>().ui(ui);
+        ui.spacing();
+
+        world.resource_mut::<UnfocusedRenderSettings>().ui(ui);
+        ui.spacing();
+
+        world.resource_mut::<UnitsSettings>().ui(ui);
+        ui.spacing();
+
+        world.resource_mut::<EditorCameraSettings>().ui(ui);
+        ui.spacing();
+
+        let units = world.resource::<UnitsSettings>().clone();
+        world.resource_mut::<GizmoSettings>().ui(ui, &units);
+        ui.spacing();
+
+        world.resource_mut::<AutoNameSettings>().ui(ui);
+        ui.spacing();
+
+        world.resource_mut::<DuplicateSettings>().ui(ui);
+        ui.spacing();
+
+        world.resource_mut::<SpawnSettings>().ui(ui);
+        ui.spacing();
+
+        world.resource_mut::<HierarchySettings>().ui(ui);
+        ui.spacing();
+
+        if world.contains_resource::<SelectionOutlineSettings>() {
+            world.resource_mut::<SelectionOutlineSettings>().ui(ui);
+            ui.spacing();
+        }
+
+        if world.contains_resource::<InspectorSettings>() {
+            world.resource_mut::<InspectorSettings>().ui(ui);
+            ui.spacing();
+        }
+
+        if world.contains_resource::<AutosaveSettings>() {
+            world.resource_mut::<AutosaveSettings>().ui(ui);
+            ui.spacing();
+        }
+
         ui.heading("Undo");
         world.resource_scope::<ChangeChainSettings, _>(|_world, mut settings| {
             ui.add(
                 egui::DragValue::new(&mut settings.max_change_chain_size)
+                    .clamp_range(1..=10000)
                     .prefix("Max change chain size: "),
             );
         });
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use bevy_egui::egui;
+use space_shared::EditorSet;
+
+use super::{
+    command_palette::tab_label,
+    editor_tab::{EditorTab, EditorTabName},
+    EditorUiAppExt,
+};
+
+/// Toggle name of editor viewport gizmos (cameras and lights)
+pub const VIEWPORT_GIZMOS: ToggleSet = ToggleSet("Viewport gizmos");
+
+const GAME_LOGIC: &str = "Game logic";
+
+/// Debug control surface to disable editor system sets and tab redraw for the session.
+/// Nothing is removed from the app, so every toggle can be enabled back
+pub struct SystemTogglesPlugin;
+
+impl Plugin for SystemTogglesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorSystemToggles>();
+        app.editor_tab_by_trait(
+            EditorTabName::Other("Systems".to_string()),
+            SystemTogglesTab,
+        );
+
+        app.editor_system_toggle(GAME_LOGIC, PreUpdate, EditorSet::Game)
+            .editor_system_toggle(GAME_LOGIC, Update, EditorSet::Game)
+            .editor_system_toggle(GAME_LOGIC, PostUpdate, EditorSet::Game)
+            .editor_system_toggle(VIEWPORT_GIZMOS.0, Update, VIEWPORT_GIZMOS);
+    }
+}
+
+/// System set, which can be used for systems without own set to make them toggleable
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ToggleSet(pub &'static str);
+
+/// Runtime state of toggles. It is not persisted, all toggles are enabled on start
+#[derive(Resource, Default)]
+pub struct EditorSystemToggles {
+    /// Toggle names with enabled flag in registration order
+    pub sets: Vec<(&'static str, bool)>,
+    /// Tabs which are not redrawn
+    pub disabled_tabs: BTreeSet<EditorTabName>,
+    /// Tabs drawn at least once in this session
+    pub drawn_tabs: BTreeSet<EditorTabName>,
+}
+
+impl EditorSystemToggles {
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.sets
+            .iter()
+            .find(|(set, _)| *set == name)
+            .map_or(true, |(_, enabled)| *enabled)
+    }
+}
+
+/// Run condition of toggleable set
+pub fn toggle_enabled(
+    name: &'static str,
+) -> impl Fn(Option<Res<EditorSystemToggles>>) -> bool + Clone {
+    move |toggles| toggles.map_or(true, |toggles| toggles.is_enabled(name))
+}
+
+pub trait SystemToggleAppExt {
+    /// Show checkbox `name` in "Systems" tab, which enables or disables `set` in `schedule`.
+    /// Same name can be used for several sets and schedules
+    fn editor_system_toggle(
+        &mut self,
+        name: &'static str,
+        schedule: impl ScheduleLabel,
+        set: impl SystemSet,
+    ) -> &mut Self;
+}
+
+impl SystemToggleAppExt for App {
+    fn editor_system_toggle(
+        &mut self,
+        name: &'static str,
+        schedule: impl ScheduleLabel,
+        set: impl SystemSet,
+    ) -> &mut Self {
+        let mut toggles = self
+            .world
+            .get_resource_or_insert_with(EditorSystemToggles::default);
+        if !toggles.sets.iter().any(|(set, _)| *set == name) {
+            toggles.sets.push((name, true));
+        }
+        self.configure_sets(schedule, set.run_if(toggle_enabled(name)))
+    }
+}
+
+/// Tab with checkboxes for registered system toggles and redraw of drawn tabs
+pub struct SystemTogglesTab;
+
+impl EditorTab for SystemTogglesTab {
+    fn ui(&mut self, ui: &mut egui::Ui, _commands: &mut Commands, world: &mut World) {
+        let own_name = EditorTabName::Other("Systems".to_string());
+        let mut toggles = world.resource_mut::<EditorSystemToggles>();
+
+        ui.heading("System sets");
+        for (name, enabled) in toggles.sets.iter_mut() {
+            ui.checkbox(enabled, *name);
+        }
+
+        ui.separator();
+        ui.heading("Tab redraw");
+        let tabs = toggles
+            .drawn_tabs
+            .iter()
+            .filter(|tab| **tab != own_name)
+            .cloned()
+            .collect::<Vec<_>>();
+        for tab in tabs {
+            let mut enabled = !toggles.disabled_tabs.contains(&tab);
+            if ui.checkbox(&mut enabled, tab_label(&tab)).changed() {
+                if enabled {
+                    toggles.disabled_tabs.remove(&tab);
+                } else {
+                    toggles.disabled_tabs.insert(tab);
+                }
+            }
+        }
+
+        ui.separator();
+        if ui.button("Enable all").clicked() {
+            for (_, enabled) in toggles.sets.iter_mut() {
+                *enabled = true;
+            }
+            toggles.disabled_tabs.clear();
+        }
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Systems".into()
+    }
+}
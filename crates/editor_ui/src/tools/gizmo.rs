@@ -1,26 +1,48 @@
-use bevy::{prelude::*, render::camera::CameraProjection};
+use bevy::{
+    ecs::system::SystemState, prelude::*, render::camera::CameraProjection, utils::HashMap,
+};
 use bevy_egui::egui::{self, Key};
 use egui_gizmo::*;
 use space_editor_core::prelude::*;
-use space_shared::EditorCameraMarker;
+use space_prefab::{editor_registry::EditorRegistry, instance::read_only_instance_root};
+use space_shared::{EditorCameraMarker, EditorRunState};
+use space_undo::{
+    begin_change_group, end_change_group, NewChange, OneFrameUndoIgnore, ReflectedComponentChange,
+};
+use std::sync::Arc;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
 
 use crate::{
+    camera_plugin::EditorCameraSettings,
     game_view::GameViewTab,
-    prelude::{CloneEvent, EditorTool},
+    hierarchy::{clone_roots, clone_subtree, request_delete, CloneOptions},
+    prelude::{EditorTool, UnitsSettings},
     tool::ToolExt,
 };
 pub struct GizmoToolPlugin;
 
 impl Plugin for GizmoToolPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<GizmoSettings>();
+        app.init_resource::<GizmoDragState>();
+        app.register_type::<GizmoSettings>();
+        #[cfg(feature = "persistence_editor")]
+        {
+            app.persistence_resource::<GizmoSettings>();
+        }
+
         app.editor_tool(GizmoTool::default());
         app.world.resource_mut::<GameViewTab>().active_tool = Some(0);
-        app.editor_hotkey(GizmoHotkey::Translate, vec![KeyCode::G]);
-        app.editor_hotkey(GizmoHotkey::Rotate, vec![KeyCode::R]);
-        app.editor_hotkey(GizmoHotkey::Scale, vec![KeyCode::S]);
+        app.editor_hotkey(GizmoHotkey::Translate, vec![KeyCode::W]);
+        app.editor_hotkey(GizmoHotkey::Rotate, vec![KeyCode::E]);
+        app.editor_hotkey(GizmoHotkey::Scale, vec![KeyCode::R]);
         app.editor_hotkey(GizmoHotkey::Delete, vec![KeyCode::X]);
         app.editor_hotkey(GizmoHotkey::Multiple, vec![KeyCode::ShiftLeft]);
         app.editor_hotkey(GizmoHotkey::Clone, vec![KeyCode::AltLeft]);
+        app.editor_hotkey(GizmoHotkey::Snap, vec![KeyCode::ControlLeft]);
+        app.editor_hotkey(GizmoHotkey::ToggleSpace, vec![KeyCode::T]);
     }
 }
 
@@ -32,6 +54,8 @@ pub enum GizmoHotkey {
     Delete,
     Multiple,
     Clone,
+    Snap,
+    ToggleSpace,
 }
 
 impl Hotkey for GizmoHotkey {
@@ -42,25 +66,388 @@ impl Hotkey for GizmoHotkey {
             Self::Scale => "Scale entity".to_string(),
             Self::Delete => "Delete entity".to_string(),
             Self::Multiple => "Change multiple entities".to_string(),
-            Self::Clone => "Clone entity".to_string(),
+            Self::Clone => "Duplicate on drag".to_string(),
+            Self::Snap => "Snap while dragging".to_string(),
+            Self::ToggleSpace => "Toggle local/world space".to_string(),
         }
     }
 }
 
+/// Axes used by gizmo handles and by position fields of gizmo tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum TransformSpace {
+    /// Axes of entity rotation. Position is shown relative to parent
+    Local,
+    /// World axes. Position is shown in world coordinates
+    #[default]
+    World,
+}
+
+impl TransformSpace {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Local => Self::World,
+            Self::World => Self::Local,
+        }
+    }
+
+    fn orientation(self) -> GizmoOrientation {
+        match self {
+            Self::Local => GizmoOrientation::Local,
+            Self::World => GizmoOrientation::Global,
+        }
+    }
+
+    /// Rotation of gizmo axes for gizmo with `model` transform
+    fn axes(self, model: &Transform) -> Quat {
+        match self {
+            Self::Local => model.rotation,
+            Self::World => Quat::IDENTITY,
+        }
+    }
+}
+
+impl ToString for TransformSpace {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Local => "Local",
+            Self::World => "World",
+        }
+        .to_string()
+    }
+}
+
+const AXES: [Vec3; 3] = [Vec3::X, Vec3::Y, Vec3::Z];
+const AXIS_NAMES: [&str; 3] = ["X", "Y", "Z"];
+const AXIS_COLORS: [egui::Color32; 3] = [
+    egui::Color32::LIGHT_RED,
+    egui::Color32::LIGHT_GREEN,
+    egui::Color32::LIGHT_BLUE,
+];
+
+/// Constraint of gizmo drag, set by X/Y/Z (or Shift + X/Y/Z for plane) during drag.
+/// Axes are gizmo axes of current [`TransformSpace`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisLock {
+    /// Change only along (or around) axis with index
+    Axis(usize),
+    /// Change only in plane orthogonal to axis with index. Rotation is limited to the axis itself
+    Plane(usize),
+}
+
+impl AxisLock {
+    /// Indices of axes along which change is allowed
+    fn free_axes(self) -> Vec<usize> {
+        match self {
+            Self::Axis(axis) => vec![axis],
+            Self::Plane(axis) => (0..3).filter(|i| *i != axis).collect(),
+        }
+    }
+
+    fn mask(self) -> Vec3 {
+        let mut mask = Vec3::ZERO;
+        for i in self.free_axes() {
+            mask[i] = 1.0;
+        }
+        mask
+    }
+
+    /// Limit gizmo change from `old` to `new`. Both transforms are in the same space, `axes` is rotation of gizmo axes
+    fn constrain(self, mode: GizmoMode, axes: Quat, old: &Transform, new: &Transform) -> Transform {
+        let mut result = *new;
+        match mode {
+            GizmoMode::Translate => {
+                let delta = axes.inverse() * (new.translation - old.translation);
+                result.translation = old.translation + axes * (delta * self.mask());
+            }
+            GizmoMode::Rotate => {
+                let axis = match self {
+                    Self::Axis(axis) | Self::Plane(axis) => axes * AXES[axis],
+                };
+                let delta = new.rotation * old.rotation.inverse();
+                result.rotation = (twist_around(delta, axis) * old.rotation).normalize();
+                // Rotation around median point also moves it
+                result.translation = old.translation;
+            }
+            GizmoMode::Scale => {
+                result.scale = old.scale + (new.scale - old.scale) * self.mask();
+            }
+        }
+        result
+    }
+}
+
+impl ToString for AxisLock {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Axis(axis) => format!("{} axis", AXIS_NAMES[*axis]),
+            Self::Plane(_) => {
+                let names = self.free_axes().into_iter().map(|i| AXIS_NAMES[i]);
+                format!("{} plane", names.collect::<String>())
+            }
+        }
+    }
+}
+
+/// Part of rotation around `axis` (twist of swing-twist decomposition)
+fn twist_around(rotation: Quat, axis: Vec3) -> Quat {
+    let projected = axis * rotation.xyz().dot(axis);
+    let twist = Quat::from_xyzw(projected.x, projected.y, projected.z, rotation.w);
+    if twist.length_squared() < f32::EPSILON {
+        Quat::IDENTITY
+    } else {
+        twist.normalize()
+    }
+}
+
+/// Screen position of world point in `rect` viewport. None for points behind camera
+fn world_to_screen(view_proj: Mat4, rect: egui::Rect, point: Vec3) -> Option<egui::Pos2> {
+    let clip = view_proj * point.extend(1.0);
+    if clip.w <= f32::EPSILON {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.w;
+    Some(egui::pos2(
+        rect.left() + (ndc.x + 1.0) * 0.5 * rect.width(),
+        rect.top() + (1.0 - ndc.y) * 0.5 * rect.height(),
+    ))
+}
+
+/// Lines through gizmo origin along free axes of lock
+fn draw_axis_lock(ui: &egui::Ui, view_proj: Mat4, lock: AxisLock, origin: Vec3, axes: Quat) {
+    let rect = ui.clip_rect();
+    let Some(center) = world_to_screen(view_proj, rect, origin) else {
+        return;
+    };
+    let length = rect.width() + rect.height();
+    for i in lock.free_axes() {
+        let Some(end) = world_to_screen(view_proj, rect, origin + axes * AXES[i]) else {
+            continue;
+        };
+        let dir = (end - center).normalized();
+        if !dir.x.is_finite() || !dir.y.is_finite() {
+            continue;
+        }
+        ui.painter().line_segment(
+            [center - dir * length, center + dir * length],
+            egui::Stroke::new(1.5, AXIS_COLORS[i]),
+        );
+    }
+}
+
+/// Gizmo drag in progress. Hotkeys which use X/Y/Z keys are ignored during drag, these keys lock axes.
+/// Box selection is not started during drag, because its hotkey is the same as snap hotkey
+#[derive(Resource, Default)]
+pub struct GizmoDragState {
+    pub dragging: bool,
+}
+
+/// Settings of transform gizmo tool
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct GizmoSettings {
+    /// Size of gizmo handles in screen points.
+    /// Gizmo is rescaled by camera distance (or orthographic scale) to keep this size on screen
+    pub base_size: f32,
+    /// Translation step in bevy units, used while snap hotkey is pressed
+    pub snap_distance: f32,
+    /// Rotation step in degrees
+    pub snap_angle: f32,
+    /// Scale ratio step
+    pub snap_scale: f32,
+    /// Orientation of gizmo handles and space of position fields
+    pub space: TransformSpace,
+}
+
+impl Default for GizmoSettings {
+    fn default() -> Self {
+        Self {
+            base_size: 75.0,
+            snap_distance: 0.5,
+            snap_angle: 15.0,
+            snap_scale: 0.25,
+            space: TransformSpace::default(),
+        }
+    }
+}
+
+impl GizmoSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui, units: &UnitsSettings) {
+        ui.heading("Gizmo snapping");
+        ui.horizontal(|ui| {
+            ui.label("Grid step:");
+            units.drag_value(ui, &mut self.snap_distance);
+            self.snap_distance = self.snap_distance.max(0.001);
+        });
+        ui.add(
+            egui::DragValue::new(&mut self.snap_angle)
+                .clamp_range(0.1..=180.0)
+                .suffix("°")
+                .prefix("Angle step: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut self.snap_scale)
+                .clamp_range(0.01..=10.0)
+                .speed(0.01)
+                .prefix("Scale step: "),
+        );
+        ui.spacing();
+        ui.separator();
+    }
+}
+
 pub struct GizmoTool {
     pub gizmo_mode: GizmoMode,
-    pub is_move_cloned_entities: bool,
+    /// Transforms of dragged entities at drag start. Whole drag is stored as one undo step
+    drag_start: Option<Vec<(Entity, Transform)>>,
+    /// Clones of duplicate drag mapped to originals. Clone gizmo uses id of original gizmo to continue the drag
+    gizmo_alias: HashMap<Entity, Entity>,
+    /// Undo group of duplicate drag is open until drag end
+    duplicate_group: bool,
+    /// Axis constraint of current drag
+    axis_lock: Option<AxisLock>,
+    /// Median gizmo was used at drag start. Shift of plane lock does not switch gizmo during drag
+    drag_multiple: bool,
 }
 
 impl Default for GizmoTool {
     fn default() -> Self {
         Self {
             gizmo_mode: GizmoMode::Translate,
-            is_move_cloned_entities: false,
+            drag_start: None,
+            gizmo_alias: HashMap::default(),
+            duplicate_group: false,
+            axis_lock: None,
+            drag_multiple: false,
+        }
+    }
+}
+
+impl GizmoTool {
+    /// Transform from gizmo result, limited by axis lock relative to gizmo model transform `old`.
+    /// Origin and axes of the first constrained gizmo are stored to `lock_origin` to draw the lock
+    fn gizmo_result(
+        &self,
+        space: TransformSpace,
+        old: &Transform,
+        result: &GizmoResult,
+        lock_origin: &mut Option<(Vec3, Quat)>,
+    ) -> Transform {
+        let new = Transform {
+            translation: Vec3::from(<[f32; 3]>::from(result.translation)),
+            rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
+            scale: Vec3::from(<[f32; 3]>::from(result.scale)),
+        };
+        let Some(lock) = self.axis_lock else {
+            return new;
+        };
+        let axes = space.axes(old);
+        lock_origin.get_or_insert((old.translation, axes));
+        lock.constrain(self.gizmo_mode, axes, old, &new)
+    }
+
+    /// Alt + translate drag: originals are returned to drag start transforms and their clones continue the drag.
+    /// Clones and their move are collected in one undo group, which is closed at drag end
+    fn start_duplicate_drag(&mut self, world: &mut World) {
+        let Some(drag_start) = self.drag_start.take() else {
+            return;
+        };
+        begin_change_group(world, "Duplicate drag");
+        self.duplicate_group = true;
+
+        let moved = drag_start
+            .iter()
+            .filter_map(|(e, old)| Some((*e, *old, *world.get::<Transform>(*e)?)))
+            .collect::<Vec<_>>();
+        for (e, old, _) in moved.iter() {
+            world
+                .entity_mut(*e)
+                .insert((*old, OneFrameUndoIgnore::default()));
+        }
+
+        let originals = moved.iter().map(|(e, ..)| *e).collect::<Vec<_>>();
+        let mut clones = vec![];
+        let mut state =
+            SystemState::<(Commands, Query<EntityRef>, Res<EditorRegistry>)>::new(world);
+        {
+            let (mut commands, query, editor_registry) = state.get_mut(world);
+            // Selected children are cloned with their selected parents
+            for root in clone_roots(&query, &originals) {
+                let Some((_, old, new)) = moved.iter().find(|(e, ..)| *e == root) else {
+                    continue;
+                };
+                let clone = clone_subtree(
+                    &mut commands,
+                    &query,
+                    &editor_registry,
+                    root,
+                    CloneOptions::default(),
+                );
+                commands
+                    .entity(clone)
+                    .insert((*new, OneFrameUndoIgnore::default()));
+                self.gizmo_alias.insert(clone, root);
+                clones.push((clone, *old));
+            }
+        }
+        state.apply(world);
+
+        let ids = clones.iter().map(|(e, _)| *e).collect::<Vec<_>>();
+        select_entities(world, &ids, SelectMode::Replace);
+        self.drag_start = Some(clones);
+    }
+}
+
+/// Text with transform delta of current drag. Shows snapped values if snapping is enabled
+fn drag_readout(
+    mode: GizmoMode,
+    old: &Transform,
+    new: &Transform,
+    units: &UnitsSettings,
+) -> String {
+    match mode {
+        GizmoMode::Translate => {
+            let delta = new.translation - old.translation;
+            format!(
+                "Δ {:.2}, {:.2}, {:.2}{}",
+                units.to_display(delta.x),
+                units.to_display(delta.y),
+                units.to_display(delta.z),
+                units.suffix()
+            )
+        }
+        GizmoMode::Rotate => {
+            let (_, angle) = (new.rotation * old.rotation.inverse()).to_axis_angle();
+            format!("∠ {:.1}°", angle.to_degrees())
+        }
+        GizmoMode::Scale => {
+            let ratio = new.scale / old.scale.max(Vec3::splat(f32::EPSILON));
+            format!("× {:.2}, {:.2}, {:.2}", ratio.x, ratio.y, ratio.z)
         }
     }
 }
 
+/// Per axis median of points. Unlike mean it is not pulled away by single far entity
+fn median_point(points: &[Vec3]) -> Vec3 {
+    if points.is_empty() {
+        return Vec3::ZERO;
+    }
+    let median = |mut values: Vec<f32>| {
+        values.sort_by(|a, b| a.total_cmp(b));
+        let mid = values.len() / 2;
+        if values.len() % 2 == 0 {
+            (values[mid - 1] + values[mid]) / 2.0
+        } else {
+            values[mid]
+        }
+    };
+    Vec3::new(
+        median(points.iter().map(|p| p.x).collect()),
+        median(points.iter().map(|p| p.y).collect()),
+        median(points.iter().map(|p| p.z).collect()),
+    )
+}
+
 impl EditorTool for GizmoTool {
     fn name(&self) -> &str {
         "Gizmo"
@@ -69,11 +456,12 @@ impl EditorTool for GizmoTool {
     fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
         // GIZMO DRAW
         // Draw gizmo per entity to individual move
-        // If SHIFT pressed draw "mean" gizmo to move all selected entities together
-        // If ALT pressed, then entity will be cloned at interact
-        // If SHIFT+ALT pressed, then all selected entities will be cloned at interact
+        // If SHIFT pressed draw "median" gizmo to move all selected entities together
+        // If ALT pressed at translate drag start, then selected entities are cloned and clones are moved
         // All hotkeys can be changes in editor ui
 
+        world.resource_mut::<GizmoDragState>().dragging = self.drag_start.is_some();
+
         let mode2name = vec![
             (GizmoMode::Translate, "⬌", "Translate"),
             (GizmoMode::Rotate, "↺", "Rotate"),
@@ -97,14 +485,87 @@ impl EditorTool for GizmoTool {
                     self.gizmo_mode = mode;
                 }
             }
+
+            let mut settings = world.resource_mut::<GizmoSettings>();
+            ui.add(
+                egui::DragValue::new(&mut settings.base_size)
+                    .clamp_range(10.0..=300.0)
+                    .prefix("Size: "),
+            );
+            let space = settings.space;
+            if ui
+                .button(space.to_string())
+                .on_hover_text("Gizmo axes and position space, toggled by T")
+                .clicked()
+            {
+                settings.space = space.toggled();
+            }
         });
 
+        // Position of single selected entity in display units
+        let units = world.resource::<UnitsSettings>().clone();
+        let space = world.resource::<GizmoSettings>().space;
+        let mut selected_transform = world.query_filtered::<(
+            Entity,
+            &mut Transform,
+            Option<&GlobalTransform>,
+            Option<&Parent>,
+        ), With<Selected>>();
+        // Content of read-only prefab instance is not moved
+        let single = selected_transform
+            .get_single(world)
+            .ok()
+            .filter(|(entity, ..)| read_only_instance_root(world, *entity).is_none())
+            .map(|(_, transform, global, parent)| {
+                (
+                    global.map_or(transform.translation, |global| global.translation()),
+                    parent.map(Parent::get),
+                )
+            });
+        if let Some((world_translation, parent)) = single {
+            // World position is converted to local by parent transform
+            let parent_global = parent
+                .filter(|_| space == TransformSpace::World)
+                .and_then(|parent| world.get::<GlobalTransform>(parent).copied());
+            let mut transform = selected_transform.single_mut(world).1;
+            let mut translation = match space {
+                TransformSpace::Local => transform.translation,
+                TransformSpace::World => world_translation,
+            };
+            let changed = ui
+                .horizontal(|ui| {
+                    ui.label(format!("Position ({}):", space.to_string().to_lowercase()));
+                    units.drag_value(ui, &mut translation.x).changed()
+                        | units.drag_value(ui, &mut translation.y).changed()
+                        | units.drag_value(ui, &mut translation.z).changed()
+                })
+                .inner;
+            if changed {
+                transform.translation = match parent_global {
+                    Some(parent_global) => parent_global
+                        .affine()
+                        .inverse()
+                        .transform_point3(translation),
+                    None => translation,
+                };
+            }
+        }
+
         let mut del = false;
         let mut clone_pressed = false;
         let mut multiple_pressed = false;
-
-        if ui.ui_contains_pointer() && !ui.ctx().wants_keyboard_input() {
-            //hot keys. Blender keys preffer
+        let mut snap_pressed = false;
+
+        // W/E fly editor camera while fly button is held
+        let flying = world
+            .get_resource::<EditorCameraSettings>()
+            .is_some_and(|settings| {
+                world
+                    .resource::<Input<MouseButton>>()
+                    .pressed(settings.fly_button)
+            });
+        if ui.ui_contains_pointer() && !ui.ctx().wants_keyboard_input() && !flying {
+            //hot keys. W/E/R as in most DCC tools
             let mode2key = vec![
                 (GizmoMode::Translate, GizmoHotkey::Translate),
                 (GizmoMode::Rotate, GizmoHotkey::Rotate),
@@ -119,26 +580,63 @@ impl EditorTool for GizmoTool {
                 }
             }
 
-            if ui.input(|s| s.key_pressed(Key::Delete) || input.just_pressed(GizmoHotkey::Delete)) {
+            if input.just_pressed(GizmoHotkey::ToggleSpace) {
+                let mut settings = world.resource_mut::<GizmoSettings>();
+                settings.space = settings.space.toggled();
+            }
+
+            // X is delete hotkey, but during drag X/Y/Z lock axes.
+            // Delete key is handled by `delete_selected` for the whole editor
+            let dragging = self.drag_start.is_some();
+            if !dragging && input.just_pressed(GizmoHotkey::Delete) {
                 del = true;
             }
+            if dragging {
+                let shift = ui.input(|s| s.modifiers.shift);
+                for (axis, key) in [Key::X, Key::Y, Key::Z].into_iter().enumerate() {
+                    if ui.input(|s| s.key_pressed(key)) {
+                        let lock = if shift {
+                            AxisLock::Plane(axis)
+                        } else {
+                            AxisLock::Axis(axis)
+                        };
+                        self.axis_lock = (self.axis_lock != Some(lock)).then_some(lock);
+                    }
+                }
+            }
 
-            if !input.pressed(GizmoHotkey::Clone) {
-                self.is_move_cloned_entities = false;
-            } else {
+            if input.pressed(GizmoHotkey::Clone) {
                 clone_pressed = true;
             }
 
             if input.pressed(GizmoHotkey::Multiple) {
                 multiple_pressed = true;
             }
+
+            if input.pressed(GizmoHotkey::Snap) {
+                snap_pressed = true;
+            }
+        }
+
+        if self.drag_start.is_some() {
+            multiple_pressed = self.drag_multiple;
         }
 
+        // Scene must not change under running game, same as in hierarchy
+        let editing = world
+            .get_resource::<State<EditorRunState>>()
+            .map_or(true, |state| state.is_editing());
+        del &= editing;
+        clone_pressed &= editing;
+
         if del {
-            let mut query = world.query_filtered::<Entity, With<Selected>>();
-            for e in query.iter(world) {
-                commands.entity(e).despawn_recursive();
-            }
+            // Same path as Delete key: one undo step, locked and editor entities are skipped,
+            // references from other entities are confirmed in hierarchy
+            let targets = world
+                .query_filtered::<Entity, (With<Selected>, Without<Locked>)>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            commands.add(move |world: &mut World| request_delete(world, targets, false));
             return;
         }
 
@@ -151,18 +649,46 @@ impl EditorTool for GizmoTool {
             (*ref_tr, ref_cam.clone())
         };
 
+        // Content of read-only prefab instances has no gizmo, same as in inspector
         let selected = world
             .query_filtered::<Entity, With<Selected>>()
             .iter(world)
+            .filter(|entity| read_only_instance_root(world, *entity).is_none())
             .collect::<Vec<_>>();
+        let settings = world.resource::<GizmoSettings>();
+        let visuals = GizmoVisuals {
+            gizmo_size: settings.base_size,
+            ..default()
+        };
+        let (snap_distance, snap_angle, snap_scale) = (
+            settings.snap_distance,
+            settings.snap_angle.to_radians(),
+            settings.snap_scale,
+        );
+        let space = settings.space;
+        let orientation = space.orientation();
         let mut disable_pan_orbit = false;
-        let _gizmo_mode = GizmoMode::Translate;
+
+        // Transforms before this frame interaction, used if the drag starts now
+        let frame_start = if self.drag_start.is_none() {
+            let mut query = world.query_filtered::<(Entity, &Transform), With<Selected>>();
+            Some(
+                query
+                    .iter(world)
+                    .map(|(e, tr)| (e, *tr))
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            None
+        };
 
         let cell = world.as_unsafe_world_cell();
 
         let view_matrix = Mat4::from(cam_transform.affine().inverse());
+        let mut gizmo_dragged = false;
+        let mut lock_origin = None;
         if multiple_pressed {
-            let mut mean_transform = Transform::IDENTITY;
+            let mut globals = vec![];
             for e in &selected {
                 let Some(ecell) = cell.get_entity(*e) else {
                     continue;
@@ -170,25 +696,34 @@ impl EditorTool for GizmoTool {
                 let Some(global_transform) = (unsafe { ecell.get::<GlobalTransform>() }) else {
                     continue;
                 };
-                let tr = global_transform.compute_transform();
-                mean_transform.translation += tr.translation;
-                mean_transform.scale += tr.scale;
+                globals.push((*e, *global_transform));
+            }
+            if globals.is_empty() {
+                return;
+            }
+
+            let mut mean_transform = Transform::IDENTITY;
+            let translations = globals
+                .iter()
+                .map(|(_, global)| global.translation())
+                .collect::<Vec<_>>();
+            mean_transform.translation = median_point(&translations);
+            mean_transform.scale = globals
+                .iter()
+                .map(|(_, global)| global.compute_transform().scale)
+                .sum::<Vec3>()
+                / globals.len() as f32;
+            // Local axes of median gizmo are axes of the first selected entity
+            if space == TransformSpace::Local {
+                mean_transform.rotation = globals[0].1.compute_transform().rotation;
             }
-            mean_transform.translation /= selected.len() as f32;
-            mean_transform.scale /= selected.len() as f32;
 
             let mut global_mean = GlobalTransform::from(mean_transform);
 
-            let mut loc_transform = vec![];
-            for e in &selected {
-                let Some(ecell) = cell.get_entity(*e) else {
-                    continue;
-                };
-                let Some(global_transform) = (unsafe { ecell.get::<GlobalTransform>() }) else {
-                    continue;
-                };
-                loc_transform.push(global_transform.reparented_to(&global_mean));
-            }
+            let loc_transform = globals
+                .iter()
+                .map(|(e, global)| (*e, global.reparented_to(&global_mean)))
+                .collect::<Vec<_>>();
 
             let mut gizmo_interacted = false;
 
@@ -197,32 +732,25 @@ impl EditorTool for GizmoTool {
                 .view_matrix(view_matrix.to_cols_array_2d().into())
                 .model_matrix(mean_transform.compute_matrix().to_cols_array_2d().into())
                 .mode(self.gizmo_mode)
+                .orientation(orientation)
+                .snapping(snap_pressed)
+                .snap_distance(snap_distance)
+                .snap_angle(snap_angle)
+                .snap_scale(snap_scale)
+                .visuals(visuals)
                 .interact(ui)
             {
                 gizmo_interacted = true;
-                mean_transform = Transform {
-                    translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                    rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                    scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                };
+                mean_transform =
+                    self.gizmo_result(space, &mean_transform, &result, &mut lock_origin);
                 disable_pan_orbit = true;
             }
 
             global_mean = GlobalTransform::from(mean_transform);
 
-            if gizmo_interacted && clone_pressed {
-                if self.is_move_cloned_entities {
-                } else {
-                    for e in selected.iter() {
-                        unsafe { cell.world_mut().send_event(CloneEvent { id: *e }) };
-                    }
-                    self.is_move_cloned_entities = true;
-                    return;
-                }
-            }
-
             if gizmo_interacted {
-                for (idx, e) in selected.iter().enumerate() {
+                gizmo_dragged = true;
+                for (e, local) in loc_transform.iter() {
                     let Some(ecell) = cell.get_entity(*e) else {
                         continue;
                     };
@@ -230,7 +758,7 @@ impl EditorTool for GizmoTool {
                         continue;
                     };
 
-                    let new_global = global_mean.mul_transform(loc_transform[idx]);
+                    let new_global = global_mean.mul_transform(*local);
 
                     if let Some(parent) = unsafe { ecell.get::<Parent>() } {
                         if let Some(parent) = cell.get_entity(parent.get()) {
@@ -256,88 +784,131 @@ impl EditorTool for GizmoTool {
                     if let Some(parent) = cell.get_entity(parent.get()) {
                         if let Some(parent_global) = unsafe { parent.get::<GlobalTransform>() } {
                             if let Some(global) = unsafe { ecell.get::<GlobalTransform>() } {
-                                if let Some(result) =
-                                    egui_gizmo::Gizmo::new(format!("Selected gizmo {:?}", *e))
-                                        .projection_matrix(
-                                            cam_proj
-                                                .get_projection_matrix()
-                                                .to_cols_array_2d()
-                                                .into(),
-                                        )
-                                        .view_matrix(view_matrix.to_cols_array_2d().into())
-                                        .model_matrix(
-                                            global.compute_matrix().to_cols_array_2d().into(),
-                                        )
-                                        .mode(self.gizmo_mode)
-                                        .interact(ui)
+                                if let Some(result) = egui_gizmo::Gizmo::new(format!(
+                                    "Selected gizmo {:?}",
+                                    self.gizmo_alias.get(e).unwrap_or(e)
+                                ))
+                                .projection_matrix(
+                                    cam_proj.get_projection_matrix().to_cols_array_2d().into(),
+                                )
+                                .view_matrix(view_matrix.to_cols_array_2d().into())
+                                .model_matrix(global.compute_matrix().to_cols_array_2d().into())
+                                .mode(self.gizmo_mode)
+                                .orientation(orientation)
+                                .snapping(snap_pressed)
+                                .snap_distance(snap_distance)
+                                .snap_angle(snap_angle)
+                                .snap_scale(snap_scale)
+                                .visuals(visuals)
+                                .interact(ui)
                                 {
-                                    let new_transform = Transform {
-                                        translation: Vec3::from(<[f32; 3]>::from(
-                                            result.translation,
-                                        )),
-                                        rotation: Quat::from_array(<[f32; 4]>::from(
-                                            result.rotation,
-                                        )),
-                                        scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                                    };
-
-                                    if clone_pressed {
-                                        if self.is_move_cloned_entities {
-                                            let new_transform =
-                                                GlobalTransform::from(new_transform);
-                                            *transform = new_transform.reparented_to(parent_global);
-                                            transform.set_changed();
-                                            disable_pan_orbit = true;
-                                        } else {
-                                            unsafe {
-                                                cell.world_mut().send_event(CloneEvent { id: *e })
-                                            };
-                                            self.is_move_cloned_entities = true;
-                                        }
-                                    } else {
-                                        let new_transform = GlobalTransform::from(new_transform);
-                                        *transform = new_transform.reparented_to(parent_global);
-                                        transform.set_changed();
-                                        disable_pan_orbit = true;
-                                    }
+                                    let new_transform = self.gizmo_result(
+                                        space,
+                                        &global.compute_transform(),
+                                        &result,
+                                        &mut lock_origin,
+                                    );
+
+                                    let new_transform = GlobalTransform::from(new_transform);
+                                    *transform = new_transform.reparented_to(parent_global);
+                                    transform.set_changed();
+                                    disable_pan_orbit = true;
+                                    gizmo_dragged = true;
                                 }
                                 continue;
                             }
                         }
                     }
                 }
-                if let Some(result) = egui_gizmo::Gizmo::new(format!("Selected gizmo {:?}", *e))
-                    .projection_matrix(cam_proj.get_projection_matrix().to_cols_array_2d().into())
-                    .view_matrix(view_matrix.to_cols_array_2d().into())
-                    .model_matrix(transform.compute_matrix().to_cols_array_2d().into())
-                    .mode(self.gizmo_mode)
-                    .interact(ui)
+                if let Some(result) = egui_gizmo::Gizmo::new(format!(
+                    "Selected gizmo {:?}",
+                    self.gizmo_alias.get(e).unwrap_or(e)
+                ))
+                .projection_matrix(cam_proj.get_projection_matrix().to_cols_array_2d().into())
+                .view_matrix(view_matrix.to_cols_array_2d().into())
+                .model_matrix(transform.compute_matrix().to_cols_array_2d().into())
+                .mode(self.gizmo_mode)
+                .orientation(orientation)
+                .snapping(snap_pressed)
+                .snap_distance(snap_distance)
+                .snap_angle(snap_angle)
+                .snap_scale(snap_scale)
+                .visuals(visuals)
+                .interact(ui)
                 {
-                    if clone_pressed {
-                        if self.is_move_cloned_entities {
-                            *transform = Transform {
-                                translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                                rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                                scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                            };
-                            transform.set_changed();
-                        } else {
-                            unsafe { cell.world_mut().send_event(CloneEvent { id: *e }) };
-                            self.is_move_cloned_entities = true;
-                        }
-                    } else {
-                        *transform = Transform {
-                            translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                            rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                            scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-                        };
-                        transform.set_changed();
-                    }
+                    *transform = self.gizmo_result(space, &transform, &result, &mut lock_origin);
+                    transform.set_changed();
+                    gizmo_dragged = true;
                     disable_pan_orbit = true;
                 }
             }
         }
 
+        let mut duplicate_drag = false;
+        if gizmo_dragged {
+            if let Some(frame_start) = frame_start {
+                self.drag_start = Some(frame_start);
+                self.drag_multiple = multiple_pressed;
+                duplicate_drag = clone_pressed && self.gizmo_mode == GizmoMode::Translate;
+            }
+            if let (Some(lock), Some((origin, axes))) = (self.axis_lock, lock_origin) {
+                let view_proj = cam_proj.get_projection_matrix() * view_matrix;
+                draw_axis_lock(ui, view_proj, lock, origin, axes);
+            }
+            // Auto undo would split slow drag into many steps, so it is ignored until drag end
+            if let Some(drag_start) = &self.drag_start {
+                for (e, _) in drag_start {
+                    commands.entity(*e).insert(OneFrameUndoIgnore::default());
+                }
+
+                let current = drag_start.iter().find_map(|(e, old)| {
+                    let ecell = cell.get_entity(*e)?;
+                    let new = unsafe { ecell.get::<Transform>() }?;
+                    Some((*old, *new))
+                });
+                if let (Some((old, new)), Some(pos)) = (current, ui.ctx().pointer_hover_pos()) {
+                    let mut text = drag_readout(self.gizmo_mode, &old, &new, &units);
+                    if let Some(lock) = self.axis_lock {
+                        text = format!("{} [{}]", text, lock.to_string());
+                    }
+                    ui.painter().text(
+                        pos + egui::vec2(16.0, 16.0),
+                        egui::Align2::LEFT_TOP,
+                        text,
+                        egui::FontId::monospace(12.0),
+                        ui.visuals().strong_text_color(),
+                    );
+                }
+            }
+        } else if let Some(drag_start) = self.drag_start.take() {
+            for (e, old_transform) in drag_start {
+                let Some(ecell) = cell.get_entity(e) else {
+                    continue;
+                };
+                let Some(new_transform) = (unsafe { ecell.get::<Transform>() }) else {
+                    continue;
+                };
+                if *new_transform == old_transform {
+                    continue;
+                }
+                unsafe {
+                    cell.world_mut().send_event(NewChange {
+                        change: Arc::new(ReflectedComponentChange::new(
+                            e,
+                            old_transform,
+                            *new_transform,
+                        )),
+                    })
+                };
+            }
+            if self.duplicate_group {
+                unsafe { end_change_group(cell.world_mut()) };
+                self.duplicate_group = false;
+            }
+            self.gizmo_alias.clear();
+            self.axis_lock = None;
+        }
+
         if disable_pan_orbit {
             unsafe {
                 cell.get_resource_mut::<crate::EditorCameraEnabled>()
@@ -345,5 +916,11 @@ impl EditorTool for GizmoTool {
                     .0 = false
             };
         }
+
+        if duplicate_drag {
+            self.start_duplicate_drag(world);
+        }
+        // Drag started in this frame is seen by box selection in the same frame
+        world.resource_mut::<GizmoDragState>().dragging = self.drag_start.is_some();
     }
 }
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use bevy::{
+    prelude::*,
+    utils::{HashSet, Uuid},
+};
+use bevy_egui::egui;
+use space_prefab::{
+    load::spawn_serialized,
+    prelude::{ChildrenPrefab, EditorUuid, EditorUuidIndex},
+    save::serialize_entities,
+};
+use space_shared::*;
+
+use crate::hierarchy::EditorNote;
+use space_undo::{begin_change_group, end_change_group, AddedEntity, ChangeChain, NewChange};
+
+/// Entities deleted through hierarchy during current session. Unlike undo steps, items are kept
+/// after unrelated edits, so deleted entity can be restored at any time until trash is emptied
+#[derive(Resource, Default)]
+pub struct HierarchyTrash {
+    pub items: Vec<TrashItem>,
+}
+
+/// One deleted root entity with all its descendants
+pub struct TrashItem {
+    pub name: String,
+    /// Number of stored entities including descendants
+    pub count: usize,
+    /// Prefab RON string created by [`serialize_entities`]
+    pub data: String,
+    /// [`EditorUuid`] of parent at the moment of deletion
+    pub parent: Option<Uuid>,
+    /// Deleted root entity. Undo of the delete respawns it with other id, which is tracked by [`ChangeChain`]
+    pub entity: Entity,
+    /// [`EditorUuid`] of deleted root
+    pub uuid: Option<Uuid>,
+    /// Notes of stored entities. [`EditorNote`] is editor only, so it is not in `data`
+    pub notes: Vec<(Entity, EditorNote)>,
+}
+
+impl TrashItem {
+    /// Deleted root exists again, for example, the delete was undone. Restoring such item would duplicate it
+    pub fn is_alive(&self, world: &World) -> bool {
+        let entity = world
+            .get_resource::<ChangeChain>()
+            .map_or(self.entity, |chain| chain.current_entity(self.entity));
+        // Entity ids have generation, so despawned root is never found again by its old id
+        let respawned = world.get_entity(entity).is_some();
+        let same_uuid = self.uuid.is_some_and(|uuid| {
+            world
+                .get_resource::<EditorUuidIndex>()
+                .and_then(|index| index.get(uuid))
+                .and_then(|entity| world.get::<EditorUuid>(entity))
+                .is_some_and(|found| found.0 == uuid)
+        });
+        respawned || same_uuid
+    }
+}
+
+impl HierarchyTrash {
+    /// Collapsing "Trash" section of hierarchy tab. Restore and empty actions are queued to `commands`
+    pub fn ui(&self, ui: &mut egui::Ui, commands: &mut Commands) {
+        egui::CollapsingHeader::new(format!("🗑 Trash ({})", self.items.len()))
+            .id_source("hierarchy_trash")
+            .default_open(false)
+            .show(ui, |ui| {
+                if self.items.is_empty() {
+                    ui.weak("Deleted entities will appear here");
+                    return;
+                }
+                // Newest first
+                for (index, item) in self.items.iter().enumerate().rev() {
+                    ui.horizontal(|ui| {
+                        if ui.button("Restore").clicked() {
+                            commands.add(move |world: &mut World| {
+                                restore_from_trash(world, index);
+                            });
+                        }
+                        if item.count > 1 {
+                            ui.label(format!("{} ({})", item.name, item.count));
+                        } else {
+                            ui.label(&item.name);
+                        }
+                    });
+                }
+                if ui.button("Empty trash").clicked() {
+                    commands.add(|world: &mut World| {
+                        world.resource_mut::<HierarchyTrash>().items.clear();
+                    });
+                }
+            });
+    }
+}
+
+/// Store subtrees of `roots` in [`HierarchyTrash`]. Must be called before entities are despawned
+pub fn move_to_trash(world: &mut World, roots: &[Entity]) {
+    if !world.contains_resource::<HierarchyTrash>() {
+        return;
+    }
+    let mut items = vec![];
+    for root in roots.iter().copied() {
+        let data = match serialize_entities(world, &[root]) {
+            Ok(data) => data,
+            Err(err) => {
+                warn!("Failed to move entity {:?} to trash: {}", root, err);
+                continue;
+            }
+        };
+        let name = world
+            .get::<Name>(root)
+            .map_or_else(|| format!("{:?}", root), |name| name.as_str().to_string());
+        let parent = world
+            .get::<Parent>(root)
+            .and_then(|parent| world.get::<EditorUuid>(parent.get()))
+            .map(|uuid| uuid.0);
+        items.push(TrashItem {
+            name,
+            count: count_prefab_subtree(world, root),
+            data,
+            parent,
+            entity: root,
+            uuid: world.get::<EditorUuid>(root).map(|uuid| uuid.0),
+            notes: subtree_notes(world, root),
+        });
+    }
+    world.resource_mut::<HierarchyTrash>().items.extend(items);
+}
+
+/// Respawn trash item with its components. Restored entity is attached to its old parent if the parent
+/// still exists. Spawn is recorded in undo as usual [`AddedEntity`] changes.
+/// Returns restored root entities
+pub fn restore_from_trash(world: &mut World, index: usize) -> Vec<Entity> {
+    let mut trash = world.resource_mut::<HierarchyTrash>();
+    if index >= trash.items.len() {
+        return vec![];
+    }
+    let item = trash.items.remove(index);
+    if item.is_alive(world) {
+        warn!(
+            "{} was restored by undo, trash item is dropped to not duplicate it",
+            item.name
+        );
+        return vec![];
+    }
+
+    let map = match spawn_serialized(world, &item.data) {
+        Ok(map) => map,
+        Err(err) => {
+            error!("Failed to restore {} from trash: {}", item.name, err);
+            return vec![];
+        }
+    };
+
+    for (stored, note) in item.notes.iter() {
+        if let Some(entity) = map.get(stored) {
+            world.entity_mut(*entity).insert(note.clone());
+        }
+    }
+
+    let children = map
+        .values()
+        .filter_map(|e| world.get::<ChildrenPrefab>(*e))
+        .flat_map(|children| children.0.iter().copied())
+        .collect::<HashSet<_>>();
+    let roots = map
+        .values()
+        .copied()
+        .filter(|e| !children.contains(e))
+        .collect::<Vec<_>>();
+
+    let parent = item
+        .parent
+        .and_then(|uuid| world.get_resource::<EditorUuidIndex>()?.get(uuid))
+        .filter(|parent| world.get_entity(*parent).is_some());
+
+    begin_change_group(world, format!("Restore {} from trash", item.name));
+    for root in roots.iter() {
+        if let Some(parent) = parent {
+            world.entity_mut(parent).add_child(*root);
+        }
+    }
+    for entity in map.values() {
+        world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: *entity }),
+        });
+    }
+    end_change_group(world);
+    info!("Restored {} from trash", item.name);
+    roots
+}
+
+/// Drop trash items, which deleted roots were brought back by undo
+pub fn prune_trash(world: &mut World) {
+    if !world.contains_resource::<HierarchyTrash>() {
+        return;
+    }
+    world.resource_scope(|world, mut trash: Mut<HierarchyTrash>| {
+        if trash.items.iter().any(|item| item.is_alive(world)) {
+            trash.items.retain(|item| !item.is_alive(world));
+        }
+    });
+}
+
+fn subtree_notes(world: &World, root: Entity) -> Vec<(Entity, EditorNote)> {
+    let mut notes = vec![];
+    let mut queue = vec![root];
+    while let Some(entity) = queue.pop() {
+        if world.get::<PrefabMarker>(entity).is_none() {
+            continue;
+        }
+        if let Some(note) = world.get::<EditorNote>(entity) {
+            notes.push((entity, note.clone()));
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            queue.extend(children.iter());
+        }
+    }
+    notes
+}
+
+fn count_prefab_subtree(world: &World, root: Entity) -> usize {
+    let children = world
+        .get::<Children>(root)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    1 + children
+        .into_iter()
+        .filter(|child| world.get::<PrefabMarker>(*child).is_some())
+        .map(|child| count_prefab_subtree(world, child))
+        .sum::<usize>()
+}
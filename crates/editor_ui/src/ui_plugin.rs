@@ -1,7 +1,22 @@
 use crate::*;
+use autosave::AutosavePlugin;
 use bevy::prelude::*;
 use camera_plugin::draw_camera_gizmo;
+use clipboard::EntityClipboardPlugin;
+use command_palette::CommandPalettePlugin;
+use console::ConsolePlugin;
+use dock_layout::DockLayoutPlugin;
+use editor_visibility::EditorVisibilityPlugin;
+use entity_search::EntitySearchPlugin;
+use isolation::IsolationPlugin;
+use load_errors::LoadErrorsViewPlugin;
+use measure::MeasurePlugin;
 use meshless_visualizer::draw_light_gizmo;
+use save_validation::SaveValidationPlugin;
+use scene_state::SceneStatePlugin;
+use selection_outline::SelectionOutlinePlugin;
+use system_toggles::{SystemTogglesPlugin, VIEWPORT_GIZMOS};
+use wireframe::WireframeViewPlugin;
 
 /// All systems for editor ui wil be placed in UiSystemSet
 #[derive(SystemSet, Hash, PartialEq, Eq, Debug, Clone, Copy)]
@@ -37,11 +52,26 @@ impl FlatPluginList for EditorUiPlugin {
             .add(GameViewPlugin)
             .add(bottom_menu::BottomMenuPlugin)
             .add(MouseCheck)
-            .add(CameraViewTabPlugin)
+            .add(CameraViewTabPlugin::default())
             .add(SpaceHierarchyPlugin::default())
             .add(SpaceInspectorPlugin)
             .add(GizmoToolPlugin)
             .add(ChangeChainViewPlugin)
+            .add(ConsolePlugin)
+            .add(LoadErrorsViewPlugin)
+            .add(SaveValidationPlugin)
+            .add(IsolationPlugin)
+            .add(EditorVisibilityPlugin)
+            .add(WireframeViewPlugin)
+            .add(SelectionOutlinePlugin)
+            .add(EntityClipboardPlugin)
+            .add(DockLayoutPlugin)
+            .add(SceneStatePlugin)
+            .add(MeasurePlugin)
+            .add(AutosavePlugin)
+            .add(CommandPalettePlugin)
+            .add(EntitySearchPlugin)
+            .add(SystemTogglesPlugin)
             .add(settings::SettingsWindowPlugin);
 
         if self.use_standard_layout {
@@ -104,6 +134,7 @@ impl Plugin for EditorUiCore {
         );
         app.init_resource::<EditorUi>();
         app.init_resource::<ScheduleEditorTabStorage>();
+        app.init_resource::<BoxedEditorTabStorage>();
         app.add_systems(
             Update,
             (
@@ -133,6 +164,10 @@ impl Plugin for EditorUiCore {
             EditorTabName::Other("Debug World Inspector".to_string()),
             self::debug_panels::DebugWorldInspector {},
         );
+        app.editor_tab_by_trait(
+            EditorTabName::Other("Editor Registry".to_string()),
+            self::debug_panels::EditorRegistryInspector::default(),
+        );
 
         app.init_resource::<EditorLoader>();
 
@@ -162,8 +197,7 @@ impl Plugin for EditorUiCore {
         app.add_systems(
             Update,
             (
-                draw_camera_gizmo,
-                draw_light_gizmo,
+                (draw_camera_gizmo, draw_light_gizmo).in_set(VIEWPORT_GIZMOS),
                 selection::delete_selected,
             )
                 .run_if(in_state(EditorState::Editor).and_then(in_state(ShowEditorUi::Show))),
@@ -312,10 +346,17 @@ impl EditorUi {
 }
 
 /// Trait for registering editor tabs via app.**
+///
+/// Downstream crates can add own tabs with [`EditorTabName::Other`] name.
+/// Registered tab will be available in "add tab" popup of dock area
 pub trait EditorUiAppExt {
+    /// Register tab backed by resource, which implements [`EditorTab`]
     fn editor_tab_by_trait<T>(&mut self, tab_id: EditorTabName, tab: T) -> &mut Self
     where
         T: EditorTab + Resource + Send + Sync + 'static;
+    /// Register tab backed by boxed [`EditorTab`] implementation
+    fn editor_tab_boxed(&mut self, tab_id: EditorTabName, tab: BoxedEditorTab) -> &mut Self;
+    /// Register tab backed by systems. Systems can get tab ui from [`EditorUiRef`] non send resource
     fn editor_tab<T>(
         &mut self,
         tab_id: EditorTabName,
@@ -349,6 +390,41 @@ impl EditorUiAppExt for App {
         self
     }
 
+    fn editor_tab_boxed(&mut self, tab_id: EditorTabName, tab: BoxedEditorTab) -> &mut Self {
+        let show_id = tab_id.clone();
+        let show_fn = Box::new(
+            move |ui: &mut egui::Ui, commands: &mut Commands, world: &mut World| {
+                world.resource_scope(|scoped_world, mut storage: Mut<BoxedEditorTabStorage>| {
+                    if let Some(tab) = storage.0.get_mut(&show_id) {
+                        tab.ui(ui, commands, scoped_world);
+                    }
+                });
+            },
+        );
+        let title_id = tab_id.clone();
+        let title_fn = Box::new(move |world: &mut World| {
+            world
+                .resource::<BoxedEditorTabStorage>()
+                .0
+                .get(&title_id)
+                .map_or_else(|| format!("{title_id:?}").into(), |tab| tab.title())
+        });
+        let reg = EditorUiReg::ResourceBased {
+            show_command: show_fn,
+            title_command: title_fn,
+        };
+
+        self.world
+            .get_resource_or_insert_with(BoxedEditorTabStorage::default)
+            .0
+            .insert(tab_id.clone(), tab);
+        self.world
+            .resource_mut::<EditorUi>()
+            .registry
+            .insert(tab_id, reg);
+        self
+    }
+
     fn editor_tab<T>(
         &mut self,
         tab_id: EditorTabName,
@@ -401,3 +477,171 @@ pub fn ui_camera_block(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct CounterTab {
+        shown: usize,
+    }
+
+    impl EditorTab for CounterTab {
+        fn ui(&mut self, ui: &mut egui::Ui, _: &mut Commands, _: &mut World) {
+            self.shown += 1;
+            ui.label(format!("Shown {} times", self.shown));
+        }
+
+        fn title(&self) -> egui::WidgetText {
+            "Counter".into()
+        }
+    }
+
+    fn configure_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<EditorUi>()
+            .init_resource::<ScheduleEditorTabStorage>()
+            .init_resource::<BoxedEditorTabStorage>();
+        app
+    }
+
+    fn show_tab(app: &mut App, tab_id: &EditorTabName) -> String {
+        let ctx = egui::Context::default();
+        let mut title = String::new();
+        app.world
+            .resource_scope(|world, mut editor: Mut<EditorUi>| {
+                let Some(EditorUiReg::ResourceBased {
+                    show_command,
+                    title_command,
+                }) = editor.registry.get_mut(tab_id)
+                else {
+                    panic!("Tab {:?} is not registered as resource based", tab_id);
+                };
+                title = title_command(world).text().to_string();
+
+                let commands_world = World::new();
+                let mut queue = CommandQueue::default();
+                let mut commands = Commands::new(&mut queue, &commands_world);
+                let _ = ctx.run(Default::default(), |ctx| {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        show_command(ui, &mut commands, world);
+                    });
+                });
+            });
+        title
+    }
+
+    #[test]
+    fn test_custom_tab_by_trait() {
+        let mut app = configure_app();
+        let tab_id = EditorTabName::Other("Counter".to_string());
+        app.editor_tab_by_trait(tab_id.clone(), CounterTab::default());
+
+        assert!(app
+            .world
+            .resource::<EditorUi>()
+            .registry
+            .contains_key(&tab_id));
+        assert_eq!(show_tab(&mut app, &tab_id), "Counter");
+        assert_eq!(app.world.resource::<CounterTab>().shown, 1);
+    }
+
+    #[test]
+    fn test_custom_tab_boxed() {
+        let mut app = configure_app();
+        let tab_id = EditorTabName::Other("Boxed counter".to_string());
+        app.editor_tab_boxed(tab_id.clone(), Box::<CounterTab>::default());
+
+        assert!(app
+            .world
+            .resource::<EditorUi>()
+            .registry
+            .contains_key(&tab_id));
+        assert_eq!(show_tab(&mut app, &tab_id), "Counter");
+        assert!(app
+            .world
+            .resource::<BoxedEditorTabStorage>()
+            .0
+            .contains_key(&tab_id));
+    }
+
+    #[test]
+    fn test_run_state_follows_requests() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<EditorState>()
+            .add_plugins(EditorSetsPlugin);
+        app.update();
+        // Editor starts in play mode without editor plugins
+        assert_eq!(
+            *app.world.resource::<State<EditorRunState>>().get(),
+            EditorRunState::Playing
+        );
+
+        app.world.send_event(EditorRunRequest::Pause);
+        app.update();
+        assert_eq!(
+            *app.world.resource::<State<EditorRunState>>().get(),
+            EditorRunState::Paused
+        );
+        let changes = app.world.resource::<Events<EditorRunStateChanged>>();
+        assert!(changes
+            .iter_current_update_events()
+            .any(|change| change.from == EditorRunState::Playing
+                && change.to == EditorRunState::Paused));
+
+        app.world.send_event(EditorRunRequest::Stop);
+        app.update();
+        app.update();
+        assert_eq!(
+            *app.world.resource::<State<EditorRunState>>().get(),
+            EditorRunState::Editing
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct GameFrames(usize);
+
+    /// Step requested after game systems ran in this frame, e.g. from UI
+    #[derive(Resource, Default)]
+    struct LateStep(bool);
+
+    #[test]
+    fn test_late_step_runs_game_systems_once() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<EditorState>()
+            .add_plugins(EditorSetsPlugin)
+            .init_resource::<GameFrames>()
+            .init_resource::<LateStep>()
+            .add_systems(
+                Update,
+                (|mut frames: ResMut<GameFrames>| frames.0 += 1).in_set(EditorSet::Game),
+            )
+            .add_systems(
+                PostUpdate,
+                |mut late: ResMut<LateStep>, mut state: ResMut<SimulationState>| {
+                    if std::mem::take(&mut late.0) {
+                        state.step = true;
+                    }
+                },
+            );
+        app.update();
+        app.world.send_event(EditorRunRequest::Pause);
+        app.update();
+        let paused_at = app.world.resource::<GameFrames>().0;
+
+        app.world.resource_mut::<LateStep>().0 = true;
+        app.update();
+        assert_eq!(app.world.resource::<GameFrames>().0, paused_at);
+        assert!(app.world.resource::<SimulationState>().step);
+
+        app.update();
+        assert_eq!(app.world.resource::<GameFrames>().0, paused_at + 1);
+        assert!(!app.world.resource::<SimulationState>().step);
+
+        app.update();
+        assert_eq!(app.world.resource::<GameFrames>().0, paused_at + 1);
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
 use bevy::ecs::system::EntityCommands;
+use bevy_egui::{egui, EguiContexts};
 
 use space_prefab::{component::*, ext::*};
 use space_shared::{LightAreaToggle, PrefabMarker};
@@ -12,6 +13,11 @@ pub struct BundleReg {
 }
 
 impl BundleReg {
+    /// Registered bundle by category and name
+    pub fn get_mut(&mut self, category: &str, name: &str) -> Option<&mut EditorBundleUntyped> {
+        self.bundles.get_mut(category)?.get_mut(name)
+    }
+
     pub fn add_bundle<T: Bundle + Clone>(&mut self, bundle: EditorBundle<T>) {
         let dyn_bundle = EditorBundleUntyped::new(bundle.data.clone(), bundle.name.clone());
 
@@ -33,19 +39,61 @@ pub struct EditorBundle<T: Bundle + Clone> {
 pub struct EditorBundleUntyped {
     pub data: Box<dyn Fn(&mut EntityCommands) + Send + Sync>,
     pub name: String,
+    /// Thumbnail shown in "Spawnable bundles" menu instead of text only button.
+    /// Can be image asset or render target image, rendered by user camera
+    pub preview: Option<Handle<Image>>,
+    /// egui texture of `preview`, registered by [`register_bundle_previews`]
+    preview_texture: Option<egui::TextureId>,
+    /// Creates `preview` once, run by [`generate_bundle_previews`]
+    preview_generator: Option<BundlePreviewGenerator>,
 }
 
+/// Hook to create bundle thumbnail with world access, for example by spawning the bundle
+/// in front of a camera which renders to the returned image. Called once
+pub type BundlePreviewGenerator = Box<dyn FnOnce(&mut World) -> Handle<Image> + Send + Sync>;
+
 impl EditorBundleUntyped {
     /// Create new untyped editor bundle
     pub fn new<T: Bundle + Clone>(data: T, name: String) -> Self {
+        Self::from_fn(name, move |cmds| {
+            cmds.insert(data.clone());
+        })
+    }
+
+    /// Create untyped editor bundle which is spawned by custom function
+    pub fn from_fn(
+        name: String,
+        data: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> Self {
         Self {
-            data: Box::new(move |cmds| {
-                cmds.insert(data.clone());
-            }),
+            data: Box::new(data),
             name,
+            preview: None,
+            preview_texture: None,
+            preview_generator: None,
         }
     }
 
+    /// Set thumbnail of bundle
+    pub fn with_preview(mut self, preview: Handle<Image>) -> Self {
+        self.preview = Some(preview);
+        self
+    }
+
+    /// Set hook which creates thumbnail of bundle
+    pub fn with_preview_generator(
+        mut self,
+        generator: impl FnOnce(&mut World) -> Handle<Image> + Send + Sync + 'static,
+    ) -> Self {
+        self.preview_generator = Some(Box::new(generator));
+        self
+    }
+
+    /// egui texture of thumbnail, if it is registered already
+    pub fn preview_texture(&self) -> Option<egui::TextureId> {
+        self.preview_texture
+    }
+
     /// Spawn in world untyped editor bundle and mark entity as part of prefab
     pub fn spawn(&self, commands: &mut Commands) -> Entity {
         let mut cmds = commands.spawn_empty();
@@ -59,6 +107,19 @@ impl EditorBundleUntyped {
 pub trait EditorUiExt {
     /// Register new bundle in editor ui
     fn editor_bundle<T: Bundle + Clone>(&mut self, category: &str, name: &str, bundle: T);
+
+    /// Set thumbnail of registered bundle, for example `asset_server.load("previews/tree.png")`.
+    /// Bundles without thumbnail are shown as text buttons
+    fn editor_bundle_preview(&mut self, category: &str, name: &str, preview: Handle<Image>);
+
+    /// Set hook which creates thumbnail of registered bundle. It runs once in editor update,
+    /// so it can use assets and spawn render target camera
+    fn editor_bundle_preview_generator(
+        &mut self,
+        category: &str,
+        name: &str,
+        generator: impl FnOnce(&mut World) -> Handle<Image> + Send + Sync + 'static,
+    );
 }
 
 impl EditorUiExt for App {
@@ -76,6 +137,80 @@ impl EditorUiExt for App {
             name: name.to_string(),
         });
     }
+
+    fn editor_bundle_preview(&mut self, category: &str, name: &str, preview: Handle<Image>) {
+        let Some(mut reg) = self.world.get_resource_mut::<BundleReg>() else {
+            warn!("Bundle {}/{} is not registered", category, name);
+            return;
+        };
+        match reg.get_mut(category, name) {
+            Some(bundle) => {
+                bundle.preview = Some(preview);
+                bundle.preview_texture = None;
+            }
+            None => warn!("Bundle {}/{} is not registered", category, name),
+        }
+    }
+
+    fn editor_bundle_preview_generator(
+        &mut self,
+        category: &str,
+        name: &str,
+        generator: impl FnOnce(&mut World) -> Handle<Image> + Send + Sync + 'static,
+    ) {
+        let Some(mut reg) = self.world.get_resource_mut::<BundleReg>() else {
+            warn!("Bundle {}/{} is not registered", category, name);
+            return;
+        };
+        match reg.get_mut(category, name) {
+            Some(bundle) => bundle.preview_generator = Some(Box::new(generator)),
+            None => warn!("Bundle {}/{} is not registered", category, name),
+        }
+    }
+}
+
+/// Run preview generators of registered bundles and store generated thumbnails
+pub fn generate_bundle_previews(world: &mut World) {
+    let generators = {
+        let mut reg = world.resource_mut::<BundleReg>();
+        reg.bypass_change_detection()
+            .bundles
+            .iter_mut()
+            .flat_map(|(category, bundles)| {
+                bundles
+                    .iter_mut()
+                    .map(move |(name, bundle)| (category.clone(), name.clone(), bundle))
+            })
+            .filter_map(|(category, name, bundle)| {
+                Some((category, name, bundle.preview_generator.take()?))
+            })
+            .collect::<Vec<_>>()
+    };
+    for (category, name, generator) in generators {
+        let preview = generator(world);
+        let mut reg = world.resource_mut::<BundleReg>();
+        if let Some(bundle) = reg.get_mut(&category, &name) {
+            bundle.preview = Some(preview);
+            bundle.preview_texture = None;
+        }
+    }
+}
+
+/// Register bundle thumbnails as egui textures, so they can be drawn by hierarchy tab
+pub fn register_bundle_previews(mut reg: ResMut<BundleReg>, mut ctxs: EguiContexts) {
+    // Texture ids are written without change detection to not repeat this every frame
+    let reg = reg.bypass_change_detection();
+    for bundle in reg
+        .bundles
+        .values_mut()
+        .flat_map(|bundles| bundles.values_mut())
+    {
+        if bundle.preview_texture.is_none() {
+            if let Some(preview) = &bundle.preview {
+                bundle.preview_texture = Some(ctxs.add_image(preview.clone()));
+            }
+        }
+    }
 }
 
 pub fn register_light_editor_bundles(app: &mut App) {
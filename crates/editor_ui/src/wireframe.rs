@@ -0,0 +1,106 @@
+use bevy::{
+    pbr::wireframe::{Wireframe, WireframeConfig},
+    prelude::*,
+};
+use space_editor_core::prelude::*;
+use space_shared::*;
+
+use crate::tools::gizmo::GizmoDragState;
+
+/// Plugin to render selected meshes or the whole scene as wireframe in editor
+pub struct WireframeViewPlugin;
+
+impl Plugin for WireframeViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_hotkey(
+            WireframeHotkey::ToggleSelected,
+            vec![KeyCode::ShiftLeft, KeyCode::Z],
+        );
+        app.add_systems(Update, wireframe_hotkey.in_set(EditorSet::Editor));
+        app.add_systems(OnEnter(EditorState::GamePrepare), exit_wireframe_view);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum WireframeHotkey {
+    ToggleSelected,
+}
+
+impl Hotkey for WireframeHotkey {
+    fn name<'a>(&self) -> String {
+        match self {
+            Self::ToggleSelected => "Toggle wireframe of selected".to_string(),
+        }
+    }
+}
+
+fn wireframe_hotkey(
+    mut commands: Commands,
+    input: Res<Input<WireframeHotkey>>,
+    gizmo_drag: Option<Res<GizmoDragState>>,
+) {
+    // Shift + Z locks plane during gizmo drag
+    if gizmo_drag.is_some_and(|drag| drag.dragging) {
+        return;
+    }
+    if input.just_pressed(WireframeHotkey::ToggleSelected) {
+        commands.add(|world: &mut World| {
+            let selected = world
+                .query_filtered::<Entity, With<Selected>>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            toggle_wireframe(world, &selected);
+        });
+    }
+}
+
+/// Toggle [`WireframeOverride`] on meshes of entities and their descendants.
+/// Wireframe is turned off if all of them already have it. Materials are not changed
+pub fn toggle_wireframe(world: &mut World, entities: &[Entity]) {
+    let mut meshes = vec![];
+    let mut queue = entities.to_vec();
+    while let Some(entity) = queue.pop() {
+        let Some(entity_ref) = world.get_entity(entity) else {
+            continue;
+        };
+        if entity_ref.contains::<Handle<Mesh>>() {
+            meshes.push(entity);
+        }
+        if let Some(children) = entity_ref.get::<Children>() {
+            queue.extend(children.iter());
+        }
+    }
+    if meshes.is_empty() {
+        return;
+    }
+
+    let enable = meshes
+        .iter()
+        .any(|entity| world.get::<WireframeOverride>(*entity).is_none());
+    for entity in meshes {
+        let mut entity = world.entity_mut(entity);
+        if enable {
+            entity.insert((WireframeOverride, Wireframe));
+        } else {
+            entity.remove::<WireframeOverride>();
+            // Selection wireframe is restored by selection systems
+            entity.remove::<Wireframe>();
+        }
+    }
+}
+
+/// Wireframe is editor view mode, so it is not kept in play mode
+fn exit_wireframe_view(
+    mut commands: Commands,
+    config: Option<ResMut<WireframeConfig>>,
+    overrides: Query<Entity, With<WireframeOverride>>,
+) {
+    if let Some(mut config) = config {
+        config.global = false;
+    }
+    for entity in overrides.iter() {
+        commands
+            .entity(entity)
+            .remove::<(WireframeOverride, Wireframe)>();
+    }
+}
@@ -3,15 +3,15 @@ use std::sync::Arc;
 use bevy::{
     ecs::system::{EntityCommand, EntityCommands},
     prelude::*,
-    reflect::{GetTypeRegistration, TypePath, TypeRegistryArc},
-    utils::{HashMap, HashSet},
+    reflect::{GetTypeRegistration, ReflectMut, TypePath, TypeRegistryArc},
+    utils::{get_short_name, HashMap, HashSet},
 };
 use space_shared::*;
 
 use space_undo::AppAutoUndo;
 use std::any::TypeId;
 
-use crate::{component::AutoStruct, save::SaveState, PrefabSet};
+use crate::{component::AutoStruct, linked::link_assets, save::SaveState, PrefabSet};
 
 /// Plugin to activate custom registry
 pub struct EditorRegistryPlugin;
@@ -43,12 +43,14 @@ impl RemoveComponent {
 /// Container struct for function to clone component in untyped style
 #[derive(Clone)]
 pub struct CloneComponent {
+    pub type_id: TypeId,
     func: Arc<dyn Fn(&mut EntityCommands, &EntityRef) + Send + Sync>,
 }
 
 impl CloneComponent {
     pub fn new<T: Component + Reflect + FromReflect>() -> Self {
         Self {
+            type_id: TypeId::of::<T>(),
             func: Arc::new(move |cmds, src| {
                 if let Some(c) = src.get::<T>() {
                     let cloned = c.clone_value();
@@ -63,6 +65,80 @@ impl CloneComponent {
     }
 }
 
+/// How [`EditorRegistry::clone_entity_flat_with_mode`] handles mesh and material assets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AssetCloneMode {
+    /// Clone gets own assets, created from its cloned prefab components
+    #[default]
+    Deep,
+    /// Clone shares mesh and material handles with source and is marked as [`LinkedAssets`](crate::linked::LinkedAssets)
+    Linked,
+}
+
+/// Call `visit` for each [`Entity`] value inside reflected `value`. Map values are not visited.
+/// Shared by reference remap and search, read-only callers visit a clone of the value
+fn visit_reflect_entities(value: &mut dyn Reflect, visit: &mut impl FnMut(&mut Entity)) {
+    if let Some(entity) = value.downcast_mut::<Entity>() {
+        visit(entity);
+        return;
+    }
+
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    visit_reflect_entities(field, visit);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    visit_reflect_entities(field, visit);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    visit_reflect_entities(field, visit);
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for index in 0..value.len() {
+                if let Some(item) = value.get_mut(index) {
+                    visit_reflect_entities(item, visit);
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            for index in 0..value.len() {
+                if let Some(item) = value.get_mut(index) {
+                    visit_reflect_entities(item, visit);
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    visit_reflect_entities(field, visit);
+                }
+            }
+        }
+        ReflectMut::Map(_) | ReflectMut::Value(_) => {}
+    }
+}
+
+/// Reflected [`Entity`] field of `component` on `entity`, which points to `target`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityReference {
+    pub entity: Entity,
+    /// Short type name of referencing component
+    pub component: String,
+    pub target: Entity,
+}
+
 /// Container struct for function to add default component in untyped style
 #[derive(Clone)]
 pub struct AddDefaultComponent {
@@ -85,6 +161,29 @@ impl AddDefaultComponent {
     }
 }
 
+/// Rule that entity with `component` must also contain `required` component
+#[derive(Clone)]
+pub struct ComponentRequirement {
+    pub component: TypeId,
+    pub component_name: String,
+    pub required: TypeId,
+    pub required_name: String,
+    /// Insert default `required` component
+    pub add_required: AddDefaultComponent,
+}
+
+impl ComponentRequirement {
+    pub fn new<T: Component, Required: Component + Default>() -> Self {
+        Self {
+            component: TypeId::of::<T>(),
+            component_name: get_short_name(std::any::type_name::<T>()),
+            required: TypeId::of::<Required>(),
+            required_name: get_short_name(std::any::type_name::<Required>()),
+            add_required: AddDefaultComponent::new::<Required>(),
+        }
+    }
+}
+
 /// Container struct for function to send default event
 #[derive(Clone)]
 pub struct SendEvent {
@@ -131,7 +230,9 @@ pub struct EditorRegistry {
     pub clone_components: Vec<CloneComponent>,
     pub remove_components: HashMap<TypeId, RemoveComponent>,
     pub send_events: Vec<SendEvent>,
-    pub silent: HashSet<TypeId>, //skip in inspector ui
+    pub silent: HashSet<TypeId>,      //skip in inspector ui
+    pub editor_only: HashSet<TypeId>, //skip in runtime clone
+    pub requirements: Vec<ComponentRequirement>,
 }
 
 impl EditorRegistry {
@@ -172,6 +273,25 @@ impl EditorRegistry {
         );
     }
 
+    /// Register new component, which will be hidden in editor UI and saved in prefab, but not copied by clone,
+    /// because its value must be unique for each entity
+    pub fn unique_register<
+        T: Component + Reflect + FromReflect + Default + Send + 'static + GetTypeRegistration,
+    >(
+        &mut self,
+    ) {
+        self.registry.write().register::<T>();
+        self.spawn_components.insert(
+            T::get_type_registration().type_id(),
+            AddDefaultComponent::new::<T>(),
+        );
+        self.silent.insert(T::get_type_registration().type_id());
+        self.remove_components.insert(
+            T::get_type_registration().type_id(),
+            RemoveComponent::new::<T>(),
+        );
+    }
+
     /// Register new component, which will be cloned with editor ui clone event
     pub fn only_clone_register<
         T: Component + Reflect + FromReflect + Default + Send + 'static + GetTypeRegistration,
@@ -181,6 +301,62 @@ impl EditorRegistry {
         self.clone_components.push(CloneComponent::new::<T>());
     }
 
+    /// Mark component as editor only. It will be skipped by [`EditorRegistry::clone_entity_runtime`]
+    pub fn editor_only_register<T: Component>(&mut self) {
+        self.editor_only.insert(TypeId::of::<T>());
+    }
+
+    /// Register rule that entity with T component must also contain Required component
+    pub fn require_register<T: Component, Required: Component + Default>(&mut self) {
+        self.requirements
+            .push(ComponentRequirement::new::<T, Required>());
+    }
+
+    /// All broken requirement rules of entity
+    pub fn missing_components(&self, entity: &EntityRef) -> Vec<ComponentRequirement> {
+        self.requirements
+            .iter()
+            .filter(|rule| {
+                entity.contains_type_id(rule.component) && !entity.contains_type_id(rule.required)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Reflected copy of registered component value of entity
+    pub fn copy_component(&self, entity: &EntityRef, id: &TypeId) -> Option<Box<dyn Reflect>> {
+        let registry = self.registry.read();
+        let reflect_component = registry.get(*id)?.data::<ReflectComponent>()?;
+        reflect_component
+            .reflect(*entity)
+            .map(|value| value.clone_value())
+    }
+
+    /// Apply copied component value to entity. Component is inserted if entity does not have it yet.
+    /// Returns false if component type is not registered
+    pub fn paste_component(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        id: &TypeId,
+        value: &dyn Reflect,
+    ) -> bool {
+        let Some(reflect_component) = self
+            .registry
+            .read()
+            .get(*id)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .cloned()
+        else {
+            return false;
+        };
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            return false;
+        };
+        reflect_component.apply_or_insert(&mut entity, value);
+        true
+    }
+
     /// Get spawn function for this component type
     pub fn get_spawn_command(&self, id: &TypeId) -> AddDefaultComponent {
         self.spawn_components.get(id).unwrap().clone()
@@ -200,6 +376,127 @@ impl EditorRegistry {
         }
     }
 
+    /// Clone all registered components like [`Self::clone_entity_flat`] and handle assets by `mode`
+    pub fn clone_entity_flat_with_mode(
+        &self,
+        cmds: &mut EntityCommands,
+        src: &EntityRef,
+        mode: AssetCloneMode,
+    ) {
+        self.clone_entity_flat(cmds, src);
+        if mode == AssetCloneMode::Linked {
+            link_assets(cmds, src);
+        }
+    }
+
+    /// Clone all registered components except editor only ones
+    pub fn clone_entity_runtime(&self, cmds: &mut EntityCommands, src: &EntityRef) {
+        for t in &self.clone_components {
+            if !self.editor_only.contains(&t.type_id) {
+                (t.func)(cmds, src);
+            }
+        }
+    }
+
+    /// Rewrite reflected [`Entity`] fields of cloned components: references to keys of `map` (source entities)
+    /// are replaced by their values (clones). References to entities outside of `map` are kept.
+    /// Only components of `map` values, which are copied by clone, are visited. Returns count of rewritten references
+    pub fn remap_entity_references(
+        &self,
+        world: &mut World,
+        map: &HashMap<Entity, Entity>,
+    ) -> usize {
+        let reflect_components = {
+            let registry = self.registry.read();
+            self.clone_components
+                .iter()
+                .filter(|clone| {
+                    clone.type_id != TypeId::of::<Parent>()
+                        && clone.type_id != TypeId::of::<Children>()
+                })
+                .filter_map(|clone| registry.get_type_data::<ReflectComponent>(clone.type_id))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let mut count = 0;
+        for clone in map.values() {
+            let Some(mut entity) = world.get_entity_mut(*clone) else {
+                continue;
+            };
+            for reflect_component in reflect_components.iter() {
+                let Some(mut component) = reflect_component.reflect_mut(&mut entity) else {
+                    continue;
+                };
+                // Component is marked as changed only if any reference was rewritten
+                let mut remapped = 0;
+                visit_reflect_entities(component.bypass_change_detection(), &mut |entity| {
+                    if let Some(new) = map.get(entity) {
+                        *entity = *new;
+                        remapped += 1;
+                    }
+                });
+                if remapped > 0 {
+                    component.set_changed();
+                    count += remapped;
+                }
+            }
+        }
+        count
+    }
+
+    /// Find reflected [`Entity`] fields of prefab entities, which point to any of `targets`.
+    /// Entities from `targets` are not scanned, hierarchy ([`Parent`], [`Children`]) is not counted.
+    /// Components are walked the same way as in [`EditorRegistry::remap_entity_references`]
+    pub fn find_entity_references(
+        &self,
+        world: &World,
+        targets: &HashSet<Entity>,
+    ) -> Vec<EntityReference> {
+        let reflect_components = {
+            let registry = self.registry.read();
+            self.clone_components
+                .iter()
+                .filter(|clone| {
+                    clone.type_id != TypeId::of::<Parent>()
+                        && clone.type_id != TypeId::of::<Children>()
+                })
+                .filter_map(|clone| {
+                    let registration = registry.get(clone.type_id)?;
+                    let reflect_component = registration.data::<ReflectComponent>()?;
+                    Some((
+                        get_short_name(registration.type_info().type_path()),
+                        reflect_component.clone(),
+                    ))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut references = vec![];
+        let mut found = vec![];
+        for entity_ref in world.iter_entities() {
+            if !entity_ref.contains::<PrefabMarker>() || targets.contains(&entity_ref.id()) {
+                continue;
+            }
+            for (name, reflect_component) in reflect_components.iter() {
+                let Some(component) = reflect_component.reflect(entity_ref) else {
+                    continue;
+                };
+                visit_reflect_entities(component.clone_value().as_mut(), &mut |entity| {
+                    if targets.contains(entity) {
+                        found.push(*entity);
+                    }
+                });
+                references.extend(found.drain(..).map(|target| EntityReference {
+                    entity: entity_ref.id(),
+                    component: name.clone(),
+                    target,
+                }));
+            }
+        }
+        references
+    }
+
     /// Register new event, which will be shown in editor UI and can be sent
     pub fn event_register<
         T: Event + Default + Resource + Reflect + Send + Clone + 'static + GetTypeRegistration,
@@ -233,6 +530,20 @@ pub trait EditorRegistryExt {
         &mut self,
     ) -> &mut Self;
 
+    /// register new component, which is saved in prefab, but hidden in editor UI and not copied by clone
+    fn editor_unique_registry<
+        T: Component + Reflect + FromReflect + Default + Send + 'static + GetTypeRegistration,
+    >(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Mark component as editor only, so it will not be copied by runtime clone
+    fn editor_only<T: Component>(&mut self) -> &mut Self;
+
+    /// Declare that entity with T component must also contain Required component.
+    /// Broken rules are shown in editor hierarchy
+    fn editor_require<T: Component, Required: Component + Default>(&mut self) -> &mut Self;
+
     /// Mark that if T component spawned, then Relation must be spawned too
     fn editor_relation<T, Relation>(&mut self) -> &mut Self
     where
@@ -302,6 +613,33 @@ impl EditorRegistryExt for App {
         self
     }
 
+    fn editor_unique_registry<
+        T: Component + Reflect + FromReflect + Default + Send + 'static + GetTypeRegistration,
+    >(
+        &mut self,
+    ) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .unique_register::<T>();
+        self.world.init_component::<T>();
+        self.register_type::<T>();
+        self
+    }
+
+    fn editor_only<T: Component>(&mut self) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .editor_only_register::<T>();
+        self
+    }
+
+    fn editor_require<T: Component, Required: Component + Default>(&mut self) -> &mut Self {
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .require_register::<T, Required>();
+        self
+    }
+
     fn editor_relation<T, Relation>(&mut self) -> &mut Self
     where
         T: Component,
@@ -405,9 +743,18 @@ fn relation_system<T: Component, Relation: Component + Default>(
 
 #[cfg(test)]
 mod tests {
-    use bevy::{ecs::system::CommandQueue, prelude::*};
-
-    use crate::prelude::{EditorRegistry, EditorRegistryExt, EditorRegistryPlugin};
+    use bevy::{
+        ecs::system::{CommandQueue, EntityCommand},
+        prelude::*,
+    };
+
+    use crate::prelude::{
+        AssetCloneMode, EditorRegistry, EditorRegistryExt, EditorRegistryPlugin, EditorUuid,
+        LinkedAssets,
+    };
+    use bevy::utils::HashSet;
+    use space_shared::PrefabMarker;
+    use std::any::TypeId;
 
     /// Test for clone logic in editor registry
     #[test]
@@ -439,4 +786,222 @@ mod tests {
             name
         );
     }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct EditorOnlyMarker;
+
+    /// Editor only components must not be copied by runtime clone
+    #[test]
+    fn clone_entity_runtime_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Name>();
+        app.editor_registry::<EditorOnlyMarker>();
+        app.editor_only::<EditorOnlyMarker>();
+
+        let e = app.world.spawn((Name::new("name"), EditorOnlyMarker)).id();
+
+        let new_e_id;
+        {
+            let mut command_queue = CommandQueue::default();
+            let mut cmds = Commands::new(&mut command_queue, &app.world);
+
+            let mut new_e = cmds.spawn_empty();
+            new_e_id = new_e.id();
+
+            app.world
+                .resource::<EditorRegistry>()
+                .clone_entity_runtime(&mut new_e, &app.world.entity(e));
+            command_queue.apply(&mut app.world);
+        }
+
+        assert!(app.world.entity(new_e_id).contains::<Name>());
+        assert!(!app.world.entity(new_e_id).contains::<EditorOnlyMarker>());
+    }
+
+    /// Linked clone must share mesh handle of source, linked clone of linked entity keeps original source
+    #[test]
+    fn clone_entity_linked_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Name>();
+
+        let mesh = Handle::<Mesh>::weak_from_u128(42);
+        let source = app.world.spawn((Name::new("name"), mesh.clone())).id();
+
+        let clone = |app: &mut App, src: Entity| {
+            let mut command_queue = CommandQueue::default();
+            let mut cmds = Commands::new(&mut command_queue, &app.world);
+            let mut new_e = cmds.spawn_empty();
+            let new_e_id = new_e.id();
+            app.world
+                .resource::<EditorRegistry>()
+                .clone_entity_flat_with_mode(
+                    &mut new_e,
+                    &app.world.entity(src),
+                    AssetCloneMode::Linked,
+                );
+            command_queue.apply(&mut app.world);
+            new_e_id
+        };
+        let linked = clone(&mut app, source);
+        let linked_twice = clone(&mut app, linked);
+
+        for e in [linked, linked_twice] {
+            assert_eq!(app.world.get::<Handle<Mesh>>(e), Some(&mesh));
+            assert_eq!(
+                app.world.get::<LinkedAssets>(e),
+                Some(&LinkedAssets { source })
+            );
+        }
+    }
+
+    /// Unique component must be saved with prefab, but not copied to clone
+    #[test]
+    fn clone_entity_skips_unique_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Name>();
+        app.editor_unique_registry::<EditorUuid>();
+
+        let e = app.world.spawn((Name::new("name"), EditorUuid::new())).id();
+
+        let new_e_id;
+        {
+            let mut command_queue = CommandQueue::default();
+            let mut cmds = Commands::new(&mut command_queue, &app.world);
+
+            let mut new_e = cmds.spawn_empty();
+            new_e_id = new_e.id();
+
+            app.world
+                .resource::<EditorRegistry>()
+                .clone_entity_flat(&mut new_e, &app.world.entity(e));
+            command_queue.apply(&mut app.world);
+        }
+
+        let registry = app.world.resource::<EditorRegistry>().clone();
+        assert!(registry
+            .registry
+            .read()
+            .contains(TypeId::of::<EditorUuid>()));
+        assert!(registry.silent.contains(&TypeId::of::<EditorUuid>()));
+        assert!(app.world.entity(new_e_id).contains::<Name>());
+        assert!(!app.world.entity(new_e_id).contains::<EditorUuid>());
+    }
+
+    /// Broken requirement rules must be reported and fixable by default component
+    #[test]
+    fn missing_components_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_require::<Name, Transform>();
+
+        let broken = app.world.spawn(Name::new("broken")).id();
+        let valid = app
+            .world
+            .spawn((Name::new("valid"), Transform::default()))
+            .id();
+
+        let registry = app.world.resource::<EditorRegistry>().clone();
+        assert!(registry
+            .missing_components(&app.world.entity(valid))
+            .is_empty());
+
+        let missing = registry.missing_components(&app.world.entity(broken));
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].required_name, "Transform");
+
+        missing[0]
+            .add_required
+            .clone()
+            .apply(broken, &mut app.world);
+        assert!(app.world.entity(broken).contains::<Transform>());
+    }
+
+    #[test]
+    fn copy_paste_component_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Transform>();
+
+        let source = app.world.spawn(Transform::from_xyz(1.0, 2.0, 3.0)).id();
+        let target = app.world.spawn_empty().id();
+        let id = TypeId::of::<Transform>();
+
+        let registry = app.world.resource::<EditorRegistry>().clone();
+        let value = registry
+            .copy_component(&app.world.entity(source), &id)
+            .unwrap();
+
+        // Missing component is inserted
+        assert!(registry.paste_component(&mut app.world, target, &id, value.as_ref()));
+        assert_eq!(
+            app.world.get::<Transform>(target).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+
+        // Existing component is overwritten
+        app.world.get_mut::<Transform>(source).unwrap().translation = Vec3::ONE;
+        let value = registry
+            .copy_component(&app.world.entity(source), &id)
+            .unwrap();
+        assert!(registry.paste_component(&mut app.world, target, &id, value.as_ref()));
+        assert_eq!(
+            app.world.get::<Transform>(target).unwrap().translation,
+            Vec3::ONE
+        );
+
+        assert!(!registry.paste_component(
+            &mut app.world,
+            target,
+            &TypeId::of::<Name>(),
+            value.as_ref()
+        ));
+    }
+
+    #[derive(Component, Reflect, Clone, Default)]
+    #[reflect(Component)]
+    struct Follow {
+        targets: Vec<Entity>,
+    }
+
+    /// References are found in nested reflected fields, hierarchy and target itself are skipped
+    #[test]
+    fn find_entity_references_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Follow>();
+
+        let target = app.world.spawn(PrefabMarker).id();
+        let child = app.world.spawn(PrefabMarker).set_parent(target).id();
+        let follower = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Follow {
+                    targets: vec![child, target],
+                },
+            ))
+            .id();
+        app.world.entity_mut(target).insert(Follow {
+            targets: vec![target],
+        });
+
+        let references = app
+            .world
+            .resource::<EditorRegistry>()
+            .find_entity_references(&app.world, &HashSet::from_iter([target]));
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].entity, follower);
+        assert_eq!(references[0].component, "Follow");
+        assert_eq!(references[0].target, target);
+    }
 }
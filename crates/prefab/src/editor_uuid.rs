@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet, Uuid},
+};
+use space_shared::PrefabMarker;
+use space_undo::ChangeChain;
+
+use crate::editor_registry::EditorRegistryExt;
+
+/// Plugin to give each prefab entity stable [`EditorUuid`]
+pub struct EditorUuidPlugin;
+
+impl Plugin for EditorUuidPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Uuid>();
+        app.editor_unique_registry::<EditorUuid>();
+        app.init_resource::<EditorUuidIndex>();
+        app.add_systems(PostUpdate, update_editor_uuids);
+    }
+}
+
+/// Stable id of prefab entity. Unlike [`Entity`], it is saved with prefab and stays the same between
+/// editor sessions and scene reloads, so it can be used to keep editor state of entity.
+/// Assigned to all [`PrefabMarker`] entities, which do not have it (including entities of old prefab files).
+/// Not copied by clone, duplicated ids (for example, of pasted entities) are replaced with new ones
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct EditorUuid(pub Uuid);
+
+impl EditorUuid {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+/// How many ids of despawned entities are kept to find entities respawned with the same id
+const DESPAWNED_UUIDS_LEN: usize = 1024;
+
+/// Current entity of each [`EditorUuid`]
+#[derive(Resource, Default)]
+pub struct EditorUuidIndex {
+    entities: HashMap<Uuid, Entity>,
+    /// Ids removed from index with their last entity, oldest first
+    despawned: VecDeque<(Uuid, Entity)>,
+}
+
+impl EditorUuidIndex {
+    pub fn get(&self, uuid: Uuid) -> Option<Entity> {
+        self.entities.get(&uuid).copied()
+    }
+}
+
+fn update_editor_uuids(
+    mut commands: Commands,
+    mut index: ResMut<EditorUuidIndex>,
+    mut change_chain: Option<ResMut<ChangeChain>>,
+    mut removed: RemovedComponents<EditorUuid>,
+    missing: Query<Entity, (With<PrefabMarker>, Without<EditorUuid>)>,
+    changed: Query<(Entity, &EditorUuid), Changed<EditorUuid>>,
+    uuids: Query<&EditorUuid>,
+) {
+    for entity in missing.iter() {
+        commands.entity(entity).insert(EditorUuid::new());
+    }
+
+    let removed = removed.read().collect::<HashSet<_>>();
+    if !removed.is_empty() {
+        let EditorUuidIndex {
+            entities,
+            despawned,
+        } = index.as_mut();
+        entities.retain(|uuid, entity| {
+            let gone =
+                removed.contains(entity) && !uuids.get(*entity).is_ok_and(|id| id.0 == *uuid);
+            if gone {
+                despawned.push_back((*uuid, *entity));
+            }
+            !gone
+        });
+        while despawned.len() > DESPAWNED_UUIDS_LEN {
+            despawned.pop_front();
+        }
+    }
+
+    for (entity, uuid) in changed.iter() {
+        if uuid.0.is_nil() {
+            commands.entity(entity).insert(EditorUuid::new());
+            continue;
+        }
+        match index.get(uuid.0) {
+            Some(prev) if prev == entity => {}
+            // Id is copied from living entity: paste or another instance of the same prefab
+            Some(prev) if uuids.get(prev) == Ok(uuid) => {
+                commands.entity(entity).insert(EditorUuid::new());
+            }
+            Some(prev) => {
+                // Entity was respawned by scene reload, so undo steps must target the new entity
+                if let Some(change_chain) = change_chain.as_mut() {
+                    change_chain.remap_entity(prev, entity);
+                }
+                index.entities.insert(uuid.0, entity);
+            }
+            None => {
+                let position = index
+                    .despawned
+                    .iter()
+                    .position(|(despawned, _)| *despawned == uuid.0);
+                if let Some((_, prev)) = position.and_then(|i| index.despawned.remove(i)) {
+                    if let Some(change_chain) = change_chain.as_mut() {
+                        change_chain.remap_entity(prev, entity);
+                    }
+                }
+                index.entities.insert(uuid.0, entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EditorRegistryPlugin;
+
+    fn uuid_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.add_plugins(EditorUuidPlugin);
+        app.init_resource::<ChangeChain>();
+        app
+    }
+
+    fn uuid_of(app: &App, entity: Entity) -> Uuid {
+        app.world.get::<EditorUuid>(entity).unwrap().0
+    }
+
+    #[test]
+    fn test_missing_uuid_is_assigned_and_indexed() {
+        let mut app = uuid_test_app();
+        let entity = app.world.spawn(PrefabMarker).id();
+        let nil = app
+            .world
+            .spawn((PrefabMarker, EditorUuid(Uuid::nil())))
+            .id();
+        app.update();
+        app.update();
+
+        for entity in [entity, nil] {
+            let uuid = uuid_of(&app, entity);
+            assert!(!uuid.is_nil());
+            assert_eq!(
+                app.world.resource::<EditorUuidIndex>().get(uuid),
+                Some(entity)
+            );
+        }
+    }
+
+    /// Copy of living entity must get its own id, original keeps the old one
+    #[test]
+    fn test_duplicated_uuid_is_replaced() {
+        let mut app = uuid_test_app();
+        let original = app.world.spawn((PrefabMarker, EditorUuid::new())).id();
+        app.update();
+
+        let uuid = uuid_of(&app, original);
+        let copy = app.world.spawn((PrefabMarker, EditorUuid(uuid))).id();
+        app.update();
+        app.update();
+
+        assert_eq!(uuid_of(&app, original), uuid);
+        assert_ne!(uuid_of(&app, copy), uuid);
+        let index = app.world.resource::<EditorUuidIndex>();
+        assert_eq!(index.get(uuid), Some(original));
+        assert_eq!(index.get(uuid_of(&app, copy)), Some(copy));
+    }
+
+    /// Entity respawned with the same id (scene reload) takes the index entry and undo steps of the old one
+    #[test]
+    fn test_respawned_uuid_remaps_entity() {
+        let mut app = uuid_test_app();
+        let uuid = EditorUuid::new();
+        let old = app.world.spawn((PrefabMarker, uuid)).id();
+        app.update();
+
+        app.world.despawn(old);
+        app.update();
+        assert_eq!(app.world.resource::<EditorUuidIndex>().get(uuid.0), None);
+
+        let new = app.world.spawn((PrefabMarker, uuid)).id();
+        app.update();
+
+        assert_eq!(uuid_of(&app, new), uuid.0);
+        assert_eq!(
+            app.world.resource::<EditorUuidIndex>().get(uuid.0),
+            Some(new)
+        );
+        assert_eq!(app.world.resource::<ChangeChain>().current_entity(old), new);
+    }
+
+    #[test]
+    fn test_despawned_uuids_are_pruned() {
+        let mut app = uuid_test_app();
+        let entities = (0..DESPAWNED_UUIDS_LEN + 1)
+            .map(|_| app.world.spawn((PrefabMarker, EditorUuid::new())).id())
+            .collect::<Vec<_>>();
+        app.update();
+
+        for entity in entities {
+            app.world.despawn(entity);
+        }
+        app.update();
+
+        let index = app.world.resource::<EditorUuidIndex>();
+        assert!(index.entities.is_empty());
+        assert_eq!(index.despawned.len(), DESPAWNED_UUIDS_LEN);
+    }
+}
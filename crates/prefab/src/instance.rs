@@ -0,0 +1,591 @@
+use std::{any::TypeId, sync::Arc, time::Duration};
+
+use bevy::{
+    prelude::*,
+    scene::DynamicEntity,
+    time::common_conditions::on_timer,
+    utils::{HashMap, HashSet, Uuid},
+};
+use space_shared::{EditorState, PrefabMarker};
+use space_undo::{
+    begin_change_group, despawn_recursive_with_undo, end_change_group, AddedEntity, AppAutoUndo,
+    NewChange,
+};
+
+use crate::{
+    editor_registry::{EditorRegistry, EditorRegistryExt},
+    editor_uuid::EditorUuid,
+    load::{deserialize_prefab, restore_prefab_hierarchy, spawn_serialized},
+    save::{build_prefab_scene, ChildrenPrefab},
+};
+
+/// How often overrides of prefab instances are compared with prefab files
+const OVERRIDES_UPDATE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Plugin for prefab instances, which remember their source prefab file and track overrides
+pub struct PrefabInstancePlugin;
+
+impl Plugin for PrefabInstancePlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_registry::<InstanceOf>();
+        app.editor_registry::<PrefabInstance>();
+        // Editor only, but "Allow editing" toggle and unpack must be undoable
+        app.auto_undo::<EditableInstance>();
+        app.init_resource::<PrefabSources>();
+
+        app.add_systems(
+            Update,
+            update_prefab_overrides
+                .run_if(in_state(EditorState::Editor))
+                .run_if(on_timer(OVERRIDES_UPDATE_PERIOD)),
+        );
+    }
+}
+
+/// Root of prefab instance. Its children are spawned from prefab file at asset `path`
+#[derive(Component, Reflect, Default, Clone, Debug)]
+#[reflect(Component)]
+pub struct InstanceOf {
+    pub path: String,
+}
+
+/// Editor only marker of instance root, which content can be edited as overrides.
+/// Content of instances without it is read-only in editor
+#[derive(Component, Default, Clone, Debug)]
+pub struct EditableInstance;
+
+/// Entity spawned from prefab file. `source` is bits of the entity id in the file (see [`Entity::to_bits`]).
+/// `source_uuid` is [`EditorUuid`] of the entity in the file, it is nil for files saved before entities had uuids.
+/// Instance entity gets its own uuid, so several instances of the same prefab do not share it
+#[derive(Component, Reflect, Default, Clone, Debug)]
+#[reflect(Component)]
+pub struct PrefabInstance {
+    pub source: u64,
+    pub source_uuid: Uuid,
+}
+
+/// Components of [`PrefabInstance`] entity, which differ from its prefab file. Updated periodically in editor,
+/// so it is not stored in undo and is found again when undo brings [`PrefabInstance`] back
+#[derive(Component, Default, Clone, Debug, PartialEq, Eq)]
+pub struct PrefabOverrides {
+    /// Components with changed values
+    pub changed: Vec<String>,
+    /// Components, which are not stored in prefab
+    pub added: Vec<String>,
+    /// Prefab components, which were removed from instance
+    pub removed: Vec<String>,
+}
+
+impl PrefabOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Cache of parsed prefab files used to find overrides. `None` is stored for files which failed to load
+#[derive(Resource, Default)]
+pub struct PrefabSources {
+    pub scenes: HashMap<String, Option<Arc<DynamicScene>>>,
+}
+
+/// Prefab files are stored in assets folder, same as saved scenes
+fn prefab_file_path(path: &str) -> String {
+    format!("assets/{}", path)
+}
+
+/// Spawn content of prefab file at asset `path` as children of `root` and mark `root` as [`InstanceOf`] the prefab.
+/// Spawned entities are editable [`PrefabMarker`] entities with [`PrefabInstance`]. Returns all spawned entities
+pub fn instantiate_prefab(
+    world: &mut World,
+    root: Entity,
+    path: &str,
+) -> Result<Vec<Entity>, String> {
+    let data = std::fs::read_to_string(prefab_file_path(path)).map_err(|err| err.to_string())?;
+    instantiate_prefab_data(world, root, path, &data)
+}
+
+fn instantiate_prefab_data(
+    world: &mut World,
+    root: Entity,
+    path: &str,
+    data: &str,
+) -> Result<Vec<Entity>, String> {
+    let map = spawn_serialized(world, data)?;
+    for (stored, spawned) in map.iter() {
+        let mut entity = world.entity_mut(*spawned);
+        let source_uuid = entity
+            .get::<EditorUuid>()
+            .map_or(Uuid::nil(), |uuid| uuid.0);
+        entity.insert((
+            PrefabInstance {
+                source: stored.to_bits(),
+                source_uuid,
+            },
+            EditorUuid::new(),
+        ));
+    }
+    let spawned = map.values().copied().collect::<Vec<_>>();
+    let top_level = restore_prefab_hierarchy(world, map);
+    world
+        .entity_mut(root)
+        .insert(InstanceOf {
+            path: path.to_string(),
+        })
+        .push_children(&top_level);
+    Ok(spawned)
+}
+
+/// Respawn content of instance `root` from its prefab file, dropping all overrides and added children.
+/// Stored as one undo step
+pub fn revert_to_prefab(world: &mut World, root: Entity) -> Result<(), String> {
+    let Some(instance) = world.get::<InstanceOf>(root).cloned() else {
+        return Err(format!("{:?} is not prefab instance", root));
+    };
+    let data =
+        std::fs::read_to_string(prefab_file_path(&instance.path)).map_err(|err| err.to_string())?;
+
+    begin_change_group(world, "Revert to prefab");
+    let content = world
+        .get::<Children>(root)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    despawn_recursive_with_undo(world, &content, "Revert to prefab");
+    let result = instantiate_prefab_data(world, root, &instance.path, &data);
+    if let Ok(spawned) = &result {
+        for entity in spawned.iter() {
+            world.send_event(NewChange {
+                change: Arc::new(AddedEntity { entity: *entity }),
+            });
+        }
+    }
+    end_change_group(world);
+    result.map(|_| ())
+}
+
+/// Break link of instance `root` with its prefab file: [`InstanceOf`] and [`PrefabInstance`] are removed,
+/// so its content becomes ordinary entities. Stored as one undo step, which links the instance back
+pub fn unpack_instance(world: &mut World, root: Entity) {
+    if world.get::<InstanceOf>(root).is_none() {
+        return;
+    }
+    begin_change_group(world, "Unpack prefab instance");
+    world
+        .entity_mut(root)
+        .remove::<(InstanceOf, EditableInstance)>();
+    let mut queue = world
+        .get::<Children>(root)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    while let Some(entity) = queue.pop() {
+        // Nested instances keep their own link
+        if world.get::<InstanceOf>(entity).is_none() {
+            if let Some(children) = world.get::<Children>(entity) {
+                queue.extend(children.iter());
+            }
+        }
+        world
+            .entity_mut(entity)
+            .remove::<(PrefabInstance, PrefabOverrides)>();
+    }
+    end_change_group(world);
+}
+
+/// Instance root, which content includes `entity`. None for entities not spawned from prefab
+pub fn instance_root(world: &World, entity: Entity) -> Option<Entity> {
+    world.get::<PrefabInstance>(entity)?;
+    let mut ancestor = world.get::<Parent>(entity).map(Parent::get);
+    while let Some(id) = ancestor {
+        if world.get::<InstanceOf>(id).is_some() {
+            return Some(id);
+        }
+        ancestor = world.get::<Parent>(id).map(Parent::get);
+    }
+    None
+}
+
+/// Instance root of `entity`, if the entity is read-only content of instance without [`EditableInstance`]
+pub fn read_only_instance_root(world: &World, entity: Entity) -> Option<Entity> {
+    instance_root(world, entity).filter(|root| world.get::<EditableInstance>(*root).is_none())
+}
+
+/// Write content of instance `root` back to its prefab file. Entities keep their ids in the file,
+/// so other instances of the prefab keep tracking overrides. Entities added to this instance
+/// will appear in other instances after [`revert_to_prefab`]. Returns path of the written file
+pub fn apply_overrides_to_prefab(world: &mut World, root: Entity) -> Result<String, String> {
+    let Some(instance) = world.get::<InstanceOf>(root).cloned() else {
+        return Err(format!("{:?} is not prefab instance", root));
+    };
+    let content = world
+        .get::<Children>(root)
+        .map(|children| {
+            children
+                .iter()
+                .copied()
+                .filter(|child| world.get::<PrefabMarker>(*child).is_some())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let mut scene = build_prefab_scene(world, &content);
+
+    // Entities from prefab keep source ids, new entities and duplicates get unused ids
+    let source_of = |entity: Entity| {
+        world
+            .get::<PrefabInstance>(entity)
+            .map(|instance| Entity::from_bits(instance.source))
+    };
+    let mut next_index = scene
+        .entities
+        .iter()
+        .filter_map(|e| source_of(e.entity))
+        .map(|source| source.index() + 1)
+        .max()
+        .unwrap_or_default();
+    let mut used = HashSet::new();
+    let mut ids = HashMap::new();
+    // Entities keep uuids of the file too, new entities store their own uuids
+    let mut file_uuids = HashMap::new();
+    for dynamic_entity in scene.entities.iter() {
+        let own_uuid = world
+            .get::<EditorUuid>(dynamic_entity.entity)
+            .map_or(Uuid::nil(), |uuid| uuid.0);
+        let (id, uuid) = match source_of(dynamic_entity.entity) {
+            Some(source) if used.insert(source) => {
+                let source_uuid = world
+                    .get::<PrefabInstance>(dynamic_entity.entity)
+                    .map_or(Uuid::nil(), |instance| instance.source_uuid);
+                let uuid = if source_uuid.is_nil() {
+                    own_uuid
+                } else {
+                    source_uuid
+                };
+                (source, uuid)
+            }
+            _ => {
+                next_index += 1;
+                (Entity::from_raw(next_index - 1), own_uuid)
+            }
+        };
+        ids.insert(dynamic_entity.entity, id);
+        file_uuids.insert(dynamic_entity.entity, uuid);
+    }
+
+    for dynamic_entity in scene.entities.iter_mut() {
+        let uuid = file_uuids[&dynamic_entity.entity];
+        for component in dynamic_entity.components.iter_mut() {
+            let is_uuid = component
+                .get_represented_type_info()
+                .is_some_and(|info| info.type_id() == TypeId::of::<EditorUuid>());
+            if is_uuid {
+                *component = Box::new(EditorUuid(uuid));
+            }
+        }
+        dynamic_entity.entity = ids[&dynamic_entity.entity];
+        dynamic_entity.components.retain(|c| {
+            c.get_represented_type_info().map_or(true, |info| {
+                info.type_id() != TypeId::of::<PrefabInstance>()
+            })
+        });
+        for component in dynamic_entity.components.iter_mut() {
+            if let Some(children) = component.downcast_mut::<ChildrenPrefab>() {
+                for child in children.0.iter_mut() {
+                    *child = ids[child];
+                }
+            }
+        }
+    }
+
+    let data = scene
+        .serialize_ron(world.resource::<AppTypeRegistry>())
+        .map_err(|err| err.to_string())?;
+    let file_path = prefab_file_path(&instance.path);
+    std::fs::write(&file_path, data).map_err(|err| err.to_string())?;
+
+    for (entity, id) in ids {
+        let source_uuid = file_uuids[&entity];
+        world.entity_mut(entity).insert(PrefabInstance {
+            source: id.to_bits(),
+            source_uuid,
+        });
+    }
+    world
+        .resource_mut::<PrefabSources>()
+        .scenes
+        .remove(&instance.path);
+    Ok(file_path)
+}
+
+/// Nearest [`InstanceOf`] prefab path of entity ancestors
+fn instance_path(world: &World, entity: Entity) -> Option<String> {
+    let root = instance_root(world, entity)?;
+    world
+        .get::<InstanceOf>(root)
+        .map(|instance| instance.path.clone())
+}
+
+/// Compare registered components of instance entity with components stored in prefab
+fn find_overrides(
+    world: &World,
+    entity: Entity,
+    source: Option<&DynamicEntity>,
+    types: &[TypeId],
+) -> PrefabOverrides {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let entity_ref = world.entity(entity);
+    let mut overrides = PrefabOverrides::default();
+    for type_id in types.iter() {
+        let Some(registration) = registry.get(*type_id) else {
+            continue;
+        };
+        let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+            continue;
+        };
+        let name = registration.type_info().type_path_table().short_path();
+        let current = reflect_component.reflect(entity_ref);
+        let stored = source.and_then(|source| {
+            source.components.iter().find(|c| {
+                c.get_represented_type_info()
+                    .is_some_and(|info| info.type_id() == *type_id)
+            })
+        });
+        match (current, stored) {
+            (Some(current), Some(stored)) => {
+                if current.reflect_partial_eq(stored.as_reflect()) == Some(false) {
+                    overrides.changed.push(name.to_string());
+                }
+            }
+            (Some(_), None) => overrides.added.push(name.to_string()),
+            (None, Some(_)) => overrides.removed.push(name.to_string()),
+            (None, None) => {}
+        }
+    }
+    overrides
+}
+
+/// [`EditorUuid`] of entity stored in prefab file
+fn stored_uuid(entity: &DynamicEntity) -> Option<Uuid> {
+    entity
+        .components
+        .iter()
+        .find_map(|component| EditorUuid::from_reflect(component.as_reflect()))
+        .map(|uuid| uuid.0)
+}
+
+fn update_prefab_overrides(world: &mut World) {
+    let skip = [
+        TypeId::of::<ChildrenPrefab>(),
+        TypeId::of::<PrefabInstance>(),
+        TypeId::of::<EditorUuid>(),
+        TypeId::of::<InstanceOf>(),
+        TypeId::of::<PrefabMarker>(),
+    ];
+    let types = world
+        .resource::<EditorRegistry>()
+        .registry
+        .read()
+        .iter()
+        .map(|registration| registration.type_id())
+        .filter(|type_id| !skip.contains(type_id))
+        .collect::<Vec<_>>();
+
+    let instances = world
+        .query::<(Entity, &PrefabInstance)>()
+        .iter(world)
+        .map(|(entity, instance)| {
+            (
+                entity,
+                Entity::from_bits(instance.source),
+                instance.source_uuid,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, source, source_uuid) in instances {
+        let Some(path) = instance_path(world, entity) else {
+            continue;
+        };
+        if !world.resource::<PrefabSources>().scenes.contains_key(&path) {
+            let scene = std::fs::read_to_string(prefab_file_path(&path))
+                .map_err(|err| err.to_string())
+                .and_then(|data| deserialize_prefab(world, &data));
+            let scene = match scene {
+                Ok(scene) => Some(Arc::new(scene)),
+                Err(err) => {
+                    warn!("Failed to load prefab {} to find overrides: {}", path, err);
+                    None
+                }
+            };
+            world
+                .resource_mut::<PrefabSources>()
+                .scenes
+                .insert(path.clone(), scene);
+        }
+        let Some(scene) = world.resource::<PrefabSources>().scenes[&path].clone() else {
+            continue;
+        };
+
+        // Entity ids in file can change when file is saved again, uuids stay
+        let stored = if source_uuid.is_nil() {
+            scene.entities.iter().find(|e| e.entity == source)
+        } else {
+            scene
+                .entities
+                .iter()
+                .find(|e| stored_uuid(e) == Some(source_uuid))
+        };
+        let overrides = find_overrides(world, entity, stored, &types);
+        if world.get::<PrefabOverrides>(entity) != Some(&overrides) {
+            world.entity_mut(entity).insert(overrides);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use space_undo::UndoPlugin;
+
+    use super::*;
+    use crate::{
+        editor_registry::EditorRegistryPlugin, editor_uuid::EditorUuidPlugin,
+        save::serialize_entities,
+    };
+
+    fn instance_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(UndoPlugin)
+            .add_plugins(EditorRegistryPlugin)
+            .add_plugins(EditorUuidPlugin)
+            .add_plugins(PrefabInstancePlugin);
+        app.editor_registry::<Name>();
+        app.editor_registry::<ChildrenPrefab>();
+        app
+    }
+
+    /// Write prefab file with "body" entity and its "wheel" child. Returns asset path of the file
+    fn write_test_prefab(app: &mut App, name: &str) -> String {
+        let body = app
+            .world
+            .spawn((PrefabMarker, Name::new("body"), EditorUuid::new()))
+            .id();
+        let wheel = app
+            .world
+            .spawn((PrefabMarker, Name::new("wheel"), EditorUuid::new()))
+            .set_parent(body)
+            .id();
+        let data = serialize_entities(&app.world, &[body]).unwrap();
+        app.world.entity_mut(body).despawn_recursive();
+        assert!(app.world.get_entity(wheel).is_none());
+
+        let path = format!("space_prefab_tests/{}.scn.ron", name);
+        let file_path = prefab_file_path(&path);
+        std::fs::create_dir_all(std::path::Path::new(&file_path).parent().unwrap()).unwrap();
+        std::fs::write(&file_path, data).unwrap();
+        path
+    }
+
+    fn remove_test_prefab(path: &str) {
+        let file_path = prefab_file_path(path);
+        let _ = std::fs::remove_file(&file_path);
+        // Folders are removed by the last finished test, only if they are empty
+        for dir in std::path::Path::new(&file_path).ancestors().skip(1) {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+
+    fn find_named(world: &World, root: Entity, name: &str) -> Entity {
+        let mut queue = vec![root];
+        while let Some(entity) = queue.pop() {
+            if world
+                .get::<Name>(entity)
+                .is_some_and(|n| n.as_str() == name)
+            {
+                return entity;
+            }
+            if let Some(children) = world.get::<Children>(entity) {
+                queue.extend(children.iter());
+            }
+        }
+        panic!("{} not found in {:?}", name, root);
+    }
+
+    #[test]
+    fn test_revert_to_prefab_drops_overrides() {
+        let mut app = instance_test_app();
+        let path = write_test_prefab(&mut app, "revert");
+        let root = app.world.spawn(PrefabMarker).id();
+        let spawned = instantiate_prefab(&mut app.world, root, &path).unwrap();
+        assert_eq!(spawned.len(), 2);
+        app.update();
+
+        let wheel = find_named(&app.world, root, "wheel");
+        app.world
+            .entity_mut(wheel)
+            .insert(Name::new("renamed wheel"));
+        let added = app
+            .world
+            .spawn((PrefabMarker, Name::new("added")))
+            .set_parent(root)
+            .id();
+
+        revert_to_prefab(&mut app.world, root).unwrap();
+        app.update();
+        remove_test_prefab(&path);
+
+        assert!(app.world.get_entity(wheel).is_none());
+        assert!(app.world.get_entity(added).is_none());
+        assert_eq!(app.world.get::<Children>(root).unwrap().len(), 1);
+        let body = find_named(&app.world, root, "body");
+        let wheel = find_named(&app.world, body, "wheel");
+        assert!(app.world.get::<PrefabInstance>(wheel).is_some());
+        assert_eq!(app.world.get::<InstanceOf>(root).unwrap().path, path);
+    }
+
+    #[test]
+    fn test_applied_overrides_keep_source_ids() {
+        let mut app = instance_test_app();
+        let path = write_test_prefab(&mut app, "apply");
+        let root = app.world.spawn(PrefabMarker).id();
+        instantiate_prefab(&mut app.world, root, &path).unwrap();
+        app.update();
+
+        let body = find_named(&app.world, root, "body");
+        let wheel = find_named(&app.world, root, "wheel");
+        let body_source = app.world.get::<PrefabInstance>(body).unwrap().clone();
+        let wheel_source = app.world.get::<PrefabInstance>(wheel).unwrap().clone();
+        assert!(!wheel_source.source_uuid.is_nil());
+
+        app.world.entity_mut(wheel).insert(Name::new("big wheel"));
+        let added = app
+            .world
+            .spawn((PrefabMarker, Name::new("door"), EditorUuid::new()))
+            .set_parent(body)
+            .id();
+        let added_uuid = app.world.get::<EditorUuid>(added).unwrap().0;
+        apply_overrides_to_prefab(&mut app.world, root).unwrap();
+
+        let other = app.world.spawn(PrefabMarker).id();
+        instantiate_prefab(&mut app.world, other, &path).unwrap();
+        app.update();
+        remove_test_prefab(&path);
+
+        let other_body = find_named(&app.world, other, "body");
+        let other_wheel = find_named(&app.world, other, "big wheel");
+        let other_door = find_named(&app.world, other, "door");
+        let other_body_source = app.world.get::<PrefabInstance>(other_body).unwrap();
+        assert_eq!(other_body_source.source, body_source.source);
+        assert_eq!(other_body_source.source_uuid, body_source.source_uuid);
+        let other_wheel_source = app.world.get::<PrefabInstance>(other_wheel).unwrap();
+        assert_eq!(other_wheel_source.source, wheel_source.source);
+        assert_eq!(other_wheel_source.source_uuid, wheel_source.source_uuid);
+
+        // New child gets unused id in file and keeps its own uuid there
+        let door_source = app.world.get::<PrefabInstance>(other_door).unwrap();
+        assert_ne!(door_source.source, body_source.source);
+        assert_ne!(door_source.source, wheel_source.source);
+        assert_eq!(door_source.source_uuid, added_uuid);
+        assert_eq!(
+            app.world.get::<PrefabInstance>(added).unwrap().source,
+            door_source.source
+        );
+    }
+}
@@ -7,6 +7,12 @@ compile_error!("feature \"f32\" and feature \"f64\" cannot be enabled at the sam
 
 /// Contains all component for prefab logic
 pub mod component;
+/// Contains stable ids of prefab entities
+pub mod editor_uuid;
+/// Contains prefab instances with override tracking
+pub mod instance;
+/// Contains linked duplicates, which share mesh and material assets
+pub mod linked;
 /// Contains systems for loading prefab from file
 pub mod load;
 /// Module contains all prefab plugin extensions
@@ -31,7 +37,14 @@ pub mod ext {
 pub mod prelude {
     pub use crate::component::*;
     pub use crate::editor_registry::*;
-    pub use crate::load::PrefabBundle;
+    pub use crate::editor_uuid::{EditorUuid, EditorUuidIndex};
+    pub use crate::instance::{
+        apply_overrides_to_prefab, instance_root, instantiate_prefab, read_only_instance_root,
+        revert_to_prefab, unpack_instance, EditableInstance, InstanceOf, PrefabInstance,
+        PrefabOverrides,
+    };
+    pub use crate::linked::{link_assets, unlink_assets, LinkedAssets};
+    pub use crate::load::{load_prefab_to_world, PrefabBundle, PrefabLoaded, PrefabUnloaded};
     pub use crate::plugins::*;
     pub use crate::save::*;
     pub use crate::PrefabSet;
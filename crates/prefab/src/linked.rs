@@ -0,0 +1,117 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use space_shared::IsolationDimmed;
+
+use crate::{
+    component::{MaterialPrefab, MeshPrimitivePrefab},
+    PrefabSet,
+};
+
+/// Plugin to keep assets of linked duplicates in sync with their source entities
+pub struct LinkedAssetsPlugin;
+
+impl Plugin for LinkedAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            sync_linked_assets.after(PrefabSet::PrefabChangeApply),
+        );
+    }
+}
+
+/// Duplicate, which shares [`Mesh`] and [`StandardMaterial`] handles with `source` entity instead of owning copies.
+/// Prefab mesh and material of `source` are copied to linked entity on change, so saved scene stays consistent.
+/// It is not reflected, so link is not saved and not copied by clone. Saved linked entities are loaded as independent
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinkedAssets {
+    pub source: Entity,
+}
+
+/// Share mesh and material handles of `src` with entity of `cmds` and mark it as [`LinkedAssets`].
+/// Duplicate of linked entity is linked to the same source. Entities without mesh and material are not linked
+pub fn link_assets(cmds: &mut EntityCommands, src: &EntityRef) {
+    let mesh = src.get::<Handle<Mesh>>();
+    // Linked entity shares material of source, not its dimmed copy
+    let material = src
+        .get::<Handle<StandardMaterial>>()
+        .map(|material| IsolationDimmed::original_material(src.get(), material));
+    if mesh.is_none() && material.is_none() {
+        return;
+    }
+    if let Some(mesh) = mesh {
+        cmds.insert(mesh.clone());
+    }
+    if let Some(material) = material {
+        cmds.insert(material.clone());
+    }
+    let source = src
+        .get::<LinkedAssets>()
+        .map_or(src.id(), |linked| linked.source);
+    cmds.insert(LinkedAssets { source });
+}
+
+/// Drop link of entity and rebuild its own mesh and material from its prefab components
+pub fn unlink_assets(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    entity_mut.remove::<LinkedAssets>();
+    if let Some(mut mesh) = entity_mut.get_mut::<MeshPrimitivePrefab>() {
+        mesh.set_changed();
+    }
+    if let Some(mut material) = entity_mut.get_mut::<MaterialPrefab>() {
+        material.set_changed();
+    }
+}
+
+fn sync_linked_assets(
+    mut commands: Commands,
+    linked: Query<(
+        Entity,
+        &LinkedAssets,
+        Option<&Handle<Mesh>>,
+        Option<&Handle<StandardMaterial>>,
+        Option<&IsolationDimmed>,
+    )>,
+    sources: Query<(
+        Option<Ref<MeshPrimitivePrefab>>,
+        Option<Ref<MaterialPrefab>>,
+        Option<&Handle<Mesh>>,
+        Option<&Handle<StandardMaterial>>,
+        Option<&IsolationDimmed>,
+    )>,
+) {
+    for (entity, link, mesh, material, dimmed) in linked.iter() {
+        let Ok((
+            source_mesh_prefab,
+            source_material_prefab,
+            source_mesh,
+            source_material,
+            source_dimmed,
+        )) = sources.get(link.source)
+        else {
+            // Source was despawned, linked entity keeps the last shared assets
+            commands.entity(entity).remove::<LinkedAssets>();
+            continue;
+        };
+
+        let mut cmds = commands.entity(entity);
+        if let Some(prefab) = source_mesh_prefab.filter(|prefab| prefab.is_changed()) {
+            cmds.insert(prefab.clone());
+        }
+        if let Some(prefab) = source_material_prefab.filter(|prefab| prefab.is_changed()) {
+            cmds.insert(prefab.clone());
+        }
+        if let Some(source_mesh) = source_mesh.filter(|source| Some(*source) != mesh) {
+            cmds.insert(source_mesh.clone());
+        }
+        // Dimmed copies of isolation view are not shared, only original materials are compared
+        let material =
+            material.map(|material| IsolationDimmed::original_material(dimmed, material));
+        let source_material = source_material
+            .map(|source| IsolationDimmed::original_material(source_dimmed, source))
+            .filter(|source| Some(*source) != material);
+        if let Some(source_material) = source_material {
+            cmds.insert(source_material.clone());
+        }
+    }
+}
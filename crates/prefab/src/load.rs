@@ -1,5 +1,6 @@
-use bevy::prelude::*;
-use bevy_scene_hook::SceneHook;
+use bevy::{prelude::*, scene::serde::SceneDeserializer, utils::HashMap};
+use bevy_scene_hook::{SceneHook, SceneHooked};
+use serde::de::DeserializeSeed;
 use space_shared::PrefabMarker;
 
 use crate::prelude::EditorRegistryExt;
@@ -44,6 +45,8 @@ pub struct PrefabAutoChild;
 impl Plugin for LoadPlugin {
     fn build(&self, app: &mut App) {
         app.editor_registry::<PrefabLoader>();
+        app.add_event::<PrefabLoaded>();
+        app.add_event::<PrefabUnloaded>();
 
         app.add_systems(
             Update,
@@ -56,9 +59,111 @@ impl Plugin for LoadPlugin {
                 .before(load_prefab),
         );
         app.add_systems(Update, auto_children);
+        app.add_systems(
+            Update,
+            prefab_loaded_event.after(bevy_scene_hook::Systems::SceneHookRunner),
+        );
     }
 }
 
+/// Sent once prefab is fully spawned into world: all entities and components are in place
+/// and hierarchy is restored
+#[derive(Event, Clone, Debug)]
+pub struct PrefabLoaded {
+    /// Entity with [`PrefabLoader`]. `None` for prefab loaded as editor scene, which has no common root
+    pub root: Option<Entity>,
+    /// Top level spawned entities
+    pub entities: Vec<Entity>,
+    /// Source path of prefab, if it was loaded from file
+    pub path: Option<String>,
+    /// Map from entities stored in prefab file to spawned ones. Empty for prefabs spawned by [`PrefabBundle`]
+    pub entity_map: HashMap<Entity, Entity>,
+}
+
+/// Sent when all prefab entities are despawned at once (for example, by "Clear all entities" in editor)
+#[derive(Event, Clone, Debug)]
+pub struct PrefabUnloaded {
+    /// Despawned top level entities
+    pub entities: Vec<Entity>,
+}
+
+/// Spawn entities from prefab RON string (for example, created by [`crate::save::serialize_entities`]).
+/// All spawned entities are marked with [`PrefabMarker`]. Returns map from stored entities to spawned ones
+pub fn spawn_serialized(world: &mut World, data: &str) -> Result<HashMap<Entity, Entity>, String> {
+    let scene = deserialize_prefab(world, data)?;
+
+    let mut map = HashMap::new();
+    scene
+        .write_to_world(world, &mut map)
+        .map_err(|err| err.to_string())?;
+    for entity in map.values() {
+        world.entity_mut(*entity).insert(PrefabMarker);
+    }
+    Ok(map)
+}
+
+/// Parse prefab RON string with types from [`AppTypeRegistry`]
+pub(crate) fn deserialize_prefab(world: &World, data: &str) -> Result<DynamicScene, String> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut deserializer = ron::de::Deserializer::from_str(data).map_err(|err| err.to_string())?;
+    SceneDeserializer {
+        type_registry: &registry,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|err| err.to_string())
+}
+
+/// Load prefab file saved by editor directly into world without asset server and editor UI.
+/// All stored component types must be registered in [`AppTypeRegistry`] before the call,
+/// for example by [`EditorRegistryExt::editor_registry`] or by adding [`crate::plugins::PrefabPlugin`].
+/// Loaded entities are marked with [`PrefabMarker`], their hierarchy is restored from [`ChildrenPrefab`]
+/// and top level entities are attached to new root entity. Returns the root
+pub fn load_prefab_to_world(
+    world: &mut World,
+    path: impl AsRef<std::path::Path>,
+) -> Result<Entity, String> {
+    let data = std::fs::read_to_string(path.as_ref()).map_err(|err| err.to_string())?;
+    let map = spawn_serialized(world, &data)?;
+    let top_level = restore_prefab_hierarchy(world, map);
+
+    let root = world
+        .spawn((TransformBundle::default(), VisibilityBundle::default()))
+        .push_children(&top_level)
+        .id();
+    Ok(root)
+}
+
+/// Restore hierarchy of entities spawned by [`spawn_serialized`] from [`ChildrenPrefab`].
+/// Returns spawned entities without parent in order of stored ids
+pub(crate) fn restore_prefab_hierarchy(
+    world: &mut World,
+    map: HashMap<Entity, Entity>,
+) -> Vec<Entity> {
+    let mut stored = map.into_iter().collect::<Vec<_>>();
+    stored.sort_by_key(|(stored, _)| *stored);
+    let spawned = stored
+        .into_iter()
+        .map(|(_, spawned)| spawned)
+        .collect::<Vec<_>>();
+
+    for entity in spawned.iter() {
+        let Some(children) = world.entity_mut(*entity).take::<ChildrenPrefab>() else {
+            continue;
+        };
+        let children = children
+            .0
+            .into_iter()
+            .filter(|child| world.get_entity(*child).is_some())
+            .collect::<Vec<_>>();
+        world.entity_mut(*entity).push_children(&children);
+    }
+
+    spawned
+        .into_iter()
+        .filter(|entity| world.get::<Parent>(*entity).is_none())
+        .collect()
+}
+
 /// This component is mark that prefab should be loaded
 #[derive(Component, Default, Reflect, Clone)]
 #[reflect(Component)]
@@ -115,6 +220,31 @@ fn load_prefab(
     }
 }
 
+/// Scene hook marks scene instance with [`SceneHooked`] when all its entities are spawned
+fn prefab_loaded_event(
+    query: Query<(&Parent, Option<&Children>), (Added<SceneHooked>, With<PrefabAutoChild>)>,
+    loaders: Query<&PrefabLoader>,
+    mut events: EventWriter<PrefabLoaded>,
+) {
+    for (parent, children) in query.iter() {
+        let Ok(loader) = loaders.get(parent.get()) else {
+            continue;
+        };
+        info!(
+            "Loaded prefab {} to {:?} with {} entities",
+            loader.path,
+            parent.get(),
+            children.map_or(0, |children| children.len())
+        );
+        events.send(PrefabLoaded {
+            root: Some(parent.get()),
+            entities: children.map_or(vec![], |children| children.to_vec()),
+            path: Some(loader.path.clone()),
+            entity_map: HashMap::default(),
+        });
+    }
+}
+
 fn conflict_resolve(
     mut commands: Commands,
     query: Query<Entity, (With<PrefabAutoChild>, With<PrefabMarker>)>,
@@ -139,3 +269,105 @@ fn auto_children(
         cmds.remove::<ChildrenPrefab>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use space_shared::PrefabMarker;
+
+    use super::load_prefab_to_world;
+    use crate::{
+        prelude::{EditorRegistryExt, EditorRegistryPlugin},
+        save::{serialize_entities, serialize_subtree, ChildrenPrefab},
+    };
+
+    /// Prefab saved by editor must be loaded back with the same hierarchy
+    #[test]
+    fn load_prefab_to_world_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Name>();
+        app.editor_registry::<ChildrenPrefab>();
+
+        let parent = app.world.spawn((PrefabMarker, Name::new("parent"))).id();
+        let child = app.world.spawn((PrefabMarker, Name::new("child"))).id();
+        app.world.entity_mut(parent).add_child(child);
+
+        let data = serialize_entities(&app.world, &[parent]).unwrap();
+        let path = std::env::temp_dir().join("space_prefab_load_to_world_test.scn.ron");
+        std::fs::write(&path, data).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(app.world.resource::<AppTypeRegistry>().clone());
+        let root = load_prefab_to_world(&mut world, &path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let top_level = world.get::<Children>(root).unwrap().to_vec();
+        assert_eq!(top_level.len(), 1);
+        assert_eq!(world.get::<Name>(top_level[0]).unwrap().as_str(), "parent");
+
+        let children = world.get::<Children>(top_level[0]).unwrap().to_vec();
+        assert_eq!(children.len(), 1);
+        assert_eq!(world.get::<Name>(children[0]).unwrap().as_str(), "child");
+        assert!(world.get::<ChildrenPrefab>(top_level[0]).is_none());
+    }
+
+    /// Subtree prefab must not depend on position of its root in scene
+    #[test]
+    fn serialize_subtree_test() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(EditorRegistryPlugin);
+        app.editor_registry::<Name>();
+        app.editor_registry::<Transform>();
+        app.editor_registry::<ChildrenPrefab>();
+
+        let root = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("root"),
+                Transform::from_xyz(10.0, 0.0, -5.0),
+            ))
+            .id();
+        let child = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("child"),
+                Transform::from_xyz(0.0, 1.0, 0.0),
+            ))
+            .id();
+        let other = app.world.spawn((PrefabMarker, Name::new("other"))).id();
+        app.world.entity_mut(root).add_child(child);
+
+        let data = serialize_subtree(&app.world, root).unwrap();
+        assert_eq!(
+            app.world.get::<Transform>(root).unwrap().translation,
+            Vec3::new(10.0, 0.0, -5.0)
+        );
+        assert!(app.world.get_entity(other).is_some());
+        let path = std::env::temp_dir().join("space_prefab_serialize_subtree_test.scn.ron");
+        std::fs::write(&path, data).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(app.world.resource::<AppTypeRegistry>().clone());
+        let loaded = load_prefab_to_world(&mut world, &path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let top_level = world.get::<Children>(loaded).unwrap().to_vec();
+        assert_eq!(top_level.len(), 1);
+        assert_eq!(world.get::<Name>(top_level[0]).unwrap().as_str(), "root");
+        assert_eq!(
+            *world.get::<Transform>(top_level[0]).unwrap(),
+            Transform::IDENTITY
+        );
+
+        let children = world.get::<Children>(top_level[0]).unwrap().to_vec();
+        assert_eq!(
+            world.get::<Transform>(children[0]).unwrap().translation,
+            Vec3::Y
+        );
+    }
+}
@@ -15,8 +15,9 @@ use bevy_scene_hook::HookPlugin;
 use space_shared::{LightAreaToggle, PrefabMarker};
 
 use crate::{
-    component, editor_registry::EditorRegistryExt, load, prelude::EditorRegistryPlugin, save,
-    spawn_system, EditorState, PrefabSet,
+    component, editor_registry::EditorRegistryExt, editor_uuid::EditorUuidPlugin,
+    instance::PrefabInstancePlugin, linked::LinkedAssetsPlugin, load,
+    prelude::EditorRegistryPlugin, save, spawn_system, EditorState, PrefabSet,
 };
 
 use component::*;
@@ -131,12 +132,17 @@ impl Plugin for BasePrefabPlugin {
 
         app.add_systems(Update, camera_render_graph_creation);
 
+        app.editor_require::<Camera, Transform>();
+        app.editor_require::<Camera2d, Camera>();
+        app.editor_require::<Camera3d, Camera>();
+
         app.editor_registry::<PlayerStart>();
         app.editor_relation::<PlayerStart, Transform>();
         app.editor_relation::<PlayerStart, GlobalTransform>();
         app.editor_relation::<PlayerStart, Visibility>();
         app.editor_relation::<PlayerStart, ViewVisibility>();
         app.editor_relation::<PlayerStart, InheritedVisibility>();
+        app.editor_require::<PlayerStart, Transform>();
 
         app.editor_relation::<Transform, GlobalTransform>();
 
@@ -148,6 +154,7 @@ impl Plugin for BasePrefabPlugin {
         app.editor_relation::<PointLight, CubemapFrusta>();
         app.editor_relation::<PointLight, Transform>();
         app.editor_relation::<PointLight, Visibility>();
+        app.editor_require::<PointLight, Transform>();
 
         app.editor_registry::<DirectionalLight>();
         app.editor_relation::<DirectionalLight, CascadesFrusta>();
@@ -156,12 +163,14 @@ impl Plugin for BasePrefabPlugin {
         app.editor_relation::<DirectionalLight, CascadesVisibleEntities>();
         app.editor_relation::<DirectionalLight, Transform>();
         app.editor_relation::<DirectionalLight, Visibility>();
+        app.editor_require::<DirectionalLight, Transform>();
 
         app.editor_registry::<SpotLight>();
         app.editor_relation::<SpotLight, VisibleEntities>();
         app.editor_relation::<SpotLight, Frustum>();
         app.editor_relation::<SpotLight, Transform>();
         app.editor_relation::<SpotLight, Visibility>();
+        app.editor_require::<SpotLight, Transform>();
 
         app.add_systems(OnEnter(EditorState::Game), spawn_player_start);
 
@@ -189,6 +198,9 @@ impl Plugin for BasePrefabPlugin {
 
         app.add_plugins(SavePrefabPlugin);
         app.add_plugins(LoadPlugin);
+        app.add_plugins(EditorUuidPlugin);
+        app.add_plugins(PrefabInstancePlugin);
+        app.add_plugins(LinkedAssetsPlugin);
     }
 }
 
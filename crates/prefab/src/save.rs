@@ -80,6 +80,84 @@ fn delete_prepared_children(mut commands: Commands, query: Query<Entity, With<Ch
     }
 }
 
+/// Serialize entities with all their [`PrefabMarker`] descendants to prefab RON string.
+/// Only components from [`EditorRegistry`] are stored, hierarchy inside the set is stored in [`ChildrenPrefab`]
+pub fn serialize_entities(world: &World, roots: &[Entity]) -> Result<String, ron::Error> {
+    build_prefab_scene(world, roots).serialize_ron(world.resource::<AppTypeRegistry>())
+}
+
+/// Serialize entity with all its [`PrefabMarker`] descendants to self-contained prefab RON string.
+/// Root is stored with identity [`Transform`], so descendants keep transforms relative to it
+pub fn serialize_subtree(world: &World, root: Entity) -> Result<String, ron::Error> {
+    let mut scene = build_prefab_scene(world, &[root]);
+    if let Some(dynamic_entity) = scene.entities.iter_mut().find(|e| e.entity == root) {
+        for component in dynamic_entity.components.iter_mut() {
+            let is_transform = component
+                .get_represented_type_info()
+                .is_some_and(|info| info.type_id() == TypeId::of::<Transform>());
+            if is_transform {
+                *component = Box::new(Transform::IDENTITY);
+            }
+        }
+    }
+    scene.serialize_ron(world.resource::<AppTypeRegistry>())
+}
+
+/// Scene with entities and their [`PrefabMarker`] descendants ready to be stored as prefab
+pub(crate) fn build_prefab_scene(world: &World, roots: &[Entity]) -> DynamicScene {
+    let mut entities = vec![];
+    let mut queue = roots.to_vec();
+    while let Some(entity) = queue.pop() {
+        let Some(entity_ref) = world.get_entity(entity) else {
+            continue;
+        };
+        if !entity_ref.contains::<PrefabMarker>() || entities.contains(&entity) {
+            continue;
+        }
+        entities.push(entity);
+        if let Some(children) = entity_ref.get::<Children>() {
+            queue.extend(children.iter());
+        }
+    }
+
+    let registry = world.resource::<EditorRegistry>().clone();
+    let allow_types: Vec<TypeId> = registry
+        .registry
+        .read()
+        .iter()
+        .map(|a| a.type_id())
+        .collect();
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .allow_all()
+        .with_filter(SceneFilter::Allowlist(HashSet::from_iter(
+            allow_types.iter().cloned(),
+        )))
+        .extract_entities(entities.iter().copied())
+        .build();
+
+    for dynamic_entity in scene.entities.iter_mut() {
+        dynamic_entity.components.retain(|c| {
+            c.get_represented_type_info().map_or(true, |info| {
+                info.type_id() != TypeId::of::<ChildrenPrefab>()
+            })
+        });
+        if let Some(children) = world.get::<Children>(dynamic_entity.entity) {
+            let children = children
+                .iter()
+                .filter(|child| entities.contains(child))
+                .copied()
+                .collect::<Vec<_>>();
+            if !children.is_empty() {
+                dynamic_entity
+                    .components
+                    .push(Box::new(ChildrenPrefab(children)));
+            }
+        }
+    }
+
+    scene
+}
+
 /// Convert world scene to prefab
 pub fn serialize_scene(world: &mut World) {
     let config = world.resource::<SaveConfig>().clone();
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use bevy_scene_hook::{HookedSceneBundle, SceneHook};
 
 use super::component::*;
+use crate::linked::LinkedAssets;
 
 /// System responsible for spawning GLTF objects in the scene
 pub fn spawn_scene(
@@ -58,10 +59,13 @@ pub fn spawn_scene(
     }
 }
 
-/// System to sync [`Mesh`] and [`MeshPrimitivePrefab`]
+/// System to sync [`Mesh`] and [`MeshPrimitivePrefab`]. [`LinkedAssets`] entities share mesh of their source
 pub fn sync_mesh(
     mut commands: Commands,
-    query: Query<(Entity, &MeshPrimitivePrefab), Changed<MeshPrimitivePrefab>>,
+    query: Query<
+        (Entity, &MeshPrimitivePrefab),
+        (Changed<MeshPrimitivePrefab>, Without<LinkedAssets>),
+    >,
     mut meshs: ResMut<Assets<Mesh>>,
 ) {
     for (e, pref) in query.iter() {
@@ -70,10 +74,10 @@ pub fn sync_mesh(
     }
 }
 
-/// System to sync [`StandardMaterial`] and [`MaterialPrefab`]
+/// System to sync [`StandardMaterial`] and [`MaterialPrefab`]. [`LinkedAssets`] entities share material of their source
 pub fn sync_material(
     mut commands: Commands,
-    query: Query<(Entity, &MaterialPrefab), Changed<MaterialPrefab>>,
+    query: Query<(Entity, &MaterialPrefab), (Changed<MaterialPrefab>, Without<LinkedAssets>)>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
 ) {
@@ -7,8 +7,9 @@ pub mod ext {
 
 pub mod prelude {
     pub use crate::{
-        EditorCameraMarker, EditorEvent, EditorPrefabPath, EditorSet, EditorState, PrefabMarker,
-        PrefabMemoryCache, SelectParent,
+        simulation_running, EditorCameraMarker, EditorEvent, EditorPrefabPath, EditorRunRequest,
+        EditorRunState, EditorRunStateChanged, EditorSet, EditorState, IsolationDimmed,
+        PrefabMarker, PrefabMemoryCache, SelectParent, SimulationState,
     };
 }
 
@@ -46,6 +47,106 @@ pub enum EditorSet {
     Game,
 }
 
+/// Run state of game logic. Paused state stops `Update` systems of [`EditorSet::Game`]
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulationState {
+    pub paused: bool,
+    /// Run game systems for one frame while paused. Reset at the end of frame in which they ran
+    pub step: bool,
+}
+
+/// Run condition for game systems, which can be paused from editor
+pub fn simulation_running(state: Res<SimulationState>) -> bool {
+    !state.paused || state.step
+}
+
+/// Public run state of editor, derived from [`EditorState`] and [`SimulationState`].
+/// Plugins can use it in run conditions, for example `.run_if(in_state(EditorRunState::Playing))`
+#[derive(States, Default, Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum EditorRunState {
+    /// Scene is edited, game logic is not running
+    #[default]
+    Editing,
+    /// Play mode, game logic is running
+    Playing,
+    /// Play mode with paused game logic
+    Paused,
+}
+
+impl EditorRunState {
+    pub fn new(editor_state: &EditorState, simulation: &SimulationState) -> Self {
+        match editor_state {
+            EditorState::Game if simulation.paused => Self::Paused,
+            EditorState::Game => Self::Playing,
+            EditorState::Loading | EditorState::Editor | EditorState::GamePrepare => Self::Editing,
+        }
+    }
+
+    /// Destructive scene edits (delete, clear) are allowed only while editing
+    pub fn is_editing(&self) -> bool {
+        *self == Self::Editing
+    }
+}
+
+/// Sent when [`EditorRunState`] is changed
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditorRunStateChanged {
+    pub from: EditorRunState,
+    pub to: EditorRunState,
+}
+
+/// Request to change [`EditorRunState`] from UI or plugins
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditorRunRequest {
+    /// Save scene and start play mode
+    Play,
+    /// Stop play mode and return to editing
+    Stop,
+    Pause,
+    Resume,
+    /// Run game logic for one frame while paused
+    Step,
+}
+
+/// Apply [`EditorRunRequest`] events to [`EditorState`] and [`SimulationState`]
+pub fn apply_run_requests(
+    mut requests: EventReader<EditorRunRequest>,
+    editor_state: Res<State<EditorState>>,
+    mut next_editor_state: ResMut<NextState<EditorState>>,
+    mut simulation: ResMut<SimulationState>,
+) {
+    for request in requests.read() {
+        match request {
+            EditorRunRequest::Play if *editor_state.get() == EditorState::Editor => {
+                next_editor_state.set(EditorState::GamePrepare);
+            }
+            EditorRunRequest::Stop if *editor_state.get() == EditorState::Game => {
+                next_editor_state.set(EditorState::Editor);
+            }
+            EditorRunRequest::Pause => simulation.paused = true,
+            EditorRunRequest::Resume => simulation.paused = false,
+            EditorRunRequest::Step if simulation.paused => simulation.step = true,
+            _ => {}
+        }
+    }
+}
+
+/// Keep [`EditorRunState`] in sync with [`EditorState`] and [`SimulationState`], send [`EditorRunStateChanged`]
+pub fn update_editor_run_state(
+    editor_state: Res<State<EditorState>>,
+    simulation: Res<SimulationState>,
+    run_state: Res<State<EditorRunState>>,
+    mut next_run_state: ResMut<NextState<EditorRunState>>,
+    mut events: EventWriter<EditorRunStateChanged>,
+) {
+    let new = EditorRunState::new(editor_state.get(), &simulation);
+    let old = *run_state.get();
+    if new != old && next_run_state.0 != Some(new) {
+        next_run_state.set(new);
+        events.send(EditorRunStateChanged { from: old, to: new });
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct PrefabMemoryCache {
     pub scene: Option<Handle<DynamicScene>>,
@@ -63,6 +164,8 @@ pub enum EditorEvent {
     Load(EditorPrefabPath),
     Save(EditorPrefabPath),
     LoadGltfAsPrefab(String),
+    /// Export all prefab entities to GLB file
+    ExportGltf(String),
     StartGame,
 }
 
@@ -74,6 +177,26 @@ pub struct SelectParent {
     pub parent: Entity,
 }
 
+/// Editor only marker with original material of entity dimmed by isolation view
+#[derive(Component)]
+pub struct IsolationDimmed {
+    pub original: Handle<StandardMaterial>,
+    pub dimmed: Handle<StandardMaterial>,
+}
+
+impl IsolationDimmed {
+    /// Material of entity without isolation dimming. Linked duplicates and exports must use it instead of `current`
+    pub fn original_material<'a>(
+        dimmed: Option<&'a Self>,
+        current: &'a Handle<StandardMaterial>,
+    ) -> &'a Handle<StandardMaterial> {
+        match dimmed {
+            Some(dimmed) if *current == dimmed.dimmed => &dimmed.original,
+            _ => current,
+        }
+    }
+}
+
 /// Component that determines if the light gizmo shall be visualized
 #[derive(Component, Default, Clone, Reflect)]
 #[reflect(Component)]
@@ -1,8 +1,11 @@
 // Remove after update to newer rust version
 #![allow(clippy::type_complexity)]
-use std::sync::Arc;
+use std::{any::TypeId, sync::Arc};
 
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    utils::{get_short_name, HashMap},
+};
 
 const MAX_REFLECT_RECURSION: i32 = 10;
 const AUTO_UNDO_LATENCY: i32 = 2;
@@ -107,9 +110,15 @@ fn update_change_chain(
     mut change_chain: ResMut<ChangeChain>,
     mut events: EventReader<NewChange>,
 ) {
+    if settings.is_changed() {
+        change_chain.trim(settings.max_change_chain_size);
+    }
+
     //collect buffer
     let mut events_on_current_frame = 0;
     for event in events.read() {
+        // Every recorded editor action (spawn, delete, reparent, component edit) passes here
+        debug!("New change: {}", event.change.debug_text());
         buffer.push(event.clone());
         events_on_current_frame += 1;
     }
@@ -118,10 +127,6 @@ fn update_change_chain(
         return;
     }
 
-    if buffer.is_empty() {
-        return;
-    }
-
     //Drop buffer to vec of arc
     let mut new_changes = vec![];
     for b in buffer.iter() {
@@ -132,22 +137,43 @@ fn update_change_chain(
     //Clear buffer
     buffer.clear();
 
-    match new_changes.len().cmp(&1) {
-        std::cmp::Ordering::Less => {}
-        std::cmp::Ordering::Equal => {
-            change_chain.changes.push(new_changes[0].clone());
+    if let Some(group) = change_chain.group.as_mut() {
+        if !new_changes.is_empty() {
+            group.latency = AUTO_UNDO_LATENCY + 1;
+        }
+        group.changes.extend(new_changes);
+        if group.depth > 0 {
+            return;
         }
-        std::cmp::Ordering::Greater => {
+        // Wait for auto undo changes of closed group
+        group.latency -= 1;
+        if group.latency > 0 {
+            return;
+        }
+        let group = change_chain.group.take().unwrap();
+        if !group.changes.is_empty() {
+            info!("{}: {} changes recorded", group.name, group.changes.len());
             change_chain.changes.push(Arc::new(ManyChanges {
-                changes: new_changes,
+                changes: group.changes,
+                name: Some(group.name),
             }));
         }
-    };
-
-    if change_chain.changes.len() > settings.max_change_chain_size {
-        let count = change_chain.changes.len() - settings.max_change_chain_size;
-        change_chain.changes.drain(0..count);
+    } else {
+        match new_changes.len().cmp(&1) {
+            std::cmp::Ordering::Less => {}
+            std::cmp::Ordering::Equal => {
+                change_chain.changes.push(new_changes[0].clone());
+            }
+            std::cmp::Ordering::Greater => {
+                change_chain.changes.push(Arc::new(ManyChanges {
+                    changes: new_changes,
+                    name: None,
+                }));
+            }
+        };
     }
+
+    change_chain.trim(settings.max_change_chain_size);
 }
 
 fn clear_one_frame_ignore(
@@ -169,27 +195,8 @@ fn undo_redo_logic(world: &mut World) {
                 let mut reader = events.get_reader();
                 for event in reader.read(&events) {
                     match event {
-                        UndoRedo::Undo => {
-                            if let Some(change) = change_chain.changes.pop() {
-                                let res = change.revert(world, &change_chain.entity_remap).unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
-                                }
-                                change_chain.changes_for_redo.push(change);
-                            }
-                        }
-                        UndoRedo::Redo => {
-                            if let Some(change) = change_chain.changes_for_redo.pop() {
-                                let inverse_change = change.get_inverse();
-                                let res = inverse_change
-                                    .revert(world, &change_chain.entity_remap)
-                                    .unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
-                                }
-                                change_chain.changes.push(change);
-                            }
-                        }
+                        UndoRedo::Undo => change_chain.undo(world),
+                        UndoRedo::Redo => change_chain.redo(world),
                     }
                 }
             }
@@ -203,11 +210,111 @@ pub struct ChangeChain {
     pub changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
     pub changes_for_redo: Vec<Arc<dyn EditorChange + Send + Sync>>,
     entity_remap: HashMap<Entity, Entity>,
+    group: Option<ChangeGroup>,
+}
+
+/// Opened group of changes, which will be pushed to change chain as one step
+struct ChangeGroup {
+    name: String,
+    changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+    /// Count of not closed [`begin_change_group`] calls
+    depth: usize,
+    /// Frames to wait for auto undo changes after group is closed
+    latency: i32,
+}
+
+/// Open named group of changes. All changes sent until [`end_change_group`] will be undone/redone as one step.
+/// Nested groups are merged into outer one
+pub fn begin_change_group(world: &mut World, name: impl Into<String>) {
+    let mut change_chain = world.resource_mut::<ChangeChain>();
+    if let Some(group) = change_chain.group.as_mut() {
+        group.depth += 1;
+        group.latency = AUTO_UNDO_LATENCY + 1;
+    } else {
+        change_chain.group = Some(ChangeGroup {
+            name: name.into(),
+            changes: vec![],
+            depth: 1,
+            latency: AUTO_UNDO_LATENCY + 1,
+        });
+    }
+}
+
+/// Close group of changes opened by [`begin_change_group`].
+/// Auto undo changes, which are detected a few frames later, are still collected in this group
+pub fn end_change_group(world: &mut World) {
+    let mut change_chain = world.resource_mut::<ChangeChain>();
+    if let Some(group) = change_chain.group.as_mut() {
+        group.depth = group.depth.saturating_sub(1);
+    } else {
+        warn!("end_change_group called without opened change group");
+    }
+}
+
+/// Undo the last step immediately, without sending [`UndoRedo::Undo`] event.
+///
+/// Ordering: [`NewChange`] events are pushed to the chain by [`UndoSet::UpdateAll`] only after a frame
+/// without new events, and auto undo changes are detected in [`UndoSet::PerType`] a few frames after the edit.
+/// Run `app.update()` until [`undo_len`] grows before calling this, otherwise an older step is undone.
+/// Components restored by undo are ignored by auto undo, so they are not recorded as new steps
+pub fn undo(world: &mut World) {
+    world.resource_scope::<ChangeChain, _>(|world, mut change_chain| change_chain.undo(world));
+}
+
+/// Redo the last undone step immediately, without sending [`UndoRedo::Redo`] event.
+/// Any new recorded change clears the redo stack. Same ordering as in [`undo`]
+pub fn redo(world: &mut World) {
+    world.resource_scope::<ChangeChain, _>(|world, mut change_chain| change_chain.redo(world));
+}
+
+/// Count of steps which can be undone. Opened change group and not yet collected changes are not counted
+pub fn undo_len(world: &World) -> usize {
+    world.resource::<ChangeChain>().undo_len()
+}
+
+/// Count of steps which can be redone
+pub fn redo_len(world: &World) -> usize {
+    world.resource::<ChangeChain>().redo_len()
+}
+
+/// Despawn entities with all their descendants as one undo step. Every despawned entity is recorded
+/// as [`RemovedEntity`] and parent-children links as [`RestoreHierarchy`], so undo restores the whole subtree.
+/// Components are restored by auto undo
+pub fn despawn_recursive_with_undo(world: &mut World, roots: &[Entity], name: impl Into<String>) {
+    let mut entities = vec![];
+    let mut queue = roots.to_vec();
+    while let Some(entity) = queue.pop() {
+        if world.get_entity(entity).is_none() || entities.contains(&entity) {
+            continue;
+        }
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            queue.extend(children.iter());
+        }
+    }
+    let hierarchy = RestoreHierarchy::record(world, &entities);
+
+    begin_change_group(world, name);
+    for entity in entities.iter() {
+        world.send_event(NewChange {
+            change: Arc::new(RemovedEntity { entity: *entity }),
+        });
+    }
+    world.send_event(NewChange {
+        change: Arc::new(hierarchy),
+    });
+    for entity in roots.iter() {
+        if let Some(entity) = world.get_entity_mut(*entity) {
+            entity.despawn_recursive();
+        }
+    }
+    end_change_group(world);
 }
 
 #[derive(Resource, Reflect)]
 #[reflect(Resource, Default)]
 pub struct ChangeChainSettings {
+    /// Maximum count of undo steps, the oldest ones are dropped. Group of changes is one step
     pub max_change_chain_size: usize,
 }
 
@@ -220,20 +327,45 @@ impl Default for ChangeChainSettings {
 }
 
 impl ChangeChain {
+    /// Applied changes from oldest to newest
+    pub fn undo_stack(&self) -> &[Arc<dyn EditorChange + Send + Sync>] {
+        &self.changes
+    }
+
+    /// Undone changes. Last one will be redone first
+    pub fn redo_stack(&self) -> &[Arc<dyn EditorChange + Send + Sync>] {
+        &self.changes_for_redo
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.changes_for_redo.len()
+    }
+
+    /// Failed change is still moved to redo stack, so broken change can not block undo of older ones
     pub fn undo(&mut self, world: &mut World) {
         if let Some(change) = self.changes.pop() {
-            let res = change.revert(world, &self.entity_remap).unwrap();
+            info!("Undo {}", change.describe());
+            match change.revert(world, &self.entity_remap) {
+                Ok(res) => self.update_remap(res),
+                Err(err) => error!("Failed to undo {}: {}", change.describe(), err),
+            }
             self.changes_for_redo.push(change);
-            self.update_remap(res);
         }
     }
 
     pub fn redo(&mut self, world: &mut World) {
         if let Some(change) = self.changes_for_redo.pop() {
+            info!("Redo {}", change.describe());
             let inverse_change = change.get_inverse();
-            let res = inverse_change.revert(world, &self.entity_remap).unwrap();
+            match inverse_change.revert(world, &self.entity_remap) {
+                Ok(res) => self.update_remap(res),
+                Err(err) => error!("Failed to redo {}: {}", change.describe(), err),
+            }
             self.changes.push(change);
-            self.update_remap(res);
         }
     }
 
@@ -247,12 +379,70 @@ impl ChangeChain {
             }
         }
     }
+
+    /// Entity, which replaced `entity` after it was respawned by undo/redo. Returns `entity` if it was not respawned
+    pub fn current_entity(&self, entity: Entity) -> Entity {
+        get_entity_with_remap(entity, &self.entity_remap)
+    }
+
+    /// Make all undo and redo steps target `new` entity instead of `old` one.
+    /// Used when entity is respawned outside of change chain (for example, by scene reload)
+    pub fn remap_entity(&mut self, old: Entity, new: Entity) {
+        for target in self.entity_remap.values_mut() {
+            if *target == old {
+                *target = new;
+            }
+        }
+        self.entity_remap.insert(old, new);
+    }
+
+    /// Drop the oldest undo steps and redo steps over `max_size`. Group is one step.
+    /// Remaining steps are newer than dropped ones, so they never need entities respawned by dropped steps
+    pub fn trim(&mut self, max_size: usize) {
+        if self.changes.len() > max_size {
+            let count = self.changes.len() - max_size;
+            self.changes.drain(0..count);
+        }
+        // Redo stack is redone from its end, so the farthest steps are at its start
+        if self.changes_for_redo.len() > max_size {
+            let count = self.changes_for_redo.len() - max_size;
+            self.changes_for_redo.drain(0..count);
+        }
+    }
 }
 
 pub fn get_entity_with_remap(entity: Entity, entity_remap: &HashMap<Entity, Entity>) -> Entity {
     *entity_remap.get(&entity).unwrap_or(&entity)
 }
 
+/// Get alive entity for change target. If target was despawned (for example, by undo of entity spawn),
+/// new entity will be spawned and added to `new_remap`, so redo of component changes after entity respawn stays consistent
+pub fn get_or_spawn_with_remap(
+    world: &mut World,
+    entity: Entity,
+    entity_remap: &HashMap<Entity, Entity>,
+    new_remap: &mut Vec<(Entity, Entity)>,
+) -> Entity {
+    let dst = get_entity_with_remap(entity, entity_remap);
+    if world.get_entity(dst).is_some() {
+        dst
+    } else {
+        let id = world.spawn_empty().id();
+        new_remap.push((entity, id));
+        id
+    }
+}
+
+/// Copy of reflected component value for revert. Fails instead of panic, if type can not be built from reflection
+fn reflect_clone<T: Reflect + FromReflect>(value: &T) -> Result<T, String> {
+    <T as FromReflect>::from_reflect(value).ok_or_else(|| {
+        format!(
+            "{} can not be created from reflection",
+            pretty_type_name::pretty_type_name::<T>()
+        )
+    })
+}
+
 pub trait EditorChange {
     fn revert(
         &self,
@@ -262,6 +452,11 @@ pub trait EditorChange {
 
     fn debug_text(&self) -> String;
 
+    /// Short human readable description of change for undo history
+    fn describe(&self) -> String {
+        self.debug_text()
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync>;
 }
 
@@ -273,7 +468,6 @@ pub enum ChangeResult {
 #[derive(Event)]
 pub enum UndoRedo {
     Undo,
-    // TODO in 0.4
     Redo,
 }
 
@@ -293,7 +487,12 @@ impl EditorChange for AddedEntity {
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
         let e = get_entity_with_remap(self.entity, entity_remap);
-        world.entity_mut(e).despawn_recursive();
+        // Entity could be despawned without recorded change, nothing to remove then
+        let Some(entity) = world.get_entity_mut(e) else {
+            warn!("Entity {:?} of undo step is already removed", e);
+            return Ok(ChangeResult::Success);
+        };
+        entity.despawn_recursive();
         world
             .resource_mut::<UndoIngnoreStorage>()
             .storage
@@ -306,6 +505,10 @@ impl EditorChange for AddedEntity {
         format!("Added Entity: {}", self.entity.index())
     }
 
+    fn describe(&self) -> String {
+        "Added Entity".to_string()
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(RemovedEntity {
             entity: self.entity,
@@ -349,6 +552,10 @@ impl EditorChange for RemovedEntity {
         format!("Removed Entity: {}", self.entity.index())
     }
 
+    fn describe(&self) -> String {
+        "Removed Entity".to_string()
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(AddedEntity {
             entity: self.entity,
@@ -371,7 +578,8 @@ impl<T: Component + Clone> EditorChange for ComponentChange<T> {
         let e = get_entity_with_remap(self.entity, entity_remap);
 
         world
-            .entity_mut(e)
+            .get_entity_mut(e)
+            .ok_or_else(|| format!("Entity {:?} not found for component change", e))?
             .insert(self.old_value.clone())
             .insert(OneFrameUndoIgnore::default());
         info!("Reverted ComponentChange for entity: {}", e.index());
@@ -382,6 +590,10 @@ impl<T: Component + Clone> EditorChange for ComponentChange<T> {
         format!("ComponentChange for entity {:?}", self.entity)
     }
 
+    fn describe(&self) -> String {
+        format!("Changed {}", get_short_name(std::any::type_name::<T>()))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(ComponentChange {
             old_value: self.new_value.clone(),
@@ -397,6 +609,17 @@ pub struct ReflectedComponentChange<T: Component + Reflect + FromReflect> {
     entity: Entity,
 }
 
+impl<T: Component + Reflect + FromReflect> ReflectedComponentChange<T> {
+    /// Change of component value made outside of auto undo (for example, whole gizmo drag)
+    pub fn new(entity: Entity, old_value: T, new_value: T) -> Self {
+        Self {
+            old_value,
+            new_value,
+            entity,
+        }
+    }
+}
+
 impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentChange<T> {
     fn revert(
         &self,
@@ -404,10 +627,12 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentCh
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
         let e = get_entity_with_remap(self.entity, entity_remap);
+        let old_value = reflect_clone(&self.old_value)?;
 
         world
-            .entity_mut(e)
-            .insert(<T as FromReflect>::from_reflect(&self.old_value).unwrap())
+            .get_entity_mut(e)
+            .ok_or_else(|| format!("Entity {:?} not found for component change", e))?
+            .insert(old_value)
             .insert(OneFrameUndoIgnore::default());
         world.send_event(UndoRedoApplied::<T> {
             entity: e,
@@ -429,6 +654,10 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentCh
         )
     }
 
+    fn describe(&self) -> String {
+        format!("Changed {}", get_short_name(std::any::type_name::<T>()))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(ReflectedComponentChange {
             old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
@@ -471,6 +700,10 @@ impl<T: Component + Clone> EditorChange for AddedComponent<T> {
         format!("AddedComponent for entity {:?}", self.entity)
     }
 
+    fn describe(&self) -> String {
+        format!("Added {}", get_short_name(std::any::type_name::<T>()))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(RemovedComponent {
             entity: self.entity,
@@ -517,6 +750,10 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedAddedCompon
         format!("ReflectedAddedComponent for entity {:?}", self.entity)
     }
 
+    fn describe(&self) -> String {
+        format!("Added {}", get_short_name(std::any::type_name::<T>()))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(ReflectedRemovedComponent {
             old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
@@ -537,21 +774,11 @@ impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
         let mut remap = vec![];
-        let dst = entity_remap.get(&self.entity).map_or_else(
-            || {
-                if world.get_entity(self.entity).is_some() {
-                    self.entity
-                } else {
-                    let id = world.spawn_empty().id();
-                    remap.push((self.entity, id));
-                    id
-                }
-            },
-            |remaped| *remaped,
-        );
+        let dst = get_or_spawn_with_remap(world, self.entity, entity_remap, &mut remap);
 
         world
-            .entity_mut(dst)
+            .get_entity_mut(dst)
+            .ok_or_else(|| format!("Entity {:?} not found for removed component", dst))?
             .insert(self.old_value.clone())
             .insert(OneFrameUndoIgnore::default());
 
@@ -564,6 +791,10 @@ impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
         format!("RemovedComponent for entity {:?}", self.entity)
     }
 
+    fn describe(&self) -> String {
+        format!("Removed {}", get_short_name(std::any::type_name::<T>()))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(AddedComponent {
             new_value: self.old_value.clone(),
@@ -584,22 +815,13 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComp
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
         let mut remap = vec![];
-        let dst = entity_remap.get(&self.entity).map_or_else(
-            || {
-                if world.get_entity(self.entity).is_some() {
-                    self.entity
-                } else {
-                    let id = world.spawn_empty().id();
-                    remap.push((self.entity, id));
-                    id
-                }
-            },
-            |remaped| *remaped,
-        );
+        let old_value = reflect_clone(&self.old_value)?;
+        let dst = get_or_spawn_with_remap(world, self.entity, entity_remap, &mut remap);
 
         world
-            .entity_mut(dst)
-            .insert(<T as FromReflect>::from_reflect(&self.old_value).unwrap())
+            .get_entity_mut(dst)
+            .ok_or_else(|| format!("Entity {:?} not found for removed component", dst))?
+            .insert(old_value)
             .insert(OneFrameUndoIgnore::default());
         world.send_event(UndoRedoApplied::<T> {
             entity: dst,
@@ -618,6 +840,10 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComp
         format!("ReflectedRemovedComponent for entity {:?}", self.entity)
     }
 
+    fn describe(&self) -> String {
+        format!("Removed {}", get_short_name(std::any::type_name::<T>()))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(ReflectedAddedComponent {
             new_value: <T as FromReflect>::from_reflect(&self.old_value).unwrap(),
@@ -626,8 +852,186 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComp
     }
 }
 
+/// Parent of entity and its position in parent [`Children`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HierarchyPlace {
+    pub parent: Option<Entity>,
+    pub index: usize,
+    pub transform: Option<Transform>,
+}
+
+impl HierarchyPlace {
+    fn of(world: &World, entity: Entity) -> Self {
+        let parent = world.get::<Parent>(entity).map(Parent::get);
+        let index = parent
+            .and_then(|parent| world.get::<Children>(parent))
+            .and_then(|children| children.iter().position(|child| *child == entity))
+            .unwrap_or(0);
+        Self {
+            parent,
+            index,
+            transform: world.get::<Transform>(entity).copied(),
+        }
+    }
+}
+
+/// Change of entity parent. Restores exact position in parent [`Children`] and local [`Transform`]
+pub struct ReparentEntity {
+    pub entity: Entity,
+    pub old: HierarchyPlace,
+    pub new: HierarchyPlace,
+}
+
+impl ReparentEntity {
+    /// Apply `reparent` function to world and record hierarchy change of entity.
+    /// Auto undo of touched entities is skipped, so change will be recorded only once
+    pub fn record(world: &mut World, entity: Entity, reparent: impl FnOnce(&mut World)) -> Self {
+        let old = HierarchyPlace::of(world, entity);
+        reparent(world);
+        let new = HierarchyPlace::of(world, entity);
+
+        ignore_auto_undo(world, [Some(entity), old.parent, new.parent]);
+        Self { entity, old, new }
+    }
+}
+
+fn ignore_auto_undo(world: &mut World, entities: impl IntoIterator<Item = Option<Entity>>) {
+    for e in entities.into_iter().flatten() {
+        if let Some(mut entity) = world.get_entity_mut(e) {
+            entity.insert(OneFrameUndoIgnore::default());
+        }
+        world
+            .resource_mut::<UndoIngnoreStorage>()
+            .storage
+            .insert(e, OneFrameUndoIgnore::default());
+    }
+}
+
+impl EditorChange for ReparentEntity {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        if world.get_entity(e).is_none() {
+            return Err(format!("Entity {:?} not found for reparent", e));
+        }
+        let old_parent = self
+            .old
+            .parent
+            .map(|parent| get_entity_with_remap(parent, entity_remap))
+            .filter(|parent| world.get_entity(*parent).is_some());
+        let new_parent = world.get::<Parent>(e).map(Parent::get);
+
+        if let Some(parent) = old_parent {
+            let len = world
+                .get::<Children>(parent)
+                .map_or(0, |children| children.iter().filter(|c| **c != e).count());
+            world
+                .entity_mut(parent)
+                .insert_children(self.old.index.min(len), &[e]);
+        } else {
+            world.entity_mut(e).remove_parent();
+        }
+        if let Some(transform) = self.old.transform {
+            world.entity_mut(e).insert(transform);
+        }
+
+        ignore_auto_undo(world, [Some(e), old_parent, new_parent]);
+        info!("Reverted ReparentEntity for entity: {}", e.index());
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        format!(
+            "Reparent entity {:?} from {:?} to {:?}",
+            self.entity, self.old.parent, self.new.parent
+        )
+    }
+
+    fn describe(&self) -> String {
+        if self.new.parent.is_some() {
+            "Reparent".to_string()
+        } else {
+            "Detach".to_string()
+        }
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(ReparentEntity {
+            entity: self.entity,
+            old: self.new,
+            new: self.old,
+        })
+    }
+}
+
 pub struct ManyChanges {
     changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+    /// Name of change group from [`begin_change_group`]
+    name: Option<String>,
+}
+
+/// Parent-children links of removed entities. Reverting it attaches restored entities back
+/// to their parents in original [`Children`] order
+pub struct RestoreHierarchy {
+    pub links: Vec<(Entity, Vec<Entity>)>,
+}
+
+impl RestoreHierarchy {
+    /// Collect links of `entities` with their children before removal
+    pub fn record(world: &World, entities: &[Entity]) -> Self {
+        let links = entities
+            .iter()
+            .filter_map(|entity| {
+                world
+                    .get::<Children>(*entity)
+                    .map(|children| (*entity, children.to_vec()))
+            })
+            .collect();
+        Self { links }
+    }
+}
+
+impl EditorChange for RestoreHierarchy {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        for (parent, children) in self.links.iter() {
+            let parent = get_entity_with_remap(*parent, entity_remap);
+            if world.get_entity(parent).is_none() {
+                continue;
+            }
+            let children = children
+                .iter()
+                .map(|child| get_entity_with_remap(*child, entity_remap))
+                .filter(|child| world.get_entity(*child).is_some())
+                .collect::<Vec<_>>();
+            world.entity_mut(parent).push_children(&children);
+            ignore_auto_undo(world, children.iter().map(|child| Some(*child)));
+            ignore_auto_undo(world, [Some(parent)]);
+        }
+        info!("Reverted RestoreHierarchy");
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        format!("Restore hierarchy of {} entities", self.links.len())
+    }
+
+    fn describe(&self) -> String {
+        "Restore hierarchy".to_string()
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        // Entities will be despawned by other changes of the same step, links are kept for next undo
+        Arc::new(RestoreHierarchy {
+            links: self.links.clone(),
+        })
+    }
 }
 
 impl EditorChange for ManyChanges {
@@ -655,7 +1059,15 @@ impl EditorChange for ManyChanges {
     }
 
     fn debug_text(&self) -> String {
-        "ManyChanges".to_string()
+        self.name
+            .clone()
+            .unwrap_or_else(|| "ManyChanges".to_string())
+    }
+
+    fn describe(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{} changes", self.changes.len()))
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
@@ -668,6 +1080,64 @@ impl EditorChange for ManyChanges {
 
         Arc::new(ManyChanges {
             changes: new_changes,
+            name: self.name.clone(),
+        })
+    }
+}
+
+/// Change of component value made by reflection based UI (inspector), where component type is known only by [`TypeId`].
+/// Values are reflected copies of component, which are applied back with [`ReflectComponent`] from [`AppTypeRegistry`]
+pub struct ComponentModified {
+    pub entity: Entity,
+    pub type_id: TypeId,
+    pub type_name: String,
+    pub old_value: Box<dyn Reflect>,
+    pub new_value: Box<dyn Reflect>,
+}
+
+impl EditorChange for ComponentModified {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let reflect_component = registry
+            .get(self.type_id)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .ok_or_else(|| {
+                format!(
+                    "{} is not registered as reflected component",
+                    self.type_name
+                )
+            })?;
+        let mut entity = world
+            .get_entity_mut(e)
+            .ok_or_else(|| format!("Entity {:?} not found for {} change", e, self.type_name))?;
+
+        reflect_component.apply_or_insert(&mut entity, self.old_value.as_ref());
+        entity.insert(OneFrameUndoIgnore::default());
+        info!("Reverted ComponentModified for entity: {}", e.index());
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        format!("{} modified for entity {:?}", self.type_name, self.entity)
+    }
+
+    fn describe(&self) -> String {
+        format!("Changed {}", self.type_name)
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(ComponentModified {
+            entity: self.entity,
+            type_id: self.type_id,
+            type_name: self.type_name.clone(),
+            old_value: self.new_value.clone_value(),
+            new_value: self.old_value.clone_value(),
         })
     }
 }
@@ -1124,6 +1594,71 @@ mod tests {
         assert!(app.world.get_entity(test_id).is_none());
     }
 
+    #[test]
+    fn test_change_chain_size_limit() {
+        let mut app = configure_app();
+        app.world
+            .resource_mut::<ChangeChainSettings>()
+            .max_change_chain_size = 3;
+        app.update();
+
+        let entities = (0..5)
+            .map(|_| app.world.spawn_empty().id())
+            .collect::<Vec<_>>();
+        for entity in entities.iter() {
+            app.world.send_event(NewChange {
+                change: Arc::new(AddedEntity { entity: *entity }),
+            });
+            app.update();
+            app.update();
+        }
+        assert_eq!(app.world.resource::<ChangeChain>().changes.len(), 3);
+
+        // Entity of kept step is removed without undo, its undo must not panic
+        app.world.entity_mut(entities[4]).despawn();
+        for _ in 0..5 {
+            app.world.send_event(UndoRedo::Undo);
+            app.update();
+        }
+        assert!(app.world.get_entity(entities[0]).is_some());
+        assert!(app.world.get_entity(entities[1]).is_some());
+        assert!(app.world.get_entity(entities[2]).is_none());
+        assert!(app.world.get_entity(entities[3]).is_none());
+        assert_eq!(app.world.resource::<ChangeChain>().redo_stack().len(), 3);
+
+        app.world
+            .resource_mut::<ChangeChainSettings>()
+            .max_change_chain_size = 1;
+        app.update();
+        assert_eq!(app.world.resource::<ChangeChain>().redo_stack().len(), 1);
+    }
+
+    /// Steps of entity, which was respawned outside of change chain (scene reload), must target the new entity
+    #[test]
+    fn test_remap_entity_after_respawn() {
+        let mut app = configure_app();
+        app.update();
+
+        let old = app.world.spawn_empty().id();
+        app.world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: old }),
+        });
+        app.update();
+        app.update();
+
+        app.world.despawn(old);
+        let new = app.world.spawn_empty().id();
+        app.world
+            .resource_mut::<ChangeChain>()
+            .remap_entity(old, new);
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+
+        assert!(app.world.get_entity(new).is_none());
+    }
+
     #[test]
     fn test_undo_with_remap() {
         let mut app = configure_app();
@@ -1172,4 +1707,332 @@ mod tests {
         let mut query = app.world.query::<&Children>();
         assert!(query.get_single(&app.world).is_ok());
     }
+
+    #[test]
+    fn test_redo_after_entity_respawn() {
+        let mut app = configure_app();
+        app.auto_undo::<Name>();
+
+        app.update();
+
+        let test_id = app.world.spawn_empty().id();
+        app.world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: test_id }),
+        });
+
+        app.update();
+        app.update();
+
+        app.world
+            .entity_mut(test_id)
+            .insert(Name::default())
+            .insert(UndoMarker);
+        app.world.get_mut::<Name>(test_id).unwrap().set_changed();
+
+        for _ in 0..6 {
+            app.update();
+        }
+
+        // Two undo/redo cycles: second redo must reuse remap of respawned entity
+        for _ in 0..2 {
+            app.world.send_event(UndoRedo::Undo);
+            app.update();
+            app.update();
+            app.world.send_event(UndoRedo::Undo);
+            app.update();
+            app.update();
+
+            assert!(app.world.get_entity(test_id).is_none());
+            assert_eq!(
+                app.world.resource::<ChangeChain>().changes_for_redo.len(),
+                2
+            );
+
+            app.world.send_event(UndoRedo::Redo);
+            app.update();
+            app.update();
+            app.world.send_event(UndoRedo::Redo);
+            app.update();
+            app.update();
+
+            let mut query = app.world.query::<&Name>();
+            assert_eq!(query.iter(&app.world).count(), 1);
+            assert!(app
+                .world
+                .resource::<ChangeChain>()
+                .changes_for_redo
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn test_change_group_undo_in_one_step() {
+        let mut app = configure_app();
+        app.update();
+
+        let first = app.world.spawn_empty().id();
+        let second = app.world.spawn_empty().id();
+
+        begin_change_group(&mut app.world, "Spawn two");
+        app.world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: first }),
+        });
+        app.update();
+        app.update();
+        app.world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: second }),
+        });
+        end_change_group(&mut app.world);
+
+        for _ in 0..6 {
+            app.update();
+        }
+
+        let change_chain = app.world.resource::<ChangeChain>();
+        assert_eq!(change_chain.changes.len(), 1);
+        assert_eq!(change_chain.changes[0].debug_text(), "Spawn two");
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+
+        assert!(app.world.get_entity(first).is_none());
+        assert!(app.world.get_entity(second).is_none());
+    }
+
+    #[test]
+    fn test_restore_hierarchy_after_removal() {
+        let mut app = configure_app();
+        app.add_plugins(HierarchyPlugin);
+        app.update();
+
+        let parent = app.world.spawn_empty().id();
+        let children = [
+            app.world.spawn_empty().id(),
+            app.world.spawn_empty().id(),
+            app.world.spawn_empty().id(),
+        ];
+        app.world.entity_mut(parent).push_children(&children);
+
+        begin_change_group(&mut app.world, "Clear");
+        let hierarchy = RestoreHierarchy::record(&app.world, &[parent]);
+        for entity in std::iter::once(parent).chain(children) {
+            app.world.send_event(NewChange {
+                change: Arc::new(RemovedEntity { entity }),
+            });
+        }
+        app.world.send_event(NewChange {
+            change: Arc::new(hierarchy),
+        });
+        app.world.entity_mut(parent).despawn_recursive();
+        end_change_group(&mut app.world);
+
+        for _ in 0..6 {
+            app.update();
+        }
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+
+        let remap = app.world.resource::<ChangeChain>().entity_remap.clone();
+        let new_parent = remap[&parent];
+        let restored = app.world.get::<Children>(new_parent).unwrap().to_vec();
+        let expected = children
+            .iter()
+            .map(|child| remap[child])
+            .collect::<Vec<_>>();
+        assert_eq!(restored, expected);
+    }
+
+    #[test]
+    fn test_despawn_recursive_with_undo_restores_subtree() {
+        let mut app = configure_app();
+        app.add_plugins(HierarchyPlugin);
+        app.auto_reflected_undo::<Name>();
+        app.update();
+
+        let parent = app.world.spawn((UndoMarker, Name::new("parent"))).id();
+        let child = app.world.spawn((UndoMarker, Name::new("child"))).id();
+        let grandchild = app.world.spawn((UndoMarker, Name::new("grandchild"))).id();
+        app.world.entity_mut(parent).add_child(child);
+        app.world.entity_mut(child).add_child(grandchild);
+
+        for _ in 0..6 {
+            app.update();
+        }
+
+        despawn_recursive_with_undo(&mut app.world, &[parent], "Delete");
+        for _ in 0..6 {
+            app.update();
+        }
+        assert!(app.world.get_entity(grandchild).is_none());
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+
+        let remap = app.world.resource::<ChangeChain>().entity_remap.clone();
+        let restored_child = app.world.get::<Children>(remap[&parent]).unwrap().to_vec();
+        assert_eq!(restored_child, vec![remap[&child]]);
+        let restored_grandchild = app.world.get::<Children>(remap[&child]).unwrap().to_vec();
+        assert_eq!(restored_grandchild, vec![remap[&grandchild]]);
+        assert_eq!(
+            app.world.get::<Name>(remap[&grandchild]).unwrap().as_str(),
+            "grandchild"
+        );
+    }
+
+    #[test]
+    fn test_reparent_undo_keeps_sibling_order() {
+        let mut app = configure_app();
+        app.add_plugins(HierarchyPlugin);
+
+        let parent = app.world.spawn(UndoMarker).id();
+        let first = app.world.spawn(UndoMarker).id();
+        let second = app.world.spawn(UndoMarker).id();
+        let third = app.world.spawn(UndoMarker).id();
+        app.world
+            .entity_mut(parent)
+            .push_children(&[first, second, third]);
+
+        app.update();
+        app.update();
+
+        let change = ReparentEntity::record(&mut app.world, second, |world| {
+            world.entity_mut(second).remove_parent();
+        });
+        app.world.send_event(NewChange {
+            change: Arc::new(change),
+        });
+
+        app.update();
+        app.update();
+
+        assert!(app.world.get::<Parent>(second).is_none());
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+
+        let children = app.world.get::<Children>(parent).unwrap();
+        assert_eq!(children.to_vec(), vec![first, second, third]);
+
+        app.world.send_event(UndoRedo::Redo);
+        app.update();
+        app.update();
+
+        assert!(app.world.get::<Parent>(second).is_none());
+    }
+
+    #[test]
+    fn test_component_modified_undo_redo() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let entity = app.world.spawn(Transform::from_xyz(1.0, 0.0, 0.0)).id();
+        app.world.send_event(NewChange {
+            change: Arc::new(ComponentModified {
+                entity,
+                type_id: TypeId::of::<Transform>(),
+                type_name: "Transform".to_string(),
+                old_value: Box::new(Transform::IDENTITY),
+                new_value: Box::new(Transform::from_xyz(1.0, 0.0, 0.0)),
+            }),
+        });
+        app.update();
+        app.update();
+
+        app.world.send_event(UndoRedo::Undo);
+        app.update();
+        assert_eq!(
+            *app.world.get::<Transform>(entity).unwrap(),
+            Transform::IDENTITY
+        );
+
+        app.world.send_event(UndoRedo::Redo);
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(entity).unwrap().translation,
+            Vec3::X
+        );
+    }
+
+    #[test]
+    fn test_programmatic_undo_redo() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let entity = app.world.spawn(Transform::from_xyz(1.0, 0.0, 0.0)).id();
+        app.world.send_event(NewChange {
+            change: Arc::new(ComponentModified {
+                entity,
+                type_id: TypeId::of::<Transform>(),
+                type_name: "Transform".to_string(),
+                old_value: Box::new(Transform::IDENTITY),
+                new_value: Box::new(Transform::from_xyz(1.0, 0.0, 0.0)),
+            }),
+        });
+        app.update();
+        assert_eq!(undo_len(&app.world), 0);
+        app.update();
+        assert_eq!(undo_len(&app.world), 1);
+        assert_eq!(redo_len(&app.world), 0);
+
+        undo(&mut app.world);
+        assert_eq!(
+            *app.world.get::<Transform>(entity).unwrap(),
+            Transform::IDENTITY
+        );
+        assert_eq!(undo_len(&app.world), 0);
+        assert_eq!(redo_len(&app.world), 1);
+
+        redo(&mut app.world);
+        assert_eq!(
+            app.world.get::<Transform>(entity).unwrap().translation,
+            Vec3::X
+        );
+        assert_eq!(undo_len(&app.world), 1);
+        assert_eq!(redo_len(&app.world), 0);
+    }
+
+    #[test]
+    fn test_revert_of_removed_entity_fails_without_panic() {
+        let mut app = configure_app();
+        let entity = app.world.spawn(Name::new("old")).id();
+        app.world.despawn(entity);
+        let remap = HashMap::new();
+
+        let change = ComponentChange {
+            old_value: Name::new("old"),
+            new_value: Name::new("new"),
+            entity,
+        };
+        assert!(change.revert(&mut app.world, &remap).is_err());
+
+        let change = ReflectedComponentChange::new(entity, Name::new("old"), Name::new("new"));
+        assert!(change.revert(&mut app.world, &remap).is_err());
+        assert!(change.get_inverse().revert(&mut app.world, &remap).is_err());
+
+        let change = ComponentModified {
+            entity,
+            type_id: TypeId::of::<Name>(),
+            type_name: "Name".to_string(),
+            old_value: Box::new(Name::new("old")),
+            new_value: Box::new(Name::new("new")),
+        };
+        assert!(change.revert(&mut app.world, &remap).is_err());
+
+        // Type without reflect data can not be applied even to existing entity
+        let alive = app.world.spawn(Name::new("alive")).id();
+        let change = ComponentModified {
+            entity: alive,
+            type_id: TypeId::of::<OneFrameUndoIgnore>(),
+            type_name: "OneFrameUndoIgnore".to_string(),
+            old_value: Box::new(Name::new("old")),
+            new_value: Box::new(Name::new("new")),
+        };
+        assert!(change.revert(&mut app.world, &remap).is_err());
+        assert_eq!(app.world.get::<Name>(alive).unwrap().as_str(), "alive");
+    }
 }
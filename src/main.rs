@@ -2,6 +2,18 @@ use bevy::prelude::*;
 
 fn main() {
     let mut app = App::new();
+    #[cfg(feature = "editor")]
+    {
+        use bevy::log::LogPlugin;
+        use space_editor_ui::console::console_subscriber;
+
+        // Log output is also captured by editor "Console" tab
+        app.add_plugins(DefaultPlugins.set(LogPlugin {
+            update_subscriber: Some(console_subscriber),
+            ..default()
+        }));
+    }
+    #[cfg(not(feature = "editor"))]
     app.add_plugins(DefaultPlugins);
     #[cfg(feature = "editor")]
     {